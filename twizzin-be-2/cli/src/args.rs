@@ -0,0 +1,142 @@
+use anyhow::{anyhow, bail, Context, Result};
+use std::str::FromStr;
+
+// Command line split into positional words and `--name value` options.
+// Options can repeat (`--fee-recipient` does) and may also be written `--name=value`.
+pub struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let Some(option) = arg.strip_prefix("--") else {
+                positional.push(arg);
+                continue;
+            };
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name.to_string(), value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--{} needs a value", option))?;
+                    (option.to_string(), value)
+                }
+            };
+            if name.is_empty() {
+                bail!("Options need a name: {}", arg);
+            }
+            options.push((name, value));
+        }
+
+        Ok(Self {
+            positional,
+            options,
+        })
+    }
+
+    pub fn positional(&self, index: usize) -> Option<&str> {
+        self.positional.get(index).map(String::as_str)
+    }
+
+    // The last value given for the option
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Every value given for a repeatable option, in order
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    pub fn parse_value<T>(&self, name: &str) -> Result<Option<T>>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.value(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|error| anyhow!("Invalid --{} {:?}: {}", name, value, error))
+            })
+            .transpose()
+    }
+
+    pub fn required<T>(&self, name: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.parse_value(name)?
+            .with_context(|| format!("--{} is required", name))
+    }
+
+    // Fails on options the command doesn't take, so typos aren't silently ignored
+    pub fn check_options(&self, allowed: &[&str]) -> Result<()> {
+        match self
+            .options
+            .iter()
+            .find(|(option, _)| !allowed.contains(&option.as_str()))
+        {
+            Some((option, _)) => bail!("Unknown option --{}", option),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Args {
+        Args::parse(line.split_whitespace().map(String::from)).unwrap()
+    }
+
+    #[test]
+    fn test_positional_and_options() {
+        let args = parse("game start ABC --total-time 60000 --url=http://localhost:8899");
+        assert_eq!(args.positional(0), Some("game"));
+        assert_eq!(args.positional(1), Some("start"));
+        assert_eq!(args.positional(2), Some("ABC"));
+        assert_eq!(args.positional(3), None);
+        assert_eq!(args.required::<i64>("total-time").unwrap(), 60000);
+        assert_eq!(args.value("url"), Some("http://localhost:8899"));
+        assert_eq!(args.value("keypair"), None);
+    }
+
+    #[test]
+    fn test_repeated_options() {
+        let args =
+            parse("config init --fee-recipient a:5000 --fee-recipient b:5000 --fee 1 --fee 2");
+        assert_eq!(args.values("fee-recipient"), vec!["a:5000", "b:5000"]);
+        assert_eq!(args.value("fee"), Some("2"));
+    }
+
+    #[test]
+    fn test_bad_options() {
+        assert!(Args::parse(["--url".to_string()]).is_err());
+        assert!(Args::parse(["--=1".to_string()]).is_err());
+
+        let args = parse("game start ABC --total-time soon");
+        assert!(args.required::<i64>("total-time").is_err());
+        assert!(args.required::<i64>("missing").is_err());
+        assert!(args.check_options(&["total-time"]).is_ok());
+        assert!(args.check_options(&["url"]).is_err());
+    }
+}
+
+// cargo test -p twizzin-cli args -- --nocapture
@@ -0,0 +1,181 @@
+use crate::args::Args;
+use crate::commands::Cli;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::system_program;
+use anyhow::{anyhow, bail, Context, Result};
+use twizzin_be_2::state::{FeeRecipient, ProgramConfig, ReferralFeeSource};
+use twizzin_be_2::{accounts, instruction};
+use twizzin_client::{find_config_pda, instructions};
+
+pub const CONFIG_INIT_OPTIONS: &[&str] = &[
+    "treasury",
+    "treasury-fee",
+    "fee-recipient",
+    "referral-fee",
+    "referral-fee-source",
+];
+
+pub const CONFIG_UPDATE_OPTIONS: &[&str] = CONFIG_INIT_OPTIONS;
+
+pub const CONFIG_MIGRATE_OPTIONS: &[&str] = &[];
+
+pub fn config_init(cli: &Cli, args: &Args) -> Result<()> {
+    let referral_fee_source = match args.value("referral-fee-source") {
+        Some(source) => parse_referral_fee_source(source)?,
+        None => ReferralFeeSource::Treasury,
+    };
+    let ix = instructions::init_config(
+        accounts::InitConfig {
+            admin: cli.signer_key(),
+            config: find_config_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::InitConfig {
+            treasury_pubkey: args.required("treasury")?,
+            treasury_fee: args.required("treasury-fee")?,
+            fee_recipients: parse_fee_recipients(&args.values("fee-recipient"))?,
+            referral_fee: args.parse_value("referral-fee")?.unwrap_or(0),
+            referral_fee_source,
+        },
+    );
+    cli.send("Initialized the config", &[ix])
+}
+
+// Only the options given change. Passing --fee-recipient replaces the whole list,
+// and `--fee-recipient none` clears it so the treasury gets the full fee again.
+pub fn config_update(cli: &Cli, args: &Args) -> Result<()> {
+    let fee_recipients = args.values("fee-recipient");
+    let new_fee_recipients = match fee_recipients.as_slice() {
+        [] => None,
+        ["none"] => Some(Vec::new()),
+        recipients => Some(parse_fee_recipients(recipients)?),
+    };
+    let ix = instructions::update_config(
+        accounts::UpdateConfig {
+            authority: cli.signer_key(),
+            config: find_config_pda().0,
+        },
+        instruction::UpdateConfig {
+            new_treasury: args.parse_value("treasury")?,
+            new_treasury_fee: args.parse_value("treasury-fee")?,
+            new_fee_recipients,
+            new_referral_fee: args.parse_value("referral-fee")?,
+            new_referral_fee_source: args
+                .value("referral-fee-source")
+                .map(parse_referral_fee_source)
+                .transpose()?,
+        },
+    );
+    cli.send("Updated the config", &[ix])?;
+
+    let config: ProgramConfig = cli.fetch(&find_config_pda().0)?;
+    print_config(&config);
+    Ok(())
+}
+
+// Grows a config created before fee recipients and referral fees to the current layout
+pub fn config_migrate(cli: &Cli) -> Result<()> {
+    let ix = instructions::migrate_config(
+        accounts::MigrateConfig {
+            authority: cli.signer_key(),
+            config: find_config_pda().0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateConfig {},
+    );
+    cli.send("Migrated the config", &[ix])?;
+
+    let config: ProgramConfig = cli.fetch(&find_config_pda().0)?;
+    print_config(&config);
+    Ok(())
+}
+
+fn print_config(config: &ProgramConfig) {
+    println!(
+        "Treasury: {} ({} bps)",
+        config.treasury_pubkey, config.treasury_fee
+    );
+    for recipient in &config.fee_recipients {
+        println!(
+            "  Fee recipient: {} ({} bps)",
+            recipient.recipient, recipient.weight_bps
+        );
+    }
+    let source = match config.referral_fee_source {
+        ReferralFeeSource::Treasury => "treasury",
+        ReferralFeeSource::Commission => "commission",
+    };
+    println!(
+        "Referral fee: {} bps from the {}",
+        config.referral_fee, source
+    );
+}
+
+// Each recipient is written PUBKEY:WEIGHT_BPS
+fn parse_fee_recipients(recipients: &[&str]) -> Result<Vec<FeeRecipient>> {
+    recipients
+        .iter()
+        .map(|recipient| {
+            let (pubkey, weight) = recipient
+                .split_once(':')
+                .with_context(|| format!("Fee recipients are PUBKEY:BPS, got {}", recipient))?;
+            Ok(FeeRecipient {
+                recipient: pubkey
+                    .parse::<Pubkey>()
+                    .map_err(|error| anyhow!("Invalid fee recipient {}: {}", pubkey, error))?,
+                weight_bps: weight
+                    .parse()
+                    .with_context(|| format!("Invalid fee recipient weight {}", weight))?,
+            })
+        })
+        .collect()
+}
+
+fn parse_referral_fee_source(source: &str) -> Result<ReferralFeeSource> {
+    match source {
+        "treasury" => Ok(ReferralFeeSource::Treasury),
+        "commission" => Ok(ReferralFeeSource::Commission),
+        _ => bail!(
+            "--referral-fee-source is treasury or commission, got {}",
+            source
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fee_recipients() {
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let recipients =
+            parse_fee_recipients(&[&format!("{}:7000", first), &format!("{}:3000", second)])
+                .unwrap();
+        assert_eq!(recipients.len(), 2);
+        assert_eq!(recipients[0].recipient, first);
+        assert_eq!(recipients[0].weight_bps, 7000);
+        assert_eq!(recipients[1].recipient, second);
+        assert_eq!(recipients[1].weight_bps, 3000);
+
+        assert!(parse_fee_recipients(&[&first.to_string()]).is_err());
+        assert!(parse_fee_recipients(&["not-a-pubkey:100"]).is_err());
+        assert!(parse_fee_recipients(&[&format!("{}:70000", first)]).is_err());
+    }
+
+    #[test]
+    fn test_parse_referral_fee_source() {
+        assert!(matches!(
+            parse_referral_fee_source("treasury").unwrap(),
+            ReferralFeeSource::Treasury
+        ));
+        assert!(matches!(
+            parse_referral_fee_source("commission").unwrap(),
+            ReferralFeeSource::Commission
+        ));
+        assert!(parse_referral_fee_source("Treasury").is_err());
+    }
+}
+
+// cargo test -p twizzin-cli config -- --nocapture
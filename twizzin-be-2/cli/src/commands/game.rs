@@ -0,0 +1,360 @@
+use crate::args::Args;
+use crate::commands::{token_account, Cli};
+use crate::game_file::load_game_file;
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::{system_program, Discriminator};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token;
+use anyhow::{bail, Result};
+use std::path::Path;
+use twizzin_be_2::constants::SOL_ADDRESS;
+use twizzin_be_2::state::{
+    AccessMode, GameReferral, Gate, PlayerAccount, ProgramConfig, Winners, LEGACY_PLAYER_SPACE,
+};
+use twizzin_be_2::{accounts, instruction, ID};
+use twizzin_client::{
+    decode_account, determine_winners, expected_winner_count, find_config_pda,
+    find_game_code_registry_pda, find_game_pda, find_referral_balance_pda, find_vault_pda,
+    find_winners_pda, instructions, rank_players, GAME_FIELD_OFFSET,
+};
+
+pub const GAME_CREATE_OPTIONS: &[&str] = &[];
+pub const GAME_START_OPTIONS: &[&str] = &["total-time"];
+pub const GAME_END_OPTIONS: &[&str] = &[];
+pub const GAME_SETTLE_REFERRALS_OPTIONS: &[&str] = &[];
+pub const GAME_RANK_OPTIONS: &[&str] = &[];
+pub const GAME_DECLARE_WINNERS_OPTIONS: &[&str] = &[];
+pub const GAME_CLOSE_OPTIONS: &[&str] = &[];
+pub const GAME_MIGRATE_OPTIONS: &[&str] = &[];
+
+// Creates a public, ungated game from a game file with the signer as admin
+pub fn game_create(cli: &Cli, path: &Path) -> Result<()> {
+    let loaded = load_game_file(path)?;
+    let game = loaded.game;
+    let admin = cli.signer_key();
+    let (game_address, _) = find_game_pda(&admin, &game.code);
+    let (vault, _) = find_vault_pda(&admin, &game.code);
+    let is_native = loaded.token_mint.to_string() == SOL_ADDRESS;
+    // Nothing is on chain yet to build the game's token accounts from
+    let token_account = |owner: &Pubkey| {
+        (!is_native).then(|| get_associated_token_address(owner, &loaded.token_mint))
+    };
+
+    let ix = instructions::init_game(
+        accounts::InitGame {
+            admin,
+            game: game_address,
+            game_code_registry: find_game_code_registry_pda(&game.code).0,
+            token_mint: loaded.token_mint,
+            vault,
+            vault_token_account: token_account(&vault),
+            admin_token_account: token_account(&admin),
+            season: None,
+            tournament: None,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitGame {
+            name: game.name,
+            game_code: game.code.clone(),
+            entry_fee: game.entry_fee,
+            commission: game.commission,
+            start_time: game.start_time,
+            end_time: game.end_time,
+            max_winners: game.max_winners,
+            answer_hash: loaded.answer_hash,
+            donation_amount: game.donation_amount,
+            all_are_winners: game.all_are_winners,
+            even_split: game.even_split,
+            access_mode: AccessMode::Public,
+            gate: Gate::Open,
+            metadata_uri: game.metadata_uri,
+            question_set_hash: loaded.question_set_hash,
+            wrong_answer_penalty: game.wrong_answer_penalty,
+            speed_bonus: game.speed_bonus,
+            speed_bonus_window: game.speed_bonus_window,
+            merkle_version: game.merkle_version,
+        },
+    );
+    cli.send(&format!("Created game {}", game.code), &[ix])?;
+    println!("Game: {}", game_address);
+    println!("Questions: {}", loaded.answer_key.len());
+    println!("Answer root: {}", hex(&loaded.answer_hash));
+    Ok(())
+}
+
+// Starts the game now. The game keeps its scheduled length unless --total-time (ms) is given.
+pub fn game_start(cli: &Cli, game_code: &str, args: &Args) -> Result<()> {
+    let (game_address, game) = cli.find_game(game_code)?;
+    let total_time = match args.parse_value("total-time")? {
+        Some(total_time) => total_time,
+        None => game.end_time - game.start_time,
+    };
+    let ix = instructions::start_game(
+        accounts::StartGame {
+            authority: cli.signer_key(),
+            game: game_address,
+            game_operator: cli.game_operator(&game_address, &game),
+        },
+        instruction::StartGame { total_time },
+    );
+    cli.send(&format!("Started game {}", game_code), &[ix])
+}
+
+// Pays out the fees and holds back the referral rewards for game_settle_referrals
+pub fn game_end(cli: &Cli, game_code: &str) -> Result<()> {
+    let (game_address, game) = cli.find_game(game_code)?;
+    let (config_address, _) = find_config_pda();
+    let config: ProgramConfig = cli.fetch(&config_address)?;
+    let (vault, _) = find_vault_pda(&game.creator, &game.game_code);
+
+    let remaining_accounts: Vec<AccountMeta> = config
+        .fee_recipients
+        .iter()
+        .map(|fee_recipient| {
+            let account =
+                token_account(&game, &fee_recipient.recipient).unwrap_or(fee_recipient.recipient);
+            AccountMeta::new(account, false)
+        })
+        .collect();
+
+    let ix = instructions::end_game(
+        accounts::EndGame {
+            authority: cli.signer_key(),
+            admin: game.admin,
+            game: game_address,
+            game_operator: cli.game_operator(&game_address, &game),
+            vault,
+            vault_token_account: token_account(&game, &vault),
+            admin_token_account: token_account(&game, &game.admin),
+            treasury_token_account: token_account(&game, &config.treasury_pubkey),
+            config: config_address,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            treasury: config.treasury_pubkey,
+        },
+        instruction::EndGame {},
+        remaining_accounts,
+    );
+    cli.send(&format!("Ended game {}", game_code), &[ix])
+}
+
+// Pays every referrer end_game held rewards back for, one transaction each
+pub fn game_settle_referrals(cli: &Cli, game_code: &str) -> Result<()> {
+    let (game_address, game) = cli.find_game(game_code)?;
+    let (vault, _) = find_vault_pda(&game.creator, &game.game_code);
+
+    let game_referrals = fetch_game_referrals(cli, &game_address)?;
+    if game_referrals.is_empty() {
+        println!("No referrals left to settle for game {}", game_code);
+        return Ok(());
+    }
+    for (game_referral_address, game_referral) in game_referrals {
+        let (referral_balance, _) =
+            find_referral_balance_pda(&game_referral.referrer, &game.token_mint);
+        let ix = instructions::settle_referral(
+            accounts::SettleReferral {
+                game: game_address,
+                game_referral: game_referral_address,
+                referral_balance,
+                vault,
+                vault_token_account: token_account(&game, &vault),
+                referral_token_account: token_account(&game, &referral_balance),
+                token_program: token::ID,
+            },
+            instruction::SettleReferral {},
+        );
+        cli.send(
+            &format!("Settled referrals for {}", game_referral.referrer),
+            &[ix],
+        )?;
+    }
+    Ok(())
+}
+
+// Moves a game the signer created before admin transfers onto the current layout,
+// then its players, one transaction each
+pub fn game_migrate(cli: &Cli, game_code: &str) -> Result<()> {
+    // Legacy games have no code registry, but were seeded with their admin
+    let (game_address, _) = find_game_pda(&cli.signer_key(), game_code);
+    let ix = instructions::migrate_game(
+        accounts::MigrateGame {
+            admin: cli.signer_key(),
+            game: game_address,
+            winners: find_winners_pda(&game_address).0,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateGame {},
+    );
+    cli.send(&format!("Migrated game {}", game_code), &[ix])?;
+
+    let legacy_players = cli
+        .rpc
+        .get_program_accounts(
+            &ID,
+            &[
+                (0, &PlayerAccount::DISCRIMINATOR),
+                (GAME_FIELD_OFFSET, game_address.as_ref()),
+            ],
+        )?
+        .into_iter()
+        .filter(|(_, data)| data.len() == LEGACY_PLAYER_SPACE);
+    for (player_account, _) in legacy_players {
+        let ix = instructions::migrate_player_account(
+            accounts::MigratePlayerAccount {
+                payer: cli.signer_key(),
+                game: game_address,
+                player_account,
+                system_program: system_program::ID,
+            },
+            instruction::MigratePlayerAccount {},
+        );
+        cli.send(
+            &format!("Migrated player account {}", player_account),
+            &[ix],
+        )?;
+    }
+    Ok(())
+}
+
+// Prints every player who submitted, best first, marking the winners to declare
+pub fn game_rank(cli: &Cli, game_code: &str) -> Result<()> {
+    let (game_address, game) = cli.find_game(game_code)?;
+    let players = fetch_players(cli, &game_address)?;
+    let ranked = rank_players(&players);
+    let winners = expected_winner_count(&game) as usize;
+
+    println!(
+        "{} of {} players finished - {} winners",
+        ranked.len(),
+        players.len(),
+        winners
+    );
+    println!(
+        "{:>4}  {:<44}  {:>6}  {:>7}  {:>13}",
+        "rank", "player", "score", "correct", "finished"
+    );
+    for (i, player) in ranked.iter().enumerate() {
+        println!(
+            "{:>4}  {:<44}  {:>6}  {:>7}  {:>13}{}",
+            i + 1,
+            player.player.to_string(),
+            player.score,
+            player.num_correct,
+            player.finished_time,
+            if i < winners { "  *" } else { "" }
+        );
+    }
+    Ok(())
+}
+
+pub fn game_declare_winners(cli: &Cli, game_code: &str) -> Result<()> {
+    let (game_address, game) = cli.find_game(game_code)?;
+    let players = fetch_players(cli, &game_address)?;
+    let winners = determine_winners(&game, &players)?;
+    let (vault, _) = find_vault_pda(&game.creator, &game.game_code);
+    let (winners_address, _) = find_winners_pda(&game_address);
+
+    // Carries on from any batches an earlier run got through
+    let declared: Vec<Pubkey> = cli
+        .fetch_optional::<Winners>(&winners_address)?
+        .map(|declared| {
+            declared
+                .winners
+                .iter()
+                .map(|winner| winner.player)
+                .collect()
+        })
+        .unwrap_or_default();
+    if !winners.starts_with(&declared) {
+        bail!(
+            "Winners declared for game {} don't match the ranking",
+            game_code
+        );
+    }
+
+    let ixs = instructions::declare_winners(
+        accounts::DeclareWinners {
+            authority: cli.signer_key(),
+            game: game_address,
+            game_operator: cli.game_operator(&game_address, &game),
+            vault,
+            vault_token_account: token_account(&game, &vault),
+            winners: winners_address,
+            season: game.season,
+            system_program: system_program::ID,
+        },
+        &winners,
+        declared.len(),
+    );
+    for ix in ixs {
+        cli.send(&format!("Declared winners for game {}", game_code), &[ix])?;
+    }
+    for (i, winner) in winners.iter().enumerate() {
+        println!("{:>4}  {}", i + 1, winner);
+    }
+    Ok(())
+}
+
+pub fn game_close(cli: &Cli, game_code: &str) -> Result<()> {
+    let (game_address, game) = cli.find_game(game_code)?;
+    let (vault, _) = find_vault_pda(&game.creator, &game.game_code);
+
+    let ix = instructions::close_game(
+        accounts::CloseGame {
+            admin: cli.signer_key(),
+            game: game_address,
+            winners: find_winners_pda(&game_address).0,
+            game_code_registry: game
+                .code_registered
+                .then(|| find_game_code_registry_pda(&game.game_code).0),
+            vault,
+            vault_token_account: token_account(&game, &vault),
+            admin_token_account: token_account(&game, &game.admin),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CloseGame {},
+    );
+    cli.send(&format!("Closed game {}", game_code), &[ix])
+}
+
+fn fetch_players(cli: &Cli, game_address: &Pubkey) -> Result<Vec<PlayerAccount>> {
+    cli.rpc
+        .get_program_accounts(
+            &ID,
+            &[
+                (0, &PlayerAccount::DISCRIMINATOR),
+                (GAME_FIELD_OFFSET, game_address.as_ref()),
+            ],
+        )?
+        .iter()
+        .map(|(_, data)| Ok(decode_account(data)?))
+        .collect()
+}
+
+// Referrers settle_referral still has to pay
+fn fetch_game_referrals(cli: &Cli, game_address: &Pubkey) -> Result<Vec<(Pubkey, GameReferral)>> {
+    let game_referrals = cli.rpc.get_program_accounts(
+        &ID,
+        &[
+            (0, &GameReferral::DISCRIMINATOR),
+            (GAME_FIELD_OFFSET, game_address.as_ref()),
+        ],
+    )?;
+    let mut unsettled = Vec::new();
+    for (address, data) in game_referrals {
+        let game_referral: GameReferral = decode_account(&data)?;
+        if !game_referral.settled {
+            unsettled.push((address, game_referral));
+        }
+    }
+    Ok(unsettled)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
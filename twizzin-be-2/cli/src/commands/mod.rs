@@ -0,0 +1,79 @@
+use crate::rpc::RpcClient;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::AccountDeserialize;
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::{Context, Result};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signer};
+use twizzin_be_2::state::{Game, GameCodeRegistry};
+use twizzin_client::{
+    decode_account, find_game_code_registry_pda, find_game_operator_pda, find_game_pda,
+};
+
+pub mod config;
+pub use config::*;
+
+pub mod game;
+pub use game::*;
+
+pub mod player;
+pub use player::*;
+
+// The RPC connection and the wallet signing and paying for every transaction
+pub struct Cli {
+    pub rpc: RpcClient,
+    pub signer: Keypair,
+}
+
+impl Cli {
+    pub fn signer_key(&self) -> Pubkey {
+        self.signer.pubkey()
+    }
+
+    pub fn send(&self, action: &str, instructions: &[Instruction]) -> Result<()> {
+        let signature = self
+            .rpc
+            .send_and_confirm(instructions, &self.signer)
+            .with_context(|| format!("Couldn't {}", action))?;
+        println!("{}: {}", action, signature);
+        Ok(())
+    }
+
+    pub fn fetch_optional<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        self.rpc
+            .get_account_data(address)?
+            .map(|data| decode_account(&data))
+            .transpose()
+            .with_context(|| format!("Couldn't decode account {}", address))
+    }
+
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        self.fetch_optional(address)?
+            .with_context(|| format!("Account {} doesn't exist", address))
+    }
+
+    // Looks the game up through the code registry, so any host or operator can use the code
+    pub fn find_game(&self, game_code: &str) -> Result<(Pubkey, Game)> {
+        let (registry_address, _) = find_game_code_registry_pda(game_code);
+        let game_address = match self.fetch_optional::<GameCodeRegistry>(&registry_address)? {
+            Some(registry) => registry.game,
+            // Games from before code registries were seeded with their admin
+            None => find_game_pda(&self.signer_key(), game_code).0,
+        };
+        let game = self
+            .fetch_optional(&game_address)?
+            .with_context(|| format!("No game uses the code {}", game_code))?;
+        Ok((game_address, game))
+    }
+
+    // Operators sign through their GameOperator account - the admin doesn't need one
+    pub fn game_operator(&self, game_address: &Pubkey, game: &Game) -> Option<Pubkey> {
+        let signer = self.signer_key();
+        (game.admin != signer).then(|| find_game_operator_pda(game_address, &signer).0)
+    }
+}
+
+// The owner's associated token account for SPL games - SOL games don't use one
+pub fn token_account(game: &Game, owner: &Pubkey) -> Option<Pubkey> {
+    (!game.is_native).then(|| get_associated_token_address(owner, &game.token_mint))
+}
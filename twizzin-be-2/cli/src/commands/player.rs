@@ -0,0 +1,42 @@
+use crate::commands::{token_account, Cli};
+use anchor_lang::system_program;
+use anchor_spl::token;
+use anyhow::Result;
+use twizzin_be_2::state::Submission;
+use twizzin_be_2::{accounts, instruction};
+use twizzin_client::{
+    find_player_pda, find_profile_pda, find_submission_pda, find_vault_pda, find_winners_pda,
+    instructions,
+};
+
+pub const PLAYER_CLAIM_OPTIONS: &[&str] = &[];
+
+// Claims the signer's prize and closes their player and submission accounts. Lifetime
+// and season stats are updated when the player has a profile or the game counts
+// towards a season.
+pub fn player_claim(cli: &Cli, game_code: &str) -> Result<()> {
+    let (game_address, game) = cli.find_game(game_code)?;
+    let player = cli.signer_key();
+    let (vault, _) = find_vault_pda(&game.creator, &game.game_code);
+    let (profile, _) = find_profile_pda(&player);
+    let (submission, _) = find_submission_pda(&game_address, &player);
+    let has_submission = cli.fetch_optional::<Submission>(&submission)?.is_some();
+
+    let ix = instructions::claim(
+        accounts::Claim {
+            player,
+            game: game_address,
+            winners: find_winners_pda(&game_address).0,
+            player_account: find_player_pda(&game_address, &player).0,
+            submission: has_submission.then_some(submission),
+            vault,
+            vault_token_account: token_account(&game, &vault),
+            player_token_account: token_account(&game, &player),
+            profile,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::Claim {},
+    );
+    cli.send(&format!("Claimed the prize for game {}", game_code), &[ix])
+}
@@ -0,0 +1,395 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::hash::hash;
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+use twizzin_be_2::constants::SOL_ADDRESS;
+use twizzin_be_2::state::{
+    AnswerKeyEntry, AnswerType, MAX_ANSWER_LENGTH, MAX_QUESTIONS, MAX_QUESTION_ID_LENGTH,
+};
+use twizzin_be_2::utils::merkle::MERKLE_V2;
+use twizzin_client::MerkleTree;
+
+// A game and its questions as the host writes them, in JSON or YAML, e.g.
+// { "name": "Friday Quiz", "code": "FRI1", "startTime": 1735689600000,
+//   "endTime": 1735693200000, "maxWinners": 3, "entryFee": 10000000,
+//   "questions": [{ "id": "q1", "text": "2 + 2?", "options": ["a) 3", "b) 4"],
+//                   "type": "choice", "answer": "b" },
+//                 { "id": "q2", "type": "numeric", "min": 95, "max": 105, "points": 2 }] }
+// Amounts are in the mint's base units (lamports for SOL) and times in milliseconds.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct GameFile {
+    pub name: String,
+    pub code: String,
+    #[serde(default)]
+    pub token_mint: Option<String>, // Defaults to SOL
+    #[serde(default)]
+    pub entry_fee: u64,
+    #[serde(default)]
+    pub commission: u16,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub max_winners: u8,
+    #[serde(default)]
+    pub donation_amount: u64,
+    #[serde(default)]
+    pub all_are_winners: bool,
+    #[serde(default)]
+    pub even_split: bool,
+    #[serde(default)]
+    pub metadata_uri: Option<String>,
+    #[serde(default)]
+    pub wrong_answer_penalty: u16,
+    #[serde(default)]
+    pub speed_bonus: u16,
+    #[serde(default)]
+    pub speed_bonus_window: u32,
+    #[serde(default = "default_merkle_version")]
+    pub merkle_version: u8,
+    pub questions: Vec<Question>,
+}
+
+// Questions are numbered by their position in the file
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Question {
+    pub id: String,
+    #[serde(default)]
+    pub text: String, // Only shown to players, never stored on chain
+    #[serde(default)]
+    pub options: Vec<String>, // The choices players pick from, shown the same way
+    #[serde(flatten)]
+    pub answer: QuestionAnswer,
+    #[serde(default = "default_points")]
+    pub points: u16,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum QuestionAnswer {
+    Choice { answer: String },
+    MultiSelect { answer: String },
+    FreeText { answer: String },
+    Numeric { min: i64, max: i64 },
+}
+
+fn default_merkle_version() -> u8 {
+    MERKLE_V2
+}
+
+fn default_points() -> u16 {
+    1
+}
+
+// The game file plus everything derived from it for init_game
+pub struct LoadedGame {
+    pub game: GameFile,
+    pub token_mint: Pubkey,
+    pub answer_key: Vec<AnswerKeyEntry>,
+    pub answer_hash: [u8; 32],
+    pub question_set_hash: [u8; 32], // See question_set_hash
+}
+
+#[derive(Clone, Copy)]
+pub enum GameFileFormat {
+    Json,
+    Yaml,
+}
+
+pub fn load_game_file(path: &Path) -> Result<LoadedGame> {
+    let format = match path.extension().and_then(|extension| extension.to_str()) {
+        Some("json") => GameFileFormat::Json,
+        Some("yaml") | Some("yml") => GameFileFormat::Yaml,
+        _ => bail!("Game files must be .json or .yaml: {}", path.display()),
+    };
+    let contents = std::fs::read(path).with_context(|| format!("Can't read {}", path.display()))?;
+    parse_game_file(&contents, format)
+        .with_context(|| format!("Invalid game file {}", path.display()))
+}
+
+pub fn parse_game_file(contents: &[u8], format: GameFileFormat) -> Result<LoadedGame> {
+    let game: GameFile = match format {
+        GameFileFormat::Json => serde_json::from_slice(contents)?,
+        GameFileFormat::Yaml => serde_yaml::from_slice(contents)?,
+    };
+    let token_mint = game
+        .token_mint
+        .as_deref()
+        .unwrap_or(SOL_ADDRESS)
+        .parse()
+        .map_err(|error| anyhow!("Invalid tokenMint: {}", error))?;
+
+    let answer_key = answer_key_entries(&game.questions)?;
+    let answer_hash = MerkleTree::new(&answer_key, game.merkle_version)?.root();
+
+    Ok(LoadedGame {
+        token_mint,
+        answer_key,
+        answer_hash,
+        question_set_hash: question_set_hash(&game.questions)?,
+        game,
+    })
+}
+
+// sha256 of what players are shown as a JSON list of [id, text, options] per question,
+// however the file was written. Answers are left out - the hash is public from the
+// moment the game is created.
+pub fn question_set_hash(questions: &[Question]) -> Result<[u8; 32]> {
+    let content: Vec<(&str, &str, &[String])> = questions
+        .iter()
+        .map(|question| {
+            (
+                question.id.as_str(),
+                question.text.as_str(),
+                question.options.as_slice(),
+            )
+        })
+        .collect();
+    Ok(hash(&serde_json::to_vec(&content)?).to_bytes())
+}
+
+fn answer_key_entries(questions: &[Question]) -> Result<Vec<AnswerKeyEntry>> {
+    if questions.is_empty() {
+        bail!("A game needs at least one question");
+    }
+    if questions.len() > MAX_QUESTIONS {
+        bail!("A game can have at most {} questions", MAX_QUESTIONS);
+    }
+
+    questions
+        .iter()
+        .enumerate()
+        .map(|(display_order, question)| {
+            if question.id.is_empty() || question.id.len() > MAX_QUESTION_ID_LENGTH {
+                bail!(
+                    "Question {} needs an id of 1 to {} bytes",
+                    display_order + 1,
+                    MAX_QUESTION_ID_LENGTH
+                );
+            }
+            if questions[..display_order]
+                .iter()
+                .any(|earlier| earlier.id == question.id)
+            {
+                bail!("Question id {} is used twice", question.id);
+            }
+
+            let (answer_type, answer) = match &question.answer {
+                QuestionAnswer::Choice { answer } => (AnswerType::Choice, answer.clone()),
+                QuestionAnswer::MultiSelect { answer } => (AnswerType::MultiSelect, answer.clone()),
+                QuestionAnswer::FreeText { answer } => (AnswerType::FreeText, answer.clone()),
+                QuestionAnswer::Numeric { min, max } => {
+                    if min > max {
+                        bail!("Question {} has min above max", question.id);
+                    }
+                    (
+                        AnswerType::Numeric {
+                            min: *min,
+                            max: *max,
+                        },
+                        String::new(),
+                    )
+                }
+            };
+            if answer.len() > MAX_ANSWER_LENGTH {
+                bail!(
+                    "Question {} has an answer over {} bytes",
+                    question.id,
+                    MAX_ANSWER_LENGTH
+                );
+            }
+
+            Ok(AnswerKeyEntry {
+                display_order: display_order as u8,
+                answer_type,
+                answer,
+                question_id: question.id.clone(),
+                points: question.points,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use twizzin_be_2::utils::merkle::MERKLE_V1;
+
+    const GAME: &str = r#"{
+        "name": "Friday Quiz",
+        "code": "FRI1",
+        "entryFee": 10000000,
+        "startTime": 1735689600000,
+        "endTime": 1735693200000,
+        "maxWinners": 3,
+        "questions": [
+            { "id": "q1", "text": "2 + 2?", "options": ["a) 3", "b) 4"], "type": "choice", "answer": "b" },
+            { "id": "q2", "type": "multiSelect", "answer": "c,a" },
+            { "id": "q3", "type": "freeText", "answer": "New York" },
+            { "id": "q4", "type": "numeric", "min": 95, "max": 105, "points": 2 }
+        ]
+    }"#;
+
+    // The same game as GAME
+    const YAML_GAME: &str = r#"
+name: Friday Quiz
+code: FRI1
+entryFee: 10000000
+startTime: 1735689600000
+endTime: 1735693200000
+maxWinners: 3
+questions:
+  - id: q1
+    text: 2 + 2?
+    options: [a) 3, b) 4]
+    type: choice
+    answer: b
+  - { id: q2, type: multiSelect, answer: "c,a" }
+  - { id: q3, type: freeText, answer: New York }
+  - { id: q4, type: numeric, min: 95, max: 105, points: 2 }
+"#;
+
+    fn parse_json(contents: &str) -> Result<LoadedGame> {
+        parse_game_file(contents.as_bytes(), GameFileFormat::Json)
+    }
+
+    fn with_questions(questions: &str) -> String {
+        GAME.replace(
+            &GAME[GAME.find("\"questions\"").unwrap()..GAME.rfind('}').unwrap()],
+            &format!("\"questions\": {}\n", questions),
+        )
+    }
+
+    #[test]
+    fn test_parse_game_file() {
+        let loaded = parse_json(GAME).unwrap();
+        assert_eq!(loaded.game.name, "Friday Quiz");
+        assert_eq!(loaded.game.entry_fee, 10_000_000);
+        assert_eq!(loaded.game.commission, 0);
+        assert_eq!(loaded.game.merkle_version, MERKLE_V2);
+        assert_eq!(loaded.token_mint.to_string(), SOL_ADDRESS);
+        let content = r#"[["q1","2 + 2?",["a) 3","b) 4"]],["q2","",[]],["q3","",[]],["q4","",[]]]"#;
+        assert_eq!(
+            loaded.question_set_hash,
+            hash(content.as_bytes()).to_bytes()
+        );
+
+        let key = &loaded.answer_key;
+        assert_eq!(key.len(), 4);
+        assert!(key
+            .iter()
+            .enumerate()
+            .all(|(i, entry)| entry.display_order as usize == i));
+        assert!(matches!(key[0].answer_type, AnswerType::Choice));
+        assert!(matches!(key[1].answer_type, AnswerType::MultiSelect));
+        assert!(matches!(key[2].answer_type, AnswerType::FreeText));
+        assert!(matches!(
+            key[3].answer_type,
+            AnswerType::Numeric { min: 95, max: 105 }
+        ));
+        assert_eq!(key[3].answer, "");
+        assert_eq!(key[0].points, 1);
+        assert_eq!(key[3].points, 2);
+
+        // The root is the client's tree over the same entries
+        let tree = MerkleTree::new(key, MERKLE_V2).unwrap();
+        assert_eq!(loaded.answer_hash, tree.root());
+    }
+
+    #[test]
+    fn test_merkle_version_changes_the_root() {
+        let v2 = parse_json(GAME).unwrap();
+        let v1_game = GAME.replace(
+            "\"maxWinners\": 3,",
+            "\"maxWinners\": 3, \"merkleVersion\": 1,",
+        );
+        let v1 = parse_json(&v1_game).unwrap();
+        assert_eq!(v1.game.merkle_version, MERKLE_V1);
+        assert_ne!(v1.answer_hash, v2.answer_hash);
+
+        let v3_game = GAME.replace(
+            "\"maxWinners\": 3,",
+            "\"maxWinners\": 3, \"merkleVersion\": 3,",
+        );
+        assert!(parse_json(&v3_game).is_err());
+    }
+
+    #[test]
+    fn test_question_set_hash_ignores_answers() {
+        let loaded = parse_json(GAME).unwrap();
+        let new_answer = parse_json(&GAME.replace("\"c,a\"", "\"b\"")).unwrap();
+        assert_eq!(loaded.question_set_hash, new_answer.question_set_hash);
+        assert_ne!(loaded.answer_hash, new_answer.answer_hash);
+
+        let new_text = parse_json(&GAME.replace("2 + 2?", "2 + 3?")).unwrap();
+        assert_ne!(loaded.question_set_hash, new_text.question_set_hash);
+        assert_eq!(loaded.answer_hash, new_text.answer_hash);
+
+        // Swapping the options round changes what the answer means
+        let new_options = parse_json(&GAME.replace("b) 4", "b) 5")).unwrap();
+        assert_ne!(loaded.question_set_hash, new_options.question_set_hash);
+        assert_eq!(loaded.answer_hash, new_options.answer_hash);
+    }
+
+    #[test]
+    fn test_yaml_matches_json() {
+        let json = parse_json(GAME).unwrap();
+        let yaml = parse_game_file(YAML_GAME.as_bytes(), GameFileFormat::Yaml).unwrap();
+        assert_eq!(yaml.game.name, json.game.name);
+        assert_eq!(yaml.game.entry_fee, json.game.entry_fee);
+        assert_eq!(yaml.answer_hash, json.answer_hash);
+        assert_eq!(yaml.question_set_hash, json.question_set_hash);
+
+        let typo = YAML_GAME.replace("maxWinners: 3", "maxWinners: 3\nmaxWiners: 1");
+        assert!(parse_game_file(typo.as_bytes(), GameFileFormat::Yaml).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_questions() {
+        let bad = [
+            "[]",
+            r#"[{ "id": "", "type": "choice", "answer": "a" }]"#,
+            r#"[{ "id": "q1", "type": "choice", "answer": "a" },
+                { "id": "q1", "type": "choice", "answer": "b" }]"#,
+            r#"[{ "id": "q1", "type": "numeric", "min": 5, "max": 1 }]"#,
+            r#"[{ "id": "q1", "type": "essay", "answer": "a" }]"#,
+            r#"[{ "id": "q1", "type": "numeric", "answer": "a" }]"#,
+        ];
+        for questions in bad {
+            assert!(
+                parse_json(&with_questions(questions)).is_err(),
+                "{}",
+                questions
+            );
+        }
+
+        let long_answer = format!(
+            r#"[{{ "id": "q1", "type": "freeText", "answer": "{}" }}]"#,
+            "a".repeat(MAX_ANSWER_LENGTH + 1)
+        );
+        assert!(parse_json(&with_questions(&long_answer)).is_err());
+
+        // As many questions as the program takes an answer key for
+        let questions = |count: usize| {
+            format!(
+                "[{}]",
+                (0..count)
+                    .map(|i| format!(r#"{{ "id": "q{}", "type": "choice", "answer": "a" }}"#, i))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        };
+        assert!(parse_json(&with_questions(&questions(MAX_QUESTIONS))).is_ok());
+        assert!(parse_json(&with_questions(&questions(MAX_QUESTIONS + 1))).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields_and_formats() {
+        let typo = GAME.replace("\"maxWinners\"", "\"maxWiners\": 1, \"maxWinners\"");
+        assert!(parse_json(&typo).is_err());
+        assert!(load_game_file(Path::new("game.txt")).is_err());
+    }
+}
+
+// cargo test -p twizzin-cli game_file -- --nocapture
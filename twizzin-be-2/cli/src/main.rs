@@ -0,0 +1,116 @@
+// twizzin - runs games end to end from the command line, against a local validator by default
+use anyhow::{bail, Context, Result};
+use solana_sdk::signature::read_keypair_file;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+mod args;
+mod commands;
+mod game_file;
+mod rpc;
+
+use args::Args;
+use commands::*;
+use rpc::RpcClient;
+
+const DEFAULT_URL: &str = "http://127.0.0.1:8899";
+const GLOBAL_OPTIONS: &[&str] = &["url", "keypair"];
+
+const USAGE: &str = "\
+Usage: twizzin <command> [options]
+
+Commands:
+  config init --treasury <PUBKEY> --treasury-fee <BPS> [--fee-recipient <PUBKEY:BPS>]...
+              [--referral-fee <BPS>] [--referral-fee-source treasury|commission]
+  config update [--treasury <PUBKEY>] [--treasury-fee <BPS>] [--fee-recipient <PUBKEY:BPS|none>]...
+                [--referral-fee <BPS>] [--referral-fee-source treasury|commission]
+  config migrate
+  game create <GAME_FILE.json|.yaml>
+  game start <CODE> [--total-time <MS>]
+  game end <CODE>
+  game settle-referrals <CODE>
+  game rank <CODE>
+  game declare-winners <CODE>
+  game close <CODE>
+  game migrate <CODE>
+  player claim <CODE>
+
+Options:
+  --url <URL>          RPC endpoint [default: http://127.0.0.1:8899]
+  --keypair <PATH>     Signing and paying wallet [default: ~/.config/solana/id.json]";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {:#}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    let args = Args::parse(std::env::args().skip(1))?;
+    let (Some(group), Some(command)) = (args.positional(0), args.positional(1)) else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    let options = match (group, command) {
+        ("config", "init") => CONFIG_INIT_OPTIONS,
+        ("config", "update") => CONFIG_UPDATE_OPTIONS,
+        ("config", "migrate") => CONFIG_MIGRATE_OPTIONS,
+        ("game", "create") => GAME_CREATE_OPTIONS,
+        ("game", "start") => GAME_START_OPTIONS,
+        ("game", "end") => GAME_END_OPTIONS,
+        ("game", "settle-referrals") => GAME_SETTLE_REFERRALS_OPTIONS,
+        ("game", "rank") => GAME_RANK_OPTIONS,
+        ("game", "declare-winners") => GAME_DECLARE_WINNERS_OPTIONS,
+        ("game", "close") => GAME_CLOSE_OPTIONS,
+        ("game", "migrate") => GAME_MIGRATE_OPTIONS,
+        ("player", "claim") => PLAYER_CLAIM_OPTIONS,
+        _ => bail!("Unknown command {} {}\n\n{}", group, command, USAGE),
+    };
+    let allowed: Vec<&str> = options.iter().chain(GLOBAL_OPTIONS).copied().collect();
+    args.check_options(&allowed)?;
+
+    let cli = connect(&args)?;
+    match (group, command) {
+        ("config", "init") => config_init(&cli, &args),
+        ("config", "update") => config_update(&cli, &args),
+        ("config", "migrate") => config_migrate(&cli),
+        ("game", "create") => game_create(&cli, Path::new(operand(&args, "GAME_FILE")?)),
+        ("game", "start") => game_start(&cli, operand(&args, "CODE")?, &args),
+        ("game", "end") => game_end(&cli, operand(&args, "CODE")?),
+        ("game", "settle-referrals") => game_settle_referrals(&cli, operand(&args, "CODE")?),
+        ("game", "rank") => game_rank(&cli, operand(&args, "CODE")?),
+        ("game", "declare-winners") => game_declare_winners(&cli, operand(&args, "CODE")?),
+        ("game", "close") => game_close(&cli, operand(&args, "CODE")?),
+        ("game", "migrate") => game_migrate(&cli, operand(&args, "CODE")?),
+        ("player", "claim") => player_claim(&cli, operand(&args, "CODE")?),
+        _ => unreachable!(),
+    }
+}
+
+// The word after the subcommand, e.g. the game code
+fn operand<'a>(args: &'a Args, name: &str) -> Result<&'a str> {
+    args.positional(2)
+        .with_context(|| format!("Missing <{}>\n\n{}", name, USAGE))
+}
+
+fn connect(args: &Args) -> Result<Cli> {
+    let keypair_path = match args.value("keypair") {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let home = std::env::var("HOME").context("HOME isn't set - pass --keypair")?;
+            Path::new(&home).join(".config/solana/id.json")
+        }
+    };
+    let signer = read_keypair_file(&keypair_path)
+        .map_err(|error| anyhow::anyhow!("Can't read {}: {}", keypair_path.display(), error))?;
+
+    Ok(Cli {
+        rpc: RpcClient::new(args.value("url").unwrap_or(DEFAULT_URL)),
+        signer,
+    })
+}
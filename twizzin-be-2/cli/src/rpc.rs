@@ -0,0 +1,157 @@
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Context, Result};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_client;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+// The RPC calls the commands make, every one at confirmed commitment
+pub struct RpcClient {
+    client: rpc_client::RpcClient,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Self {
+        let client = rpc_client::RpcClient::new_with_commitment(
+            url.to_string(),
+            CommitmentConfig::confirmed(),
+        );
+        Self { client }
+    }
+
+    // None when the account doesn't exist
+    pub fn get_account_data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        let account = self
+            .client
+            .get_account_with_commitment(address, self.client.commitment())
+            .map_err(describe_rpc_error)
+            .context("getAccountInfo failed")?
+            .value;
+        Ok(account.map(|account| account.data))
+    }
+
+    // Accounts owned by the program that match every memcmp filter, as (offset, bytes)
+    pub fn get_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: &[(usize, &[u8])],
+    ) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+        let filters = filters
+            .iter()
+            .map(|(offset, bytes)| {
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(*offset, bytes.to_vec()))
+            })
+            .collect();
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.client.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let accounts = self
+            .client
+            .get_program_accounts_with_config(program_id, config)
+            .map_err(describe_rpc_error)
+            .context("getProgramAccounts failed")?;
+        Ok(accounts
+            .into_iter()
+            .map(|(address, account)| (address, account.data))
+            .collect())
+    }
+
+    // Signs with the payer alone and waits for the transaction to be confirmed
+    pub fn send_and_confirm(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+    ) -> Result<Signature> {
+        let blockhash = self
+            .client
+            .get_latest_blockhash()
+            .map_err(describe_rpc_error)
+            .context("getLatestBlockhash failed")?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            blockhash,
+        );
+        self.client
+            .send_and_confirm_transaction(&transaction)
+            .map_err(describe_rpc_error)
+            .with_context(|| format!("Transaction {} failed", transaction.signatures[0]))
+    }
+}
+
+// Includes the program logs from failed preflight simulations
+fn describe_rpc_error(error: ClientError) -> anyhow::Error {
+    match error.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            message,
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            ..
+        }) => match &result.logs {
+            Some(logs) if !logs.is_empty() => {
+                anyhow!("{}\n  {}", message, logs.join("\n  "))
+            }
+            _ => anyhow!("{}", message),
+        },
+        _ => error.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_response::RpcSimulateTransactionResult;
+
+    fn preflight_failure(logs: Option<Vec<String>>) -> ClientError {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            code: -32002,
+            message: "Transaction simulation failed".to_string(),
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(
+                RpcSimulateTransactionResult {
+                    err: None,
+                    logs,
+                    accounts: None,
+                    units_consumed: None,
+                    return_data: None,
+                    inner_instructions: None,
+                },
+            ),
+        })
+        .into()
+    }
+
+    #[test]
+    fn test_describe_rpc_error() {
+        let logs = vec![
+            "Program log: AnchorError".to_string(),
+            "Program failed".to_string(),
+        ];
+        assert_eq!(
+            describe_rpc_error(preflight_failure(Some(logs))).to_string(),
+            "Transaction simulation failed\n  Program log: AnchorError\n  Program failed"
+        );
+        assert_eq!(
+            describe_rpc_error(preflight_failure(None)).to_string(),
+            "Transaction simulation failed"
+        );
+
+        // Anything else keeps the client's own message
+        let other = ClientErrorKind::Custom("Bad".to_string());
+        let message = other.to_string();
+        assert_eq!(describe_rpc_error(other.into()).to_string(), message);
+    }
+}
+
+// cargo test -p twizzin-cli rpc -- --nocapture
@@ -0,0 +1,133 @@
+use crate::error::ClientError;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use twizzin_be_2::state::{
+    AnswerKey, Game, GameCodeRegistry, GameOperator, GameReferral, PlayerAccount, PlayerProfile,
+    ProgramConfig, ReferralBalance, Season, SeasonPlayer, Submission, Tournament, TournamentEntry,
+    Winners,
+};
+
+// Where the game pubkey sits in a PlayerAccount, Submission or Winners account -
+// right after the discriminator - for memcmp filters when fetching a game's accounts
+pub const GAME_FIELD_OFFSET: usize = 8;
+
+// Decodes account data, checking the discriminator
+pub fn decode_account<T: AccountDeserialize>(data: &[u8]) -> Result<T, ClientError> {
+    T::try_deserialize(&mut &data[..])
+        .map_err(|error| ClientError::InvalidAccountData(error.to_string()))
+}
+
+// Any account owned by the program, told apart by its discriminator
+pub enum ProgramAccount {
+    Config(ProgramConfig),
+    Game(Box<Game>),
+    GameCodeRegistry(GameCodeRegistry),
+    Player(PlayerAccount),
+    Submission(Submission),
+    Winners(Winners),
+    AnswerKey(AnswerKey),
+    GameOperator(GameOperator),
+    Profile(PlayerProfile),
+    ReferralBalance(ReferralBalance),
+    GameReferral(GameReferral),
+    Season(Season),
+    SeasonPlayer(SeasonPlayer),
+    Tournament(Tournament),
+    TournamentEntry(TournamentEntry),
+}
+
+impl ProgramAccount {
+    pub fn decode(data: &[u8]) -> Result<Self, ClientError> {
+        let discriminator = data
+            .get(..8)
+            .ok_or_else(|| ClientError::InvalidAccountData("too short".to_string()))?;
+
+        Ok(match discriminator {
+            d if d == ProgramConfig::DISCRIMINATOR => Self::Config(decode_account(data)?),
+            d if d == Game::DISCRIMINATOR => Self::Game(Box::new(decode_account(data)?)),
+            d if d == GameCodeRegistry::DISCRIMINATOR => {
+                Self::GameCodeRegistry(decode_account(data)?)
+            }
+            d if d == PlayerAccount::DISCRIMINATOR => Self::Player(decode_account(data)?),
+            d if d == Submission::DISCRIMINATOR => Self::Submission(decode_account(data)?),
+            d if d == Winners::DISCRIMINATOR => Self::Winners(decode_account(data)?),
+            d if d == AnswerKey::DISCRIMINATOR => Self::AnswerKey(decode_account(data)?),
+            d if d == GameOperator::DISCRIMINATOR => Self::GameOperator(decode_account(data)?),
+            d if d == PlayerProfile::DISCRIMINATOR => Self::Profile(decode_account(data)?),
+            d if d == ReferralBalance::DISCRIMINATOR => {
+                Self::ReferralBalance(decode_account(data)?)
+            }
+            d if d == GameReferral::DISCRIMINATOR => Self::GameReferral(decode_account(data)?),
+            d if d == Season::DISCRIMINATOR => Self::Season(decode_account(data)?),
+            d if d == SeasonPlayer::DISCRIMINATOR => Self::SeasonPlayer(decode_account(data)?),
+            d if d == Tournament::DISCRIMINATOR => Self::Tournament(decode_account(data)?),
+            d if d == TournamentEntry::DISCRIMINATOR => {
+                Self::TournamentEntry(decode_account(data)?)
+            }
+            _ => {
+                return Err(ClientError::InvalidAccountData(
+                    "unknown discriminator".to_string(),
+                ))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::AccountSerialize;
+
+    fn player_account(game: Pubkey) -> PlayerAccount {
+        PlayerAccount {
+            game,
+            player: Pubkey::new_unique(),
+            join_time: 1_000,
+            finished_time: 2_000,
+            num_correct: 3,
+            answer_hash: [7; 32],
+            bump: 255,
+            referrer: None,
+            score: -4,
+            answered: [1; 32],
+        }
+    }
+
+    fn encode<T: AccountSerialize>(account: &T) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_decode_account() {
+        let game = Pubkey::new_unique();
+        let data = encode(&player_account(game));
+
+        let decoded: PlayerAccount = decode_account(&data).unwrap();
+        assert_eq!(decoded.game, game);
+        assert_eq!(decoded.score, -4);
+        assert_eq!(
+            &data[GAME_FIELD_OFFSET..GAME_FIELD_OFFSET + 32],
+            game.as_ref()
+        );
+
+        // The discriminator has to match the type
+        assert!(decode_account::<Winners>(&data).is_err());
+        assert!(decode_account::<PlayerAccount>(&data[..20]).is_err());
+    }
+
+    #[test]
+    fn test_decode_any_program_account() {
+        let data = encode(&player_account(Pubkey::new_unique()));
+        assert!(matches!(
+            ProgramAccount::decode(&data),
+            Ok(ProgramAccount::Player(_))
+        ));
+
+        assert!(ProgramAccount::decode(&[0; 4]).is_err());
+        assert!(ProgramAccount::decode(&[0; 64]).is_err());
+    }
+}
+
+// cargo test -p twizzin-client decode -- --nocapture
@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Account data doesn't decode: {0}")]
+    InvalidAccountData(String),
+    #[error("Answer tree needs at least one answer")]
+    EmptyTree,
+    #[error("Answers must be in display order starting at 0")]
+    AnswersOutOfOrder,
+    #[error("Unknown merkle tree version {0}")]
+    InvalidMerkleVersion(u8),
+    #[error("A v0 tree can't hold answer {0} - its questions are all choices worth one point")]
+    UncoveredAnswer(u8),
+    #[error("No answer at position {0}")]
+    InvalidIndex(usize),
+    #[error("Multiproofs need a v2 tree")]
+    MultiproofRequiresV2,
+    #[error("Only {finished} players finished but {expected} winners are needed")]
+    NotEnoughFinishedPlayers { finished: usize, expected: usize },
+    #[error("Event data doesn't decode: {0}")]
+    InvalidEventData(String),
+}
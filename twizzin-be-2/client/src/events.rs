@@ -0,0 +1,253 @@
+use crate::error::ClientError;
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use twizzin_be_2::state::{
+    AnswerChunkSubmitted, AnswerKeyRevealed, AnswersSubmitted, GameAdminTransferStarted,
+    GameAdminTransferred, GameClosed, GameCreated, GameEnded, GameOperatorRemoved, GameOperatorSet,
+    GameStarted, GameUpdated, PlayerAccountClosed, PlayerJoined, PlayerRescored, ProfileUpdated,
+    ReferralRewarded, ReferralWithdrawn, SeasonCreated, SeasonHostApproved, SeasonHostRemoved,
+    SeasonPointsAwarded, SeasonSettled, TournamentClosed, TournamentCreated, TournamentJoined,
+    TournamentPrizeClaimed, TournamentRoundAdded, TournamentSettled, TreasuryFeeSplit,
+    WinnersDeclared,
+};
+use twizzin_be_2::{ClaimEvent, ID};
+
+// Anchor's emit! logs each event as `Program data: <base64 of discriminator + borsh>`
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+// Any event the program emits, told apart by its discriminator
+pub enum ProgramEvent {
+    GameCreated(GameCreated),
+    GameUpdated(GameUpdated),
+    GameStarted(GameStarted),
+    GameEnded(GameEnded),
+    GameClosed(GameClosed),
+    GameAdminTransferStarted(GameAdminTransferStarted),
+    GameAdminTransferred(GameAdminTransferred),
+    GameOperatorSet(GameOperatorSet),
+    GameOperatorRemoved(GameOperatorRemoved),
+    PlayerJoined(PlayerJoined),
+    PlayerAccountClosed(PlayerAccountClosed),
+    AnswerChunkSubmitted(AnswerChunkSubmitted),
+    AnswersSubmitted(AnswersSubmitted),
+    AnswerKeyRevealed(AnswerKeyRevealed),
+    PlayerRescored(PlayerRescored),
+    WinnersDeclared(WinnersDeclared),
+    Claim(ClaimEvent),
+    TreasuryFeeSplit(TreasuryFeeSplit),
+    ReferralRewarded(ReferralRewarded),
+    ReferralWithdrawn(ReferralWithdrawn),
+    ProfileUpdated(ProfileUpdated),
+    SeasonCreated(SeasonCreated),
+    SeasonHostApproved(SeasonHostApproved),
+    SeasonHostRemoved(SeasonHostRemoved),
+    SeasonPointsAwarded(SeasonPointsAwarded),
+    SeasonSettled(SeasonSettled),
+    TournamentCreated(TournamentCreated),
+    TournamentJoined(TournamentJoined),
+    TournamentRoundAdded(TournamentRoundAdded),
+    TournamentSettled(TournamentSettled),
+    TournamentPrizeClaimed(TournamentPrizeClaimed),
+    TournamentClosed(TournamentClosed),
+}
+
+impl ProgramEvent {
+    // None for a discriminator this version of the client doesn't know
+    pub fn decode(data: &[u8]) -> Result<Option<Self>, ClientError> {
+        let discriminator = data
+            .get(..8)
+            .ok_or_else(|| ClientError::InvalidEventData("too short".to_string()))?;
+
+        Ok(Some(match discriminator {
+            d if d == GameCreated::DISCRIMINATOR => Self::GameCreated(decode_event(data)?),
+            d if d == GameUpdated::DISCRIMINATOR => Self::GameUpdated(decode_event(data)?),
+            d if d == GameStarted::DISCRIMINATOR => Self::GameStarted(decode_event(data)?),
+            d if d == GameEnded::DISCRIMINATOR => Self::GameEnded(decode_event(data)?),
+            d if d == GameClosed::DISCRIMINATOR => Self::GameClosed(decode_event(data)?),
+            d if d == GameAdminTransferStarted::DISCRIMINATOR => {
+                Self::GameAdminTransferStarted(decode_event(data)?)
+            }
+            d if d == GameAdminTransferred::DISCRIMINATOR => {
+                Self::GameAdminTransferred(decode_event(data)?)
+            }
+            d if d == GameOperatorSet::DISCRIMINATOR => Self::GameOperatorSet(decode_event(data)?),
+            d if d == GameOperatorRemoved::DISCRIMINATOR => {
+                Self::GameOperatorRemoved(decode_event(data)?)
+            }
+            d if d == PlayerJoined::DISCRIMINATOR => Self::PlayerJoined(decode_event(data)?),
+            d if d == PlayerAccountClosed::DISCRIMINATOR => {
+                Self::PlayerAccountClosed(decode_event(data)?)
+            }
+            d if d == AnswerChunkSubmitted::DISCRIMINATOR => {
+                Self::AnswerChunkSubmitted(decode_event(data)?)
+            }
+            d if d == AnswersSubmitted::DISCRIMINATOR => {
+                Self::AnswersSubmitted(decode_event(data)?)
+            }
+            d if d == AnswerKeyRevealed::DISCRIMINATOR => {
+                Self::AnswerKeyRevealed(decode_event(data)?)
+            }
+            d if d == PlayerRescored::DISCRIMINATOR => Self::PlayerRescored(decode_event(data)?),
+            d if d == WinnersDeclared::DISCRIMINATOR => Self::WinnersDeclared(decode_event(data)?),
+            d if d == ClaimEvent::DISCRIMINATOR => Self::Claim(decode_event(data)?),
+            d if d == TreasuryFeeSplit::DISCRIMINATOR => {
+                Self::TreasuryFeeSplit(decode_event(data)?)
+            }
+            d if d == ReferralRewarded::DISCRIMINATOR => {
+                Self::ReferralRewarded(decode_event(data)?)
+            }
+            d if d == ReferralWithdrawn::DISCRIMINATOR => {
+                Self::ReferralWithdrawn(decode_event(data)?)
+            }
+            d if d == ProfileUpdated::DISCRIMINATOR => Self::ProfileUpdated(decode_event(data)?),
+            d if d == SeasonCreated::DISCRIMINATOR => Self::SeasonCreated(decode_event(data)?),
+            d if d == SeasonHostApproved::DISCRIMINATOR => {
+                Self::SeasonHostApproved(decode_event(data)?)
+            }
+            d if d == SeasonHostRemoved::DISCRIMINATOR => {
+                Self::SeasonHostRemoved(decode_event(data)?)
+            }
+            d if d == SeasonPointsAwarded::DISCRIMINATOR => {
+                Self::SeasonPointsAwarded(decode_event(data)?)
+            }
+            d if d == SeasonSettled::DISCRIMINATOR => Self::SeasonSettled(decode_event(data)?),
+            d if d == TournamentCreated::DISCRIMINATOR => {
+                Self::TournamentCreated(decode_event(data)?)
+            }
+            d if d == TournamentJoined::DISCRIMINATOR => {
+                Self::TournamentJoined(decode_event(data)?)
+            }
+            d if d == TournamentRoundAdded::DISCRIMINATOR => {
+                Self::TournamentRoundAdded(decode_event(data)?)
+            }
+            d if d == TournamentSettled::DISCRIMINATOR => {
+                Self::TournamentSettled(decode_event(data)?)
+            }
+            d if d == TournamentPrizeClaimed::DISCRIMINATOR => {
+                Self::TournamentPrizeClaimed(decode_event(data)?)
+            }
+            d if d == TournamentClosed::DISCRIMINATOR => {
+                Self::TournamentClosed(decode_event(data)?)
+            }
+            _ => return Ok(None),
+        }))
+    }
+}
+
+fn decode_event<T: AnchorDeserialize>(data: &[u8]) -> Result<T, ClientError> {
+    T::try_from_slice(&data[8..]).map_err(|error| ClientError::InvalidEventData(error.to_string()))
+}
+
+// The program's events from a transaction's log messages, in emit order. Only data
+// logged while the program itself is running counts - invocations are tracked so
+// events from other programs, including ones the program calls, are skipped.
+pub fn parse_events<S: AsRef<str>>(logs: &[S]) -> Result<Vec<ProgramEvent>, ClientError> {
+    let program_id = ID.to_string();
+    let mut invocations: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if invocations.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            let data = STANDARD
+                .decode(data)
+                .map_err(|error| ClientError::InvalidEventData(error.to_string()))?;
+            if let Some(event) = ProgramEvent::decode(&data)? {
+                events.push(event);
+            }
+        } else if let Some(rest) = log.strip_prefix("Program ") {
+            let mut words = rest.split_whitespace();
+            match (words.next(), words.next()) {
+                (Some(program), Some("invoke")) => invocations.push(program),
+                (Some(_), Some("success")) | (Some(_), Some("failed:")) => {
+                    invocations.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::Event;
+
+    fn program_data(event: &impl Event) -> String {
+        format!("{}{}", PROGRAM_DATA_PREFIX, STANDARD.encode(event.data()))
+    }
+
+    fn game_started(game: Pubkey) -> GameStarted {
+        GameStarted {
+            admin: Pubkey::new_unique(),
+            game,
+            start_time: 1_000,
+            end_time: 61_000,
+        }
+    }
+
+    #[test]
+    fn test_decode_event() {
+        let game = Pubkey::new_unique();
+        let event = ClaimEvent {
+            player: Pubkey::new_unique(),
+            game,
+            prize_amount: 500,
+            rank: 2,
+        };
+        match ProgramEvent::decode(&event.data()).unwrap() {
+            Some(ProgramEvent::Claim(claim)) => {
+                assert_eq!(claim.game, game);
+                assert_eq!(claim.prize_amount, 500);
+                assert_eq!(claim.rank, 2);
+            }
+            _ => panic!("expected a claim"),
+        }
+
+        // Unknown events are skipped, truncated known ones are an error
+        assert!(ProgramEvent::decode(&[9; 40]).unwrap().is_none());
+        assert!(ProgramEvent::decode(&event.data()[..20]).is_err());
+        assert!(ProgramEvent::decode(&[0; 4]).is_err());
+    }
+
+    #[test]
+    fn test_parse_events_tracks_invocations() {
+        let program = ID.to_string();
+        let game = Pubkey::new_unique();
+        let other = Pubkey::new_unique().to_string();
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            "Program log: Instruction: StartGame".to_string(),
+            // Data logged by a program twizzin calls isn't ours
+            format!("Program {} invoke [2]", other),
+            program_data(&game_started(Pubkey::new_unique())),
+            format!("Program {} success", other),
+            program_data(&game_started(game)),
+            format!("Program {} consumed 5000 of 200000 compute units", program),
+            format!("Program {} success", program),
+            // Nor is data from a later top level instruction to another program
+            format!("Program {} invoke [1]", other),
+            program_data(&game_started(Pubkey::new_unique())),
+            format!("Program {} failed: custom program error: 0x1", other),
+        ];
+
+        let events = parse_events(&logs).unwrap();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], ProgramEvent::GameStarted(started) if started.game == game));
+
+        let bad_base64 = vec![
+            format!("Program {} invoke [1]", program),
+            format!("{}not base64!", PROGRAM_DATA_PREFIX),
+        ];
+        assert!(parse_events(&bad_base64).is_err());
+    }
+}
+
+// cargo test -p twizzin-client events -- --nocapture
@@ -0,0 +1,440 @@
+use crate::pda::{find_player_pda, find_season_player_pda};
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use twizzin_be_2::utils::season::SEASON_POINTS;
+use twizzin_be_2::{accounts, instruction, ID};
+
+// Typed builders for every program instruction. Accounts are Anchor's generated
+// account lists (None for an optional account the instruction doesn't need) and
+// args are the instruction's arguments - see the pda module for the addresses.
+
+fn build(
+    accounts: impl ToAccountMetas,
+    args: impl InstructionData,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    let mut metas = accounts.to_account_metas(None);
+    metas.extend(remaining_accounts);
+    Instruction {
+        program_id: ID,
+        accounts: metas,
+        data: args.data(),
+    }
+}
+
+pub fn init_config(accounts: accounts::InitConfig, args: instruction::InitConfig) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn update_config(
+    accounts: accounts::UpdateConfig,
+    args: instruction::UpdateConfig,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn migrate_config(
+    accounts: accounts::MigrateConfig,
+    args: instruction::MigrateConfig,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn migrate_game(
+    accounts: accounts::MigrateGame,
+    args: instruction::MigrateGame,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn migrate_player_account(
+    accounts: accounts::MigratePlayerAccount,
+    args: instruction::MigratePlayerAccount,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn init_game(accounts: accounts::InitGame, args: instruction::InitGame) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn update_game(accounts: accounts::UpdateGame, args: instruction::UpdateGame) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn join_game(accounts: accounts::JoinGame, args: instruction::JoinGame) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn start_game(accounts: accounts::StartGame, args: instruction::StartGame) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn submit_answers(
+    accounts: accounts::SubmitAnswers,
+    args: instruction::SubmitAnswers,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+// Shares submit_answers' accounts - answers are checked against one multiproof
+pub fn submit_answers_batch(
+    accounts: accounts::SubmitAnswers,
+    args: instruction::SubmitAnswersBatch,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn submit_answer_chunk(
+    accounts: accounts::SubmitAnswerChunk,
+    args: instruction::SubmitAnswerChunk,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn finalize_submission(
+    accounts: accounts::FinalizeSubmission,
+    args: instruction::FinalizeSubmission,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+// Remaining accounts are the config's fee recipients in order
+pub fn end_game(
+    accounts: accounts::EndGame,
+    args: instruction::EndGame,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(accounts, args, remaining_accounts)
+}
+
+// Most winners one declare_winners transaction fits - an SPL season game's first
+// batch, where every winner also brings a season account
+pub const DECLARE_WINNERS_BATCH: usize = 8;
+
+// One instruction per batch of winners, each sent in its own transaction and in
+// order, after the `declared` winners already on chain. A game without winners
+// still declares its one empty batch. Each batch's player accounts are passed in
+// rank order after the named accounts, and season games also take the season
+// accounts of the winners who score points.
+pub fn declare_winners(
+    accounts: accounts::DeclareWinners,
+    winner_pubkeys: &[Pubkey],
+    declared: usize,
+) -> Vec<Instruction> {
+    let game = accounts.game;
+    let season = accounts.season;
+    let named = accounts.to_account_metas(None);
+
+    let batches: Vec<&[Pubkey]> = if declared == 0 && winner_pubkeys.is_empty() {
+        vec![&[]]
+    } else {
+        winner_pubkeys[declared..]
+            .chunks(DECLARE_WINNERS_BATCH)
+            .collect()
+    };
+    batches
+        .into_iter()
+        .enumerate()
+        .map(|(i, batch)| {
+            let batch_start = declared + i * DECLARE_WINNERS_BATCH;
+            let mut metas = named.clone();
+            metas.extend(
+                batch.iter().map(|winner| {
+                    AccountMeta::new_readonly(find_player_pda(&game, winner).0, false)
+                }),
+            );
+            if let Some(season) = season {
+                metas.extend(
+                    batch
+                        .iter()
+                        .take(SEASON_POINTS.len().saturating_sub(batch_start))
+                        .map(|winner| {
+                            AccountMeta::new(find_season_player_pda(&season, winner).0, false)
+                        }),
+                );
+            }
+            Instruction {
+                program_id: ID,
+                accounts: metas,
+                data: instruction::DeclareWinners {
+                    winner_pubkeys: batch.to_vec(),
+                }
+                .data(),
+            }
+        })
+        .collect()
+}
+
+pub fn claim(accounts: accounts::Claim, args: instruction::Claim) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn close_game(accounts: accounts::CloseGame, args: instruction::CloseGame) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn close_player_account(
+    accounts: accounts::ClosePlayerAccount,
+    args: instruction::ClosePlayerAccount,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn init_profile(
+    accounts: accounts::InitProfile,
+    args: instruction::InitProfile,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn create_season(
+    accounts: accounts::CreateSeason,
+    args: instruction::CreateSeason,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn approve_season_host(
+    accounts: accounts::ApproveSeasonHost,
+    args: instruction::ApproveSeasonHost,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn remove_season_host(
+    accounts: accounts::RemoveSeasonHost,
+    args: instruction::RemoveSeasonHost,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+// Remaining accounts are the leaderboard winners' wallets (or token accounts) in rank order
+pub fn settle_season(
+    accounts: accounts::SettleSeason,
+    args: instruction::SettleSeason,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(accounts, args, remaining_accounts)
+}
+
+// One referrer per instruction, after the game has ended
+pub fn settle_referral(
+    accounts: accounts::SettleReferral,
+    args: instruction::SettleReferral,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn withdraw_referral_balance(
+    accounts: accounts::WithdrawReferralBalance,
+    args: instruction::WithdrawReferralBalance,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn init_tournament(
+    accounts: accounts::InitTournament,
+    args: instruction::InitTournament,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn join_tournament(
+    accounts: accounts::JoinTournament,
+    args: instruction::JoinTournament,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+// Remaining accounts are the config's fee recipients in order, like end_game
+pub fn settle_tournament(
+    accounts: accounts::SettleTournament,
+    args: instruction::SettleTournament,
+    remaining_accounts: Vec<AccountMeta>,
+) -> Instruction {
+    build(accounts, args, remaining_accounts)
+}
+
+pub fn claim_tournament_prize(
+    accounts: accounts::ClaimTournamentPrize,
+    args: instruction::ClaimTournamentPrize,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn close_tournament(
+    accounts: accounts::CloseTournament,
+    args: instruction::CloseTournament,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn set_game_operator(
+    accounts: accounts::SetGameOperator,
+    args: instruction::SetGameOperator,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn remove_game_operator(
+    accounts: accounts::RemoveGameOperator,
+    args: instruction::RemoveGameOperator,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn transfer_game_admin(
+    accounts: accounts::TransferGameAdmin,
+    args: instruction::TransferGameAdmin,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn accept_game_admin(
+    accounts: accounts::AcceptGameAdmin,
+    args: instruction::AcceptGameAdmin,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn reveal_answer_key(
+    accounts: accounts::RevealAnswerKey,
+    args: instruction::RevealAnswerKey,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn reveal_answer_key_chunk(
+    accounts: accounts::RevealAnswerKeyChunk,
+    args: instruction::RevealAnswerKeyChunk,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn finalize_answer_key(
+    accounts: accounts::FinalizeAnswerKey,
+    args: instruction::FinalizeAnswerKey,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+pub fn rescore_player(
+    accounts: accounts::RescorePlayer,
+    args: instruction::RescorePlayer,
+) -> Instruction {
+    build(accounts, args, Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pda::{find_game_pda, find_season_pda, find_vault_pda, find_winners_pda};
+    use anchor_lang::{system_program, Discriminator};
+
+    #[test]
+    fn test_declare_winners() {
+        let authority = Pubkey::new_unique();
+        let (game, _) = find_game_pda(&authority, "QUIZ1");
+        let winners = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+        let ixs = declare_winners(
+            accounts::DeclareWinners {
+                authority,
+                game,
+                game_operator: None,
+                vault: find_vault_pda(&authority, "QUIZ1").0,
+                vault_token_account: None,
+                winners: find_winners_pda(&game).0,
+                season: None,
+                system_program: system_program::ID,
+            },
+            &winners,
+            0,
+        );
+
+        assert_eq!(ixs.len(), 1);
+        let ix = &ixs[0];
+        assert_eq!(ix.program_id, ID);
+        assert_eq!(ix.data[..8], instruction::DeclareWinners::DISCRIMINATOR);
+        // Optional accounts left out are passed as the program id
+        assert_eq!(ix.accounts[2].pubkey, ID);
+        assert_eq!(ix.accounts.len(), 8 + winners.len());
+        for (meta, winner) in ix.accounts[8..].iter().zip(&winners) {
+            assert_eq!(meta.pubkey, find_player_pda(&game, winner).0);
+            assert!(!meta.is_writable && !meta.is_signer);
+        }
+    }
+
+    #[test]
+    fn test_declare_season_winners() {
+        let authority = Pubkey::new_unique();
+        let (game, _) = find_game_pda(&authority, "QUIZ1");
+        let season = find_season_pda(1).0;
+        let winners: Vec<Pubkey> = (0..12).map(|_| Pubkey::new_unique()).collect();
+
+        let ixs = declare_winners(
+            accounts::DeclareWinners {
+                authority,
+                game,
+                game_operator: None,
+                vault: find_vault_pda(&authority, "QUIZ1").0,
+                vault_token_account: None,
+                winners: find_winners_pda(&game).0,
+                season: Some(season),
+                system_program: system_program::ID,
+            },
+            &winners,
+            0,
+        );
+
+        // Only the winners who score points bring their season accounts
+        let batches: Vec<_> = winners.chunks(DECLARE_WINNERS_BATCH).collect();
+        assert_eq!(ixs.len(), batches.len());
+        let mut season_players = Vec::new();
+        for (ix, batch) in ixs.iter().zip(&batches) {
+            for (meta, winner) in ix.accounts[8..].iter().zip(batch.iter()) {
+                assert_eq!(meta.pubkey, find_player_pda(&game, winner).0);
+            }
+            season_players.extend(&ix.accounts[8 + batch.len()..]);
+        }
+        assert_eq!(season_players.len(), SEASON_POINTS.len());
+        for (meta, winner) in season_players.iter().zip(&winners) {
+            assert_eq!(meta.pubkey, find_season_player_pda(&season, winner).0);
+            assert!(meta.is_writable && !meta.is_signer);
+        }
+    }
+
+    #[test]
+    fn test_declare_winners_batches() {
+        let authority = Pubkey::new_unique();
+        let (game, _) = find_game_pda(&authority, "QUIZ1");
+        let winners: Vec<Pubkey> = (0..20).map(|_| Pubkey::new_unique()).collect();
+        let accounts = || accounts::DeclareWinners {
+            authority,
+            game,
+            game_operator: None,
+            vault: find_vault_pda(&authority, "QUIZ1").0,
+            vault_token_account: None,
+            winners: find_winners_pda(&game).0,
+            season: None,
+            system_program: system_program::ID,
+        };
+
+        // A game without winners still declares, once
+        assert_eq!(declare_winners(accounts(), &[], 0).len(), 1);
+
+        // Picking up after the batches already on chain
+        let ixs = declare_winners(accounts(), &winners, DECLARE_WINNERS_BATCH);
+        assert_eq!(ixs.len(), 2);
+        let first = &ixs[0].accounts[8];
+        assert_eq!(
+            first.pubkey,
+            find_player_pda(&game, &winners[DECLARE_WINNERS_BATCH]).0
+        );
+        assert!(declare_winners(accounts(), &winners, winners.len()).is_empty());
+    }
+}
+
+// cargo test -p twizzin-client instructions -- --nocapture
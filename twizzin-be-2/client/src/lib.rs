@@ -0,0 +1,24 @@
+// Off-chain helpers for the twizzin program - everything a client needs to find
+// accounts, build instructions, read account data and events, prove answers and
+// pick winners.
+pub use twizzin_be_2::ID;
+
+pub mod error;
+pub use error::*;
+
+pub mod pda;
+pub use pda::*;
+
+pub mod instructions;
+
+pub mod decode;
+pub use decode::*;
+
+pub mod events;
+pub use events::*;
+
+pub mod merkle;
+pub use merkle::*;
+
+pub mod ranking;
+pub use ranking::*;
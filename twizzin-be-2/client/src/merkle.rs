@@ -0,0 +1,317 @@
+use crate::error::ClientError;
+use twizzin_be_2::state::{AnswerInput, AnswerKeyEntry, AnswerMultiproof};
+use twizzin_be_2::utils::merkle::{
+    create_versioned_leaf_hash, hash_node, hash_pair, leaf_covers, validate_merkle_version,
+    EMPTY_NODE, MERKLE_V0, MERKLE_V2,
+};
+
+// A game's answer tree, built the same way the program rebuilds it from a revealed key.
+// Entries are the correct answers in display order - entry i is leaf i.
+pub struct MerkleTree {
+    merkle_version: u8,
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    pub fn new(entries: &[AnswerKeyEntry], merkle_version: u8) -> Result<Self, ClientError> {
+        // New games can't pick v0, but migrated games still answer against it
+        if merkle_version != MERKLE_V0 {
+            validate_merkle_version(merkle_version)
+                .map_err(|_| ClientError::InvalidMerkleVersion(merkle_version))?;
+        }
+        if entries.is_empty() {
+            return Err(ClientError::EmptyTree);
+        }
+        if entries
+            .iter()
+            .enumerate()
+            .any(|(i, entry)| entry.display_order as usize != i)
+        {
+            return Err(ClientError::AnswersOutOfOrder);
+        }
+        if let Some(entry) = entries
+            .iter()
+            .find(|entry| !leaf_covers(merkle_version, &entry.answer_type, entry.points))
+        {
+            return Err(ClientError::UncoveredAnswer(entry.display_order));
+        }
+
+        let leaves: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|entry| {
+                create_versioned_leaf_hash(
+                    merkle_version,
+                    entry.display_order,
+                    &entry.answer_type,
+                    &entry.answer,
+                    &entry.question_id,
+                    entry.points,
+                )
+            })
+            .collect();
+
+        let mut layers = vec![leaves];
+        while layers[layers.len() - 1].len() > 1 {
+            let next = next_layer(&layers[layers.len() - 1], merkle_version);
+            layers.push(next);
+        }
+
+        Ok(Self {
+            merkle_version,
+            layers,
+        })
+    }
+
+    // The game's answer_hash
+    pub fn root(&self) -> [u8; 32] {
+        self.layers[self.layers.len() - 1][0]
+    }
+
+    pub fn merkle_version(&self) -> u8 {
+        self.merkle_version
+    }
+
+    pub fn leaf(&self, index: usize) -> Option<[u8; 32]> {
+        self.layers[0].get(index).copied()
+    }
+
+    // Siblings from the leaf up. v1 skips an odd node's missing sibling, v2 sends EMPTY_NODE.
+    pub fn proof(&self, index: usize) -> Result<Vec<[u8; 32]>, ClientError> {
+        if index >= self.layers[0].len() {
+            return Err(ClientError::InvalidIndex(index));
+        }
+
+        let mut proof = Vec::with_capacity(self.layers.len() - 1);
+        let mut index = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            match layer.get(index ^ 1) {
+                Some(sibling) => proof.push(*sibling),
+                None if self.merkle_version == MERKLE_V2 => proof.push(EMPTY_NODE),
+                None => {}
+            }
+            index /= 2;
+        }
+        Ok(proof)
+    }
+
+    // One proof for every leaf in `indices`, for submit_answers_batch. Siblings are
+    // listed bottom layer first and left to right, skipping any the leaves rebuild.
+    pub fn multiproof(&self, indices: &[u8]) -> Result<AnswerMultiproof, ClientError> {
+        if self.merkle_version != MERKLE_V2 {
+            return Err(ClientError::MultiproofRequiresV2);
+        }
+        let mut claimed = indices.to_vec();
+        claimed.sort_unstable();
+        claimed.dedup();
+        if let Some(index) = claimed
+            .iter()
+            .find(|index| **index as usize >= self.layers[0].len())
+        {
+            return Err(ClientError::InvalidIndex(*index as usize));
+        }
+
+        let mut proof = Vec::new();
+        let mut known: Vec<usize> = claimed.iter().map(|index| *index as usize).collect();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            for index in &known {
+                let sibling = index ^ 1;
+                if !known.contains(&sibling) {
+                    proof.push(layer.get(sibling).copied().unwrap_or(EMPTY_NODE));
+                }
+            }
+            known = known.iter().map(|index| index / 2).collect();
+            known.dedup();
+        }
+
+        Ok(AnswerMultiproof {
+            claimed,
+            proof,
+            depth: (self.layers.len() - 1) as u8,
+        })
+    }
+
+    // A player's answer to question `index`, with the proof for that position
+    pub fn answer_input(
+        &self,
+        entries: &[AnswerKeyEntry],
+        index: usize,
+        answer: &str,
+        answered_at: i64,
+    ) -> Result<AnswerInput, ClientError> {
+        let entry = entries.get(index).ok_or(ClientError::InvalidIndex(index))?;
+        Ok(AnswerInput {
+            display_order: entry.display_order,
+            answer_type: entry.answer_type,
+            answer: answer.to_string(),
+            question_id: entry.question_id.clone(),
+            points: entry.points,
+            answered_at,
+            proof: self.proof(index)?,
+        })
+    }
+}
+
+fn next_layer(nodes: &[[u8; 32]], merkle_version: u8) -> Vec<[u8; 32]> {
+    nodes
+        .chunks(2)
+        .map(|pair| match (pair, merkle_version) {
+            ([left, right], MERKLE_V2) => hash_node(*left, *right),
+            ([left], MERKLE_V2) => hash_node(*left, EMPTY_NODE),
+            ([first, second], _) => hash_pair(*first, *second),
+            ([single], _) => *single,
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use twizzin_be_2::state::AnswerType;
+    use twizzin_be_2::utils::merkle::{
+        compute_versioned_root, verify_multiproof_v2, verify_versioned_proof, MERKLE_V1,
+    };
+
+    fn entries(count: u8) -> Vec<AnswerKeyEntry> {
+        (0..count)
+            .map(|i| AnswerKeyEntry {
+                display_order: i,
+                answer_type: AnswerType::Choice,
+                answer: "a".to_string(),
+                question_id: format!("question-{}", i),
+                points: 1 + i as u16,
+            })
+            .collect()
+    }
+
+    fn to_hex(bytes: [u8; 32]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_proofs_verify_on_chain() {
+        for version in [MERKLE_V1, MERKLE_V2] {
+            for count in 1..=12 {
+                let tree = MerkleTree::new(&entries(count), version).unwrap();
+                let leaves: Vec<_> = (0..count as usize).map(|i| tree.leaf(i).unwrap()).collect();
+                assert_eq!(compute_versioned_root(version, &leaves), Some(tree.root()));
+
+                for i in 0..count {
+                    let proof = tree.proof(i as usize).unwrap();
+                    assert!(verify_versioned_proof(
+                        version,
+                        tree.leaf(i as usize).unwrap(),
+                        &proof,
+                        i,
+                        tree.root()
+                    ));
+                }
+                assert!(tree.proof(count as usize).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiproofs_verify_on_chain() {
+        for count in 1..=12u8 {
+            let tree = MerkleTree::new(&entries(count), MERKLE_V2).unwrap();
+            let all: Vec<u8> = (0..count).collect();
+            let subsets = [
+                all.clone(),
+                all.iter().copied().filter(|i| i % 3 != 1).collect(),
+                vec![count - 1, 0, count - 1],
+            ];
+            for indices in &subsets {
+                let multiproof = tree.multiproof(indices).unwrap();
+                let leaves: Vec<_> = multiproof
+                    .claimed
+                    .iter()
+                    .map(|i| (*i as u32, tree.leaf(*i as usize).unwrap()))
+                    .collect();
+                assert!(verify_multiproof_v2(
+                    &leaves,
+                    &multiproof.proof,
+                    multiproof.depth,
+                    tree.root()
+                ));
+            }
+            assert!(tree.multiproof(&[count]).is_err());
+        }
+
+        let v1 = MerkleTree::new(&entries(3), MERKLE_V1).unwrap();
+        assert!(matches!(
+            v1.multiproof(&[0]),
+            Err(ClientError::MultiproofRequiresV2)
+        ));
+    }
+
+    // The vectors pinned by the program's test_cross_language_vectors
+    #[test]
+    fn test_matches_program_vectors() {
+        let entry =
+            |display_order, answer_type, answer: &str, question_id: &str, points| AnswerKeyEntry {
+                display_order,
+                answer_type,
+                answer: answer.to_string(),
+                question_id: question_id.to_string(),
+                points,
+            };
+        let entries = vec![
+            entry(0, AnswerType::Choice, "a", "q1", 1),
+            entry(1, AnswerType::MultiSelect, "c, a", "q2", 2),
+            entry(2, AnswerType::FreeText, " New  York", "q3", 3),
+            entry(3, AnswerType::Numeric { min: -5, max: 105 }, "", "q4", 5),
+            entry(4, AnswerType::Choice, "b", "q5", 8),
+        ];
+
+        let v1 = MerkleTree::new(&entries, MERKLE_V1).unwrap();
+        assert_eq!(
+            to_hex(v1.root()),
+            "3dbad17fa49bb519347f206314a503295b45ed267282f76abee9bf2402367601"
+        );
+        let v2 = MerkleTree::new(&entries, MERKLE_V2).unwrap();
+        assert_eq!(
+            to_hex(v2.root()),
+            "a9a4ad2809f047af2ec029096549f758c41d030cb55c4d95086044cf6c7c35f2"
+        );
+    }
+
+    #[test]
+    fn test_invalid_trees() {
+        assert!(matches!(
+            MerkleTree::new(&[], MERKLE_V1),
+            Err(ClientError::EmptyTree)
+        ));
+        assert!(matches!(
+            MerkleTree::new(&entries(2), 3),
+            Err(ClientError::InvalidMerkleVersion(3))
+        ));
+        // v0 leaves only hold choices worth a point, and the second question is worth two
+        assert!(MerkleTree::new(&entries(1), MERKLE_V0).is_ok());
+        assert!(matches!(
+            MerkleTree::new(&entries(2), MERKLE_V0),
+            Err(ClientError::UncoveredAnswer(1))
+        ));
+        let mut shuffled = entries(3);
+        shuffled.swap(0, 2);
+        assert!(matches!(
+            MerkleTree::new(&shuffled, MERKLE_V2),
+            Err(ClientError::AnswersOutOfOrder)
+        ));
+    }
+
+    #[test]
+    fn test_answer_input() {
+        let entries = entries(5);
+        let tree = MerkleTree::new(&entries, MERKLE_V2).unwrap();
+        let input = tree.answer_input(&entries, 3, "b", 1_500).unwrap();
+        assert_eq!(input.display_order, 3);
+        assert_eq!(input.question_id, "question-3");
+        assert_eq!(input.points, 4);
+        assert_eq!(input.answer, "b");
+        assert_eq!(input.proof, tree.proof(3).unwrap());
+        assert!(tree.answer_input(&entries, 5, "a", 0).is_err());
+    }
+}
+
+// cargo test -p twizzin-client merkle -- --nocapture
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::Pubkey;
+use twizzin_be_2::utils::normalize_game_code;
+use twizzin_be_2::ID;
+
+// Program derived addresses, with the same seeds as the program's account constraints
+
+pub fn find_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &ID)
+}
+
+pub fn find_game_pda(creator: &Pubkey, game_code: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"game", creator.as_ref(), game_code.as_bytes()], &ID)
+}
+
+pub fn find_vault_pda(creator: &Pubkey, game_code: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", creator.as_ref(), game_code.as_bytes()], &ID)
+}
+
+// Game codes are unique ignoring case and surrounding whitespace
+pub fn find_game_code_registry_pda(game_code: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"game_code", normalize_game_code(game_code).as_bytes()],
+        &ID,
+    )
+}
+
+pub fn find_player_pda(game: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"player", game.as_ref(), player.as_ref()], &ID)
+}
+
+pub fn find_submission_pda(game: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"submission", game.as_ref(), player.as_ref()], &ID)
+}
+
+pub fn find_winners_pda(game: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"winners", game.as_ref()], &ID)
+}
+
+pub fn find_answer_key_pda(game: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"answer_key", game.as_ref()], &ID)
+}
+
+pub fn find_game_operator_pda(game: &Pubkey, operator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"game_operator", game.as_ref(), operator.as_ref()], &ID)
+}
+
+pub fn find_profile_pda(player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"profile", player.as_ref()], &ID)
+}
+
+pub fn find_referral_balance_pda(referrer: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"referral", referrer.as_ref(), token_mint.as_ref()], &ID)
+}
+
+pub fn find_game_referral_pda(game: &Pubkey, referrer: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"game_referral", game.as_ref(), referrer.as_ref()], &ID)
+}
+
+pub fn find_season_pda(season_id: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"season", season_id.to_le_bytes().as_ref()], &ID)
+}
+
+pub fn find_season_vault_pda(season: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"season_vault", season.as_ref()], &ID)
+}
+
+pub fn find_season_player_pda(season: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"season_player", season.as_ref(), player.as_ref()], &ID)
+}
+
+pub fn find_tournament_pda(admin: &Pubkey, tournament_code: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"tournament", admin.as_ref(), tournament_code.as_bytes()],
+        &ID,
+    )
+}
+
+pub fn find_tournament_vault_pda(tournament: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"tournament_vault", tournament.as_ref()], &ID)
+}
+
+pub fn find_tournament_entry_pda(tournament: &Pubkey, player: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"tournament_entry", tournament.as_ref(), player.as_ref()],
+        &ID,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_pdas_depend_on_creator_and_code() {
+        let creator = Pubkey::new_unique();
+        let (game, _) = find_game_pda(&creator, "QUIZ1");
+        assert_ne!(game, find_game_pda(&creator, "QUIZ2").0);
+        assert_ne!(game, find_game_pda(&Pubkey::new_unique(), "QUIZ1").0);
+        assert_ne!(game, find_vault_pda(&creator, "QUIZ1").0);
+    }
+
+    #[test]
+    fn test_game_code_registry_is_normalized() {
+        assert_eq!(
+            find_game_code_registry_pda(" quiz1 "),
+            find_game_code_registry_pda("QUIZ1")
+        );
+    }
+
+    #[test]
+    fn test_player_pdas() {
+        let game = Pubkey::new_unique();
+        let player = Pubkey::new_unique();
+        assert_ne!(
+            find_player_pda(&game, &player).0,
+            find_submission_pda(&game, &player).0
+        );
+        assert_ne!(
+            find_player_pda(&game, &player).0,
+            find_player_pda(&game, &Pubkey::new_unique()).0
+        );
+    }
+}
+
+// cargo test -p twizzin-client pda -- --nocapture
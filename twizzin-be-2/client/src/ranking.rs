@@ -0,0 +1,109 @@
+use crate::error::ClientError;
+use anchor_lang::prelude::Pubkey;
+use std::cmp::{min, Reverse};
+use twizzin_be_2::state::{Game, PlayerAccount, MAX_WINNERS};
+
+// How many winners declare_winners expects for the game
+pub fn expected_winner_count(game: &Game) -> u8 {
+    let players = min(game.finished_players, MAX_WINNERS as u32) as u8;
+    if game.all_are_winners {
+        players
+    } else {
+        min(game.max_winners, players)
+    }
+}
+
+// Players who submitted, best first - highest score, then earliest finish.
+// Exact ties fall back to the player's pubkey so the order is stable.
+pub fn rank_players(players: &[PlayerAccount]) -> Vec<&PlayerAccount> {
+    let mut ranked: Vec<&PlayerAccount> = players
+        .iter()
+        .filter(|player| player.finished_time > 0)
+        .collect();
+    ranked.sort_by_key(|player| (Reverse(player.score), player.finished_time, player.player));
+    ranked
+}
+
+// The winners to pass to declare_winners, in rank order
+pub fn determine_winners(
+    game: &Game,
+    players: &[PlayerAccount],
+) -> Result<Vec<Pubkey>, ClientError> {
+    pick_winners(expected_winner_count(game) as usize, players)
+}
+
+fn pick_winners(expected: usize, players: &[PlayerAccount]) -> Result<Vec<Pubkey>, ClientError> {
+    let ranked = rank_players(players);
+    if ranked.len() < expected {
+        return Err(ClientError::NotEnoughFinishedPlayers {
+            finished: ranked.len(),
+            expected,
+        });
+    }
+
+    Ok(ranked[..expected]
+        .iter()
+        .map(|player| player.player)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(score: i32, finished_time: i64) -> PlayerAccount {
+        PlayerAccount {
+            game: Pubkey::default(),
+            player: Pubkey::new_unique(),
+            join_time: 0,
+            finished_time,
+            num_correct: 0,
+            answer_hash: [0; 32],
+            bump: 0,
+            referrer: None,
+            score,
+            answered: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_rank_players() {
+        let players = vec![
+            player(5, 3_000),
+            player(8, 4_000),
+            player(5, 2_000),
+            player(9, 0), // Never submitted
+            player(-2, 1_000),
+        ];
+        let ranked: Vec<(i32, i64)> = rank_players(&players)
+            .iter()
+            .map(|player| (player.score, player.finished_time))
+            .collect();
+        assert_eq!(ranked, [(8, 4_000), (5, 2_000), (5, 3_000), (-2, 1_000)]);
+    }
+
+    #[test]
+    fn test_pick_winners() {
+        let players = vec![player(5, 3_000), player(8, 4_000), player(5, 2_000)];
+        let winners = pick_winners(2, &players).unwrap();
+        assert_eq!(winners, [players[1].player, players[2].player]);
+
+        assert!(matches!(
+            pick_winners(4, &players),
+            Err(ClientError::NotEnoughFinishedPlayers {
+                finished: 3,
+                expected: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn test_exact_ties_go_to_the_lower_pubkey() {
+        let players = vec![player(5, 3_000), player(5, 3_000), player(1, 1_000)];
+        let lower = players[0].player.min(players[1].player);
+        let higher = players[0].player.max(players[1].player);
+        assert_eq!(pick_winners(2, &players).unwrap(), [lower, higher]);
+    }
+}
+
+// cargo test -p twizzin-client ranking -- --nocapture
@@ -0,0 +1,93 @@
+// twizzin-indexer - replays the program's events from transaction log dumps into SQLite
+use anyhow::{bail, Context, Result};
+use std::fs::File;
+use std::io::{stdin, BufRead, BufReader};
+use std::process::ExitCode;
+
+mod source;
+mod sqlite;
+mod store;
+
+use source::read_dump;
+use store::{Outcome, Store};
+
+const USAGE: &str = "\
+Usage: twizzin-indexer --db <PATH> [DUMP.jsonl]...
+
+Reads JSONL transaction dumps, oldest transaction first, and keeps the games,
+players, submissions, winners and claims tables in the database up to date.
+Reads stdin when no dump or `-` is given. Transactions already in the database
+are skipped, so dumps can be replayed or overlap.";
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {:#}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    let mut db_path = None;
+    let mut dumps = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--db" => db_path = Some(args.next().context("--db needs a path")?),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if arg.starts_with("--") => bail!("Unknown option {}\n\n{}", arg, USAGE),
+            _ => dumps.push(arg),
+        }
+    }
+    let db_path = db_path.with_context(|| format!("--db is required\n\n{}", USAGE))?;
+    if dumps.is_empty() {
+        dumps.push("-".to_string());
+    }
+
+    let store = Store::open(&db_path)?;
+    let mut totals = Totals::default();
+    for dump in &dumps {
+        let reader: Box<dyn BufRead> = match dump.as_str() {
+            "-" => Box::new(stdin().lock()),
+            path => Box::new(BufReader::new(
+                File::open(path).with_context(|| format!("Can't open {}", path))?,
+            )),
+        };
+        for transaction in read_dump(reader) {
+            let transaction = transaction.with_context(|| format!("Reading {}", dump))?;
+            totals.add(store.apply(&transaction)?);
+        }
+    }
+
+    println!(
+        "Indexed {} transactions with {} events, skipped {} already indexed and {} failed",
+        totals.indexed, totals.events, totals.already_indexed, totals.failed
+    );
+    Ok(())
+}
+
+#[derive(Default)]
+struct Totals {
+    indexed: usize,
+    events: usize,
+    already_indexed: usize,
+    failed: usize,
+}
+
+impl Totals {
+    fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Indexed { events } => {
+                self.indexed += 1;
+                self.events += events;
+            }
+            Outcome::Failed => self.failed += 1,
+            Outcome::AlreadyIndexed => self.already_indexed += 1,
+        }
+    }
+}
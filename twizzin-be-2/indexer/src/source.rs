@@ -0,0 +1,113 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::io::BufRead;
+
+// One transaction's log messages from a dump
+pub struct TransactionLogs {
+    pub signature: String,
+    pub slot: u64,
+    pub failed: bool, // Failed transactions log events that never happened
+    pub logs: Vec<String>,
+}
+
+// Reads a JSONL dump, one transaction per line, oldest first. Each line is either
+//   { "signature": "...", "slot": 1, "logs": ["Program ... invoke [1]", ...], "err": null }
+// or what an RPC's getTransaction returns (the bare result or the whole JSON-RPC
+// response), so dumps can be recorded straight from a node. Blank lines are skipped.
+pub fn read_dump(reader: impl BufRead) -> impl Iterator<Item = Result<TransactionLogs>> {
+    reader
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(i, line)| {
+            let line = line?;
+            parse_record(&line).with_context(|| format!("Invalid record on line {}", i + 1))
+        })
+}
+
+pub fn parse_record(line: &str) -> Result<TransactionLogs> {
+    let mut record: Value = serde_json::from_str(line)?;
+    if record.get("jsonrpc").is_some() {
+        record = record["result"].take();
+    }
+
+    let (signature, logs, err) = match record.get("meta") {
+        Some(meta) => (
+            &record["transaction"]["signatures"][0],
+            &meta["logMessages"],
+            &meta["err"],
+        ),
+        None => (&record["signature"], &record["logs"], &record["err"]),
+    };
+
+    Ok(TransactionLogs {
+        signature: signature
+            .as_str()
+            .context("Record has no signature")?
+            .to_string(),
+        slot: record["slot"].as_u64().context("Record has no slot")?,
+        failed: !err.is_null(),
+        logs: logs
+            .as_array()
+            .context("Record has no logs")?
+            .iter()
+            .map(|log| {
+                log.as_str()
+                    .map(String::from)
+                    .context("Logs must be strings")
+            })
+            .collect::<Result<_>>()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain_record() {
+        let record =
+            parse_record(r#"{ "signature": "sig1", "slot": 7, "logs": ["a", "b"] }"#).unwrap();
+        assert_eq!(record.signature, "sig1");
+        assert_eq!(record.slot, 7);
+        assert!(!record.failed);
+        assert_eq!(record.logs, vec!["a", "b"]);
+
+        let failed = parse_record(
+            r#"{ "signature": "sig2", "slot": 8, "logs": [], "err": { "Custom": 1 } }"#,
+        )
+        .unwrap();
+        assert!(failed.failed);
+    }
+
+    #[test]
+    fn test_parse_rpc_records() {
+        let result = r#"{ "slot": 9, "meta": { "err": null, "logMessages": ["a"] },
+            "transaction": { "signatures": ["sig3"], "message": {} } }"#
+            .replace('\n', "");
+        let record = parse_record(&result).unwrap();
+        assert_eq!(record.signature, "sig3");
+        assert_eq!(record.slot, 9);
+        assert!(!record.failed);
+        assert_eq!(record.logs, vec!["a"]);
+
+        let response = format!(r#"{{ "jsonrpc": "2.0", "id": 1, "result": {} }}"#, result);
+        assert_eq!(parse_record(&response).unwrap().signature, "sig3");
+    }
+
+    #[test]
+    fn test_read_dump() {
+        let dump = "{ \"signature\": \"a\", \"slot\": 1, \"logs\": [] }\n\n\
+                    { \"signature\": \"b\", \"slot\": 2, \"logs\": [] }\n";
+        let records: Vec<_> = read_dump(dump.as_bytes()).collect::<Result<_>>().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].signature, "b");
+
+        let error = read_dump("{}\n".as_bytes()).next().unwrap().err().unwrap();
+        assert!(error.to_string().contains("line 1"));
+        assert!(parse_record(r#"{ "signature": "a", "slot": 1 }"#).is_err());
+        assert!(parse_record(r#"{ "signature": "a", "slot": 1, "logs": [1] }"#).is_err());
+    }
+}
+
+// cargo test -p twizzin-indexer source -- --nocapture
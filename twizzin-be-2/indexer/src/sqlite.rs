@@ -0,0 +1,93 @@
+// The calls the indexer makes, over rusqlite: statements take their parameters as a
+// list of values and queries hand back whole rows.
+use anyhow::Result;
+use rusqlite::params_from_iter;
+
+pub use rusqlite::types::Value;
+
+pub struct Connection {
+    db: rusqlite::Connection,
+}
+
+impl Connection {
+    // Creates the database file if it doesn't exist. ":memory:" opens a private in-memory one.
+    pub fn open(path: &str) -> Result<Self> {
+        let db = rusqlite::Connection::open(path)?;
+        Ok(Self { db })
+    }
+
+    // Runs one or more statements that take no parameters
+    pub fn execute_batch(&self, sql: &str) -> Result<()> {
+        Ok(self.db.execute_batch(sql)?)
+    }
+
+    // Runs a single statement, returning how many rows it changed
+    pub fn execute(&self, sql: &str, params: &[Value]) -> Result<usize> {
+        Ok(self.db.execute(sql, params_from_iter(params))?)
+    }
+
+    pub fn query(&self, sql: &str, params: &[Value]) -> Result<Vec<Vec<Value>>> {
+        let mut statement = self.db.prepare(sql)?;
+        let columns = statement.column_count();
+        let rows = statement.query_map(params_from_iter(params), |row| {
+            (0..columns).map(|column| row.get(column)).collect()
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    // Runs f inside a transaction, rolling back everything it did if it fails
+    pub fn transaction<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        // The transaction rolls back if it's dropped before the commit
+        let transaction = self.db.unchecked_transaction()?;
+        let value = f(self)?;
+        transaction.commit()?;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let db = Connection::open(":memory:").unwrap();
+        db.execute_batch("CREATE TABLE t (id INTEGER PRIMARY KEY, name TEXT, note TEXT)")
+            .unwrap();
+        let changed = db
+            .execute(
+                "INSERT INTO t (id, name, note) VALUES (?1, ?2, ?3)",
+                &[(-5i64).into(), Value::Text("héllo".into()), Value::Null],
+            )
+            .unwrap();
+        assert_eq!(changed, 1);
+
+        let rows = db.query("SELECT id, name, note FROM t", &[]).unwrap();
+        assert_eq!(
+            rows,
+            vec![vec![
+                Value::Integer(-5),
+                Value::Text("héllo".into()),
+                Value::Null
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_errors_and_rollback() {
+        let db = Connection::open(":memory:").unwrap();
+        db.execute_batch("CREATE TABLE t (id INTEGER PRIMARY KEY)")
+            .unwrap();
+        assert!(db.execute("INSERT INTO missing VALUES (1)", &[]).is_err());
+
+        let result: Result<()> = db.transaction(|db| {
+            db.execute("INSERT INTO t (id) VALUES (1)", &[])?;
+            db.execute("INSERT INTO t (id) VALUES (1)", &[])?;
+            Ok(())
+        });
+        assert!(result.is_err());
+        assert!(db.query("SELECT id FROM t", &[]).unwrap().is_empty());
+    }
+}
+
+// cargo test -p twizzin-indexer sqlite -- --nocapture
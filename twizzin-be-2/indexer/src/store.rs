@@ -0,0 +1,488 @@
+use crate::source::TransactionLogs;
+use crate::sqlite::{Connection, Value};
+use anyhow::{Context, Result};
+use twizzin_client::{parse_events, ProgramEvent};
+
+// Games, players, submissions, winners and claims as the program's events describe
+// them. Amounts are in the game mint's base units and times in milliseconds.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    failed INTEGER NOT NULL,
+    events INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS games (
+    game TEXT PRIMARY KEY,
+    admin TEXT NOT NULL,
+    name TEXT NOT NULL,
+    game_code TEXT NOT NULL,
+    entry_fee INTEGER NOT NULL,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    total_pot INTEGER,
+    treasury_fee INTEGER,
+    admin_commission INTEGER,
+    referral_rewards INTEGER,
+    updated_slot INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS players (
+    game TEXT NOT NULL,
+    player TEXT NOT NULL,
+    join_time INTEGER NOT NULL,
+    referrer TEXT,
+    closed INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (game, player)
+);
+CREATE TABLE IF NOT EXISTS submissions (
+    game TEXT NOT NULL,
+    player TEXT NOT NULL,
+    num_correct INTEGER NOT NULL,
+    score INTEGER NOT NULL,
+    finished_time INTEGER,
+    PRIMARY KEY (game, player)
+);
+CREATE TABLE IF NOT EXISTS winners (
+    game TEXT PRIMARY KEY,
+    num_winners INTEGER NOT NULL,
+    total_prize_pool INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS claims (
+    game TEXT NOT NULL,
+    player TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    prize_amount INTEGER NOT NULL,
+    signature TEXT NOT NULL,
+    PRIMARY KEY (game, player)
+);
+";
+
+// What happened to a transaction from the dump
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Indexed { events: usize },
+    Failed,         // Recorded so it's skipped next time, but its events are ignored
+    AlreadyIndexed, // Replays of a dump change nothing
+}
+
+pub struct Store {
+    db: Connection,
+}
+
+impl Store {
+    pub fn open(path: &str) -> Result<Self> {
+        let db = Connection::open(path)?;
+        db.execute_batch(SCHEMA)?;
+        Ok(Self { db })
+    }
+
+    // Applies every event in the transaction, or none of them if anything fails.
+    // Transactions have to be applied in the order they landed on chain.
+    pub fn apply(&self, transaction: &TransactionLogs) -> Result<Outcome> {
+        let signature = transaction.signature.as_str();
+        let slot = int(transaction.slot)?;
+
+        self.db.transaction(|db| {
+            let seen = db.query(
+                "SELECT 1 FROM transactions WHERE signature = ?1",
+                &[signature.to_string().into()],
+            )?;
+            if !seen.is_empty() {
+                return Ok(Outcome::AlreadyIndexed);
+            }
+
+            let events = if transaction.failed {
+                Vec::new()
+            } else {
+                parse_events(&transaction.logs)
+                    .with_context(|| format!("Bad event in {}", signature))?
+            };
+            for event in &events {
+                apply_event(db, event, signature, slot)?;
+            }
+
+            db.execute(
+                "INSERT INTO transactions (signature, slot, failed, events) VALUES (?1, ?2, ?3, ?4)",
+                &[
+                    signature.to_string().into(),
+                    slot.into(),
+                    (transaction.failed as i64).into(),
+                    (events.len() as i64).into(),
+                ],
+            )?;
+            Ok(match transaction.failed {
+                true => Outcome::Failed,
+                false => Outcome::Indexed {
+                    events: events.len(),
+                },
+            })
+        })
+    }
+}
+
+// Events that don't touch the tables - fees, referrals, profiles, seasons,
+// tournaments and operators - are left to other consumers
+fn apply_event(db: &Connection, event: &ProgramEvent, signature: &str, slot: i64) -> Result<()> {
+    match event {
+        ProgramEvent::GameCreated(created) => {
+            // A code can be reused once its game is closed, so this starts the row afresh
+            db.execute(
+                "INSERT INTO games (game, admin, name, game_code, entry_fee, start_time,
+                    end_time, status, updated_slot)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'created', ?8)
+                 ON CONFLICT (game) DO UPDATE SET admin = ?2, name = ?3, game_code = ?4,
+                    entry_fee = ?5, start_time = ?6, end_time = ?7, status = 'created',
+                    total_pot = NULL, treasury_fee = NULL, admin_commission = NULL,
+                    referral_rewards = NULL, updated_slot = ?8",
+                &[
+                    created.game.to_string().into(),
+                    created.admin.to_string().into(),
+                    created.name.clone().into(),
+                    created.game_code.clone().into(),
+                    int(created.entry_fee)?.into(),
+                    created.start_time.into(),
+                    created.end_time.into(),
+                    slot.into(),
+                ],
+            )?;
+        }
+        ProgramEvent::GameUpdated(updated) => {
+            db.execute(
+                "UPDATE games SET name = ?2, entry_fee = ?3, start_time = ?4, end_time = ?5,
+                    updated_slot = ?6
+                 WHERE game = ?1",
+                &[
+                    updated.game.to_string().into(),
+                    updated.name.clone().into(),
+                    int(updated.entry_fee)?.into(),
+                    updated.start_time.into(),
+                    updated.end_time.into(),
+                    slot.into(),
+                ],
+            )?;
+        }
+        ProgramEvent::GameStarted(started) => {
+            db.execute(
+                "UPDATE games SET start_time = ?2, end_time = ?3, status = 'started',
+                    updated_slot = ?4
+                 WHERE game = ?1",
+                &[
+                    started.game.to_string().into(),
+                    started.start_time.into(),
+                    started.end_time.into(),
+                    slot.into(),
+                ],
+            )?;
+        }
+        ProgramEvent::GameAdminTransferred(transferred) => {
+            db.execute(
+                "UPDATE games SET admin = ?2, updated_slot = ?3 WHERE game = ?1",
+                &[
+                    transferred.game.to_string().into(),
+                    transferred.new_admin.to_string().into(),
+                    slot.into(),
+                ],
+            )?;
+        }
+        ProgramEvent::GameEnded(ended) => {
+            db.execute(
+                "UPDATE games SET end_time = ?2, status = 'ended', total_pot = ?3,
+                    treasury_fee = ?4, admin_commission = ?5, referral_rewards = ?6,
+                    updated_slot = ?7
+                 WHERE game = ?1",
+                &[
+                    ended.game.to_string().into(),
+                    ended.end_time.into(),
+                    int(ended.total_pot)?.into(),
+                    int(ended.treasury_fee)?.into(),
+                    int(ended.admin_commission)?.into(),
+                    int(ended.referral_rewards)?.into(),
+                    slot.into(),
+                ],
+            )?;
+        }
+        ProgramEvent::GameClosed(closed) => {
+            db.execute(
+                "UPDATE games SET status = 'closed', updated_slot = ?2 WHERE game = ?1",
+                &[closed.game.to_string().into(), slot.into()],
+            )?;
+        }
+        ProgramEvent::PlayerJoined(joined) => {
+            db.execute(
+                "INSERT INTO players (game, player, join_time, referrer)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (game, player) DO UPDATE SET join_time = ?3, referrer = ?4,
+                    closed = 0",
+                &[
+                    joined.game.to_string().into(),
+                    joined.player.to_string().into(),
+                    joined.join_time.into(),
+                    joined.referrer.map(|referrer| referrer.to_string()).into(),
+                ],
+            )?;
+        }
+        ProgramEvent::PlayerAccountClosed(closed) => {
+            db.execute(
+                "UPDATE players SET closed = 1 WHERE game = ?1 AND player = ?2",
+                &[
+                    closed.game.to_string().into(),
+                    closed.player.to_string().into(),
+                ],
+            )?;
+        }
+        // Chunks carry the running totals - the submission isn't finished until finalized
+        ProgramEvent::AnswerChunkSubmitted(chunk) => {
+            upsert_submission(
+                db,
+                &[
+                    chunk.game.to_string().into(),
+                    chunk.player.to_string().into(),
+                    (chunk.num_correct as i64).into(),
+                    (chunk.score as i64).into(),
+                    Value::Null,
+                ],
+            )?;
+        }
+        ProgramEvent::AnswersSubmitted(submitted) => {
+            upsert_submission(
+                db,
+                &[
+                    submitted.game.to_string().into(),
+                    submitted.player.to_string().into(),
+                    (submitted.num_correct as i64).into(),
+                    (submitted.score as i64).into(),
+                    submitted.finished_time.into(),
+                ],
+            )?;
+        }
+        ProgramEvent::PlayerRescored(rescored) => {
+            db.execute(
+                "UPDATE submissions SET num_correct = ?3, score = ?4
+                 WHERE game = ?1 AND player = ?2",
+                &[
+                    rescored.game.to_string().into(),
+                    rescored.player.to_string().into(),
+                    (rescored.num_correct as i64).into(),
+                    (rescored.score as i64).into(),
+                ],
+            )?;
+        }
+        ProgramEvent::WinnersDeclared(declared) => {
+            db.execute(
+                "INSERT INTO winners (game, num_winners, total_prize_pool) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (game) DO UPDATE SET num_winners = ?2, total_prize_pool = ?3",
+                &[
+                    declared.game.to_string().into(),
+                    (declared.num_winners as i64).into(),
+                    int(declared.total_prize_pool)?.into(),
+                ],
+            )?;
+        }
+        ProgramEvent::Claim(claim) => {
+            db.execute(
+                "INSERT INTO claims (game, player, rank, prize_amount, signature)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (game, player) DO UPDATE SET rank = ?3, prize_amount = ?4,
+                    signature = ?5",
+                &[
+                    claim.game.to_string().into(),
+                    claim.player.to_string().into(),
+                    (claim.rank as i64).into(),
+                    int(claim.prize_amount)?.into(),
+                    signature.to_string().into(),
+                ],
+            )?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn upsert_submission(db: &Connection, params: &[Value]) -> Result<()> {
+    db.execute(
+        "INSERT INTO submissions (game, player, num_correct, score, finished_time)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (game, player) DO UPDATE SET num_correct = ?3, score = ?4,
+            finished_time = ?5",
+        params,
+    )?;
+    Ok(())
+}
+
+// SQLite integers are signed
+fn int(value: u64) -> Result<i64> {
+    i64::try_from(value).with_context(|| format!("{} doesn't fit in an SQLite integer", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::read_dump;
+
+    // A game's life as the validator logs it: create, two joins (one referred), start,
+    // a one shot submission, a chunked one with a failed finalize before the real one,
+    // end, winners, a claim, a rescore and closing. Joins and payouts carry system
+    // program invocations, and three records are raw getTransaction responses.
+    const FRIDAY_QUIZ: &str = include_str!("../fixtures/friday_quiz.jsonl");
+
+    const ADMIN: &str = "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi";
+    const GAME: &str = "8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR";
+    const ALICE: &str = "CktRuQ2mttgRGkXJtyksdKHjUdc2C4TgDzyB98oEzy8";
+    const BOB: &str = "GgBaCs3NCBuZN12kCJgAW63ydqohFkHEdfdEXBPzLHq";
+
+    fn replay(store: &Store, dump: &str) -> Vec<Outcome> {
+        read_dump(dump.as_bytes())
+            .map(|transaction| store.apply(&transaction.unwrap()).unwrap())
+            .collect()
+    }
+
+    fn rows(store: &Store, sql: &str) -> Vec<Vec<Value>> {
+        store.db.query(sql, &[]).unwrap()
+    }
+
+    fn text(value: &str) -> Value {
+        value.to_string().into()
+    }
+
+    #[test]
+    fn test_index_game() {
+        let store = Store::open(":memory:").unwrap();
+        let outcomes = replay(&store, FRIDAY_QUIZ);
+        assert_eq!(outcomes.len(), 14);
+        assert_eq!(outcomes[6], Outcome::Failed);
+        assert_eq!(outcomes[4], Outcome::Indexed { events: 2 });
+
+        assert_eq!(
+            rows(
+                &store,
+                "SELECT game, admin, name, game_code, entry_fee, status, total_pot, treasury_fee,
+                    start_time, end_time FROM games"
+            ),
+            vec![vec![
+                text(GAME),
+                text(ADMIN),
+                text("Friday Quiz"),
+                text("FRI1"),
+                Value::Integer(10_000_000),
+                text("closed"),
+                Value::Integer(20_000_000),
+                Value::Integer(2_000_000),
+                Value::Integer(1_735_689_700_000),
+                Value::Integer(1_735_693_300_000),
+            ]]
+        );
+
+        assert_eq!(
+            rows(
+                &store,
+                "SELECT player, referrer, closed FROM players ORDER BY join_time"
+            ),
+            vec![
+                vec![text(ALICE), Value::Null, Value::Integer(0)],
+                vec![text(BOB), text(ALICE), Value::Integer(1)],
+            ]
+        );
+
+        // The failed finalize is ignored and the rescore lands on Bob's submission
+        assert_eq!(
+            rows(
+                &store,
+                "SELECT player, num_correct, score, finished_time FROM submissions
+                 ORDER BY finished_time"
+            ),
+            vec![
+                vec![
+                    text(ALICE),
+                    Value::Integer(3),
+                    Value::Integer(4),
+                    Value::Integer(1_735_689_760_000)
+                ],
+                vec![
+                    text(BOB),
+                    Value::Integer(1),
+                    Value::Integer(1),
+                    Value::Integer(1_735_689_800_000)
+                ],
+            ]
+        );
+
+        assert_eq!(
+            rows(
+                &store,
+                "SELECT game, num_winners, total_prize_pool FROM winners"
+            ),
+            vec![vec![
+                text(GAME),
+                Value::Integer(1),
+                Value::Integer(18_000_000)
+            ]]
+        );
+        assert_eq!(
+            rows(&store, "SELECT player, rank, prize_amount FROM claims"),
+            vec![vec![
+                text(ALICE),
+                Value::Integer(1),
+                Value::Integer(18_000_000)
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_replay_is_idempotent() {
+        let store = Store::open(":memory:").unwrap();
+        replay(&store, FRIDAY_QUIZ);
+        let tables = [
+            "SELECT * FROM games",
+            "SELECT * FROM players ORDER BY player",
+            "SELECT * FROM submissions ORDER BY player",
+            "SELECT * FROM winners",
+            "SELECT * FROM claims",
+            "SELECT * FROM transactions ORDER BY slot",
+        ];
+        let before: Vec<_> = tables.iter().map(|sql| rows(&store, sql)).collect();
+
+        let outcomes = replay(&store, FRIDAY_QUIZ);
+        assert!(outcomes
+            .iter()
+            .all(|outcome| *outcome == Outcome::AlreadyIndexed));
+        let after: Vec<_> = tables.iter().map(|sql| rows(&store, sql)).collect();
+        assert_eq!(before, after);
+
+        // Overlapping dumps only apply what's new
+        let store = Store::open(":memory:").unwrap();
+        let lines: Vec<&str> = FRIDAY_QUIZ.lines().collect();
+        replay(&store, &lines[..8].join("\n"));
+        let outcomes = replay(&store, FRIDAY_QUIZ);
+        assert_eq!(
+            outcomes
+                .iter()
+                .filter(|outcome| **outcome != Outcome::AlreadyIndexed)
+                .count(),
+            6
+        );
+        let overlapped: Vec<_> = tables.iter().map(|sql| rows(&store, sql)).collect();
+        assert_eq!(before, overlapped);
+    }
+
+    #[test]
+    fn test_bad_transaction_changes_nothing() {
+        let store = Store::open(":memory:").unwrap();
+        let created = FRIDAY_QUIZ.lines().next().unwrap();
+        // Truncate the event data so it no longer decodes
+        let broken = created.replace("gGpgH5QBAAA=", "gGpg");
+        let transaction = read_dump(broken.as_bytes()).next().unwrap().unwrap();
+        assert!(store.apply(&transaction).is_err());
+        assert!(rows(&store, "SELECT * FROM games").is_empty());
+        assert!(rows(&store, "SELECT * FROM transactions").is_empty());
+
+        // The intact transaction still indexes afterwards
+        let transaction = read_dump(created.as_bytes()).next().unwrap().unwrap();
+        assert_eq!(
+            store.apply(&transaction).unwrap(),
+            Outcome::Indexed { events: 1 }
+        );
+    }
+}
+
+// cargo test -p twizzin-indexer store -- --nocapture
@@ -2,6 +2,9 @@ use anchor_lang::prelude::*;
 
 pub const SOL_ADDRESS: &str = "So11111111111111111111111111111111111111112";
 
+// Metaplex token metadata program - owns the metadata accounts used for NFT gates
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
 // BKV7zy1Q74pyk3eehMrVQeau9pj2kEp6k36RZwFTFdHk (devnet)
 pub const DEVNET_WALLET_BYTES: [u8; 32] = [
     153, 81, 30, 183, 10, 130, 241, 205, 12, 135, 205, 77, 182, 252, 154, 212, 199, 199, 39, 136,
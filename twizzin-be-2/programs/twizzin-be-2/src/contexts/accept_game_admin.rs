@@ -0,0 +1,34 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, GameAdminTransferred};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptGameAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+        constraint = game.pending_admin == Some(new_admin.key()) @ ErrorCode::NotPendingAdmin
+    )]
+    pub game: Account<'info, Game>,
+}
+
+impl<'info> AcceptGameAdmin<'info> {
+    pub fn accept_game_admin(&mut self) -> Result<()> {
+        let previous_admin = self.game.admin;
+
+        // The PDAs stay seeded with the creator, so only the admin field moves
+        self.game.admin = self.new_admin.key();
+        self.game.pending_admin = None;
+
+        emit!(GameAdminTransferred {
+            game: self.game.key(),
+            previous_admin,
+            new_admin: self.new_admin.key(),
+        });
+
+        Ok(())
+    }
+}
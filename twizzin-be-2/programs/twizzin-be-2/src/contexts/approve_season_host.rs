@@ -0,0 +1,41 @@
+use crate::constants::PROGRAM_AUTHORITY;
+use crate::errors::ErrorCode;
+use crate::state::{Season, SeasonHostApproved, MAX_SEASON_HOSTS};
+use anchor_lang::prelude::*;
+
+// Lets a host add their games to the season
+#[derive(Accounts)]
+pub struct ApproveSeasonHost<'info> {
+    #[account(
+        constraint = authority.key() == PROGRAM_AUTHORITY @ ErrorCode::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"season", season.season_id.to_le_bytes().as_ref()],
+        bump = season.bump,
+        constraint = !season.settled @ ErrorCode::SeasonSettled
+    )]
+    pub season: Account<'info, Season>,
+}
+
+impl<'info> ApproveSeasonHost<'info> {
+    pub fn approve_season_host(&mut self, host: Pubkey) -> Result<()> {
+        if self.season.approved_hosts.contains(&host) {
+            return Ok(());
+        }
+        require!(
+            self.season.approved_hosts.len() < MAX_SEASON_HOSTS,
+            ErrorCode::TooManySeasonHosts
+        );
+        self.season.approved_hosts.push(host);
+
+        emit!(SeasonHostApproved {
+            season: self.season.key(),
+            host,
+        });
+
+        Ok(())
+    }
+}
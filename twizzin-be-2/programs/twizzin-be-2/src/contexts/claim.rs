@@ -2,7 +2,10 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{Token, TokenAccount};
 
 use crate::errors::ErrorCode;
-use crate::state::{Game, PlayerAccount, Winners};
+use crate::state::{Game, PlayerAccount, Submission, Winners};
+use crate::utils::profile::{
+   emit_profile_updated, load_profile, record_prize_claimed, save_profile,
+};
 
 #[event]
 pub struct ClaimEvent {
@@ -19,7 +22,7 @@ pub struct Claim<'info> {
 
    #[account(
        mut,
-       seeds = [b"game", game.admin.as_ref(), game.game_code.as_bytes()],
+       seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
        bump = game.bump
    )]
    pub game: Account<'info, Game>,
@@ -44,10 +47,19 @@ pub struct Claim<'info> {
    )]
    pub player_account: Account<'info, PlayerAccount>,
 
+   // Closed along with the player account, if the player submitted answers
+   #[account(
+       mut,
+       seeds = [b"submission", game.key().as_ref(), player.key().as_ref()],
+       bump = submission.bump,
+       close = player
+   )]
+   pub submission: Option<Account<'info, Submission>>,
+
    /// CHECK: Vault PDA that holds the funds
    #[account(
        mut,
-       seeds = [b"vault", game.admin.as_ref(), game.game_code.as_bytes()],
+       seeds = [b"vault", game.creator.as_ref(), game.game_code.as_bytes()],
        bump = game.vault_bump,
    )]
    pub vault: UncheckedAccount<'info>,
@@ -67,6 +79,15 @@ pub struct Claim<'info> {
    )]
    pub player_token_account: Option<Account<'info, TokenAccount>>,
 
+   /// CHECK: The player's profile PDA - lifetime stats are only tracked for players
+   /// who made one, but those who did always have it updated
+   #[account(
+       mut,
+       seeds = [b"profile", player.key().as_ref()],
+       bump,
+   )]
+   pub profile: UncheckedAccount<'info>,
+
    pub token_program: Program<'info, Token>,
    pub system_program: Program<'info, System>,
 }
@@ -77,6 +98,8 @@ impl<'info> Claim<'info> {
        let current_time = Clock::get()?.unix_timestamp * 1000;
        require!(current_time >= self.game.end_time, ErrorCode::GameNotEnded);
 
+       require!(self.winners.is_complete(), ErrorCode::WinnersNotDeclared);
+
        // Find winner info and verify not claimed
        let winner_info = self.winners.winners
            .iter_mut()
@@ -88,12 +111,15 @@ impl<'info> Claim<'info> {
        let prize_amount = winner_info.prize_amount;
        let rank = winner_info.rank;
 
+       // Mark as claimed, even with nothing to pay, so the game can close
+       winner_info.claimed = true;
+
        // Transfer prize
        if prize_amount > 0 {
            let vault_bump = self.game.vault_bump;
            let seeds = &[
                b"vault",
-               self.game.admin.as_ref(), 
+               self.game.creator.as_ref(), 
                self.game.game_code.as_bytes(),
                &[vault_bump],
            ];
@@ -124,9 +150,18 @@ impl<'info> Claim<'info> {
                );
                anchor_spl::token::transfer(transfer_ctx, prize_amount)?;
            }
+       }
 
-           // Mark as claimed
-           winner_info.claimed = true;
+       if let Some(mut profile) = load_profile(&self.profile)? {
+           record_prize_claimed(
+               &mut profile,
+               self.game.key(),
+               rank,
+               self.game.token_mint,
+               prize_amount,
+           )?;
+           emit_profile_updated(&profile);
+           save_profile(&self.profile, &profile)?;
        }
 
        emit!(ClaimEvent {
@@ -138,4 +173,4 @@ impl<'info> Claim<'info> {
 
        Ok(())
    }
-}
\ No newline at end of file
+}
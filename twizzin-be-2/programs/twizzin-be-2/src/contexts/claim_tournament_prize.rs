@@ -0,0 +1,139 @@
+use crate::errors::ErrorCode;
+use crate::state::{Tournament, TournamentEntry, TournamentPrizeClaimed, Winners};
+use crate::utils::prize::calculate_prizes;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct ClaimTournamentPrize<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.admin.as_ref(), tournament.tournament_code.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.settled @ ErrorCode::TournamentNotSettled
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament_entry", tournament.key().as_ref(), player.key().as_ref()],
+        bump = tournament_entry.bump,
+        constraint = !tournament_entry.claimed @ ErrorCode::PrizeAlreadyClaimed
+    )]
+    pub tournament_entry: Account<'info, TournamentEntry>,
+
+    #[account(
+        seeds = [b"winners", final_winners.game.as_ref()],
+        bump = final_winners.bump,
+        constraint = tournament.rounds.last() == Some(&final_winners.game) @ ErrorCode::InvalidTournament
+    )]
+    pub final_winners: Account<'info, Winners>,
+
+    /// CHECK: The tournament vault PDA that holds the prize pool
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump,
+    )]
+    pub tournament_vault: UncheckedAccount<'info>,
+
+    // Only needed for SPL token tournaments
+    #[account(
+        mut,
+        associated_token::mint = tournament.token_mint,
+        associated_token::authority = tournament_vault
+    )]
+    pub tournament_vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.owner == player.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = player_token_account.mint == tournament.token_mint @ ErrorCode::InvalidTokenAccount
+    )]
+    pub player_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimTournamentPrize<'info> {
+    pub fn claim_tournament_prize(&mut self) -> Result<()> {
+        let rank = self
+            .final_winners
+            .winners
+            .iter()
+            .find(|w| w.player == self.player.key())
+            .ok_or(ErrorCode::NotAWinner)?
+            .rank;
+
+        // The final round's placings split the prize pool locked in at settlement
+        let prizes = calculate_prizes(
+            self.tournament.prize_pool,
+            self.final_winners.num_winners,
+            self.tournament.even_split,
+            self.tournament.is_native,
+            0, // Rent exemption was held back in settle_tournament
+        )?;
+        let prize_amount = prizes[rank as usize - 1];
+
+        if prize_amount > 0 {
+            let tournament_key = self.tournament.key();
+            let seeds = &[
+                b"tournament_vault",
+                tournament_key.as_ref(),
+                &[self.tournament.vault_bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            if self.tournament.is_native {
+                let transfer_ctx = CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: self.tournament_vault.to_account_info(),
+                        to: self.player.to_account_info(),
+                    },
+                    signer,
+                );
+                anchor_lang::system_program::transfer(transfer_ctx, prize_amount)?;
+            } else {
+                let transfer_ctx = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: self
+                            .tournament_vault_token_account
+                            .as_ref()
+                            .ok_or(ErrorCode::VaultTokenAccountNotProvided)?
+                            .to_account_info(),
+                        to: self
+                            .player_token_account
+                            .as_ref()
+                            .ok_or(ErrorCode::PlayerTokenAccountNotProvided)?
+                            .to_account_info(),
+                        authority: self.tournament_vault.to_account_info(),
+                    },
+                    signer,
+                );
+                anchor_spl::token::transfer(transfer_ctx, prize_amount)?;
+            }
+        }
+
+        self.tournament_entry.claimed = true;
+        self.tournament.unclaimed_prizes = self
+            .tournament
+            .unclaimed_prizes
+            .checked_sub(1)
+            .ok_or(ErrorCode::NumericOverflow)?;
+
+        emit!(TournamentPrizeClaimed {
+            tournament: self.tournament.key(),
+            player: self.player.key(),
+            rank,
+            prize_amount,
+        });
+
+        Ok(())
+    }
+}
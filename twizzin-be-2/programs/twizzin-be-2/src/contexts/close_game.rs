@@ -5,7 +5,8 @@ use anchor_spl::{
 };
 
 use crate::errors::ErrorCode;
-use crate::state::{Game, GameClosed, Winners};
+use crate::state::{Game, GameClosed, GameCodeRegistry, Winners};
+use crate::utils::game_code::normalize_game_code;
 
 #[derive(Accounts)]
 pub struct CloseGame<'info> {
@@ -14,10 +15,11 @@ pub struct CloseGame<'info> {
 
     #[account(
         mut,
-        seeds = [b"game", admin.key().as_ref(), game.game_code.as_bytes()],
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.bump,
         constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin,
         constraint = Clock::get()?.unix_timestamp * 1000 >= game.end_time @ ErrorCode::GameNotEnded,
+        constraint = game.referral_escrow == 0 @ ErrorCode::ReferralsNotSettled,
         close = admin
     )]
     pub game: Account<'info, Game>,
@@ -26,15 +28,27 @@ pub struct CloseGame<'info> {
         mut,
         seeds = [b"winners", game.key().as_ref()],
         bump = winners.bump,
+        constraint = winners.is_complete() @ ErrorCode::WinnersNotDeclared,
         constraint = verify_all_claimed(&winners) @ ErrorCode::UnclaimedPrizes,
         close = admin
     )]
     pub winners: Account<'info, Winners>,
 
+    // Frees the code for other games - only games from before code registries
+    // have none to pass
+    #[account(
+        mut,
+        seeds = [b"game_code", normalize_game_code(&game.game_code).as_bytes()],
+        bump = game_code_registry.bump,
+        constraint = game_code_registry.game == game.key() @ ErrorCode::InvalidGameCodeRegistry,
+        close = admin
+    )]
+    pub game_code_registry: Option<Account<'info, GameCodeRegistry>>,
+
     /// CHECK: Vault PDA that holds the funds
     #[account(
         mut,
-        seeds = [b"vault", admin.key().as_ref(), game.game_code.as_bytes()],
+        seeds = [b"vault", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.vault_bump,
     )]
     pub vault: UncheckedAccount<'info>,
@@ -47,6 +61,15 @@ pub struct CloseGame<'info> {
     )]
     pub vault_token_account: Option<Account<'info, TokenAccount>>,
 
+    // Admin's token account for receiving any tokens left in the vault
+    #[account(
+        mut,
+        constraint = !game.is_native @ ErrorCode::InvalidTokenAccount,
+        constraint = admin_token_account.owner == game.admin @ ErrorCode::InvalidTokenAccount,
+        constraint = admin_token_account.mint == game.token_mint @ ErrorCode::InvalidTokenAccount
+    )]
+    pub admin_token_account: Option<Account<'info, TokenAccount>>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -54,17 +77,22 @@ pub struct CloseGame<'info> {
 
 impl<'info> CloseGame<'info> {
     pub fn close_game(&mut self) -> Result<()> {
+        require!(
+            !self.game.code_registered || self.game_code_registry.is_some(),
+            ErrorCode::GameCodeRegistryNotProvided
+        );
+
         let vault_balance = self.vault.lamports();
 
         // For native SOL, transfer any remaining balance to admin using PDA signing
         if vault_balance > 0 {
-            let admin_key = self.admin.key();
+            let creator_key = self.game.creator;
             let game_code = self.game.game_code.as_bytes();
             let vault_bump = self.game.vault_bump;
 
             let vault_seeds = &[
                 b"vault" as &[u8],
-                admin_key.as_ref(),
+                creator_key.as_ref(),
                 game_code,
                 &[vault_bump],
             ];
@@ -91,13 +119,13 @@ impl<'info> CloseGame<'info> {
                 .as_ref()
                 .ok_or(ErrorCode::VaultTokenAccountNotProvided)?;
 
-            let admin_key = self.admin.key();
+            let creator_key = self.game.creator;
             let game_code = self.game.game_code.as_bytes();
             let vault_bump = self.game.vault_bump;
 
             let vault_seeds = &[
                 b"vault" as &[u8],
-                admin_key.as_ref(),
+                creator_key.as_ref(),
                 game_code,
                 &[vault_bump],
             ];
@@ -105,11 +133,15 @@ impl<'info> CloseGame<'info> {
 
             // First transfer any remaining tokens to admin
             if vault_token_account.amount > 0 {
+                let admin_token_account = self
+                    .admin_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::AdminTokenAccountNotProvided)?;
                 let transfer_ctx = CpiContext::new_with_signer(
                     self.token_program.to_account_info(),
                     anchor_spl::token::Transfer {
                         from: vault_token_account.to_account_info(),
-                        to: self.admin.to_account_info(),
+                        to: admin_token_account.to_account_info(),
                         authority: self.vault.to_account_info(),
                     },
                     signer,
@@ -1,5 +1,5 @@
 use crate::errors::ErrorCode;
-use crate::state::{Game, PlayerAccount, PlayerAccountClosed, Winners};
+use crate::state::{Game, PlayerAccount, PlayerAccountClosed, Submission, Winners};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -7,14 +7,12 @@ pub struct ClosePlayerAccount<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
-    #[account(
-        seeds = [b"game", game.admin.as_ref(), game.game_code.as_bytes()],
-        bump = game.bump,
-        constraint = Clock::get()?.unix_timestamp * 1000 >= game.end_time @ ErrorCode::GameNotEnded,
-    )]
-    pub game: Account<'info, Game>,
+    /// CHECK: The game, checked in close_player_account - or its address once the
+    /// admin has closed it, since every player left behind then lost
+    pub game: UncheckedAccount<'info>,
 
-    // Optional winners account - only needed if checking if player was a winner
+    // Optional winners account - required once the player has finished, since
+    // finishers count towards the winners and may be one
     #[account(
         seeds = [b"winners", game.key().as_ref()],
         bump,
@@ -30,16 +28,44 @@ pub struct ClosePlayerAccount<'info> {
         ],
         bump = player_account.bump,
         constraint = player_account.player == player.key() @ ErrorCode::InvalidPlayer,
+        constraint = player_account.game == game.key() @ ErrorCode::InvalidGame,
         constraint = verify_can_close(&winners, &player.key()) @ ErrorCode::CannotCloseWinnerAccount,
         close = player
     )]
     pub player_account: Account<'info, PlayerAccount>,
 
+    // Only exists if the player submitted answers
+    #[account(
+        mut,
+        seeds = [b"submission", game.key().as_ref(), player.key().as_ref()],
+        bump = submission.bump,
+        close = player
+    )]
+    pub submission: Option<Account<'info, Submission>>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> ClosePlayerAccount<'info> {
     pub fn close_player_account(&mut self) -> Result<()> {
+        // A closed game has paid every winner, so its players can always leave
+        if !self.game.data_is_empty() {
+            require_keys_eq!(*self.game.owner, crate::ID, ErrorCode::InvalidGame);
+            let game = Game::try_deserialize(&mut &self.game.try_borrow_data()?[..])?;
+            require!(
+                Clock::get()?.unix_timestamp * 1000 >= game.end_time,
+                ErrorCode::GameNotEnded
+            );
+            require!(
+                self.player_account.finished_time == 0
+                    || self
+                        .winners
+                        .as_ref()
+                        .is_some_and(|winners| winners.is_complete()),
+                ErrorCode::WinnersNotDeclared
+            );
+        }
+
         // Account closure and rent refund is handled automatically by close = player
         emit!(PlayerAccountClosed {
             game: self.game.key(),
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::state::{Tournament, TournamentClosed};
+
+#[derive(Accounts)]
+pub struct CloseTournament<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.admin.as_ref(), tournament.tournament_code.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.admin == admin.key() @ ErrorCode::InvalidAdmin,
+        constraint = tournament.settled @ ErrorCode::TournamentNotSettled,
+        constraint = tournament.unclaimed_prizes == 0 @ ErrorCode::UnclaimedPrizes,
+        close = admin
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    /// CHECK: The tournament vault PDA, holding its rent and any rounding dust
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump,
+    )]
+    pub tournament_vault: UncheckedAccount<'info>,
+
+    // Only needed for SPL token tournaments
+    #[account(
+        mut,
+        associated_token::mint = tournament.token_mint,
+        associated_token::authority = tournament_vault
+    )]
+    pub tournament_vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    // Admin's token account for receiving any tokens left in the vault
+    #[account(
+        mut,
+        constraint = admin_token_account.owner == admin.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = admin_token_account.mint == tournament.token_mint @ ErrorCode::InvalidTokenAccount
+    )]
+    pub admin_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CloseTournament<'info> {
+    // Once every final round winner has claimed, hands the vault's rent and
+    // whatever the prize split couldn't divide back to the admin
+    pub fn close_tournament(&mut self) -> Result<()> {
+        let tournament_key = self.tournament.key();
+        let seeds = &[
+            b"tournament_vault",
+            tournament_key.as_ref(),
+            &[self.tournament.vault_bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        if !self.tournament.is_native {
+            let vault_token_account = self
+                .tournament_vault_token_account
+                .as_ref()
+                .ok_or(ErrorCode::VaultTokenAccountNotProvided)?;
+
+            if vault_token_account.amount > 0 {
+                let admin_token_account = self
+                    .admin_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::AdminTokenAccountNotProvided)?;
+                let transfer_ctx = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: vault_token_account.to_account_info(),
+                        to: admin_token_account.to_account_info(),
+                        authority: self.tournament_vault.to_account_info(),
+                    },
+                    signer,
+                );
+                anchor_spl::token::transfer(transfer_ctx, vault_token_account.amount)?;
+            }
+
+            let close_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                anchor_spl::token::CloseAccount {
+                    account: vault_token_account.to_account_info(),
+                    destination: self.admin.to_account_info(),
+                    authority: self.tournament_vault.to_account_info(),
+                },
+                signer,
+            );
+            anchor_spl::token::close_account(close_ctx)?;
+        }
+
+        let vault_balance = self.tournament_vault.lamports();
+        if vault_balance > 0 {
+            let transfer_ctx = CpiContext::new_with_signer(
+                self.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: self.tournament_vault.to_account_info(),
+                    to: self.admin.to_account_info(),
+                },
+                signer,
+            );
+            anchor_lang::system_program::transfer(transfer_ctx, vault_balance)?;
+        }
+
+        emit!(TournamentClosed {
+            tournament: tournament_key,
+            admin: self.admin.key(),
+            recovered_lamports: vault_balance,
+        });
+
+        Ok(())
+    }
+}
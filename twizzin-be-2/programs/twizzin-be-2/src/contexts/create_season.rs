@@ -0,0 +1,154 @@
+use crate::constants::{PROGRAM_AUTHORITY, SOL_ADDRESS};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+use std::str::FromStr;
+
+use crate::errors::ErrorCode;
+use crate::state::{Season, SeasonCreated, MAX_NAME_LENGTH, MAX_SEASON_WINNERS};
+
+#[derive(Accounts)]
+#[instruction(season_id: u32)]
+pub struct CreateSeason<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == PROGRAM_AUTHORITY @ ErrorCode::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Season::INIT_SPACE,
+        seeds = [b"season", season_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub season: Account<'info, Season>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: The season vault PDA that holds the prize pool
+    #[account(
+        mut,
+        seeds = [b"season_vault", season.key().as_ref()],
+        bump,
+    )]
+    pub season_vault: UncheckedAccount<'info>,
+
+    /// The season vault's associated token account
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = token_mint,
+        associated_token::authority = season_vault
+    )]
+    pub season_vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = authority_token_account.owner == authority.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = authority_token_account.mint == token_mint.key() @ ErrorCode::InvalidTokenAccount
+    )]
+    pub authority_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateSeason<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_season(
+        &mut self,
+        season_id: u32,
+        name: String,
+        start_time: i64,
+        end_time: i64,
+        num_winners: u8,
+        even_split: bool,
+        max_games: u32,
+        prize_pool: u64,
+        bumps: &CreateSeasonBumps,
+    ) -> Result<()> {
+        require!(
+            !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
+            ErrorCode::NameTooLong
+        );
+        require!(start_time < end_time, ErrorCode::InvalidTimeRange);
+        require!(num_winners > 0, ErrorCode::MaxWinnersTooLow);
+        require!(
+            num_winners <= MAX_SEASON_WINNERS,
+            ErrorCode::MaxWinnersTooHigh
+        );
+        require!(max_games > 0, ErrorCode::SeasonFull);
+
+        let is_native = self.token_mint.key() == Pubkey::from_str(SOL_ADDRESS).unwrap();
+
+        // Fund the season prize pool
+        if prize_pool > 0 {
+            if is_native {
+                let cpi_context = CpiContext::new(
+                    self.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: self.authority.to_account_info(),
+                        to: self.season_vault.to_account_info(),
+                    },
+                );
+                anchor_lang::system_program::transfer(cpi_context, prize_pool)?;
+            } else {
+                let authority_token_account = self
+                    .authority_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::AdminTokenAccountNotProvided)?;
+
+                let season_vault_token_account = self
+                    .season_vault_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::VaultTokenAccountNotProvided)?;
+
+                let transfer_ctx = CpiContext::new(
+                    self.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: authority_token_account.to_account_info(),
+                        to: season_vault_token_account.to_account_info(),
+                        authority: self.authority.to_account_info(),
+                    },
+                );
+                anchor_spl::token::transfer(transfer_ctx, prize_pool)?;
+            }
+        }
+
+        emit!(SeasonCreated {
+            season: self.season.key(),
+            season_id,
+            name: name.clone(),
+            prize_pool,
+            start_time,
+            end_time,
+        });
+
+        self.season.set_inner(Season {
+            season_id,
+            name,
+            token_mint: self.token_mint.key(),
+            is_native,
+            start_time,
+            end_time,
+            prize_pool,
+            num_winners,
+            even_split,
+            max_games,
+            num_games: 0,
+            games_declared: 0,
+            settled: false,
+            leaderboard: vec![],
+            bump: bumps.season,
+            vault_bump: bumps.season_vault,
+            approved_hosts: vec![],
+        });
+
+        Ok(())
+    }
+}
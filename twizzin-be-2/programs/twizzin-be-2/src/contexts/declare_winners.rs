@@ -1,27 +1,38 @@
 use crate::errors::ErrorCode;
-use crate::state::{Game, PlayerAccount, WinnerInfo, Winners, WinnersDeclared, MAX_WINNERS};
+use crate::state::{
+    Game, GameOperator, PlayerAccount, Season, SeasonPlayer, SeasonPointsAwarded, WinnerInfo,
+    Winners, WinnersDeclared, MAX_WINNERS, OPERATOR_DECLARE_WINNERS,
+};
+use crate::utils::operator::check_game_authority;
 use crate::utils::prize::calculate_prizes;
+use crate::utils::season::{season_points_for_rank, update_leaderboard, SEASON_POINTS};
 use anchor_lang::prelude::*;
 use anchor_spl::token::TokenAccount;
 #[derive(Accounts)]
 #[instruction(winner_pubkeys: Vec<Pubkey>)]
 pub struct DeclareWinners<'info> {
+    // The game admin or an operator allowed to declare winners
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"game", game.admin.as_ref(), game.game_code.as_bytes()],
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.bump,
-        constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin,
-        constraint = Clock::get()?.unix_timestamp * 1000 >= game.end_time @ ErrorCode::GameNotEnded
+        constraint = game.ended @ ErrorCode::GameNotEnded
     )]
     pub game: Account<'info, Game>,
 
+    #[account(
+        seeds = [b"game_operator", game.key().as_ref(), authority.key().as_ref()],
+        bump = game_operator.bump
+    )]
+    pub game_operator: Option<Account<'info, GameOperator>>,
+
     /// CHECK: The vault PDA that owns the token account
     #[account(
         mut,
-        seeds = [b"vault", game.admin.as_ref(), game.game_code.as_bytes()],
+        seeds = [b"vault", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.vault_bump,
     )]
     pub vault: UncheckedAccount<'info>,
@@ -34,15 +45,25 @@ pub struct DeclareWinners<'info> {
     )]
     pub vault_token_account: Option<Account<'info, TokenAccount>>,
 
+    // Created by the first batch, later batches add to it
     #[account(
-        init,
-        payer = admin,
+        init_if_needed,
+        payer = authority,
         space = Winners::INIT_SPACE,
         seeds = [b"winners", game.key().as_ref()],
         bump
     )]
     pub winners: Account<'info, Winners>,
 
+    // Required when the game counts towards a season
+    #[account(
+        mut,
+        seeds = [b"season", season.season_id.to_le_bytes().as_ref()],
+        bump = season.bump,
+        constraint = game.season == Some(season.key()) @ ErrorCode::InvalidSeason
+    )]
+    pub season: Option<Box<Account<'info, Season>>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -53,30 +74,89 @@ impl<'info> DeclareWinners<'info> {
         bumps: &DeclareWinnersBumps,
         remaining_accounts: &'info [AccountInfo<'info>],
     ) -> Result<()> {
+        check_game_authority(
+            &self.game.admin,
+            &self.authority.key(),
+            self.game_operator.as_deref(),
+            OPERATOR_DECLARE_WINNERS,
+        )?;
+
         let game = &self.game;
+        let game_key = game.key();
 
-        // Validate winner count based on game settings
-        let expected_winners = if game.all_are_winners {
-            std::cmp::min(game.total_players as u8, MAX_WINNERS)
+        // The season can't settle until each of its games has declared
+        require!(
+            game.season.is_none() || self.season.is_some(),
+            ErrorCode::SeasonAccountsNotProvided
+        );
+
+        // The first batch fixes how many win and what they split
+        if self.winners.game == Pubkey::default() {
+            // Only players who submitted can win, so no-shows can't hold the pot up
+            let finished = std::cmp::min(game.finished_players, MAX_WINNERS as u32) as u8;
+            let num_winners = if game.all_are_winners {
+                finished
+            } else {
+                std::cmp::min(game.max_winners, finished)
+            };
+
+            // Calculate total_pot from actual vault balance
+            let total_pot = if game.is_native {
+                self.vault.lamports()
+            } else {
+                // Token referral rewards wait here for settle_referral
+                self.vault_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::VaultTokenAccountNotProvided)?
+                    .amount
+                    .checked_sub(game.referral_escrow)
+                    .ok_or(ErrorCode::NumericOverflow)?
+            };
+
+            self.winners.set_inner(Winners {
+                game: game_key,
+                num_winners,
+                winners: Vec::with_capacity(num_winners as usize),
+                bump: bumps.winners,
+                total_pot,
+                last_score: i32::MAX,
+                last_finished_time: i64::MIN,
+            });
         } else {
-            std::cmp::min(
-                game.max_winners,
-                std::cmp::min(game.total_players as u8, MAX_WINNERS),
-            )
-        };
+            require!(
+                !self.winners.is_complete(),
+                ErrorCode::WinnersAlreadyDeclared
+            );
+        }
 
+        // Each batch picks up where the last one stopped, and only a game
+        // without winners declares none
+        let declared = self.winners.winners.len();
+        let num_winners = self.winners.num_winners;
         require!(
-            winner_pubkeys.len() == expected_winners as usize,
+            declared + winner_pubkeys.len() <= num_winners as usize
+                && (!winner_pubkeys.is_empty() || num_winners == 0),
             ErrorCode::InvalidWinnerCount
         );
 
-        // Verify we have the correct number of remaining accounts
+        // Verify we have the correct number of remaining accounts - the winners'
+        // player accounts, then the season accounts of those who score points
+        let season_winners = if game.season.is_some() {
+            SEASON_POINTS
+                .len()
+                .saturating_sub(declared)
+                .min(winner_pubkeys.len())
+        } else {
+            0
+        };
         require!(
-            remaining_accounts.len() == winner_pubkeys.len(),
+            remaining_accounts.len() == winner_pubkeys.len() + season_winners,
             ErrorCode::InvalidWinnerCount
         );
+        let (player_accounts, season_players) = remaining_accounts.split_at(winner_pubkeys.len());
 
-        // Check for duplicates
+        // Check for duplicates - ranking strictly below the previous batch keeps
+        // winners from repeating across batches
         let mut unique_winners = winner_pubkeys.clone();
         unique_winners.sort();
         unique_winners.dedup();
@@ -85,17 +165,16 @@ impl<'info> DeclareWinners<'info> {
             ErrorCode::DuplicateWinner
         );
 
-        let mut prev_score: u8 = u8::MAX;
-        let mut prev_time = i64::MIN;
-
-        let game_key = game.key();
+        let mut prev_score = self.winners.last_score;
+        let mut prev_time = self.winners.last_finished_time;
+        let mut prev_player = self
+            .winners
+            .winners
+            .last()
+            .map_or(Pubkey::default(), |winner| winner.player);
 
         // Validate each winner and their ordering
-        for (i, (winner_pubkey, account)) in winner_pubkeys
-            .iter()
-            .zip(remaining_accounts.iter())
-            .enumerate()
-        {
+        for (winner_pubkey, account) in winner_pubkeys.iter().zip(player_accounts.iter()) {
             let seeds = &[b"player", game_key.as_ref(), winner_pubkey.as_ref()];
             let (expected_pda, _) = Pubkey::find_program_address(seeds, &crate::ID);
             require!(account.key() == expected_pda, ErrorCode::WinnerNotPlayer);
@@ -105,63 +184,127 @@ impl<'info> DeclareWinners<'info> {
             require!(player.player == *winner_pubkey, ErrorCode::WinnerNotPlayer);
             require!(player.finished_time > 0, ErrorCode::PlayerNotFinished);
 
-            if i > 0 {
-                require!(
-                    (player.num_correct < prev_score)
-                        || (player.num_correct == prev_score && player.finished_time > prev_time),
-                    ErrorCode::InvalidWinnerOrder
-                );
-            }
+            // Highest score, then earliest finish, with exact ties going to the lower
+            // pubkey - the order twizzin_client::ranking::rank_players gives
+            require!(
+                (player.score < prev_score)
+                    || (player.score == prev_score && player.finished_time > prev_time)
+                    || (player.score == prev_score
+                        && player.finished_time == prev_time
+                        && player.player > prev_player),
+                ErrorCode::InvalidWinnerOrder
+            );
 
-            prev_score = player.num_correct;
+            prev_score = player.score;
             prev_time = player.finished_time;
+            prev_player = player.player;
         }
 
-        // Calculate total_pot from actual vault balance
-        let total_pot = if game.is_native {
-            self.vault.lamports()
+        // With nobody to pay, close_game returns the pot to the admin
+        let prizes = if num_winners == 0 {
+            Vec::new()
         } else {
-            self.vault_token_account
-                .as_ref()
-                .ok_or(ErrorCode::VaultTokenAccountNotProvided)?
-                .amount
+            calculate_prizes(
+                self.winners.total_pot,
+                num_winners,
+                game.even_split,
+                game.is_native,
+                0, // Rent exemption handled in end_game
+            )?
         };
 
-        let prizes = calculate_prizes(
-            total_pot,
-            expected_winners,
-            game.even_split,
-            game.is_native,
-            0, // Rent exemption handled in end_game
-        )?;
-
-        let mut winner_infos = Vec::with_capacity(expected_winners as usize);
-        let mut total_prize_pool = 0u64;
-
-        for (i, (pubkey, prize)) in winner_pubkeys.iter().zip(prizes.iter()).enumerate() {
-            winner_infos.push(WinnerInfo {
+        for (i, pubkey) in winner_pubkeys.iter().enumerate() {
+            let rank = declared + i + 1;
+            self.winners.winners.push(WinnerInfo {
                 player: *pubkey,
-                rank: (i + 1) as u8,
-                prize_amount: *prize,
+                rank: rank as u8,
+                prize_amount: prizes[rank - 1],
                 claimed: false,
             });
-            total_prize_pool = total_prize_pool
-                .checked_add(*prize)
+        }
+        self.winners.last_score = prev_score;
+        self.winners.last_finished_time = prev_time;
+
+        if self.winners.is_complete() {
+            let total_prize_pool = prizes
+                .iter()
+                .try_fold(0u64, |total, prize| total.checked_add(*prize))
                 .ok_or(ErrorCode::NumericOverflow)?;
+            emit!(WinnersDeclared {
+                game: game_key,
+                num_winners,
+                total_prize_pool,
+            });
+
+            if let Some(season) = self.season.as_mut() {
+                season.games_declared = season
+                    .games_declared
+                    .checked_add(1)
+                    .ok_or(ErrorCode::NumericOverflow)?;
+            }
+        }
+
+        if season_winners > 0 {
+            self.award_season_points(declared, &winner_pubkeys, season_players)?;
         }
 
-        self.winners.set_inner(Winners {
-            game: game.key(),
-            num_winners: expected_winners,
-            winners: winner_infos,
-            bump: bumps.winners,
-        });
-
-        emit!(WinnersDeclared {
-            game: game_key,
-            num_winners: expected_winners,
-            total_prize_pool,
-        });
+        Ok(())
+    }
+
+    // Adds each top finisher's points to their season total, for a batch that
+    // starts after `declared` winners
+    fn award_season_points(
+        &mut self,
+        declared: usize,
+        winner_pubkeys: &[Pubkey],
+        season_players: &[AccountInfo],
+    ) -> Result<()> {
+        let season = self
+            .season
+            .as_mut()
+            .ok_or(ErrorCode::SeasonAccountsNotProvided)?;
+
+        let current_time = Clock::get()?.unix_timestamp * 1000;
+        let season_key = season.key();
+        for (i, (winner, info)) in winner_pubkeys.iter().zip(season_players).enumerate() {
+            let seeds = &[b"season_player", season_key.as_ref(), winner.as_ref()];
+            let (expected_pda, _) = Pubkey::find_program_address(seeds, &crate::ID);
+            require!(info.key() == expected_pda, ErrorCode::InvalidSeason);
+            require!(info.is_writable, ErrorCode::InvalidSeason);
+            require_keys_eq!(
+                *info.owner,
+                crate::ID,
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+            );
+            let mut season_player =
+                SeasonPlayer::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+
+            let points = season_points_for_rank((declared + i + 1) as u8);
+            season_player.points = season_player
+                .points
+                .checked_add(points)
+                .ok_or(ErrorCode::NumericOverflow)?;
+            season_player.games_counted = season_player
+                .games_counted
+                .checked_add(1)
+                .ok_or(ErrorCode::NumericOverflow)?;
+            season_player.updated_at = current_time;
+            update_leaderboard(
+                &mut season.leaderboard,
+                season_player.player,
+                season_player.points,
+                current_time,
+            );
+            season_player.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+            emit!(SeasonPointsAwarded {
+                season: season_key,
+                game: self.game.key(),
+                player: *winner,
+                points,
+                total_points: season_player.points,
+            });
+        }
 
         Ok(())
     }
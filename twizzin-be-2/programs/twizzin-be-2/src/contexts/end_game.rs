@@ -1,6 +1,11 @@
 use crate::errors::ErrorCode;
-use crate::state::{Game, GameEnded, ProgramConfig};
-use crate::utils::fees::calculate_fees;
+use crate::state::{
+    Game, GameEnded, GameOperator, ProgramConfig, ReferralFeeSource, TreasuryFeeSplit,
+    OPERATOR_START_END,
+};
+use crate::utils::fees::{calculate_fees, VaultPayer};
+use crate::utils::operator::check_game_authority;
+use crate::utils::referral::calculate_referral_reward;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -10,21 +15,34 @@ use solana_program::rent::Rent;
 
 #[derive(Accounts)]
 pub struct EndGame<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
+    // The game admin or an operator allowed to start and end the game
+    pub authority: Signer<'info>,
+
+    /// CHECK: Receives the commission, so it must be the game admin
+    #[account(
+        mut,
+        address = game.admin @ ErrorCode::InvalidAdmin
+    )]
+    pub admin: UncheckedAccount<'info>,
 
     #[account(
         mut,
-        seeds = [b"game", game.admin.as_ref(), game.game_code.as_bytes()],
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.bump,
-        constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin
+        constraint = !game.ended @ ErrorCode::GameEnded,
     )]
     pub game: Account<'info, Game>,
 
+    #[account(
+        seeds = [b"game_operator", game.key().as_ref(), authority.key().as_ref()],
+        bump = game_operator.bump
+    )]
+    pub game_operator: Option<Account<'info, GameOperator>>,
+
     /// CHECK: The vault PDA that owns the token account
     #[account(
         mut,
-        seeds = [b"vault", game.admin.as_ref(), game.game_code.as_bytes()],
+        seeds = [b"vault", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.vault_bump,
     )]
     pub vault: UncheckedAccount<'info>,
@@ -42,7 +60,7 @@ pub struct EndGame<'info> {
         mut,
         constraint = !game.is_native @ ErrorCode::InvalidTokenAccount,
         constraint = admin_token_account.to_account_info().key() != Pubkey::default() @ ErrorCode::InvalidTokenAccount,
-        constraint = admin_token_account.owner == admin.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = admin_token_account.owner == game.admin @ ErrorCode::InvalidTokenAccount,
         constraint = admin_token_account.mint == game.token_mint @ ErrorCode::InvalidTokenAccount
     )]
     pub admin_token_account: Option<Account<'info, TokenAccount>>,
@@ -76,13 +94,21 @@ pub struct EndGame<'info> {
 }
 
 impl<'info> EndGame<'info> {
-    pub fn end_game(&mut self) -> Result<()> {
+    pub fn end_game(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        check_game_authority(
+            &self.game.admin,
+            &self.authority.key(),
+            self.game_operator.as_deref(),
+            OPERATOR_START_END,
+        )?;
+
         let current_time = Clock::get()?.unix_timestamp * 1000;
 
         // If ending early, update the end_time to current_time
         if current_time < self.game.end_time {
             self.game.end_time = current_time;
         }
+        self.game.ended = true;
 
         // Get the actual balance from the vault
         let total_pot = if self.game.is_native {
@@ -106,7 +132,7 @@ impl<'info> EndGame<'info> {
         }
 
         // Calculate fees
-        let (treasury_fee, admin_commission) = calculate_fees(
+        let (mut treasury_fee, mut admin_commission) = calculate_fees(
             total_pot,
             self.config.treasury_fee,
             self.game.commission,
@@ -114,100 +140,139 @@ impl<'info> EndGame<'info> {
             self.game.is_native,
         )?;
 
+        // Referral rewards are carved out of the treasury fee or the admin commission
+        let source_cut = match self.config.referral_fee_source {
+            ReferralFeeSource::Treasury => treasury_fee,
+            ReferralFeeSource::Commission => admin_commission,
+        };
+        let referral_reward = calculate_referral_reward(
+            self.game.entry_fee,
+            self.config.referral_fee,
+            self.game.referred_players,
+            source_cut,
+        )?;
+        let referral_rewards = referral_reward
+            .checked_mul(self.game.referred_players as u64)
+            .ok_or(ErrorCode::NumericOverflow)?;
+
+        match self.config.referral_fee_source {
+            ReferralFeeSource::Treasury => {
+                treasury_fee = treasury_fee
+                    .checked_sub(referral_rewards)
+                    .ok_or(ErrorCode::NumericOverflow)?;
+            }
+            ReferralFeeSource::Commission => {
+                admin_commission = admin_commission
+                    .checked_sub(referral_rewards)
+                    .ok_or(ErrorCode::NumericOverflow)?;
+            }
+        }
+
         // Transfer fees and commission
         if treasury_fee > 0 || admin_commission > 0 {
             let vault_bump = self.game.vault_bump;
             let seeds = &[
                 b"vault",
-                self.game.admin.as_ref(),
+                self.game.creator.as_ref(),
                 self.game.game_code.as_bytes(),
                 &[vault_bump],
             ];
             let signer = &[&seeds[..]];
-
-            if self.game.is_native {
-                // Transfer SOL to treasury
-                if treasury_fee > 0 {
-                    let treasury_transfer_ix = anchor_lang::system_program::Transfer {
-                        from: self.vault.to_account_info(),
-                        to: self.treasury.to_account_info(),
-                    };
-                    let treasury_transfer_ctx = CpiContext::new_with_signer(
-                        self.system_program.to_account_info(),
-                        treasury_transfer_ix,
-                        signer,
-                    );
-                    anchor_lang::system_program::transfer(treasury_transfer_ctx, treasury_fee)?;
-                }
-
-                // Transfer SOL commission to admin
-                if admin_commission > 0 {
-                    let admin_transfer_ix = anchor_lang::system_program::Transfer {
-                        from: self.vault.to_account_info(),
-                        to: self.admin.to_account_info(),
-                    };
-                    let admin_transfer_ctx = CpiContext::new_with_signer(
-                        self.system_program.to_account_info(),
-                        admin_transfer_ix,
-                        signer,
-                    );
-                    anchor_lang::system_program::transfer(admin_transfer_ctx, admin_commission)?;
-                }
-            } else {
-                let vault_token_account = self
+            let payer = VaultPayer {
+                is_native: self.game.is_native,
+                vault: self.vault.to_account_info(),
+                vault_token_account: self
                     .vault_token_account
                     .as_ref()
-                    .ok_or(ErrorCode::VaultTokenAccountNotProvided)?;
+                    .map(|account| account.to_account_info()),
+                signer,
+                token_program: self.token_program.to_account_info(),
+                system_program: self.system_program.to_account_info(),
+            };
 
-                // Transfer tokens to treasury
-                if treasury_fee > 0 {
-                    let treasury_token_account = self
-                        .treasury_token_account
-                        .as_ref()
-                        .ok_or(ErrorCode::TreasuryTokenAccountNotProvided)?;
-
-                    let treasury_transfer_ctx = CpiContext::new_with_signer(
-                        self.token_program.to_account_info(),
-                        anchor_spl::token::Transfer {
-                            from: vault_token_account.to_account_info(),
-                            to: treasury_token_account.to_account_info(),
-                            authority: self.vault.to_account_info(),
-                        },
-                        signer,
-                    );
-                    anchor_spl::token::transfer(treasury_transfer_ctx, treasury_fee)?;
+            if treasury_fee > 0 {
+                if self.config.fee_recipients.is_empty() {
+                    let treasury = if self.game.is_native {
+                        self.treasury.to_account_info()
+                    } else {
+                        self.treasury_token_account
+                            .as_ref()
+                            .ok_or(ErrorCode::TreasuryTokenAccountNotProvided)?
+                            .to_account_info()
+                    };
+                    payer.transfer(treasury, treasury_fee)?;
+                } else {
+                    // Split the treasury fee between the configured recipients
+                    let paid = payer.pay_fee_recipients(
+                        treasury_fee,
+                        &self.config.fee_recipients,
+                        &self.game.token_mint,
+                        remaining_accounts,
+                    )?;
+                    for (recipient, amount) in paid {
+                        emit!(TreasuryFeeSplit {
+                            game: self.game.key(),
+                            recipient,
+                            amount,
+                        });
+                    }
                 }
+            }
 
-                // Transfer tokens commission to admin
-                if admin_commission > 0 {
-                    let admin_token_account = self
-                        .admin_token_account
+            if admin_commission > 0 {
+                let admin = if self.game.is_native {
+                    self.admin.to_account_info()
+                } else {
+                    self.admin_token_account
                         .as_ref()
-                        .ok_or(ErrorCode::AdminTokenAccountNotProvided)?;
-
-                    let admin_transfer_ctx = CpiContext::new_with_signer(
-                        self.token_program.to_account_info(),
-                        anchor_spl::token::Transfer {
-                            from: vault_token_account.to_account_info(),
-                            to: admin_token_account.to_account_info(),
-                            authority: self.vault.to_account_info(),
-                        },
-                        signer,
-                    );
-                    anchor_spl::token::transfer(admin_transfer_ctx, admin_commission)?;
-                }
+                        .ok_or(ErrorCode::AdminTokenAccountNotProvided)?
+                        .to_account_info()
+                };
+                payer.transfer(admin, admin_commission)?;
             }
         }
 
+        // Hold the referral rewards back for settle_referral to pay each referrer
+        self.game.referral_reward = referral_reward;
+        self.game.referral_escrow = referral_rewards;
+        if referral_rewards > 0 && self.game.is_native {
+            self.escrow_referral_rewards(referral_rewards)?;
+        }
+
         // Emit game ended event
         emit!(GameEnded {
             game: self.game.key(),
             total_pot,
             treasury_fee,
             admin_commission,
+            referral_rewards,
             end_time: current_time,
         });
 
         Ok(())
     }
+
+    // SOL rewards move onto the game account, so the vault can pay out to nothing
+    // without leaving less than rent behind. Token rewards stay in the vault's
+    // token account and declare_winners leaves them out of the prizes.
+    fn escrow_referral_rewards(&self, amount: u64) -> Result<()> {
+        let vault_bump = self.game.vault_bump;
+        let seeds = &[
+            b"vault",
+            self.game.creator.as_ref(),
+            self.game.game_code.as_bytes(),
+            &[vault_bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: self.vault.to_account_info(),
+                to: self.game.to_account_info(),
+            },
+            signer,
+        );
+        anchor_lang::system_program::transfer(transfer_ctx, amount)
+    }
 }
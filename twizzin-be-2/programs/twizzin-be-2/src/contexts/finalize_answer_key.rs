@@ -0,0 +1,44 @@
+use crate::errors::ErrorCode;
+use crate::state::{AnswerKey, AnswerKeyRevealed, Game};
+use crate::utils::answer_key::verify_answer_key;
+use anchor_lang::prelude::*;
+
+// Checks a key revealed in chunks against the game's answer_hash - players can
+// only be re-scored against it from then on
+#[derive(Accounts)]
+pub struct FinalizeAnswerKey<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+        constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [b"answer_key", game.key().as_ref()],
+        bump = answer_key.bump,
+        constraint = !answer_key.revealed @ ErrorCode::AnswerKeyAlreadyRevealed
+    )]
+    pub answer_key: Account<'info, AnswerKey>,
+}
+
+impl<'info> FinalizeAnswerKey<'info> {
+    pub fn finalize_answer_key(&mut self) -> Result<()> {
+        verify_answer_key(
+            &self.answer_key.entries,
+            self.game.answer_hash,
+            self.game.merkle_version,
+        )?;
+        self.answer_key.revealed = true;
+
+        emit!(AnswerKeyRevealed {
+            game: self.game.key(),
+            num_questions: self.answer_key.entries.len() as u8,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, PlayerAccount, SeasonPlayer, Submission};
+use crate::utils::profile::{load_profile, save_profile};
+use crate::utils::scoring::ScoringRules;
+use crate::utils::season::open_season_player;
+use crate::utils::submission::{finish_submission, validate_finish_time};
+use anchor_lang::prelude::*;
+
+// Locks in a chunked submission - the finish time is set and no more chunks are accepted
+#[derive(Accounts)]
+pub struct FinalizeSubmission<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"player",
+            game.key().as_ref(),
+            player.key().as_ref()
+        ],
+        bump = player_account.bump,
+        constraint = player_account.player == player.key() @ ErrorCode::InvalidPlayer,
+        constraint = player_account.game == game.key() @ ErrorCode::InvalidGame,
+        constraint = player_account.finished_time == 0 @ ErrorCode::AlreadySubmitted,
+    )]
+    pub player_account: Account<'info, PlayerAccount>,
+
+    #[account(
+        seeds = [b"submission", game.key().as_ref(), player.key().as_ref()],
+        bump = submission.bump,
+        constraint = !submission.answers.is_empty() @ ErrorCode::NoAnswersSubmitted,
+    )]
+    pub submission: Account<'info, Submission>,
+
+    /// CHECK: The player's profile PDA - lifetime stats are only tracked for players
+    /// who made one, but those who did always have it updated
+    #[account(
+        mut,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+    )]
+    pub profile: UncheckedAccount<'info>,
+
+    // Required when the game counts towards a season - declare_winners adds the
+    // player's points to it
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = SeasonPlayer::INIT_SPACE,
+        seeds = [b"season_player", game.season.unwrap_or_default().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub season_player: Option<Box<Account<'info, SeasonPlayer>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FinalizeSubmission<'info> {
+    pub fn finalize_submission(
+        &mut self,
+        client_finish_time: i64,
+        bumps: &FinalizeSubmissionBumps,
+    ) -> Result<()> {
+        // Get current time in milliseconds
+        let clock = Clock::get()?;
+        let current_time = clock.unix_timestamp * 1000;
+        validate_finish_time(&self.game, client_finish_time, current_time)?;
+
+        // Answers were timed as they came in - the player can't finish before the last one
+        if ScoringRules::for_game(&self.game).has_speed_bonus() {
+            let last_answer = self.submission.answers.iter().map(|answer| answer.answered_at);
+            require!(
+                last_answer.max().unwrap_or_default() <= client_finish_time,
+                ErrorCode::InvalidAnswerTime
+            );
+        }
+
+        open_season_player(
+            &self.game,
+            self.season_player.as_deref_mut(),
+            self.player.key(),
+            bumps.season_player,
+        )?;
+
+        let mut profile = load_profile(&self.profile)?;
+        finish_submission(
+            &mut self.game,
+            &mut self.player_account,
+            &self.submission.answers,
+            profile.as_mut(),
+            client_finish_time,
+        )?;
+        if let Some(profile) = profile {
+            save_profile(&self.profile, &profile)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use crate::constants::PROGRAM_AUTHORITY;
 use crate::errors::ErrorCode;
-use crate::state::config::ProgramConfig;
+use crate::state::config::{FeeRecipient, ProgramConfig, ReferralFeeSource};
+use crate::utils::fees::validate_fee_recipients;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -24,18 +25,30 @@ pub struct InitConfig<'info> {
 }
 
 impl<'info> InitConfig<'info> {
-    pub fn init_config(&mut self, treasury_pubkey: Pubkey, treasury_fee: u16) -> Result<()> {
+    pub fn init_config(
+        &mut self,
+        treasury_pubkey: Pubkey,
+        treasury_fee: u16,
+        fee_recipients: Vec<FeeRecipient>,
+        referral_fee: u16,
+        referral_fee_source: ReferralFeeSource,
+    ) -> Result<()> {
         // 1000 = 10%
         require!(treasury_fee <= 1000, ErrorCode::TreasuryFeeTooHigh);
         require!(
             !treasury_pubkey.eq(&Pubkey::default()),
             ErrorCode::TreasuryAddressBlank
         );
+        validate_fee_recipients(&fee_recipients)?;
+        require!(referral_fee <= 1000, ErrorCode::ReferralFeeTooHigh);
 
         self.config.set_inner(ProgramConfig {
             treasury_pubkey,
             authority_pubkey: PROGRAM_AUTHORITY,
             treasury_fee,
+            fee_recipients,
+            referral_fee,
+            referral_fee_source,
         });
         Ok(())
     }
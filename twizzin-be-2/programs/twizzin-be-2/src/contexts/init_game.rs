@@ -7,7 +7,16 @@ use anchor_spl::{
 use std::str::FromStr;
 
 use crate::errors::ErrorCode;
-use crate::state::{Game, GameCreated, MAX_GAME_CODE_LENGTH, MAX_NAME_LENGTH, MAX_WINNERS};
+use crate::state::{
+    AccessMode, Game, GameCodeRegistry, GameCreated, Gate, Season, Tournament,
+    TournamentRoundAdded, MAX_GAME_CODE_LENGTH, MAX_NAME_LENGTH, MAX_WINNERS,
+};
+use crate::utils::access::validate_access_mode;
+use crate::utils::game_code::normalize_game_code;
+use crate::utils::merkle::validate_merkle_version;
+use crate::utils::metadata::validate_metadata_uri;
+use crate::utils::scoring::validate_speed_bonus;
+use crate::utils::gate::validate_gate;
 
 #[derive(Accounts)]
 #[instruction(
@@ -27,6 +36,16 @@ pub struct InitGame<'info> {
     )]
     pub game: Account<'info, Game>,
 
+    // Claims the code across all hosts - taken codes already have a game set
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = GameCodeRegistry::INIT_SPACE,
+        seeds = [b"game_code", normalize_game_code(&game_code).as_bytes()],
+        bump
+    )]
+    pub game_code_registry: Box<Account<'info, GameCodeRegistry>>,
+
     pub token_mint: Account<'info, Mint>,
 
     /// CHECK: The vault PDA that will own the token account
@@ -55,12 +74,31 @@ pub struct InitGame<'info> {
     )]
     pub admin_token_account: Option<Account<'info, TokenAccount>>,
 
+    // Only needed when the game opts in to a season
+    #[account(
+        mut,
+        seeds = [b"season", season.season_id.to_le_bytes().as_ref()],
+        bump = season.bump,
+        constraint = !season.settled @ ErrorCode::SeasonSettled
+    )]
+    pub season: Option<Box<Account<'info, Season>>>,
+
+    // Only needed when the game is the next round of a tournament
+    #[account(
+        mut,
+        seeds = [b"tournament", admin.key().as_ref(), tournament.tournament_code.as_bytes()],
+        bump = tournament.bump,
+        constraint = !tournament.settled @ ErrorCode::TournamentSettled
+    )]
+    pub tournament: Option<Box<Account<'info, Tournament>>>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> InitGame<'info> {
+    #[allow(clippy::too_many_arguments)]
     pub fn init_game(
         &mut self,
         name: String,
@@ -74,22 +112,89 @@ impl<'info> InitGame<'info> {
         donation_amount: u64,
         all_are_winners: bool,
         even_split: bool,
+        access_mode: AccessMode,
+        gate: Gate,
+        metadata_uri: Option<String>,
+        question_set_hash: [u8; 32],
+        wrong_answer_penalty: u16,
+        speed_bonus: u16,
+        speed_bonus_window: u32,
+        merkle_version: u8,
         bumps: &InitGameBumps,
     ) -> Result<()> {
         require!(
-            name.len() > 0 && name.len() <= MAX_NAME_LENGTH,
+            !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
             ErrorCode::NameTooLong
         );
         require!(
-            game_code.len() > 0 && game_code.len() <= MAX_GAME_CODE_LENGTH,
+            !game_code.is_empty() && game_code.len() <= MAX_GAME_CODE_LENGTH,
+            ErrorCode::GameCodeTooLong
+        );
+        require!(
+            !normalize_game_code(&game_code).is_empty(),
             ErrorCode::GameCodeTooLong
         );
+        require!(
+            self.game_code_registry.game == Pubkey::default(),
+            ErrorCode::GameCodeTaken
+        );
         require!(max_winners > 0, ErrorCode::MaxWinnersTooLow);
         require!(max_winners <= MAX_WINNERS, ErrorCode::MaxWinnersTooHigh);
         require!(start_time < end_time, ErrorCode::InvalidTimeRange);
+        validate_access_mode(&access_mode)?;
+        validate_gate(&gate)?;
+        validate_metadata_uri(&metadata_uri)?;
+        validate_speed_bonus(speed_bonus, speed_bonus_window)?;
+        validate_merkle_version(merkle_version)?;
 
         let is_native = self.token_mint.key() == Pubkey::from_str(SOL_ADDRESS).unwrap();
 
+        // Opt in to the season if one was provided
+        let season = match self.season.as_mut() {
+            Some(season) => {
+                require!(
+                    season.approved_hosts.contains(&self.admin.key()),
+                    ErrorCode::HostNotApproved
+                );
+                require!(
+                    start_time >= season.start_time && end_time <= season.end_time,
+                    ErrorCode::GameOutsideSeason
+                );
+                require!(season.num_games < season.max_games, ErrorCode::SeasonFull);
+                season.num_games += 1;
+                Some(season.key())
+            }
+            None => None,
+        };
+
+        // Add the game as the tournament's next round
+        let (tournament, tournament_round) = match self.tournament.as_mut() {
+            Some(tournament) => {
+                // Players pay once into the tournament vault, not per round
+                require!(entry_fee == 0, ErrorCode::TournamentRoundEntryFee);
+                require!(
+                    tournament.rounds.len() < tournament.num_rounds as usize,
+                    ErrorCode::TournamentFull
+                );
+                let round = tournament.rounds.len() as u8;
+                tournament.rounds.push(self.game.key());
+
+                emit!(TournamentRoundAdded {
+                    tournament: tournament.key(),
+                    game: self.game.key(),
+                    round,
+                });
+
+                (Some(tournament.key()), round)
+            }
+            None => (None, 0),
+        };
+
+        self.game_code_registry.set_inner(GameCodeRegistry {
+            game: self.game.key(),
+            bump: bumps.game_code_registry,
+        });
+
         // Handle initial donation if provided
         if donation_amount > 0 {
             if is_native {
@@ -156,6 +261,26 @@ impl<'info> InitGame<'info> {
             is_native,
             all_are_winners,
             even_split,
+            referred_players: 0,
+            season,
+            tournament,
+            tournament_round,
+            access_mode,
+            gate,
+            creator: self.admin.key(),
+            pending_admin: None,
+            metadata_uri,
+            question_set_hash,
+            wrong_answer_penalty,
+            speed_bonus,
+            speed_bonus_window,
+            merkle_version,
+            finished_players: 0,
+            ended: false,
+            referral_reward: 0,
+            referral_escrow: 0,
+            code_registered: true,
+            started: false,
         });
 
         Ok(())
@@ -0,0 +1,42 @@
+use crate::state::PlayerProfile;
+use crate::utils::profile::emit_profile_updated;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitProfile<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        init,
+        payer = player,
+        space = PlayerProfile::INIT_SPACE,
+        seeds = [b"profile", player.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitProfile<'info> {
+    pub fn init_profile(&mut self, bumps: &InitProfileBumps) -> Result<()> {
+        self.profile.set_inner(PlayerProfile {
+            player: self.player.key(),
+            games_played: 0,
+            total_correct: 0,
+            wins: 0,
+            podium_finishes: 0,
+            current_streak: 0,
+            best_streak: 0,
+            winnings: vec![],
+            last_game: Pubkey::default(),
+            last_game_won: false,
+            bump: bumps.profile,
+        });
+
+        emit_profile_updated(&self.profile);
+
+        Ok(())
+    }
+}
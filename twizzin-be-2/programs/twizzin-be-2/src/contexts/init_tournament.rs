@@ -0,0 +1,122 @@
+use crate::constants::SOL_ADDRESS;
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount},
+};
+use std::str::FromStr;
+
+use crate::errors::ErrorCode;
+use crate::state::{
+    Tournament, TournamentCreated, MAX_GAME_CODE_LENGTH, MAX_NAME_LENGTH, MAX_TOURNAMENT_ROUNDS,
+    MAX_WINNERS,
+};
+
+#[derive(Accounts)]
+#[instruction(tournament_code: String)]
+pub struct InitTournament<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = Tournament::INIT_SPACE,
+        seeds = [b"tournament", admin.key().as_ref(), tournament_code.as_bytes()],
+        bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: The tournament vault PDA that holds the entry fees
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump,
+    )]
+    pub tournament_vault: UncheckedAccount<'info>,
+
+    /// The tournament vault's associated token account
+    #[account(
+        init_if_needed,
+        payer = admin,
+        associated_token::mint = token_mint,
+        associated_token::authority = tournament_vault
+    )]
+    pub tournament_vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitTournament<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_tournament(
+        &mut self,
+        tournament_code: String,
+        name: String,
+        entry_fee: u64,
+        commission: u16,
+        num_rounds: u8,
+        advance_count: u8,
+        even_split: bool,
+        registration_end: i64,
+        bumps: &InitTournamentBumps,
+    ) -> Result<()> {
+        require!(
+            !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
+            ErrorCode::NameTooLong
+        );
+        require!(
+            !tournament_code.is_empty() && tournament_code.len() <= MAX_GAME_CODE_LENGTH,
+            ErrorCode::GameCodeTooLong
+        );
+        require!(
+            num_rounds > 0 && num_rounds <= MAX_TOURNAMENT_ROUNDS,
+            ErrorCode::InvalidTournamentRounds
+        );
+        require!(advance_count > 0, ErrorCode::MaxWinnersTooLow);
+        require!(advance_count <= MAX_WINNERS, ErrorCode::MaxWinnersTooHigh);
+        require!(commission <= 1000, ErrorCode::InvalidBasisPoints);
+
+        let current_time = Clock::get()?.unix_timestamp * 1000;
+        require!(registration_end > current_time, ErrorCode::InvalidTimeRange);
+
+        let is_native = self.token_mint.key() == Pubkey::from_str(SOL_ADDRESS).unwrap();
+
+        emit!(TournamentCreated {
+            admin: self.admin.key(),
+            tournament: self.tournament.key(),
+            name: name.clone(),
+            tournament_code: tournament_code.clone(),
+            entry_fee,
+            num_rounds,
+            advance_count,
+        });
+
+        self.tournament.set_inner(Tournament {
+            admin: self.admin.key(),
+            name,
+            tournament_code,
+            token_mint: self.token_mint.key(),
+            is_native,
+            entry_fee,
+            commission,
+            num_rounds,
+            advance_count,
+            even_split,
+            registration_end,
+            total_players: 0,
+            rounds: vec![],
+            settled: false,
+            prize_pool: 0,
+            unclaimed_prizes: 0,
+            bump: bumps.tournament,
+            vault_bump: bumps.tournament_vault,
+        });
+
+        Ok(())
+    }
+}
@@ -1,20 +1,33 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    token::{Token, TokenAccount},
+    token::{Mint, Token, TokenAccount},
     associated_token::AssociatedToken,
 };
 
+use crate::constants::TOKEN_METADATA_PROGRAM_ID;
 use crate::errors::ErrorCode;
-use crate::state::{Game, PlayerAccount, PlayerJoined};
+use crate::state::{
+    AccessMode, AccessProof, Game, GameReferral, Gate, MintListProof, PlayerAccount,
+    PlayerJoined, ReferralBalance, Tournament, TournamentEntry, Winners,
+};
+use crate::utils::access::{
+    create_allowlist_leaf, create_invite_message, verify_ed25519_instruction,
+};
+use crate::utils::gate::{create_mint_list_leaf, read_verified_collection};
+use crate::utils::merkle::verify_merkle_proof_v2;
+use solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
 
 #[derive(Accounts)]
+#[instruction(referrer: Option<Pubkey>)]
 pub struct JoinGame<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"game", game.admin.as_ref(), game.game_code.as_bytes()],
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.bump
     )]
     pub game: Account<'info, Game>,
@@ -35,7 +48,7 @@ pub struct JoinGame<'info> {
     /// CHECK: The vault PDA that will own the token account
     #[account(
         mut,
-        seeds = [b"vault", game.admin.as_ref(), game.game_code.as_bytes()],
+        seeds = [b"vault", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.vault_bump,
     )]
     pub vault: UncheckedAccount<'info>,
@@ -60,17 +73,99 @@ pub struct JoinGame<'info> {
     )]
     pub player_token_account: Option<Account<'info, TokenAccount>>,
 
+    // Referral accounts - only needed when joining with a referrer
+    #[account(
+        constraint = referrer_player_account.game == game.key() @ ErrorCode::ReferrerNotPlayer,
+        constraint = Some(referrer_player_account.player) == referrer @ ErrorCode::ReferrerNotPlayer
+    )]
+    pub referrer_player_account: Option<Box<Account<'info, PlayerAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = GameReferral::INIT_SPACE,
+        seeds = [b"game_referral", game.key().as_ref(), referrer.unwrap_or_default().as_ref()],
+        bump
+    )]
+    pub game_referral: Option<Box<Account<'info, GameReferral>>>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = ReferralBalance::INIT_SPACE,
+        seeds = [b"referral", referrer.unwrap_or_default().as_ref(), game.token_mint.as_ref()],
+        bump
+    )]
+    pub referral_balance: Option<Box<Account<'info, ReferralBalance>>>,
+
+    // Only needed for SPL token games - holds the referrer's token rewards
+    #[account(
+        init_if_needed,
+        payer = player,
+        associated_token::mint = token_mint,
+        associated_token::authority = referral_balance
+    )]
+    pub referral_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    #[account(
+        constraint = token_mint.key() == game.token_mint @ ErrorCode::InvalidTokenAccount
+    )]
+    pub token_mint: Option<Box<Account<'info, Mint>>>,
+
+    // Tournament accounts - only needed when the game is a tournament round
+    #[account(
+        constraint = game.tournament == Some(tournament.key()) @ ErrorCode::InvalidTournament
+    )]
+    pub tournament: Option<Box<Account<'info, Tournament>>>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament_entry", game.tournament.unwrap_or_default().as_ref(), player.key().as_ref()],
+        bump = tournament_entry.bump
+    )]
+    pub tournament_entry: Option<Box<Account<'info, TournamentEntry>>>,
+
+    // Winners of the previous round - not needed for the first round
+    pub previous_winners: Option<Box<Account<'info, Winners>>>,
+
+    // Token account proving the player holds the gated token or NFT
+    pub gate_token_account: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// CHECK: Metaplex metadata of the gated NFT - owner and address checked in check_gate
+    pub gate_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar - only needed to verify signed invites
+    #[account(address = solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> JoinGame<'info> {
-    pub fn join_game(&mut self, bumps: &JoinGameBumps) -> Result<()> {
+    pub fn join_game(
+        &mut self,
+        referrer: Option<Pubkey>,
+        access_proof: Option<AccessProof>,
+        gate_proof: Option<MintListProof>,
+        bumps: &JoinGameBumps,
+    ) -> Result<()> {
         // Verify game hasn't ended
         let current_time = Clock::get()?.unix_timestamp * 1000;
         require!(current_time < self.game.end_time, ErrorCode::GameEnded);
 
+        self.check_access(access_proof, current_time)?;
+        self.check_gate(gate_proof)?;
+
+        if self.game.tournament.is_some() {
+            self.check_tournament_eligibility()?;
+        }
+
+        if let Some(referrer) = referrer {
+            self.record_referral(referrer, bumps)?;
+        }
+
         // Only handle entry fee transfer if amount is greater than 0
         if self.game.entry_fee > 0 {
             if self.game.is_native {
@@ -112,8 +207,11 @@ impl<'info> JoinGame<'info> {
             join_time: current_time,
             finished_time: 0,
             num_correct: 0,
+            score: 0,
             answer_hash: [0; 32],
             bump: bumps.player_account,
+            referrer,
+            answered: [0; 32],
         });
 
         // Increment total players in game
@@ -125,8 +223,235 @@ impl<'info> JoinGame<'info> {
             game: self.game.key(),
             player: self.player.key(),
             join_time: current_time,
+            referrer,
         });
 
         Ok(())
     }
+
+    // Private games need either a merkle proof that the player is on the
+    // allowlist or an invite signed through a preceding ed25519 instruction
+    fn check_access(&self, access_proof: Option<AccessProof>, current_time: i64) -> Result<()> {
+        let player = self.player.key();
+
+        match (self.game.access_mode, access_proof) {
+            (AccessMode::Public, _) => Ok(()),
+            (AccessMode::Allowlist { root }, Some(AccessProof::Allowlist { proof, index })) => {
+                let leaf = create_allowlist_leaf(&player);
+                require!(
+                    verify_merkle_proof_v2(leaf, &proof, index, root),
+                    ErrorCode::NotOnAllowlist
+                );
+                Ok(())
+            }
+            (
+                AccessMode::SignedInvite { invite_signer },
+                Some(AccessProof::SignedInvite { expiry }),
+            ) => {
+                require!(current_time < expiry, ErrorCode::InviteExpired);
+
+                let instructions_sysvar = self
+                    .instructions_sysvar
+                    .as_ref()
+                    .ok_or(ErrorCode::AccessProofNotProvided)?;
+                let current_index = load_current_index_checked(instructions_sysvar)?;
+                require!(current_index > 0, ErrorCode::InvalidInvite);
+                let ed25519_ix =
+                    load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+
+                let message = create_invite_message(&self.game.key(), &player, expiry);
+                if verify_ed25519_instruction(&ed25519_ix, &self.game.admin, &message).is_ok() {
+                    return Ok(());
+                }
+                require!(
+                    invite_signer != Pubkey::default(),
+                    ErrorCode::InvalidInvite
+                );
+                verify_ed25519_instruction(&ed25519_ix, &invite_signer, &message)
+            }
+            _ => err!(ErrorCode::AccessProofNotProvided),
+        }
+    }
+
+    // Gated games need the player to show a token account holding the gated
+    // token, or an NFT from the gated collection or mint list
+    fn check_gate(&self, gate_proof: Option<MintListProof>) -> Result<()> {
+        if self.game.gate == Gate::Open {
+            return Ok(());
+        }
+
+        let gate_token_account = self
+            .gate_token_account
+            .as_ref()
+            .ok_or(ErrorCode::GateTokenAccountNotProvided)?;
+        require!(
+            gate_token_account.owner == self.player.key(),
+            ErrorCode::GateInvalidTokenAccount
+        );
+
+        match self.game.gate {
+            Gate::Open => {}
+            Gate::Token { mint, min_amount } => {
+                require!(
+                    gate_token_account.mint == mint,
+                    ErrorCode::GateInvalidTokenAccount
+                );
+                require!(
+                    gate_token_account.amount >= min_amount,
+                    ErrorCode::GateInsufficientBalance
+                );
+            }
+            Gate::NftCollection { collection } => {
+                require!(
+                    gate_token_account.amount > 0,
+                    ErrorCode::GateInsufficientBalance
+                );
+
+                let gate_metadata = self
+                    .gate_metadata
+                    .as_ref()
+                    .ok_or(ErrorCode::GateInvalidMetadata)?;
+                require!(
+                    gate_metadata.owner == &TOKEN_METADATA_PROGRAM_ID,
+                    ErrorCode::GateInvalidMetadata
+                );
+                let (expected_metadata, _) = Pubkey::find_program_address(
+                    &[
+                        b"metadata",
+                        TOKEN_METADATA_PROGRAM_ID.as_ref(),
+                        gate_token_account.mint.as_ref(),
+                    ],
+                    &TOKEN_METADATA_PROGRAM_ID,
+                );
+                require!(
+                    gate_metadata.key() == expected_metadata,
+                    ErrorCode::GateInvalidMetadata
+                );
+
+                let data = gate_metadata.try_borrow_data()?;
+                let (metadata_mint, verified_collection) =
+                    read_verified_collection(&data).ok_or(ErrorCode::GateNftNotInCollection)?;
+                require!(
+                    metadata_mint == gate_token_account.mint,
+                    ErrorCode::GateInvalidMetadata
+                );
+                require!(
+                    verified_collection == collection,
+                    ErrorCode::GateNftNotInCollection
+                );
+            }
+            Gate::NftMintList { root } => {
+                require!(
+                    gate_token_account.amount > 0,
+                    ErrorCode::GateInsufficientBalance
+                );
+
+                let proof = gate_proof.ok_or(ErrorCode::GateNftNotInList)?;
+                let leaf = create_mint_list_leaf(&gate_token_account.mint);
+                require!(
+                    verify_merkle_proof_v2(leaf, &proof.proof, proof.index, root),
+                    ErrorCode::GateNftNotInList
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    // Only players who paid the tournament entry can join the first round, and only
+    // the top finishers of the previous round can join the rounds after it
+    fn check_tournament_eligibility(&mut self) -> Result<()> {
+        let tournament = self
+            .tournament
+            .as_ref()
+            .ok_or(ErrorCode::TournamentAccountsNotProvided)?;
+        let tournament_entry = self
+            .tournament_entry
+            .as_mut()
+            .ok_or(ErrorCode::TournamentAccountsNotProvided)?;
+
+        let round = self.game.tournament_round;
+        if round > 0 {
+            let previous_winners = self
+                .previous_winners
+                .as_ref()
+                .ok_or(ErrorCode::TournamentAccountsNotProvided)?;
+            require!(
+                previous_winners.game == tournament.rounds[round as usize - 1],
+                ErrorCode::InvalidTournament
+            );
+            require!(previous_winners.is_complete(), ErrorCode::WinnersNotDeclared);
+
+            let advanced = previous_winners
+                .winners
+                .iter()
+                .any(|w| w.player == self.player.key() && w.rank <= tournament.advance_count);
+            require!(advanced, ErrorCode::NotAdvancedToRound);
+        }
+
+        tournament_entry.rounds_joined = round + 1;
+
+        Ok(())
+    }
+
+    // Tallies the referral against the referrer so end_game can pay them out
+    fn record_referral(&mut self, referrer: Pubkey, bumps: &JoinGameBumps) -> Result<()> {
+        require!(referrer != self.player.key(), ErrorCode::SelfReferral);
+
+        // The referrer has to have joined the game themselves
+        require!(
+            self.referrer_player_account.is_some(),
+            ErrorCode::ReferrerNotPlayer
+        );
+
+        let game_key = self.game.key();
+        let token_mint = self.game.token_mint;
+
+        if !self.game.is_native {
+            require!(
+                self.referral_token_account.is_some(),
+                ErrorCode::ReferralAccountsNotProvided
+            );
+        }
+
+        let referral_balance = self
+            .referral_balance
+            .as_mut()
+            .ok_or(ErrorCode::ReferralAccountsNotProvided)?;
+
+        // Set up the referrer's balance the first time they're credited
+        if referral_balance.referrer == Pubkey::default() {
+            referral_balance.referrer = referrer;
+            referral_balance.token_mint = token_mint;
+            referral_balance.bump = bumps
+                .referral_balance
+                .ok_or(ErrorCode::ReferralAccountsNotProvided)?;
+        }
+
+        let game_referral = self
+            .game_referral
+            .as_mut()
+            .ok_or(ErrorCode::ReferralAccountsNotProvided)?;
+
+        if game_referral.game == Pubkey::default() {
+            game_referral.game = game_key;
+            game_referral.referrer = referrer;
+            game_referral.bump = bumps
+                .game_referral
+                .ok_or(ErrorCode::ReferralAccountsNotProvided)?;
+        }
+
+        game_referral.referred_players = game_referral
+            .referred_players
+            .checked_add(1)
+            .ok_or(ErrorCode::PlayerCountOverflow)?;
+
+        self.game.referred_players = self
+            .game
+            .referred_players
+            .checked_add(1)
+            .ok_or(ErrorCode::PlayerCountOverflow)?;
+
+        Ok(())
+    }
 }
\ No newline at end of file
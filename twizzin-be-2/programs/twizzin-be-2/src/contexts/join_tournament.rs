@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+use crate::errors::ErrorCode;
+use crate::state::{Tournament, TournamentEntry, TournamentJoined};
+
+#[derive(Accounts)]
+pub struct JoinTournament<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.admin.as_ref(), tournament.tournament_code.as_bytes()],
+        bump = tournament.bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    #[account(
+        init,
+        payer = player,
+        space = TournamentEntry::INIT_SPACE,
+        seeds = [b"tournament_entry", tournament.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub tournament_entry: Account<'info, TournamentEntry>,
+
+    /// CHECK: The tournament vault PDA that holds the entry fees
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump,
+    )]
+    pub tournament_vault: UncheckedAccount<'info>,
+
+    // Only needed for SPL token tournaments
+    #[account(
+        mut,
+        associated_token::mint = tournament.token_mint,
+        associated_token::authority = tournament_vault
+    )]
+    pub tournament_vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = player_token_account.owner == player.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = player_token_account.mint == tournament.token_mint @ ErrorCode::InvalidTokenAccount
+    )]
+    pub player_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> JoinTournament<'info> {
+    pub fn join_tournament(&mut self, bumps: &JoinTournamentBumps) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp * 1000;
+        require!(
+            current_time < self.tournament.registration_end,
+            ErrorCode::TournamentRegistrationClosed
+        );
+
+        // Entry is paid once and covers every round the player reaches
+        let entry_fee = self.tournament.entry_fee;
+        if entry_fee > 0 {
+            if self.tournament.is_native {
+                let cpi_context = CpiContext::new(
+                    self.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: self.player.to_account_info(),
+                        to: self.tournament_vault.to_account_info(),
+                    },
+                );
+                anchor_lang::system_program::transfer(cpi_context, entry_fee)?;
+            } else {
+                let player_token_account = self
+                    .player_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::PlayerTokenAccountNotProvided)?;
+
+                let tournament_vault_token_account =
+                    self.tournament_vault_token_account
+                        .as_ref()
+                        .ok_or(ErrorCode::VaultTokenAccountNotProvided)?;
+
+                let transfer_ctx = CpiContext::new(
+                    self.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: player_token_account.to_account_info(),
+                        to: tournament_vault_token_account.to_account_info(),
+                        authority: self.player.to_account_info(),
+                    },
+                );
+                anchor_spl::token::transfer(transfer_ctx, entry_fee)?;
+            }
+        }
+
+        self.tournament_entry.set_inner(TournamentEntry {
+            tournament: self.tournament.key(),
+            player: self.player.key(),
+            rounds_joined: 0,
+            claimed: false,
+            bump: bumps.tournament_entry,
+        });
+
+        self.tournament.total_players = self
+            .tournament
+            .total_players
+            .checked_add(1)
+            .ok_or(ErrorCode::PlayerCountOverflow)?;
+
+        emit!(TournamentJoined {
+            tournament: self.tournament.key(),
+            player: self.player.key(),
+            join_time: current_time,
+        });
+
+        Ok(())
+    }
+}
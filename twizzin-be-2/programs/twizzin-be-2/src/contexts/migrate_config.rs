@@ -0,0 +1,53 @@
+use crate::constants::PROGRAM_AUTHORITY;
+use crate::errors::ErrorCode;
+use crate::state::config::{ProgramConfig, LEGACY_CONFIG_SPACE};
+use crate::utils::migrate::grow_account;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+// Grows a config created before fee recipients and referral fees existed. The new
+// fields start zeroed, which reads as no recipients and no referral fee.
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    #[account(
+        mut,
+        constraint = authority.key() == PROGRAM_AUTHORITY @ ErrorCode::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Doesn't deserialize as a ProgramConfig until it's migrated - checked in migrate_config
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        owner = crate::ID
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateConfig<'info> {
+    pub fn migrate_config(&mut self) -> Result<()> {
+        let config = self.config.to_account_info();
+        require!(
+            config.data_len() == LEGACY_CONFIG_SPACE,
+            ErrorCode::AlreadyMigrated
+        );
+        require!(
+            config.try_borrow_data()?[..8] == ProgramConfig::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        // The authority pays the rent for the extra space
+        grow_account(
+            &config,
+            &self.authority.to_account_info(),
+            &self.system_program.to_account_info(),
+            ProgramConfig::INIT_SPACE,
+        )?;
+
+        msg!("Config migrated to {} bytes", ProgramConfig::INIT_SPACE);
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, LEGACY_GAME_SPACE};
+use crate::utils::merkle::MERKLE_V0;
+use crate::utils::migrate::grow_account;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+// Grows a game created before admin transfers and the fields added since. Its
+// PDAs were seeded with the admin, who becomes the creator. The new fields start
+// zeroed, which reads as a public, ungated game with no scoring extras - players
+// who already joined are moved over with migrate_player_account.
+#[derive(Accounts)]
+pub struct MigrateGame<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Doesn't deserialize as a Game until it's migrated - checked in migrate_game
+    #[account(mut, owner = crate::ID)]
+    pub game: UncheckedAccount<'info>,
+
+    /// CHECK: The game's winners PDA - declared winners mean the game already ended
+    #[account(seeds = [b"winners", game.key().as_ref()], bump)]
+    pub winners: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigrateGame<'info> {
+    pub fn migrate_game(&mut self) -> Result<()> {
+        let game_info = self.game.to_account_info();
+        require!(
+            game_info.data_len() == LEGACY_GAME_SPACE,
+            ErrorCode::AlreadyMigrated
+        );
+        require!(
+            game_info.try_borrow_data()?[..8] == Game::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        // The admin pays the rent for the extra space
+        grow_account(
+            &game_info,
+            &self.admin.to_account_info(),
+            &self.system_program.to_account_info(),
+            Game::INIT_SPACE,
+        )?;
+
+        // The old fields keep their place, so the zeroed tail reads as the new ones
+        let mut game = Game::try_deserialize(&mut &game_info.try_borrow_data()?[..])?;
+        require_keys_eq!(game.admin, self.admin.key(), ErrorCode::InvalidAdmin);
+        let seeds = &[
+            b"game",
+            game.admin.as_ref(),
+            game.game_code.as_bytes(),
+            &[game.bump],
+        ];
+        let game_pda = Pubkey::create_program_address(seeds, &crate::ID)
+            .map_err(|_| anchor_lang::error::ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(
+            game_pda,
+            game_info.key(),
+            anchor_lang::error::ErrorCode::ConstraintSeeds
+        );
+
+        game.creator = game.admin;
+        // Its answer_hash was built from the original leaves
+        game.merkle_version = MERKLE_V0;
+        // end_game used to take fees every time it ran
+        game.ended = !self.winners.data_is_empty();
+        // start_game left no mark, so go by the start time it set
+        game.started = game.ended || Clock::get()?.unix_timestamp * 1000 >= game.start_time;
+        game.try_serialize(&mut &mut game_info.try_borrow_mut_data()?[..])?;
+
+        msg!("Game migrated to {} bytes", Game::INIT_SPACE);
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, PlayerAccount, LEGACY_PLAYER_SPACE};
+use crate::utils::migrate::grow_account;
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+
+// Grows a player account from before referrals and scoring, once its game has been
+// migrated. Legacy games ranked players by correct answers, so that's their score.
+#[derive(Accounts)]
+pub struct MigratePlayerAccount<'info> {
+    // Anyone can pay to move a player over
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+    )]
+    pub game: Account<'info, Game>,
+
+    /// CHECK: Doesn't deserialize as a PlayerAccount until it's migrated - checked in
+    /// migrate_player_account
+    #[account(mut, owner = crate::ID)]
+    pub player_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MigratePlayerAccount<'info> {
+    pub fn migrate_player_account(&mut self) -> Result<()> {
+        let player_info = self.player_account.to_account_info();
+        require!(
+            player_info.data_len() == LEGACY_PLAYER_SPACE,
+            ErrorCode::AlreadyMigrated
+        );
+        require!(
+            player_info.try_borrow_data()?[..8] == PlayerAccount::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+
+        grow_account(
+            &player_info,
+            &self.payer.to_account_info(),
+            &self.system_program.to_account_info(),
+            PlayerAccount::INIT_SPACE,
+        )?;
+
+        let mut player_account =
+            PlayerAccount::try_deserialize(&mut &player_info.try_borrow_data()?[..])?;
+        require_keys_eq!(player_account.game, self.game.key(), ErrorCode::InvalidGame);
+        let game_key = self.game.key();
+        let seeds = &[
+            b"player",
+            game_key.as_ref(),
+            player_account.player.as_ref(),
+            &[player_account.bump],
+        ];
+        let player_pda = Pubkey::create_program_address(seeds, &crate::ID)
+            .map_err(|_| anchor_lang::error::ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(
+            player_pda,
+            player_info.key(),
+            anchor_lang::error::ErrorCode::ConstraintSeeds
+        );
+
+        player_account.score = player_account.num_correct as i32;
+        // Only players who submitted can win, and the legacy game never counted them
+        if player_account.finished_time > 0 {
+            self.game.finished_players = self
+                .game
+                .finished_players
+                .checked_add(1)
+                .ok_or(ErrorCode::PlayerCountOverflow)?;
+        }
+        player_account.try_serialize(&mut &mut player_info.try_borrow_mut_data()?[..])?;
+
+        msg!(
+            "Player account migrated to {} bytes",
+            PlayerAccount::INIT_SPACE
+        );
+        Ok(())
+    }
+}
@@ -4,6 +4,15 @@ pub use init_config::*;
 pub mod update_config;
 pub use update_config::*;
 
+pub mod migrate_config;
+pub use migrate_config::*;
+
+pub mod migrate_game;
+pub use migrate_game::*;
+
+pub mod migrate_player_account;
+pub use migrate_player_account::*;
+
 pub mod init_game;
 pub use init_game::*;
 
@@ -33,3 +42,69 @@ pub use close_game::*;
 
 pub mod close_player_account;
 pub use close_player_account::*;
+
+pub mod settle_referral;
+pub use settle_referral::*;
+
+pub mod withdraw_referral_balance;
+pub use withdraw_referral_balance::*;
+
+pub mod init_profile;
+pub use init_profile::*;
+
+pub mod create_season;
+pub use create_season::*;
+
+pub mod approve_season_host;
+pub use approve_season_host::*;
+
+pub mod remove_season_host;
+pub use remove_season_host::*;
+
+pub mod settle_season;
+pub use settle_season::*;
+
+pub mod init_tournament;
+pub use init_tournament::*;
+
+pub mod join_tournament;
+pub use join_tournament::*;
+
+pub mod settle_tournament;
+pub use settle_tournament::*;
+
+pub mod claim_tournament_prize;
+pub use claim_tournament_prize::*;
+
+pub mod close_tournament;
+pub use close_tournament::*;
+
+pub mod set_game_operator;
+pub use set_game_operator::*;
+
+pub mod remove_game_operator;
+pub use remove_game_operator::*;
+
+pub mod transfer_game_admin;
+pub use transfer_game_admin::*;
+
+pub mod accept_game_admin;
+pub use accept_game_admin::*;
+
+pub mod reveal_answer_key;
+pub use reveal_answer_key::*;
+
+pub mod reveal_answer_key_chunk;
+pub use reveal_answer_key_chunk::*;
+
+pub mod finalize_answer_key;
+pub use finalize_answer_key::*;
+
+pub mod rescore_player;
+pub use rescore_player::*;
+
+pub mod submit_answer_chunk;
+pub use submit_answer_chunk::*;
+
+pub mod finalize_submission;
+pub use finalize_submission::*;
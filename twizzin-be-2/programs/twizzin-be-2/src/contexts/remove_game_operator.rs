@@ -0,0 +1,35 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, GameOperator, GameOperatorRemoved};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RemoveGameOperator<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+        constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [b"game_operator", game.key().as_ref(), game_operator.operator.as_ref()],
+        bump = game_operator.bump,
+        close = admin
+    )]
+    pub game_operator: Account<'info, GameOperator>,
+}
+
+impl<'info> RemoveGameOperator<'info> {
+    pub fn remove_game_operator(&mut self) -> Result<()> {
+        emit!(GameOperatorRemoved {
+            game: self.game.key(),
+            operator: self.game_operator.operator,
+        });
+
+        Ok(())
+    }
+}
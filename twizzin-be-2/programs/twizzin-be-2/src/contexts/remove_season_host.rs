@@ -0,0 +1,38 @@
+use crate::constants::PROGRAM_AUTHORITY;
+use crate::errors::ErrorCode;
+use crate::state::{Season, SeasonHostRemoved};
+use anchor_lang::prelude::*;
+
+// Stops a host adding more games - games already in the season stay in it
+#[derive(Accounts)]
+pub struct RemoveSeasonHost<'info> {
+    #[account(
+        constraint = authority.key() == PROGRAM_AUTHORITY @ ErrorCode::InvalidAuthority
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"season", season.season_id.to_le_bytes().as_ref()],
+        bump = season.bump,
+    )]
+    pub season: Account<'info, Season>,
+}
+
+impl<'info> RemoveSeasonHost<'info> {
+    pub fn remove_season_host(&mut self, host: Pubkey) -> Result<()> {
+        let hosts = &mut self.season.approved_hosts;
+        let position = hosts
+            .iter()
+            .position(|approved| *approved == host)
+            .ok_or(ErrorCode::HostNotApproved)?;
+        hosts.remove(position);
+
+        emit!(SeasonHostRemoved {
+            season: self.season.key(),
+            host,
+        });
+
+        Ok(())
+    }
+}
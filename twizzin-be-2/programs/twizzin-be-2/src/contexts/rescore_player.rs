@@ -0,0 +1,76 @@
+use crate::errors::ErrorCode;
+use crate::state::{AnswerKey, Game, PlayerAccount, PlayerRescored, Submission};
+use crate::utils::answer_key::score_submission;
+use crate::utils::scoring::ScoringRules;
+use anchor_lang::prelude::*;
+
+// Anyone can re-score a player once the key is public - the result only
+// depends on the revealed key and the player's stored submission. Scores are
+// final once declare_winners has ranked players by them.
+#[derive(Accounts)]
+pub struct RescorePlayer<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        seeds = [b"answer_key", game.key().as_ref()],
+        bump = answer_key.bump,
+        constraint = answer_key.revealed @ ErrorCode::AnswerKeyNotRevealed
+    )]
+    pub answer_key: Account<'info, AnswerKey>,
+
+    #[account(
+        mut,
+        seeds = [b"player", game.key().as_ref(), player_account.player.as_ref()],
+        bump = player_account.bump,
+        constraint = player_account.finished_time > 0 @ ErrorCode::PlayerNotFinished
+    )]
+    pub player_account: Account<'info, PlayerAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"submission", game.key().as_ref(), player_account.player.as_ref()],
+        bump = submission.bump
+    )]
+    pub submission: Account<'info, Submission>,
+
+    /// CHECK: The game's winners PDA - created by the first declare_winners batch
+    #[account(
+        seeds = [b"winners", game.key().as_ref()],
+        bump,
+        constraint = winners.data_is_empty() @ ErrorCode::ScoresFrozen
+    )]
+    pub winners: UncheckedAccount<'info>,
+}
+
+impl<'info> RescorePlayer<'info> {
+    pub fn rescore_player(&mut self) -> Result<()> {
+        let previous_num_correct = self.player_account.num_correct;
+        let previous_score = self.player_account.score;
+        let (num_correct, score) = score_submission(
+            &mut self.submission.answers,
+            &self.answer_key.entries,
+            &ScoringRules::for_game(&self.game),
+            self.game.start_time.max(self.player_account.join_time),
+            self.player_account.finished_time,
+        )?;
+        self.player_account.num_correct = num_correct;
+        self.player_account.score = score;
+
+        emit!(PlayerRescored {
+            game: self.game.key(),
+            player: self.player_account.player,
+            previous_num_correct,
+            num_correct,
+            previous_score,
+            score,
+        });
+
+        Ok(())
+    }
+}
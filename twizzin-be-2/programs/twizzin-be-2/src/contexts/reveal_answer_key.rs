@@ -0,0 +1,54 @@
+use crate::errors::ErrorCode;
+use crate::state::{AnswerKey, AnswerKeyEntry, AnswerKeyRevealed, Game};
+use crate::utils::answer_key::verify_answer_key;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(entries: Vec<AnswerKeyEntry>)]
+pub struct RevealAnswerKey<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+        constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin,
+        constraint = Clock::get()?.unix_timestamp * 1000 >= game.end_time @ ErrorCode::GameNotEnded
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = AnswerKey::space(&entries),
+        seeds = [b"answer_key", game.key().as_ref()],
+        bump
+    )]
+    pub answer_key: Account<'info, AnswerKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RevealAnswerKey<'info> {
+    pub fn reveal_answer_key(
+        &mut self,
+        entries: Vec<AnswerKeyEntry>,
+        bumps: &RevealAnswerKeyBumps,
+    ) -> Result<()> {
+        verify_answer_key(&entries, self.game.answer_hash, self.game.merkle_version)?;
+
+        emit!(AnswerKeyRevealed {
+            game: self.game.key(),
+            num_questions: entries.len() as u8,
+        });
+
+        self.answer_key.set_inner(AnswerKey {
+            game: self.game.key(),
+            entries,
+            bump: bumps.answer_key,
+            revealed: true,
+        });
+
+        Ok(())
+    }
+}
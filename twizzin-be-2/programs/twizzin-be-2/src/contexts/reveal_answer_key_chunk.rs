@@ -0,0 +1,95 @@
+use crate::errors::ErrorCode;
+use crate::state::{AnswerKey, AnswerKeyEntry, Game, MAX_QUESTIONS};
+use crate::utils::account::resize_pda;
+use anchor_lang::prelude::*;
+
+// Reveals part of the answer key, for games with more questions than fit in one
+// transaction. Entries build up in display order until finalize_answer_key checks
+// the whole key against the game's answer_hash.
+#[derive(Accounts)]
+pub struct RevealAnswerKeyChunk<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+        constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin,
+        constraint = Clock::get()?.unix_timestamp * 1000 >= game.end_time @ ErrorCode::GameNotEnded
+    )]
+    pub game: Account<'info, Game>,
+
+    /// CHECK: The game's AnswerKey - created by the first chunk and grown to fit
+    /// each one after it, so it's loaded and saved by hand
+    #[account(
+        mut,
+        seeds = [b"answer_key", game.key().as_ref()],
+        bump
+    )]
+    pub answer_key: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RevealAnswerKeyChunk<'info> {
+    pub fn reveal_answer_key_chunk(
+        &mut self,
+        entries: Vec<AnswerKeyEntry>,
+        bumps: &RevealAnswerKeyChunkBumps,
+    ) -> Result<()> {
+        let mut answer_key = self.load_answer_key(bumps.answer_key)?;
+        require!(!answer_key.revealed, ErrorCode::AnswerKeyAlreadyRevealed);
+
+        // Each chunk carries on from where the last one stopped
+        let revealed = answer_key.entries.len();
+        require!(
+            !entries.is_empty() && revealed + entries.len() <= MAX_QUESTIONS,
+            ErrorCode::InvalidAnswerKey
+        );
+        for (i, entry) in entries.iter().enumerate() {
+            require!(
+                entry.display_order as usize == revealed + i,
+                ErrorCode::InvalidAnswerKey
+            );
+        }
+        answer_key.entries.extend(entries);
+
+        self.save_answer_key(&answer_key)
+    }
+
+    fn load_answer_key(&self, bump: u8) -> Result<AnswerKey> {
+        let info = self.answer_key.to_account_info();
+        if info.data_is_empty() {
+            return Ok(AnswerKey {
+                game: self.game.key(),
+                entries: Vec::new(),
+                bump,
+                revealed: false,
+            });
+        }
+
+        require_keys_eq!(
+            *info.owner,
+            crate::ID,
+            anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+        );
+        let data = info.try_borrow_data()?;
+        AnswerKey::try_deserialize(&mut &data[..])
+    }
+
+    // Sizes the account to the entries so far, with the admin paying for the extra rent
+    fn save_answer_key(&self, answer_key: &AnswerKey) -> Result<()> {
+        let info = self.answer_key.to_account_info();
+        let game = self.game.key();
+        resize_pda(
+            &info,
+            &self.admin.to_account_info(),
+            &self.system_program.to_account_info(),
+            AnswerKey::space(&answer_key.entries),
+            &[b"answer_key".as_ref(), game.as_ref(), &[answer_key.bump]],
+        )?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        answer_key.try_serialize(&mut &mut data[..])
+    }
+}
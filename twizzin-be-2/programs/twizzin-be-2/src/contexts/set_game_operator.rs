@@ -0,0 +1,57 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, GameOperator, GameOperatorSet};
+use crate::utils::operator::validate_operator_permissions;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(operator: Pubkey)]
+pub struct SetGameOperator<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+        constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = GameOperator::INIT_SPACE,
+        seeds = [b"game_operator", game.key().as_ref(), operator.as_ref()],
+        bump
+    )]
+    pub game_operator: Account<'info, GameOperator>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SetGameOperator<'info> {
+    pub fn set_game_operator(
+        &mut self,
+        operator: Pubkey,
+        permissions: u8,
+        bumps: &SetGameOperatorBumps,
+    ) -> Result<()> {
+        require!(operator != self.admin.key(), ErrorCode::InvalidAuthority);
+        validate_operator_permissions(permissions)?;
+
+        self.game_operator.set_inner(GameOperator {
+            game: self.game.key(),
+            operator,
+            permissions,
+            bump: bumps.game_operator,
+            granted_by: self.admin.key(),
+        });
+
+        emit!(GameOperatorSet {
+            game: self.game.key(),
+            operator,
+            permissions,
+        });
+
+        Ok(())
+    }
+}
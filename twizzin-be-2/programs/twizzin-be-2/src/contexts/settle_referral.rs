@@ -0,0 +1,140 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, GameReferral, ReferralBalance, ReferralRewarded};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+// Pays one referrer their share of the rewards end_game held back. Anyone can
+// settle, one referrer per instruction, so ending a game never has to carry
+// every referrer's accounts.
+#[derive(Accounts)]
+pub struct SettleReferral<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+        constraint = game.ended @ ErrorCode::GameNotEnded,
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [b"game_referral", game.key().as_ref(), game_referral.referrer.as_ref()],
+        bump = game_referral.bump,
+        constraint = !game_referral.settled @ ErrorCode::ReferralAlreadySettled,
+    )]
+    pub game_referral: Account<'info, GameReferral>,
+
+    #[account(
+        mut,
+        seeds = [b"referral", game_referral.referrer.as_ref(), game.token_mint.as_ref()],
+        bump = referral_balance.bump,
+    )]
+    pub referral_balance: Account<'info, ReferralBalance>,
+
+    /// CHECK: The vault PDA that owns the token account
+    #[account(
+        seeds = [b"vault", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.vault_bump,
+    )]
+    pub vault: UncheckedAccount<'info>,
+
+    // Only needed for SPL token games
+    #[account(
+        mut,
+        associated_token::mint = game.token_mint,
+        associated_token::authority = vault
+    )]
+    pub vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    // Only needed for SPL token games - holds the referrer's token rewards
+    #[account(
+        mut,
+        associated_token::mint = game.token_mint,
+        associated_token::authority = referral_balance
+    )]
+    pub referral_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> SettleReferral<'info> {
+    pub fn settle_referral(&mut self) -> Result<()> {
+        let amount = self
+            .game
+            .referral_reward
+            .checked_mul(self.game_referral.referred_players as u64)
+            .ok_or(ErrorCode::NumericOverflow)?;
+
+        if amount > 0 {
+            if self.game.is_native {
+                // SOL rewards were moved onto the game account by end_game
+                let game_info = self.game.to_account_info();
+                let balance_info = self.referral_balance.to_account_info();
+                **game_info.try_borrow_mut_lamports()? = game_info
+                    .lamports()
+                    .checked_sub(amount)
+                    .ok_or(ErrorCode::NumericOverflow)?;
+                **balance_info.try_borrow_mut_lamports()? = balance_info
+                    .lamports()
+                    .checked_add(amount)
+                    .ok_or(ErrorCode::NumericOverflow)?;
+            } else {
+                let vault_token_account = self
+                    .vault_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::VaultTokenAccountNotProvided)?;
+                let referral_token_account = self
+                    .referral_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::ReferralAccountsNotProvided)?;
+
+                let vault_bump = self.game.vault_bump;
+                let seeds = &[
+                    b"vault",
+                    self.game.creator.as_ref(),
+                    self.game.game_code.as_bytes(),
+                    &[vault_bump],
+                ];
+                let signer = &[&seeds[..]];
+
+                let transfer_ctx = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: vault_token_account.to_account_info(),
+                        to: referral_token_account.to_account_info(),
+                        authority: self.vault.to_account_info(),
+                    },
+                    signer,
+                );
+                anchor_spl::token::transfer(transfer_ctx, amount)?;
+            }
+        }
+
+        self.game.referral_escrow = self
+            .game
+            .referral_escrow
+            .checked_sub(amount)
+            .ok_or(ErrorCode::NumericOverflow)?;
+
+        self.referral_balance.balance = self
+            .referral_balance
+            .balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::NumericOverflow)?;
+        self.referral_balance.total_earned = self
+            .referral_balance
+            .total_earned
+            .checked_add(amount)
+            .ok_or(ErrorCode::NumericOverflow)?;
+        self.game_referral.settled = true;
+
+        emit!(ReferralRewarded {
+            game: self.game.key(),
+            referrer: self.game_referral.referrer,
+            referred_players: self.game_referral.referred_players,
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use crate::errors::ErrorCode;
+use crate::state::{Season, SeasonSettled};
+use crate::utils::prize::calculate_prizes;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SettleSeason<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"season", season.season_id.to_le_bytes().as_ref()],
+        bump = season.bump,
+        constraint = !season.settled @ ErrorCode::SeasonSettled,
+        constraint = Clock::get()?.unix_timestamp * 1000 >= season.end_time @ ErrorCode::SeasonNotEnded,
+        constraint = season.games_declared == season.num_games @ ErrorCode::SeasonGamesPending
+    )]
+    pub season: Account<'info, Season>,
+
+    /// CHECK: The season vault PDA that holds the prize pool
+    #[account(
+        mut,
+        seeds = [b"season_vault", season.key().as_ref()],
+        bump = season.vault_bump,
+    )]
+    pub season_vault: UncheckedAccount<'info>,
+
+    // Only needed for SPL token seasons
+    #[account(
+        mut,
+        associated_token::mint = season.token_mint,
+        associated_token::authority = season_vault
+    )]
+    pub season_vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleSeason<'info> {
+    // Pays the prize pool out to the top of the leaderboard. Winner accounts are
+    // passed as remaining accounts in leaderboard order - wallets for SOL seasons,
+    // token accounts owned by the winners for SPL seasons.
+    pub fn settle_season(&mut self, remaining_accounts: &'info [AccountInfo<'info>]) -> Result<()> {
+        let season = &self.season;
+        require!(
+            !season.leaderboard.is_empty(),
+            ErrorCode::EmptySeasonLeaderboard
+        );
+
+        let num_winners = std::cmp::min(season.num_winners as usize, season.leaderboard.len());
+        require!(
+            remaining_accounts.len() == num_winners,
+            ErrorCode::InvalidSeasonWinner
+        );
+
+        // Pay out the actual vault balance
+        let total_pot = if season.is_native {
+            self.season_vault.lamports()
+        } else {
+            self.season_vault_token_account
+                .as_ref()
+                .ok_or(ErrorCode::VaultTokenAccountNotProvided)?
+                .amount
+        };
+
+        let prizes = calculate_prizes(
+            total_pot,
+            num_winners as u8,
+            season.even_split,
+            season.is_native,
+            0, // The season vault holds no data, so it can be emptied
+        )?;
+
+        let season_key = season.key();
+        let seeds = &[b"season_vault", season_key.as_ref(), &[season.vault_bump]];
+        let signer = &[&seeds[..]];
+
+        let mut total_prize_pool = 0u64;
+
+        for ((standing, account), prize) in season
+            .leaderboard
+            .iter()
+            .zip(remaining_accounts.iter())
+            .zip(prizes.iter())
+        {
+            require!(account.is_writable, ErrorCode::InvalidSeasonWinner);
+
+            if season.is_native {
+                require!(
+                    account.key() == standing.player,
+                    ErrorCode::InvalidSeasonWinner
+                );
+            } else {
+                let token_account = Account::<TokenAccount>::try_from(account)?;
+                require!(
+                    token_account.owner == standing.player,
+                    ErrorCode::InvalidSeasonWinner
+                );
+                require!(
+                    token_account.mint == season.token_mint,
+                    ErrorCode::InvalidSeasonWinner
+                );
+            }
+
+            if *prize == 0 {
+                continue;
+            }
+
+            if season.is_native {
+                let transfer_ctx = CpiContext::new_with_signer(
+                    self.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: self.season_vault.to_account_info(),
+                        to: account.clone(),
+                    },
+                    signer,
+                );
+                anchor_lang::system_program::transfer(transfer_ctx, *prize)?;
+            } else {
+                let season_vault_token_account = self
+                    .season_vault_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::VaultTokenAccountNotProvided)?;
+
+                let transfer_ctx = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    anchor_spl::token::Transfer {
+                        from: season_vault_token_account.to_account_info(),
+                        to: account.clone(),
+                        authority: self.season_vault.to_account_info(),
+                    },
+                    signer,
+                );
+                anchor_spl::token::transfer(transfer_ctx, *prize)?;
+            }
+
+            total_prize_pool = total_prize_pool
+                .checked_add(*prize)
+                .ok_or(ErrorCode::NumericOverflow)?;
+        }
+
+        self.season.settled = true;
+
+        emit!(SeasonSettled {
+            season: season_key,
+            num_winners: num_winners as u8,
+            total_prize_pool,
+        });
+
+        Ok(())
+    }
+}
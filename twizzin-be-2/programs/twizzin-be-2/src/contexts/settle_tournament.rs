@@ -0,0 +1,187 @@
+use crate::errors::ErrorCode;
+use crate::state::{ProgramConfig, Tournament, TournamentSettled, Winners};
+use crate::utils::fees::{calculate_fees, VaultPayer};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use solana_program::rent::Rent;
+
+#[derive(Accounts)]
+pub struct SettleTournament<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"tournament", tournament.admin.as_ref(), tournament.tournament_code.as_bytes()],
+        bump = tournament.bump,
+        constraint = tournament.admin == admin.key() @ ErrorCode::InvalidAdmin,
+        constraint = !tournament.settled @ ErrorCode::TournamentSettled,
+        constraint = tournament.rounds.len() == tournament.num_rounds as usize @ ErrorCode::TournamentNotComplete
+    )]
+    pub tournament: Account<'info, Tournament>,
+
+    // Winners of the final round - declaring them is what completes the tournament
+    #[account(
+        seeds = [b"winners", final_winners.game.as_ref()],
+        bump = final_winners.bump,
+        constraint = tournament.rounds.last() == Some(&final_winners.game) @ ErrorCode::TournamentNotComplete,
+        constraint = final_winners.is_complete() @ ErrorCode::TournamentNotComplete
+    )]
+    pub final_winners: Account<'info, Winners>,
+
+    /// CHECK: The tournament vault PDA that holds the entry fees
+    #[account(
+        mut,
+        seeds = [b"tournament_vault", tournament.key().as_ref()],
+        bump = tournament.vault_bump,
+    )]
+    pub tournament_vault: UncheckedAccount<'info>,
+
+    // Only needed for SPL token tournaments
+    #[account(
+        mut,
+        associated_token::mint = tournament.token_mint,
+        associated_token::authority = tournament_vault
+    )]
+    pub tournament_vault_token_account: Option<Account<'info, TokenAccount>>,
+
+    // Admin's token account for receiving commission
+    #[account(
+        mut,
+        constraint = admin_token_account.owner == admin.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = admin_token_account.mint == tournament.token_mint @ ErrorCode::InvalidTokenAccount
+    )]
+    pub admin_token_account: Option<Account<'info, TokenAccount>>,
+
+    // Treasury's token account for receiving fees
+    #[account(
+        mut,
+        constraint = treasury_token_account.owner == config.treasury_pubkey @ ErrorCode::InvalidTokenAccount,
+        constraint = treasury_token_account.mint == tournament.token_mint @ ErrorCode::InvalidTokenAccount
+    )]
+    pub treasury_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, ProgramConfig>,
+
+    /// CHECK: Safe because we just transfer SOL to this account
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury_pubkey @ ErrorCode::InvalidTreasury
+    )]
+    pub treasury: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleTournament<'info> {
+    // Takes the treasury fee and admin commission out of the tournament vault and
+    // locks in the prize pool the final round's winners claim from. Fee recipient
+    // accounts are passed as remaining accounts in config order, like end_game.
+    pub fn settle_tournament(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        let is_native = self.tournament.is_native;
+
+        let total_pot = if is_native {
+            self.tournament_vault.lamports()
+        } else {
+            self.tournament_vault_token_account
+                .as_ref()
+                .ok_or(ErrorCode::VaultTokenAccountNotProvided)?
+                .amount
+        };
+
+        // Keep the SOL vault rent exempt while prizes are claimed one at a time
+        let rent_exemption = if is_native {
+            Rent::get()?.minimum_balance(0)
+        } else {
+            0
+        };
+
+        let (treasury_fee, admin_commission) = calculate_fees(
+            total_pot,
+            self.config.treasury_fee,
+            self.tournament.commission,
+            rent_exemption,
+            is_native,
+        )?;
+
+        let tournament_key = self.tournament.key();
+        let seeds = &[
+            b"tournament_vault",
+            tournament_key.as_ref(),
+            &[self.tournament.vault_bump],
+        ];
+        let signer = &[&seeds[..]];
+        let payer = VaultPayer {
+            is_native,
+            vault: self.tournament_vault.to_account_info(),
+            vault_token_account: self
+                .tournament_vault_token_account
+                .as_ref()
+                .map(|account| account.to_account_info()),
+            signer,
+            token_program: self.token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        if treasury_fee > 0 {
+            if self.config.fee_recipients.is_empty() {
+                let treasury = if is_native {
+                    self.treasury.to_account_info()
+                } else {
+                    self.treasury_token_account
+                        .as_ref()
+                        .ok_or(ErrorCode::TreasuryTokenAccountNotProvided)?
+                        .to_account_info()
+                };
+                payer.transfer(treasury, treasury_fee)?;
+            } else {
+                payer.pay_fee_recipients(
+                    treasury_fee,
+                    &self.config.fee_recipients,
+                    &self.tournament.token_mint,
+                    remaining_accounts,
+                )?;
+            }
+        }
+
+        if admin_commission > 0 {
+            let admin = if is_native {
+                self.admin.to_account_info()
+            } else {
+                self.admin_token_account
+                    .as_ref()
+                    .ok_or(ErrorCode::AdminTokenAccountNotProvided)?
+                    .to_account_info()
+            };
+            payer.transfer(admin, admin_commission)?;
+        }
+
+        let prize_pool = total_pot
+            .saturating_sub(rent_exemption)
+            .checked_sub(treasury_fee)
+            .and_then(|pot| pot.checked_sub(admin_commission))
+            .ok_or(ErrorCode::NumericOverflow)?;
+
+        self.tournament.prize_pool = prize_pool;
+        self.tournament.unclaimed_prizes = self.final_winners.num_winners;
+        self.tournament.settled = true;
+
+        emit!(TournamentSettled {
+            tournament: tournament_key,
+            total_pot,
+            treasury_fee,
+            admin_commission,
+            prize_pool,
+        });
+
+        Ok(())
+    }
+}
@@ -1,27 +1,40 @@
 use crate::errors::ErrorCode;
-use crate::state::{Game, GameStarted};
+use crate::state::{Game, GameOperator, GameStarted, OPERATOR_START_END};
+use crate::utils::operator::check_game_authority;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
 pub struct StartGame<'info> {
-    #[account(
-        constraint = game.admin == admin.key() @ ErrorCode::InvalidAuthority
-    )]
-    pub admin: Signer<'info>,
+    // The game admin or an operator allowed to start and end the game
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"game", admin.key().as_ref(), game.game_code.as_bytes()],
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.bump,
+        constraint = !game.ended @ ErrorCode::GameEnded,
     )]
     pub game: Account<'info, Game>,
+
+    #[account(
+        seeds = [b"game_operator", game.key().as_ref(), authority.key().as_ref()],
+        bump = game_operator.bump
+    )]
+    pub game_operator: Option<Account<'info, GameOperator>>,
 }
 
 impl<'info> StartGame<'info> {
     pub fn start_game(&mut self, total_time: i64) -> Result<()> {
+        check_game_authority(
+            &self.game.admin,
+            &self.authority.key(),
+            self.game_operator.as_deref(),
+            OPERATOR_START_END,
+        )?;
+
         // Store the keys before mutating game
         let game_key = self.game.key();
-        let admin_key = self.admin.key();
+        let admin_key = self.game.admin;
 
         let game = &mut self.game;
         let current_time = Clock::get()?.unix_timestamp * 1000;
@@ -30,6 +43,7 @@ impl<'info> StartGame<'info> {
         game.start_time = current_time;
         // Calculate end time based on total_time
         game.end_time = current_time + total_time;
+        game.started = true;
 
         // Use the stored keys in the event
         emit!(GameStarted {
@@ -0,0 +1,159 @@
+use crate::errors::ErrorCode;
+use crate::state::{AnswerChunkSubmitted, AnswerInput, Game, PlayerAccount, Submission};
+use crate::utils::account::resize_pda;
+use crate::utils::merkle::{create_versioned_leaf_hash, leaf_covers, verify_versioned_proof};
+use crate::utils::scoring::ScoringRules;
+use crate::utils::submission::record_answers;
+use anchor_lang::prelude::*;
+
+// Submits part of a player's answers, for games with more questions than fit in one
+// transaction. Scores build up on the player account and the submission grows with
+// each chunk until finalize_submission locks in the finish time.
+#[derive(Accounts)]
+pub struct SubmitAnswerChunk<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump
+    )]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"player",
+            game.key().as_ref(),
+            player.key().as_ref()
+        ],
+        bump = player_account.bump,
+        constraint = player_account.player == player.key() @ ErrorCode::InvalidPlayer,
+        constraint = player_account.game == game.key() @ ErrorCode::InvalidGame,
+        constraint = player_account.finished_time == 0 @ ErrorCode::AlreadySubmitted,
+    )]
+    pub player_account: Account<'info, PlayerAccount>,
+
+    /// CHECK: The player's Submission - created by the first chunk and grown to fit
+    /// each one after it. Anchor's init_if_needed can't resize, so it's loaded and
+    /// saved by hand.
+    #[account(
+        mut,
+        seeds = [b"submission", game.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub submission: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SubmitAnswerChunk<'info> {
+    pub fn submit_answer_chunk(
+        &mut self,
+        answers: Vec<AnswerInput>,
+        bumps: &SubmitAnswerChunkBumps,
+    ) -> Result<()> {
+        // Get current time in milliseconds
+        let clock = Clock::get()?;
+        let current_time = clock.unix_timestamp * 1000;
+        require!(
+            current_time >= self.game.start_time,
+            ErrorCode::GameNotStarted
+        );
+        require!(current_time <= self.game.end_time, ErrorCode::GameEnded);
+
+        let mut submission = self.load_submission(bumps.submission)?;
+
+        let merkle_version = self.game.merkle_version;
+        let root = self.game.answer_hash;
+        let player_start = self.game.start_time.max(self.player_account.join_time);
+        let num_answers = answers.len() as u8;
+        record_answers(
+            &mut self.player_account,
+            &mut submission.answers,
+            answers,
+            &ScoringRules::for_game(&self.game),
+            player_start,
+            current_time,
+            |answer| {
+                if !leaf_covers(merkle_version, &answer.answer_type, answer.points) {
+                    return false;
+                }
+                let leaf = create_versioned_leaf_hash(
+                    merkle_version,
+                    answer.display_order,
+                    &answer.answer_type,
+                    &answer.answer,
+                    &answer.question_id,
+                    answer.points,
+                );
+                verify_versioned_proof(
+                    merkle_version,
+                    leaf,
+                    &answer.proof,
+                    answer.display_order,
+                    root,
+                )
+            },
+        )?;
+        self.save_submission(&submission)?;
+
+        emit!(AnswerChunkSubmitted {
+            game: self.game.key(),
+            player: self.player.key(),
+            num_answers,
+            num_correct: self.player_account.num_correct,
+            score: self.player_account.score,
+        });
+
+        Ok(())
+    }
+
+    fn load_submission(&self, bump: u8) -> Result<Submission> {
+        let info = self.submission.to_account_info();
+        if info.data_is_empty() {
+            return Ok(Submission {
+                game: self.game.key(),
+                player: self.player.key(),
+                answers: Vec::new(),
+                bump,
+            });
+        }
+
+        require_keys_eq!(
+            *info.owner,
+            crate::ID,
+            anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+        );
+        let data = info.try_borrow_data()?;
+        Submission::try_deserialize(&mut &data[..])
+    }
+
+    // Sizes the account to the submission, with the player paying for the extra rent
+    fn save_submission(&self, submission: &Submission) -> Result<()> {
+        let info = self.submission.to_account_info();
+        let space = Submission::space(
+            submission
+                .answers
+                .iter()
+                .map(|answer| answer.answer.len()),
+        );
+        let game = self.game.key();
+        let player = self.player.key();
+        resize_pda(
+            &info,
+            &self.player.to_account_info(),
+            &self.system_program.to_account_info(),
+            space,
+            &[
+                b"submission".as_ref(),
+                game.as_ref(),
+                player.as_ref(),
+                &[submission.bump],
+            ],
+        )?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        submission.try_serialize(&mut &mut data[..])
+    }
+}
@@ -1,15 +1,24 @@
 use crate::errors::ErrorCode;
-use crate::state::{AnswerInput, AnswersSubmitted, Game, PlayerAccount};
-use crate::utils::merkle::{create_leaf_hash, verify_merkle_proof};
+use crate::state::{AnswerInput, AnswerMultiproof, Game, PlayerAccount, SeasonPlayer, Submission};
+use crate::utils::merkle::{
+    create_leaf_hash_v2, create_versioned_leaf_hash, leaf_covers, verify_multiproof_v2,
+    verify_versioned_proof, MERKLE_V2,
+};
+use crate::utils::profile::{load_profile, save_profile};
+use crate::utils::scoring::ScoringRules;
+use crate::utils::season::open_season_player;
+use crate::utils::submission::{finish_submission, record_answers, validate_finish_time};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
+#[instruction(answers: Vec<AnswerInput>)]
 pub struct SubmitAnswers<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
     #[account(
-        seeds = [b"game", game.admin.as_ref(), game.game_code.as_bytes()],
+        mut,
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.bump
     )]
     pub game: Account<'info, Game>,
@@ -28,6 +37,35 @@ pub struct SubmitAnswers<'info> {
     )]
     pub player_account: Account<'info, PlayerAccount>,
 
+    #[account(
+        init,
+        payer = player,
+        space = Submission::space(answers.iter().map(|answer| answer.answer.len())),
+        seeds = [b"submission", game.key().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub submission: Account<'info, Submission>,
+
+    /// CHECK: The player's profile PDA - lifetime stats are only tracked for players
+    /// who made one, but those who did always have it updated
+    #[account(
+        mut,
+        seeds = [b"profile", player.key().as_ref()],
+        bump,
+    )]
+    pub profile: UncheckedAccount<'info>,
+
+    // Required when the game counts towards a season - declare_winners adds the
+    // player's points to it
+    #[account(
+        init_if_needed,
+        payer = player,
+        space = SeasonPlayer::INIT_SPACE,
+        seeds = [b"season_player", game.season.unwrap_or_default().as_ref(), player.key().as_ref()],
+        bump
+    )]
+    pub season_player: Option<Box<Account<'info, SeasonPlayer>>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -36,48 +74,134 @@ impl<'info> SubmitAnswers<'info> {
         &mut self,
         answers: Vec<AnswerInput>,
         client_finish_time: i64,
+        bumps: &SubmitAnswersBumps,
     ) -> Result<()> {
-        // Get current time in milliseconds
-        let clock = Clock::get()?;
-        let current_time = clock.unix_timestamp * 1000;
+        let merkle_version = self.game.merkle_version;
+        let root = self.game.answer_hash;
 
-        // Validate client_finish_time
+        self.save_submission(answers, client_finish_time, bumps, |answer| {
+            if !leaf_covers(merkle_version, &answer.answer_type, answer.points) {
+                return false;
+            }
+            let leaf = create_versioned_leaf_hash(
+                merkle_version,
+                answer.display_order,
+                &answer.answer_type,
+                &answer.answer,
+                &answer.question_id,
+                answer.points,
+            );
+            verify_versioned_proof(
+                merkle_version,
+                leaf,
+                &answer.proof,
+                answer.display_order,
+                root,
+            )
+        })
+    }
+
+    // Verifies every claimed answer with one multiproof instead of a proof per answer.
+    // Claims point at the first answer to each question, and unclaimed answers are wrong.
+    pub fn submit_answers_batch(
+        &mut self,
+        answers: Vec<AnswerInput>,
+        multiproof: AnswerMultiproof,
+        client_finish_time: i64,
+        bumps: &SubmitAnswersBumps,
+    ) -> Result<()> {
         require!(
-            client_finish_time >= self.game.start_time,
-            ErrorCode::GameNotStarted
+            self.game.merkle_version == MERKLE_V2,
+            ErrorCode::MultiproofRequiresV2
         );
         require!(
-            client_finish_time <= self.game.end_time,
-            ErrorCode::GameEnded
+            answers.iter().all(|answer| answer.proof.is_empty()),
+            ErrorCode::InvalidMultiproof
         );
-        // Ensure client_finish_time isn't in the future relative to blockchain time
+
+        let mut claimed = [false; 256];
+        let mut leaves = Vec::with_capacity(multiproof.claimed.len());
+        for display_order in multiproof.claimed {
+            let answer = answers
+                .iter()
+                .find(|answer| answer.display_order == display_order)
+                .ok_or(ErrorCode::InvalidMultiproof)?;
+            claimed[display_order as usize] = true;
+            leaves.push((
+                display_order as u32,
+                create_leaf_hash_v2(
+                    display_order,
+                    &answer.answer_type,
+                    &answer.answer,
+                    &answer.question_id,
+                    answer.points,
+                ),
+            ));
+        }
         require!(
-            client_finish_time < current_time,
-            ErrorCode::InvalidFinishTime
+            verify_multiproof_v2(
+                &leaves,
+                &multiproof.proof,
+                multiproof.depth,
+                self.game.answer_hash
+            ),
+            ErrorCode::InvalidMultiproof
         );
 
-        // Verify answers and count correct ones
-        let mut num_correct = 0;
-
-        for answer in answers.iter() {
-            let leaf = create_leaf_hash(answer.display_order, &answer.answer, &answer.question_id);
-
-            if verify_merkle_proof(leaf, &answer.proof, self.game.answer_hash) {
-                num_correct += 1;
-            }
-        }
+        self.save_submission(answers, client_finish_time, bumps, |answer| {
+            claimed[answer.display_order as usize]
+        })
+    }
 
-        // Update player account with client's finish time
-        self.player_account.finished_time = client_finish_time;
-        self.player_account.num_correct = num_correct;
+    // Scores and stores a whole submission in one go - `is_proven` checks an answer
+    // against the key
+    fn save_submission(
+        &mut self,
+        answers: Vec<AnswerInput>,
+        client_finish_time: i64,
+        bumps: &SubmitAnswersBumps,
+        is_proven: impl Fn(&AnswerInput) -> bool,
+    ) -> Result<()> {
+        // Get current time in milliseconds
+        let clock = Clock::get()?;
+        let current_time = clock.unix_timestamp * 1000;
+        validate_finish_time(&self.game, client_finish_time, current_time)?;
 
-        // Emit event with client's finish time
-        emit!(AnswersSubmitted {
+        self.submission.set_inner(Submission {
             game: self.game.key(),
             player: self.player.key(),
-            num_correct,
-            finished_time: client_finish_time,
+            answers: Vec::with_capacity(answers.len()),
+            bump: bumps.submission,
         });
+        let player_start = self.game.start_time.max(self.player_account.join_time);
+        record_answers(
+            &mut self.player_account,
+            &mut self.submission.answers,
+            answers,
+            &ScoringRules::for_game(&self.game),
+            player_start,
+            client_finish_time,
+            is_proven,
+        )?;
+
+        open_season_player(
+            &self.game,
+            self.season_player.as_deref_mut(),
+            self.player.key(),
+            bumps.season_player,
+        )?;
+
+        let mut profile = load_profile(&self.profile)?;
+        finish_submission(
+            &mut self.game,
+            &mut self.player_account,
+            &self.submission.answers,
+            profile.as_mut(),
+            client_finish_time,
+        )?;
+        if let Some(profile) = profile {
+            save_profile(&self.profile, &profile)?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,39 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, GameAdminTransferStarted};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct TransferGameAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
+        bump = game.bump,
+        constraint = game.admin == admin.key() @ ErrorCode::InvalidAdmin
+    )]
+    pub game: Account<'info, Game>,
+}
+
+impl<'info> TransferGameAdmin<'info> {
+    // Starts a transfer - the new admin has to accept before anything changes.
+    // Passing the current admin cancels a pending transfer.
+    pub fn transfer_game_admin(&mut self, new_admin: Pubkey) -> Result<()> {
+        require!(new_admin != Pubkey::default(), ErrorCode::InvalidNewAdmin);
+
+        if new_admin == self.game.admin {
+            self.game.pending_admin = None;
+            return Ok(());
+        }
+
+        self.game.pending_admin = Some(new_admin);
+
+        emit!(GameAdminTransferStarted {
+            game: self.game.key(),
+            admin: self.game.admin,
+            pending_admin: new_admin,
+        });
+
+        Ok(())
+    }
+}
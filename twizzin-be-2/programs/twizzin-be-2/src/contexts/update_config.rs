@@ -1,6 +1,7 @@
 use crate::constants::PROGRAM_AUTHORITY;
 use crate::errors::ErrorCode;
-use crate::state::config::ProgramConfig;
+use crate::state::config::{FeeRecipient, ProgramConfig, ReferralFeeSource};
+use crate::utils::fees::validate_fee_recipients;
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -24,6 +25,9 @@ impl<'info> UpdateConfig<'info> {
         &mut self,
         new_treasury: Option<Pubkey>,
         new_treasury_fee: Option<u16>,
+        new_fee_recipients: Option<Vec<FeeRecipient>>,
+        new_referral_fee: Option<u16>,
+        new_referral_fee_source: Option<ReferralFeeSource>,
     ) -> Result<()> {
         // Validate fee if provided
         if let Some(fee) = new_treasury_fee {
@@ -42,6 +46,27 @@ impl<'info> UpdateConfig<'info> {
             msg!("Treasury updated to: {}", treasury);
         }
 
+        // Validate and update fee recipients if provided
+        if let Some(fee_recipients) = new_fee_recipients {
+            validate_fee_recipients(&fee_recipients)?;
+            msg!(
+                "Fee recipients updated: {} recipients",
+                fee_recipients.len()
+            );
+            self.config.fee_recipients = fee_recipients;
+        }
+
+        // Validate referral fee if provided
+        if let Some(referral_fee) = new_referral_fee {
+            require!(referral_fee <= 1000, ErrorCode::ReferralFeeTooHigh);
+            self.config.referral_fee = referral_fee;
+            msg!("Referral fee updated to: {}bps", referral_fee);
+        }
+
+        if let Some(referral_fee_source) = new_referral_fee_source {
+            self.config.referral_fee_source = referral_fee_source;
+        }
+
         Ok(())
     }
 }
@@ -1,5 +1,14 @@
 use crate::errors::ErrorCode;
-use crate::state::{Game, GameUpdated, MAX_NAME_LENGTH, MAX_WINNERS};
+use crate::state::{
+    AccessMode, Game, GameOperator, Gate, GameUpdated, Season, MAX_NAME_LENGTH, MAX_WINNERS,
+    OPERATOR_UPDATE_GAME,
+};
+use crate::utils::access::validate_access_mode;
+use crate::utils::gate::validate_gate;
+use crate::utils::merkle::validate_merkle_version;
+use crate::utils::metadata::validate_metadata_uri;
+use crate::utils::scoring::validate_speed_bonus;
+use crate::utils::operator::check_game_authority;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
@@ -8,23 +17,27 @@ use anchor_spl::{
 
 #[derive(Accounts)]
 pub struct UpdateGame<'info> {
-    #[account(
-        constraint = game.admin == admin.key() @ ErrorCode::InvalidAuthority
-    )]
+    // The game admin or an operator allowed to update the game
     #[account(mut)]
-    pub admin: Signer<'info>,
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"game", admin.key().as_ref(), game.game_code.as_bytes()],
+        seeds = [b"game", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.bump,
     )]
     pub game: Account<'info, Game>,
 
+    #[account(
+        seeds = [b"game_operator", game.key().as_ref(), authority.key().as_ref()],
+        bump = game_operator.bump
+    )]
+    pub game_operator: Option<Account<'info, GameOperator>>,
+
     /// CHECK: The vault PDA that will own the token account
     #[account(
         mut,
-        seeds = [b"vault", admin.key().as_ref(), game.game_code.as_bytes()],
+        seeds = [b"vault", game.creator.as_ref(), game.game_code.as_bytes()],
         bump = game.vault_bump,
     )]
     pub vault: UncheckedAccount<'info>,
@@ -43,17 +56,26 @@ pub struct UpdateGame<'info> {
         mut,
         constraint = game.is_native || 
             (admin_token_account.to_account_info().key() != Pubkey::default() && 
-             admin_token_account.owner == admin.key() &&
+             admin_token_account.owner == game.admin &&
              admin_token_account.mint == token_mint.key())
     )]
     pub admin_token_account: Option<Account<'info, TokenAccount>>,
 
+    // Required to reschedule a season game, which has to stay inside the season
+    #[account(
+        seeds = [b"season", season.season_id.to_le_bytes().as_ref()],
+        bump = season.bump,
+        constraint = game.season == Some(season.key()) @ ErrorCode::InvalidSeason
+    )]
+    pub season: Option<Box<Account<'info, Season>>>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> UpdateGame<'info> {
+    #[allow(clippy::too_many_arguments)]
     pub fn update_game(
         &mut self,
         new_name: Option<String>,
@@ -66,13 +88,87 @@ impl<'info> UpdateGame<'info> {
         new_donation_amount: Option<u64>,
         new_all_are_winners: Option<bool>,
         new_even_split: Option<bool>,
+        new_access_mode: Option<AccessMode>,
+        new_gate: Option<Gate>,
+        new_metadata_uri: Option<String>,
+        new_question_set_hash: Option<[u8; 32]>,
+        new_wrong_answer_penalty: Option<u16>,
+        new_speed_bonus: Option<u16>,
+        new_speed_bonus_window: Option<u32>,
+        new_merkle_version: Option<u8>,
     ) -> Result<()> {
+        let is_admin = check_game_authority(
+            &self.game.admin,
+            &self.authority.key(),
+            self.game_operator.as_deref(),
+            OPERATOR_UPDATE_GAME,
+        )?;
+
+        // Operators only run the schedule and presentation - the answers, prizes,
+        // access and scoring stay the admin's call
+        require!(
+            is_admin
+                || (new_entry_fee.is_none()
+                    && new_commission.is_none()
+                    && new_max_winners.is_none()
+                    && new_answer_hash.is_none()
+                    && new_donation_amount.is_none()
+                    && new_all_are_winners.is_none()
+                    && new_even_split.is_none()
+                    && new_access_mode.is_none()
+                    && new_gate.is_none()
+                    && new_wrong_answer_penalty.is_none()
+                    && new_speed_bonus.is_none()
+                    && new_speed_bonus_window.is_none()
+                    && new_merkle_version.is_none()),
+            ErrorCode::OperatorNotPermitted
+        );
+
         let game = &mut self.game;
 
+        // A game has started once start_game ran, its start time passed or it was
+        // ended early. After that the start can't move back to reopen what it
+        // froze, and the end can only be pushed later while the game is running.
+        let current_time = Clock::get()?.unix_timestamp * 1000;
+        let started = game.started || game.ended || current_time >= game.start_time;
+        if started {
+            require!(new_start_time.is_none(), ErrorCode::ScheduleFrozen);
+            if let Some(end_time) = new_end_time {
+                require!(
+                    !game.ended && current_time < game.end_time && end_time > game.end_time,
+                    ErrorCode::ScheduleFrozen
+                );
+            }
+        }
+
+        // Players verify the questions against what was committed before the start
+        if new_metadata_uri.is_some() || new_question_set_hash.is_some() {
+            require!(!started, ErrorCode::GameMetadataFrozen);
+        }
+
+        // Changing how answers are marked mid-game would be unfair to whoever already submitted
+        if new_wrong_answer_penalty.is_some()
+            || new_speed_bonus.is_some()
+            || new_speed_bonus_window.is_some()
+        {
+            require!(!started, ErrorCode::ScoringFrozen);
+        }
+
+        // Players joined for the prize pool as it stood at the start
+        if new_donation_amount.is_some() {
+            require!(!started, ErrorCode::DonationFrozen);
+        }
+
+        // Submissions are checked against the answer hash, and a revealed key
+        // rescores everyone against it - a new one would change who was right
+        if new_answer_hash.is_some() || new_merkle_version.is_some() {
+            require!(!started, ErrorCode::AnswerHashFrozen);
+        }
+
         // Update name if provided
         if let Some(name) = new_name {
             require!(
-                name.len() > 0 && name.len() <= MAX_NAME_LENGTH,
+                !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
                 ErrorCode::NameTooLong
             );
             game.name = name;
@@ -80,6 +176,10 @@ impl<'info> UpdateGame<'info> {
 
         // Update entry fee if provided
         if let Some(entry_fee) = new_entry_fee {
+            require!(
+                game.tournament.is_none() || entry_fee == 0,
+                ErrorCode::TournamentRoundEntryFee
+            );
             game.entry_fee = entry_fee;
         }
 
@@ -92,6 +192,16 @@ impl<'info> UpdateGame<'info> {
         let start_time = new_start_time.unwrap_or(game.start_time);
         let end_time = new_end_time.unwrap_or(game.end_time);
         require!(start_time < end_time, ErrorCode::InvalidTimeRange);
+        if game.season.is_some() && (new_start_time.is_some() || new_end_time.is_some()) {
+            let season = self
+                .season
+                .as_ref()
+                .ok_or(ErrorCode::SeasonAccountsNotProvided)?;
+            require!(
+                start_time >= season.start_time && end_time <= season.end_time,
+                ErrorCode::GameOutsideSeason
+            );
+        }
 
         if new_start_time.is_some() {
             game.start_time = start_time;
@@ -112,6 +222,12 @@ impl<'info> UpdateGame<'info> {
             game.answer_hash = answer_hash;
         }
 
+        // Update merkle version if provided - goes with a rebuilt answer hash
+        if let Some(merkle_version) = new_merkle_version {
+            validate_merkle_version(merkle_version)?;
+            game.merkle_version = merkle_version;
+        }
+
         // Update all are winners if provided
         if let Some(all_are_winners) = new_all_are_winners {
             game.all_are_winners = all_are_winners;
@@ -122,6 +238,44 @@ impl<'info> UpdateGame<'info> {
             game.even_split = even_split;
         }
 
+        // Update access mode if provided
+        if let Some(access_mode) = new_access_mode {
+            validate_access_mode(&access_mode)?;
+            game.access_mode = access_mode;
+        }
+
+        // Update gate if provided - players who joined were checked against the old one
+        if let Some(gate) = new_gate {
+            require!(game.total_players == 0, ErrorCode::GateLocked);
+            validate_gate(&gate)?;
+            game.gate = gate;
+        }
+
+        // Update metadata uri if provided
+        if new_metadata_uri.is_some() {
+            validate_metadata_uri(&new_metadata_uri)?;
+            game.metadata_uri = new_metadata_uri;
+        }
+
+        // Update question set hash if provided
+        if let Some(question_set_hash) = new_question_set_hash {
+            game.question_set_hash = question_set_hash;
+        }
+
+        // Update wrong answer penalty if provided
+        if let Some(wrong_answer_penalty) = new_wrong_answer_penalty {
+            game.wrong_answer_penalty = wrong_answer_penalty;
+        }
+
+        // Update speed bonus if provided
+        if new_speed_bonus.is_some() || new_speed_bonus_window.is_some() {
+            let speed_bonus = new_speed_bonus.unwrap_or(game.speed_bonus);
+            let speed_bonus_window = new_speed_bonus_window.unwrap_or(game.speed_bonus_window);
+            validate_speed_bonus(speed_bonus, speed_bonus_window)?;
+            game.speed_bonus = speed_bonus;
+            game.speed_bonus_window = speed_bonus_window;
+        }
+
         // Handle donation amount changes if provided
         if let Some(new_amount) = new_donation_amount {
             if new_amount != game.donation_amount {
@@ -134,7 +288,7 @@ impl<'info> UpdateGame<'info> {
                         let cpi_context = CpiContext::new(
                             self.system_program.to_account_info(),
                             anchor_lang::system_program::Transfer {
-                                from: self.admin.to_account_info(),
+                                from: self.authority.to_account_info(),
                                 to: self.vault.to_account_info(),
                             },
                         );
@@ -156,7 +310,7 @@ impl<'info> UpdateGame<'info> {
                             anchor_spl::token::Transfer {
                                 from: admin_token_account.to_account_info(),
                                 to: vault_token_account.to_account_info(),
-                                authority: self.admin.to_account_info(),
+                                authority: self.authority.to_account_info(),
                             },
                         );
                         anchor_spl::token::transfer(transfer_ctx, additional_amount)?;
@@ -167,10 +321,10 @@ impl<'info> UpdateGame<'info> {
 
                     if game.is_native {
                         // Transfer SOL back to admin
-                        let admin_key = self.admin.key();
+                        let creator_key = game.creator;
                         let seeds = &[
                             b"vault",
-                            admin_key.as_ref(),
+                            creator_key.as_ref(),
                             game.game_code.as_bytes(),
                             &[game.vault_bump],
                         ];
@@ -180,7 +334,7 @@ impl<'info> UpdateGame<'info> {
                             self.system_program.to_account_info(),
                             anchor_lang::system_program::Transfer {
                                 from: self.vault.to_account_info(),
-                                to: self.admin.to_account_info(),
+                                to: self.authority.to_account_info(),
                             },
                             signer_seeds,
                         );
@@ -197,10 +351,10 @@ impl<'info> UpdateGame<'info> {
                             .as_ref()
                             .ok_or(ErrorCode::AdminTokenAccountNotProvided)?;
 
-                        let admin_key = self.admin.key();
+                        let creator_key = game.creator;
                         let vault_seeds = &[
                             b"vault",
-                            admin_key.as_ref(),
+                            creator_key.as_ref(),
                             game.game_code.as_bytes(),
                             &[game.vault_bump],
                         ];
@@ -226,7 +380,7 @@ impl<'info> UpdateGame<'info> {
 
         // Emit update event
         emit!(GameUpdated {
-            admin: self.admin.key(),
+            admin: self.game.admin,
             game: self.game.key(),
             name: self.game.name.clone(),
             entry_fee: self.game.entry_fee,
@@ -0,0 +1,102 @@
+use crate::constants::SOL_ADDRESS;
+use crate::errors::ErrorCode;
+use crate::state::{ReferralBalance, ReferralWithdrawn};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use std::str::FromStr;
+
+#[derive(Accounts)]
+pub struct WithdrawReferralBalance<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"referral", referrer.key().as_ref(), referral_balance.token_mint.as_ref()],
+        bump = referral_balance.bump,
+        constraint = referral_balance.referrer == referrer.key() @ ErrorCode::InvalidReferralAccount
+    )]
+    pub referral_balance: Account<'info, ReferralBalance>,
+
+    // Only needed for SPL token balances
+    #[account(
+        mut,
+        associated_token::mint = referral_balance.token_mint,
+        associated_token::authority = referral_balance
+    )]
+    pub referral_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = referrer_token_account.owner == referrer.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = referrer_token_account.mint == referral_balance.token_mint @ ErrorCode::InvalidTokenAccount
+    )]
+    pub referrer_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> WithdrawReferralBalance<'info> {
+    pub fn withdraw_referral_balance(&mut self) -> Result<()> {
+        let amount = self.referral_balance.balance;
+        require!(amount > 0, ErrorCode::NothingToWithdraw);
+
+        let is_native = self.referral_balance.token_mint == Pubkey::from_str(SOL_ADDRESS).unwrap();
+
+        if !is_native {
+            let referral_token_account = self
+                .referral_token_account
+                .as_ref()
+                .ok_or(ErrorCode::ReferralAccountsNotProvided)?;
+            let referrer_token_account = self
+                .referrer_token_account
+                .as_ref()
+                .ok_or(ErrorCode::PlayerTokenAccountNotProvided)?;
+
+            let referrer_key = self.referrer.key();
+            let token_mint = self.referral_balance.token_mint;
+            let seeds = &[
+                b"referral",
+                referrer_key.as_ref(),
+                token_mint.as_ref(),
+                &[self.referral_balance.bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: referral_token_account.to_account_info(),
+                    to: referrer_token_account.to_account_info(),
+                    authority: self.referral_balance.to_account_info(),
+                },
+                signer,
+            );
+            anchor_spl::token::transfer(transfer_ctx, amount)?;
+        } else {
+            // SOL rewards sit on the balance account itself on top of its rent
+            let balance_info = self.referral_balance.to_account_info();
+            let referrer_info = self.referrer.to_account_info();
+
+            **balance_info.try_borrow_mut_lamports()? = balance_info
+                .lamports()
+                .checked_sub(amount)
+                .ok_or(ErrorCode::NumericOverflow)?;
+            **referrer_info.try_borrow_mut_lamports()? = referrer_info
+                .lamports()
+                .checked_add(amount)
+                .ok_or(ErrorCode::NumericOverflow)?;
+        }
+
+        self.referral_balance.balance = 0;
+
+        emit!(ReferralWithdrawn {
+            referrer: self.referrer.key(),
+            token_mint: self.referral_balance.token_mint,
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -82,4 +82,150 @@ pub enum ErrorCode {
     UnclaimedPrizes,
     #[msg("Cannot close winner account")]
     CannotCloseWinnerAccount,
+    #[msg("Too many fee recipients - can't be more than 5")]
+    TooManyFeeRecipients,
+    #[msg("Fee recipient weights must sum to 10000 basis points")]
+    InvalidFeeRecipientWeights,
+    #[msg("Fee recipient accounts don't match config")]
+    InvalidFeeRecipient,
+    #[msg("Referral fee too high - can't be more than 10%")]
+    ReferralFeeTooHigh,
+    #[msg("Players can't refer themselves")]
+    SelfReferral,
+    #[msg("Referrer must have joined the game")]
+    ReferrerNotPlayer,
+    #[msg("Referral accounts not provided")]
+    ReferralAccountsNotProvided,
+    #[msg("Invalid referral account")]
+    InvalidReferralAccount,
+    #[msg("Nothing to withdraw")]
+    NothingToWithdraw,
+    #[msg("Season has already been settled")]
+    SeasonSettled,
+    #[msg("Season has not ended")]
+    SeasonNotEnded,
+    #[msg("Game is outside the season")]
+    GameOutsideSeason,
+    #[msg("Season has no more game slots")]
+    SeasonFull,
+    #[msg("Season accounts not provided")]
+    SeasonAccountsNotProvided,
+    #[msg("Invalid season")]
+    InvalidSeason,
+    #[msg("Season has no players on the leaderboard")]
+    EmptySeasonLeaderboard,
+    #[msg("Season winner accounts don't match the leaderboard")]
+    InvalidSeasonWinner,
+    #[msg("Tournament rounds can't charge an entry fee")]
+    TournamentRoundEntryFee,
+    #[msg("Tournament already has all its rounds")]
+    TournamentFull,
+    #[msg("Tournament registration is closed")]
+    TournamentRegistrationClosed,
+    #[msg("Tournament accounts not provided")]
+    TournamentAccountsNotProvided,
+    #[msg("Invalid tournament")]
+    InvalidTournament,
+    #[msg("Player did not advance to this round")]
+    NotAdvancedToRound,
+    #[msg("Tournament final round has not been played")]
+    TournamentNotComplete,
+    #[msg("Tournament has already been settled")]
+    TournamentSettled,
+    #[msg("Tournament has not been settled")]
+    TournamentNotSettled,
+    #[msg("Invalid number of tournament rounds")]
+    InvalidTournamentRounds,
+    #[msg("Invalid access mode")]
+    InvalidAccessMode,
+    #[msg("Access proof not provided")]
+    AccessProofNotProvided,
+    #[msg("Player is not on the allowlist")]
+    NotOnAllowlist,
+    #[msg("Invalid invite signature")]
+    InvalidInvite,
+    #[msg("Invite has expired")]
+    InviteExpired,
+    #[msg("Invalid gate")]
+    InvalidGate,
+    #[msg("Gate can't change once players have joined")]
+    GateLocked,
+    #[msg("Gate token account not provided")]
+    GateTokenAccountNotProvided,
+    #[msg("Gate token account must be owned by the player and hold the gated mint")]
+    GateInvalidTokenAccount,
+    #[msg("Not enough of the gated token to join")]
+    GateInsufficientBalance,
+    #[msg("Invalid NFT metadata account")]
+    GateInvalidMetadata,
+    #[msg("NFT is not from the gated collection")]
+    GateNftNotInCollection,
+    #[msg("NFT is not on the gated mint list")]
+    GateNftNotInList,
+    #[msg("Invalid operator permissions")]
+    InvalidOperatorPermissions,
+    #[msg("Operator doesn't have permission for this action")]
+    OperatorNotPermitted,
+    #[msg("Invalid new admin")]
+    InvalidNewAdmin,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("Game code is already in use")]
+    GameCodeTaken,
+    #[msg("Game code registry doesn't belong to this game")]
+    InvalidGameCodeRegistry,
+    #[msg("Metadata URI is empty or too long")]
+    InvalidMetadataUri,
+    #[msg("Game metadata can't change once the game has started")]
+    GameMetadataFrozen,
+    #[msg("Answer key is incomplete or out of display order")]
+    InvalidAnswerKey,
+    #[msg("Answer key doesn't match the game's answer hash")]
+    AnswerKeyMismatch,
+    #[msg("Answer is too long")]
+    AnswerTooLong,
+    #[msg("Scoring rules can't change once the game has started")]
+    ScoringFrozen,
+    #[msg("A speed bonus needs a window to decay over")]
+    InvalidSpeedBonus,
+    #[msg("Answer times must be in order within the player's time in the game")]
+    InvalidAnswerTime,
+    #[msg("Unknown merkle tree version")]
+    InvalidMerkleVersion,
+    #[msg("Batch answer verification needs a v2 merkle tree")]
+    MultiproofRequiresV2,
+    #[msg("Answer multiproof doesn't match the answer key")]
+    InvalidMultiproof,
+    #[msg("No answers have been submitted")]
+    NoAnswersSubmitted,
+    #[msg("Winners haven't been declared yet")]
+    WinnersNotDeclared,
+    #[msg("Account is already on the current layout")]
+    AlreadyMigrated,
+    #[msg("Referral rewards for this game have already been settled")]
+    ReferralAlreadySettled,
+    #[msg("Every referrer has to be paid before the game closes")]
+    ReferralsNotSettled,
+    #[msg("Host isn't approved to add games to this season")]
+    HostNotApproved,
+    #[msg("Season already has the most approved hosts")]
+    TooManySeasonHosts,
+    #[msg("Game code registry not provided")]
+    GameCodeRegistryNotProvided,
+    #[msg("Once the game has started its start time is fixed and its end can only be extended")]
+    ScheduleFrozen,
+    #[msg("Answer hash can't change once the game has started")]
+    AnswerHashFrozen,
+    #[msg("Every winner has already been declared")]
+    WinnersAlreadyDeclared,
+    #[msg("Answer key hasn't been revealed")]
+    AnswerKeyNotRevealed,
+    #[msg("Answer key has already been revealed")]
+    AnswerKeyAlreadyRevealed,
+    #[msg("Scores can't change once winners are being declared")]
+    ScoresFrozen,
+    #[msg("Every game in the season has to declare its winners first")]
+    SeasonGamesPending,
+    #[msg("Donation can't change once the game has started")]
+    DonationFrozen,
 }
@@ -10,7 +10,10 @@ pub mod errors;
 pub mod state;
 pub mod utils;
 
-use crate::state::AnswerInput;
+use crate::state::{
+    AccessMode, AccessProof, AnswerInput, AnswerKeyEntry, AnswerMultiproof, FeeRecipient, Gate,
+    MintListProof, ReferralFeeSource,
+};
 
 #[program]
 pub mod twizzin_be_2 {
@@ -20,18 +23,49 @@ pub mod twizzin_be_2 {
         ctx: Context<InitConfig>,
         treasury_pubkey: Pubkey,
         treasury_fee: u16,
+        fee_recipients: Vec<FeeRecipient>,
+        referral_fee: u16,
+        referral_fee_source: ReferralFeeSource,
     ) -> Result<()> {
-        ctx.accounts.init_config(treasury_pubkey, treasury_fee)
+        ctx.accounts.init_config(
+            treasury_pubkey,
+            treasury_fee,
+            fee_recipients,
+            referral_fee,
+            referral_fee_source,
+        )
     }
 
     pub fn update_config(
         ctx: Context<UpdateConfig>,
         new_treasury: Option<Pubkey>,
         new_treasury_fee: Option<u16>,
+        new_fee_recipients: Option<Vec<FeeRecipient>>,
+        new_referral_fee: Option<u16>,
+        new_referral_fee_source: Option<ReferralFeeSource>,
     ) -> Result<()> {
-        ctx.accounts.update_config(new_treasury, new_treasury_fee)
+        ctx.accounts.update_config(
+            new_treasury,
+            new_treasury_fee,
+            new_fee_recipients,
+            new_referral_fee,
+            new_referral_fee_source,
+        )
+    }
+
+    pub fn migrate_config(ctx: Context<MigrateConfig>) -> Result<()> {
+        ctx.accounts.migrate_config()
     }
 
+    pub fn migrate_game(ctx: Context<MigrateGame>) -> Result<()> {
+        ctx.accounts.migrate_game()
+    }
+
+    pub fn migrate_player_account(ctx: Context<MigratePlayerAccount>) -> Result<()> {
+        ctx.accounts.migrate_player_account()
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn init_game(
         ctx: Context<InitGame>,
         name: String,
@@ -45,6 +79,14 @@ pub mod twizzin_be_2 {
         donation_amount: u64,
         all_are_winners: bool,
         even_split: bool,
+        access_mode: AccessMode,
+        gate: Gate,
+        metadata_uri: Option<String>,
+        question_set_hash: [u8; 32],
+        wrong_answer_penalty: u16,
+        speed_bonus: u16,
+        speed_bonus_window: u32,
+        merkle_version: u8,
     ) -> Result<()> {
         ctx.accounts.init_game(
             name,
@@ -58,10 +100,19 @@ pub mod twizzin_be_2 {
             donation_amount,
             all_are_winners,
             even_split,
+            access_mode,
+            gate,
+            metadata_uri,
+            question_set_hash,
+            wrong_answer_penalty,
+            speed_bonus,
+            speed_bonus_window,
+            merkle_version,
             &ctx.bumps,
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update_game(
         ctx: Context<UpdateGame>,
         new_name: Option<String>,
@@ -74,6 +125,14 @@ pub mod twizzin_be_2 {
         new_donation_amount: Option<u64>,
         new_all_are_winners: Option<bool>,
         new_even_split: Option<bool>,
+        new_access_mode: Option<AccessMode>,
+        new_gate: Option<Gate>,
+        new_metadata_uri: Option<String>,
+        new_question_set_hash: Option<[u8; 32]>,
+        new_wrong_answer_penalty: Option<u16>,
+        new_speed_bonus: Option<u16>,
+        new_speed_bonus_window: Option<u32>,
+        new_merkle_version: Option<u8>,
     ) -> Result<()> {
         ctx.accounts.update_game(
             new_name,
@@ -86,11 +145,25 @@ pub mod twizzin_be_2 {
             new_donation_amount,
             new_all_are_winners,
             new_even_split,
+            new_access_mode,
+            new_gate,
+            new_metadata_uri,
+            new_question_set_hash,
+            new_wrong_answer_penalty,
+            new_speed_bonus,
+            new_speed_bonus_window,
+            new_merkle_version,
         )
     }
 
-    pub fn join_game(ctx: Context<JoinGame>) -> Result<()> {
-        ctx.accounts.join_game(&ctx.bumps)
+    pub fn join_game(
+        ctx: Context<JoinGame>,
+        referrer: Option<Pubkey>,
+        access_proof: Option<AccessProof>,
+        gate_proof: Option<MintListProof>,
+    ) -> Result<()> {
+        ctx.accounts
+            .join_game(referrer, access_proof, gate_proof, &ctx.bumps)
     }
 
     pub fn start_game(ctx: Context<StartGame>, total_time: i64) -> Result<()> {
@@ -102,11 +175,37 @@ pub mod twizzin_be_2 {
         answers: Vec<AnswerInput>,
         client_finish_time: i64,
     ) -> Result<()> {
-        ctx.accounts.submit_answers(answers, client_finish_time)
+        ctx.accounts
+            .submit_answers(answers, client_finish_time, &ctx.bumps)
     }
 
-    pub fn end_game(ctx: Context<EndGame>) -> Result<()> {
-        ctx.accounts.end_game()
+    pub fn submit_answers_batch(
+        ctx: Context<SubmitAnswers>,
+        answers: Vec<AnswerInput>,
+        multiproof: AnswerMultiproof,
+        client_finish_time: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .submit_answers_batch(answers, multiproof, client_finish_time, &ctx.bumps)
+    }
+
+    pub fn submit_answer_chunk(
+        ctx: Context<SubmitAnswerChunk>,
+        answers: Vec<AnswerInput>,
+    ) -> Result<()> {
+        ctx.accounts.submit_answer_chunk(answers, &ctx.bumps)
+    }
+
+    pub fn finalize_submission(
+        ctx: Context<FinalizeSubmission>,
+        client_finish_time: i64,
+    ) -> Result<()> {
+        ctx.accounts.finalize_submission(client_finish_time, &ctx.bumps)
+    }
+
+    pub fn end_game<'info>(ctx: Context<'_, '_, 'info, 'info, EndGame<'info>>) -> Result<()> {
+        let remaining_accounts: &'info [AccountInfo<'info>] = ctx.remaining_accounts;
+        ctx.accounts.end_game(remaining_accounts)
     }
 
     pub fn declare_winners<'info>(
@@ -129,4 +228,143 @@ pub mod twizzin_be_2 {
     pub fn close_player_account(ctx: Context<ClosePlayerAccount>) -> Result<()> {
         ctx.accounts.close_player_account()
     }
+
+    pub fn init_profile(ctx: Context<InitProfile>) -> Result<()> {
+        ctx.accounts.init_profile(&ctx.bumps)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_season(
+        ctx: Context<CreateSeason>,
+        season_id: u32,
+        name: String,
+        start_time: i64,
+        end_time: i64,
+        num_winners: u8,
+        even_split: bool,
+        max_games: u32,
+        prize_pool: u64,
+    ) -> Result<()> {
+        ctx.accounts.create_season(
+            season_id,
+            name,
+            start_time,
+            end_time,
+            num_winners,
+            even_split,
+            max_games,
+            prize_pool,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn approve_season_host(ctx: Context<ApproveSeasonHost>, host: Pubkey) -> Result<()> {
+        ctx.accounts.approve_season_host(host)
+    }
+
+    pub fn remove_season_host(ctx: Context<RemoveSeasonHost>, host: Pubkey) -> Result<()> {
+        ctx.accounts.remove_season_host(host)
+    }
+
+    pub fn settle_season<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleSeason<'info>>,
+    ) -> Result<()> {
+        let remaining_accounts: &'info [AccountInfo<'info>] = ctx.remaining_accounts;
+        ctx.accounts.settle_season(remaining_accounts)
+    }
+
+    pub fn settle_referral(ctx: Context<SettleReferral>) -> Result<()> {
+        ctx.accounts.settle_referral()
+    }
+
+    pub fn withdraw_referral_balance(ctx: Context<WithdrawReferralBalance>) -> Result<()> {
+        ctx.accounts.withdraw_referral_balance()
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_tournament(
+        ctx: Context<InitTournament>,
+        tournament_code: String,
+        name: String,
+        entry_fee: u64,
+        commission: u16,
+        num_rounds: u8,
+        advance_count: u8,
+        even_split: bool,
+        registration_end: i64,
+    ) -> Result<()> {
+        ctx.accounts.init_tournament(
+            tournament_code,
+            name,
+            entry_fee,
+            commission,
+            num_rounds,
+            advance_count,
+            even_split,
+            registration_end,
+            &ctx.bumps,
+        )
+    }
+
+    pub fn join_tournament(ctx: Context<JoinTournament>) -> Result<()> {
+        ctx.accounts.join_tournament(&ctx.bumps)
+    }
+
+    pub fn settle_tournament<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleTournament<'info>>,
+    ) -> Result<()> {
+        let remaining_accounts: &'info [AccountInfo<'info>] = ctx.remaining_accounts;
+        ctx.accounts.settle_tournament(remaining_accounts)
+    }
+
+    pub fn claim_tournament_prize(ctx: Context<ClaimTournamentPrize>) -> Result<()> {
+        ctx.accounts.claim_tournament_prize()
+    }
+
+    pub fn close_tournament(ctx: Context<CloseTournament>) -> Result<()> {
+        ctx.accounts.close_tournament()
+    }
+
+    pub fn set_game_operator(
+        ctx: Context<SetGameOperator>,
+        operator: Pubkey,
+        permissions: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_game_operator(operator, permissions, &ctx.bumps)
+    }
+
+    pub fn remove_game_operator(ctx: Context<RemoveGameOperator>) -> Result<()> {
+        ctx.accounts.remove_game_operator()
+    }
+
+    pub fn transfer_game_admin(ctx: Context<TransferGameAdmin>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.transfer_game_admin(new_admin)
+    }
+
+    pub fn accept_game_admin(ctx: Context<AcceptGameAdmin>) -> Result<()> {
+        ctx.accounts.accept_game_admin()
+    }
+
+    pub fn reveal_answer_key(
+        ctx: Context<RevealAnswerKey>,
+        entries: Vec<AnswerKeyEntry>,
+    ) -> Result<()> {
+        ctx.accounts.reveal_answer_key(entries, &ctx.bumps)
+    }
+
+    pub fn reveal_answer_key_chunk(
+        ctx: Context<RevealAnswerKeyChunk>,
+        entries: Vec<AnswerKeyEntry>,
+    ) -> Result<()> {
+        ctx.accounts.reveal_answer_key_chunk(entries, &ctx.bumps)
+    }
+
+    pub fn finalize_answer_key(ctx: Context<FinalizeAnswerKey>) -> Result<()> {
+        ctx.accounts.finalize_answer_key()
+    }
+
+    pub fn rescore_player(ctx: Context<RescorePlayer>) -> Result<()> {
+        ctx.accounts.rescore_player()
+    }
 }
@@ -0,0 +1,59 @@
+use crate::state::AnswerType;
+use anchor_lang::prelude::*;
+
+pub const MAX_ANSWER_LENGTH: usize = 32;
+pub const MAX_QUESTION_ID_LENGTH: usize = 36; // GUID
+pub const MAX_QUESTIONS: usize = u8::MAX as usize; // Display orders are a u8
+
+// One leaf of the game's answer merkle tree
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct AnswerKeyEntry {
+    pub display_order: u8,
+    pub answer_type: AnswerType,
+    pub answer: String,
+    pub question_id: String,
+    pub points: u16,
+}
+
+// Correct answers published after the game, checked against game.answer_hash.
+// A key too big for one transaction is revealed in chunks and only counts once
+// finalize_answer_key has checked the whole of it.
+#[account]
+pub struct AnswerKey {
+    pub game: Pubkey,
+    pub entries: Vec<AnswerKeyEntry>, // Ordered by display order
+    pub bump: u8,
+    pub revealed: bool, // Checked against the game's answer_hash
+}
+
+impl AnswerKey {
+    // Sized to the revealed entries rather than the maximum
+    pub fn space(entries: &[AnswerKeyEntry]) -> usize {
+        8 + // discriminator
+        32 + // game pubkey
+        4 + entries
+            .iter()
+            .map(|entry| {
+                1 + 17 + 4 + entry.answer.len() + 4 + entry.question_id.len() + 2
+            })
+            .sum::<usize>() + // entries
+        1 + // bump
+        1 // revealed
+    }
+}
+
+#[event]
+pub struct AnswerKeyRevealed {
+    pub game: Pubkey,
+    pub num_questions: u8,
+}
+
+#[event]
+pub struct PlayerRescored {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub previous_num_correct: u8,
+    pub num_correct: u8,
+    pub previous_score: i32,
+    pub score: i32,
+}
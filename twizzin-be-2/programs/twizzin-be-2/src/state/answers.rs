@@ -1,11 +1,71 @@
 use anchor_lang::prelude::*;
+
+// How a question is answered - part of its leaf, see utils::answer_format
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AnswerType {
+    Choice,                         // A single option ('a', 'b', 'c')
+    MultiSelect,                    // Comma separated options in any order ("c,a")
+    FreeText,                       // Compared ignoring case and extra whitespace
+    Numeric { min: i64, max: i64 }, // Any whole number in the range is correct
+}
+
 // Input structure for a single answer
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct AnswerInput {
     pub display_order: u8,
-    pub answer: String,       // The player's guess ('a', 'b', 'c'
-    pub question_id: String,  // The GUID that serves as the salt
-    pub proof: Vec<[u8; 32]>, // Merkle proof for this answer
+    pub answer_type: AnswerType, // Must match the type committed in the leaf
+    pub answer: String,          // The player's guess ('a', 'b', 'c' - empty to skip the question
+    pub question_id: String,     // The GUID that serves as the salt
+    pub points: u16,             // What the question is worth - part of the leaf
+    pub answered_at: i64,        // When the player answered in ms - only used for the speed bonus
+    pub proof: Vec<[u8; 32]>,    // Merkle proof for this answer
+}
+
+// One proof for every answer a player claims is correct, used by submit_answers_batch
+// in place of the per-answer proofs. Only v2 trees, whose leaves have fixed positions.
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct AnswerMultiproof {
+    pub claimed: Vec<u8>,     // Display orders the proof covers, in increasing order
+    pub proof: Vec<[u8; 32]>, // Siblings the claimed leaves can't rebuild, bottom layer first
+    pub depth: u8,            // Height of the answer tree
+}
+
+// A player's answer as it was submitted, without the proof
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SubmittedAnswer {
+    pub display_order: u8,
+    pub answer: String,
+    pub is_correct: bool, // Only the first answer to a question can count
+    pub answered_at: i64,
+}
+
+// Everything a player submitted, kept for disputes and per-question analytics
+#[account]
+pub struct Submission {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub answers: Vec<SubmittedAnswer>, // In the order they were submitted
+    pub bump: u8,
+}
+
+impl Submission {
+    // Sized to the submitted answers rather than the maximum
+    pub fn space(answer_lengths: impl Iterator<Item = usize>) -> usize {
+        8 + // discriminator
+        32 + // game pubkey
+        32 + // player pubkey
+        4 + answer_lengths.map(|len| 1 + 4 + len + 1 + 8).sum::<usize>() + // answers
+        1 // bump
+    }
+}
+
+#[event]
+pub struct AnswerChunkSubmitted {
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub num_answers: u8, // Answers in this chunk
+    pub num_correct: u8, // Running totals so far
+    pub score: i32,
 }
 
 #[event]
@@ -13,5 +73,6 @@ pub struct AnswersSubmitted {
     pub game: Pubkey,
     pub player: Pubkey,
     pub num_correct: u8,
+    pub score: i32,
     pub finished_time: i64,
 }
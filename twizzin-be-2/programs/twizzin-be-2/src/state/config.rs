@@ -1,12 +1,44 @@
 use anchor_lang::prelude::*;
 
+pub const MAX_FEE_RECIPIENTS: usize = 5;
+
+// Configs created before fee recipients and referral fees - see migrate_config
+pub const LEGACY_CONFIG_SPACE: usize = 8 + 32 + 32 + 2;
+
 #[account]
 pub struct ProgramConfig {
     pub treasury_pubkey: Pubkey,
     pub authority_pubkey: Pubkey,
     pub treasury_fee: u16,
+    // Optional split of the treasury fee - empty means it all goes to treasury_pubkey
+    pub fee_recipients: Vec<FeeRecipient>,
+    pub referral_fee: u16, // basis points (bps) of each referred player's entry fee
+    pub referral_fee_source: ReferralFeeSource,
+}
+
+// Which cut the referral rewards are paid out of
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ReferralFeeSource {
+    Treasury,
+    Commission,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeRecipient {
+    pub recipient: Pubkey, // Wallet that receives this share of the treasury fee
+    pub weight_bps: u16,   // Share of the treasury fee (all weights sum to 10_000)
 }
 
 impl Space for ProgramConfig {
-    const INIT_SPACE: usize = 8 + 32 + 32 + 2;
+    const INIT_SPACE: usize = 8 + 32 + 32 + 2 +
+        4 + (32 + 2) * MAX_FEE_RECIPIENTS + // fee recipients vec
+        2 +                                 // referral fee
+        1; // referral fee source
+}
+
+#[event]
+pub struct TreasuryFeeSplit {
+    pub game: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
 }
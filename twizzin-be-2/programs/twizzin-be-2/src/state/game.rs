@@ -2,6 +2,11 @@ use anchor_lang::prelude::*;
 
 pub const MAX_NAME_LENGTH: usize = 32;
 pub const MAX_GAME_CODE_LENGTH: usize = 16;
+pub const MAX_METADATA_URI_LENGTH: usize = 200;
+
+// Games created before admin transfers and everything added since, which stopped
+// at even_split - see migrate_game
+pub const LEGACY_GAME_SPACE: usize = 204;
 
 #[account]
 pub struct Game {
@@ -22,6 +27,55 @@ pub struct Game {
     pub is_native: bool,
     pub all_are_winners: bool,
     pub even_split: bool,
+    pub referred_players: u32,      // Players who joined with a referrer
+    pub season: Option<Pubkey>,     // Season this game counts towards
+    pub tournament: Option<Pubkey>, // Tournament this game is a round of
+    pub tournament_round: u8,       // Zero-based round index within the tournament
+    pub access_mode: AccessMode,    // Who is allowed to join
+    pub gate: Gate,                 // What players have to hold to join - locked once anyone joins
+    pub creator: Pubkey, // Seeds the game and vault PDAs so they survive admin transfers
+    pub pending_admin: Option<Pubkey>, // Set by transfer_game_admin until the new admin accepts
+    pub metadata_uri: Option<String>, // Off-chain JSON with description, image and question set id
+    pub question_set_hash: [u8; 32], // Commits to the full question content - frozen once started
+    pub wrong_answer_penalty: u16,   // Points lost per wrong answer - 0 turns negative marking off
+    pub speed_bonus: u16,            // Most a fast correct answer earns on top - 0 turns it off
+    pub speed_bonus_window: u32,     // Milliseconds for the speed bonus to decay to nothing
+    pub merkle_version: u8,          // Tree format answer_hash was built with, see utils::merkle
+    pub finished_players: u32,       // Players who submitted - the only ones who can win
+    pub ended: bool,                 // Set by end_game, so fees are only taken once
+    pub referral_reward: u64,        // Each referred player earns their referrer this much
+    pub referral_escrow: u64,        // Referral rewards held back until settle_referral pays them
+    pub code_registered: bool,       // Holds a code registry - games from before registries don't
+    pub started: bool,               // Set once the game starts - a later start time doesn't unset it
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AccessMode {
+    Public,
+    Allowlist { root: [u8; 32] }, // Merkle root of the wallets allowed to join
+    SignedInvite { invite_signer: Pubkey }, // Signs invites alongside the admin
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    Open,
+    Token { mint: Pubkey, min_amount: u64 }, // Hold at least min_amount of the mint
+    NftCollection { collection: Pubkey },    // Hold an NFT from a verified collection
+    NftMintList { root: [u8; 32] },          // Hold an NFT whose mint is in the merkle root
+}
+
+// Proof a player passes to join a game that isn't public
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum AccessProof {
+    Allowlist { proof: Vec<[u8; 32]>, index: u32 }, // index is the player's leaf position
+    SignedInvite { expiry: i64 }, // Signed invite is in the preceding ed25519 instruction
+}
+
+// Proof a player passes to join a mint list gated game
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintListProof {
+    pub proof: Vec<[u8; 32]>,
+    pub index: u32, // Position of the gate token's mint in the list
 }
 
 impl Space for Game {
@@ -42,7 +96,27 @@ impl Space for Game {
         8 +                        // donation_amount
         1 +                        // is_native
         1 +                        // all_are_winners
-        1; // even_split
+        1 +                        // even_split
+        4 +                        // referred_players
+        1 + 32 +                   // season
+        1 + 32 +                   // tournament
+        1 +                        // tournament round
+        1 + 32 +                   // access mode
+        1 + 40 +                   // gate
+        32 +                       // creator
+        1 + 32 +                   // pending admin
+        1 + 4 + MAX_METADATA_URI_LENGTH + // metadata uri
+        32 +                       // question set hash
+        2 +                        // wrong answer penalty
+        2 +                        // speed bonus
+        4 +                        // speed bonus window
+        1 +                        // merkle version
+        4 +                        // finished players
+        1 +                        // ended
+        8 +                        // referral reward
+        8 +                        // referral escrow
+        1 +                        // code registered
+        1;                         // started
 }
 
 #[event]
@@ -80,6 +154,7 @@ pub struct GameEnded {
     pub total_pot: u64,
     pub treasury_fee: u64,
     pub admin_commission: u64,
+    pub referral_rewards: u64,
     pub end_time: i64,
 }
 
@@ -89,3 +164,17 @@ pub struct GameClosed {
     pub admin: Pubkey,
     pub recovered_lamports: u64,
 }
+
+#[event]
+pub struct GameAdminTransferStarted {
+    pub game: Pubkey,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct GameAdminTransferred {
+    pub game: Pubkey,
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
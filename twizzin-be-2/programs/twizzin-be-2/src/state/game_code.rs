@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+// Maps a normalised game code to the one game using it, so players joining
+// by code can't land in another host's game with the same code.
+#[account]
+pub struct GameCodeRegistry {
+    pub game: Pubkey,
+    pub bump: u8,
+}
+
+impl GameCodeRegistry {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        32 +    // game pubkey
+        1; // bump
+}
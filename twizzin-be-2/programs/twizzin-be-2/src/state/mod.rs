@@ -12,3 +12,24 @@ pub use answers::*;
 
 pub mod winners;
 pub use winners::*;
+
+pub mod referral;
+pub use referral::*;
+
+pub mod profile;
+pub use profile::*;
+
+pub mod season;
+pub use season::*;
+
+pub mod tournament;
+pub use tournament::*;
+
+pub mod operator;
+pub use operator::*;
+
+pub mod game_code;
+pub use game_code::*;
+
+pub mod answer_key;
+pub use answer_key::*;
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+// Permission bits an admin can grant to a game operator
+pub const OPERATOR_START_END: u8 = 1 << 0; // start_game and end_game
+pub const OPERATOR_DECLARE_WINNERS: u8 = 1 << 1;
+pub const OPERATOR_UPDATE_GAME: u8 = 1 << 2; // Name, metadata and times only
+pub const OPERATOR_ALL_PERMISSIONS: u8 =
+    OPERATOR_START_END | OPERATOR_DECLARE_WINNERS | OPERATOR_UPDATE_GAME;
+
+// Lets another key run a game on the admin's behalf. Operators can never
+// move commission or donations - those still only go to the game admin - or
+// change the answers, prizes, access or scoring.
+#[account]
+pub struct GameOperator {
+    pub game: Pubkey,
+    pub operator: Pubkey,
+    pub permissions: u8,
+    pub bump: u8,
+    pub granted_by: Pubkey, // Admin who granted it - lapses once the game changes hands
+}
+
+impl GameOperator {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        32 +    // game pubkey
+        32 +    // operator pubkey
+        1 +     // permissions
+        1 +     // bump
+        32; // granted_by
+}
+
+#[event]
+pub struct GameOperatorSet {
+    pub game: Pubkey,
+    pub operator: Pubkey,
+    pub permissions: u8,
+}
+
+#[event]
+pub struct GameOperatorRemoved {
+    pub game: Pubkey,
+    pub operator: Pubkey,
+}
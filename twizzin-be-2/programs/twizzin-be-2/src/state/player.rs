@@ -1,5 +1,8 @@
 use anchor_lang::prelude::*;
 
+// Player accounts from before referrals and scoring - see migrate_player_account
+pub const LEGACY_PLAYER_SPACE: usize = 8 + 32 + 32 + 8 + 8 + 1 + 32 + 1;
+
 #[account]
 pub struct PlayerAccount {
     pub game: Pubkey,
@@ -9,6 +12,9 @@ pub struct PlayerAccount {
     pub num_correct: u8,
     pub answer_hash: [u8; 32], // Hash of their answers (set when submitting)
     pub bump: u8,              // PDA bump
+    pub referrer: Option<Pubkey>, // Wallet that referred this player
+    pub score: i32,               // Points and speed bonuses for correct answers less any penalties
+    pub answered: [u8; 32],       // Bitmap of answered display orders, kept across answer chunks
 }
 
 impl PlayerAccount {
@@ -19,7 +25,19 @@ impl PlayerAccount {
         8 +     // finished_time
         1 +     // num_correct
         32 +    // answer_hash
-        1; // bump
+        1 +     // bump
+        1 + 32 + // referrer
+        4 + // score
+        32; // answered
+
+    // Marks a question as answered, returning whether this is its first answer
+    pub fn mark_answered(&mut self, display_order: u8) -> bool {
+        let byte = display_order as usize / 8;
+        let bit = 1 << (display_order % 8);
+        let first = self.answered[byte] & bit == 0;
+        self.answered[byte] |= bit;
+        first
+    }
 }
 
 #[event]
@@ -27,6 +45,7 @@ pub struct PlayerJoined {
     pub game: Pubkey,
     pub player: Pubkey,
     pub join_time: i64,
+    pub referrer: Option<Pubkey>,
 }
 
 #[event]
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_PROFILE_MINTS: usize = 10;
+
+// Lifetime stats for a wallet across every game it has played
+#[account]
+pub struct PlayerProfile {
+    pub player: Pubkey,
+    pub games_played: u32,
+    pub total_correct: u32,
+    pub wins: u32,            // First place finishes
+    pub podium_finishes: u32, // Top 3 finishes
+    pub current_streak: u32,  // Consecutive games won a prize in
+    pub best_streak: u32,
+    pub winnings: Vec<MintWinnings>, // Total prizes claimed per mint
+    pub last_game: Pubkey,           // Most recent game answers were submitted for
+    pub last_game_won: bool,         // Whether a prize was claimed for last_game
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct MintWinnings {
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+impl PlayerProfile {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        32 +    // player pubkey
+        4 +     // games_played
+        4 +     // total_correct
+        4 +     // wins
+        4 +     // podium_finishes
+        4 +     // current_streak
+        4 +     // best_streak
+        4 + (32 + 8) * MAX_PROFILE_MINTS + // winnings vec
+        32 +    // last_game
+        1 +     // last_game_won
+        1; // bump
+}
+
+#[event]
+pub struct ProfileUpdated {
+    pub player: Pubkey,
+    pub games_played: u32,
+    pub total_correct: u32,
+    pub wins: u32,
+    pub podium_finishes: u32,
+    pub current_streak: u32,
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+// Claimable referral rewards for a referrer in a single mint
+#[account]
+pub struct ReferralBalance {
+    pub referrer: Pubkey,
+    pub token_mint: Pubkey,
+    pub balance: u64,      // Amount the referrer can withdraw
+    pub total_earned: u64, // Lifetime referral rewards
+    pub bump: u8,
+}
+
+impl ReferralBalance {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        32 +    // referrer pubkey
+        32 +    // token mint
+        8 +     // balance
+        8 +     // total_earned
+        1; // bump
+}
+
+// Number of players a referrer brought into a single game
+#[account]
+pub struct GameReferral {
+    pub game: Pubkey,
+    pub referrer: Pubkey,
+    pub referred_players: u32,
+    pub settled: bool, // Set once settle_referral has paid out the rewards
+    pub bump: u8,
+}
+
+impl GameReferral {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        32 +    // game pubkey
+        32 +    // referrer pubkey
+        4 +     // referred_players
+        1 +     // settled
+        1; // bump
+}
+
+#[event]
+pub struct ReferralRewarded {
+    pub game: Pubkey,
+    pub referrer: Pubkey,
+    pub referred_players: u32,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ReferralWithdrawn {
+    pub referrer: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
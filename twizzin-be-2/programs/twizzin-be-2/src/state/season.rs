@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+
+use crate::state::MAX_NAME_LENGTH;
+
+pub const MAX_SEASON_WINNERS: u8 = 10;
+pub const MAX_SEASON_HOSTS: usize = 20;
+
+#[account]
+pub struct Season {
+    pub season_id: u32,
+    pub name: String,
+    pub token_mint: Pubkey,
+    pub is_native: bool,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub prize_pool: u64,
+    pub num_winners: u8, // Number of leaderboard places paid out
+    pub even_split: bool,
+    pub max_games: u32, // Number of games that can opt in
+    pub num_games: u32,
+    pub games_declared: u32, // Games with all their winners in - settling waits for every one
+    pub settled: bool,
+    pub leaderboard: Vec<SeasonStanding>, // Top players ordered by points, then time reached
+    pub bump: u8,
+    pub vault_bump: u8,
+    pub approved_hosts: Vec<Pubkey>, // Admins allowed to add games to the season
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SeasonStanding {
+    pub player: Pubkey,
+    pub points: u32,
+    pub updated_at: i64, // When the player reached their points - earlier wins ties
+}
+
+impl Space for Season {
+    const INIT_SPACE: usize = 8 + // discriminator
+        4 +                       // season id
+        4 + MAX_NAME_LENGTH +     // name string
+        32 +                      // token mint
+        1 +                       // is_native
+        8 +                       // start time
+        8 +                       // end time
+        8 +                       // prize pool
+        1 +                       // num winners
+        1 +                       // even split
+        4 +                       // max games
+        4 +                       // num games
+        4 +                       // games declared
+        1 +                       // settled
+        4 + (32 + 4 + 8) * MAX_SEASON_WINNERS as usize + // leaderboard
+        1 +                       // bump
+        1 +                       // vault bump
+        4 + 32 * MAX_SEASON_HOSTS; // approved hosts
+}
+
+// A player's running points total for a season
+#[account]
+pub struct SeasonPlayer {
+    pub season: Pubkey,
+    pub player: Pubkey,
+    pub points: u32,
+    pub games_counted: u32,
+    pub updated_at: i64,
+    pub bump: u8,
+}
+
+impl SeasonPlayer {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        32 +    // season pubkey
+        32 +    // player pubkey
+        4 +     // points
+        4 +     // games_counted
+        8 +     // updated_at
+        1; // bump
+}
+
+#[event]
+pub struct SeasonCreated {
+    pub season: Pubkey,
+    pub season_id: u32,
+    pub name: String,
+    pub prize_pool: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct SeasonHostApproved {
+    pub season: Pubkey,
+    pub host: Pubkey,
+}
+
+#[event]
+pub struct SeasonHostRemoved {
+    pub season: Pubkey,
+    pub host: Pubkey,
+}
+
+#[event]
+pub struct SeasonPointsAwarded {
+    pub season: Pubkey,
+    pub game: Pubkey,
+    pub player: Pubkey,
+    pub points: u32,
+    pub total_points: u32,
+}
+
+#[event]
+pub struct SeasonSettled {
+    pub season: Pubkey,
+    pub num_winners: u8,
+    pub total_prize_pool: u64,
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{MAX_GAME_CODE_LENGTH, MAX_NAME_LENGTH};
+
+pub const MAX_TOURNAMENT_ROUNDS: u8 = 8;
+
+#[account]
+pub struct Tournament {
+    pub admin: Pubkey,
+    pub name: String,
+    pub tournament_code: String,
+    pub token_mint: Pubkey,
+    pub is_native: bool,
+    pub entry_fee: u64,  // Paid once into the tournament vault
+    pub commission: u16, // basis points (bps)
+    pub num_rounds: u8,
+    pub advance_count: u8, // Top K finishers of a round who can join the next one
+    pub even_split: bool,
+    pub registration_end: i64,
+    pub total_players: u32,
+    pub rounds: Vec<Pubkey>, // Round games in play order
+    pub settled: bool,
+    pub prize_pool: u64, // Pot left for the final round's winners once fees are taken
+    pub unclaimed_prizes: u8, // Final round winners yet to claim - the tournament closes at zero
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+impl Space for Tournament {
+    const INIT_SPACE: usize = 8 + // discriminator
+        32 +                       // admin pubkey
+        4 + MAX_NAME_LENGTH +      // name string
+        4 + MAX_GAME_CODE_LENGTH + // tournament code string
+        32 +                       // token mint
+        1 +                        // is_native
+        8 +                        // entry fee
+        2 +                        // commission
+        1 +                        // num rounds
+        1 +                        // advance count
+        1 +                        // even split
+        8 +                        // registration end
+        4 +                        // total players
+        4 + 32 * MAX_TOURNAMENT_ROUNDS as usize + // rounds
+        1 +                        // settled
+        8 +                        // prize pool
+        1 +                        // unclaimed prizes
+        1 +                        // bump
+        1; // vault bump
+}
+
+// A player's paid entry into a tournament
+#[account]
+pub struct TournamentEntry {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub rounds_joined: u8, // Number of rounds the player has joined so far
+    pub claimed: bool,
+    pub bump: u8,
+}
+
+impl TournamentEntry {
+    pub const INIT_SPACE: usize = 8 + // discriminator
+        32 +    // tournament pubkey
+        32 +    // player pubkey
+        1 +     // rounds_joined
+        1 +     // claimed
+        1; // bump
+}
+
+#[event]
+pub struct TournamentCreated {
+    pub admin: Pubkey,
+    pub tournament: Pubkey,
+    pub name: String,
+    pub tournament_code: String,
+    pub entry_fee: u64,
+    pub num_rounds: u8,
+    pub advance_count: u8,
+}
+
+#[event]
+pub struct TournamentJoined {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub join_time: i64,
+}
+
+#[event]
+pub struct TournamentRoundAdded {
+    pub tournament: Pubkey,
+    pub game: Pubkey,
+    pub round: u8,
+}
+
+#[event]
+pub struct TournamentSettled {
+    pub tournament: Pubkey,
+    pub total_pot: u64,
+    pub treasury_fee: u64,
+    pub admin_commission: u64,
+    pub prize_pool: u64,
+}
+
+#[event]
+pub struct TournamentPrizeClaimed {
+    pub tournament: Pubkey,
+    pub player: Pubkey,
+    pub rank: u8,
+    pub prize_amount: u64,
+}
+
+#[event]
+pub struct TournamentClosed {
+    pub tournament: Pubkey,
+    pub admin: Pubkey,
+    pub recovered_lamports: u64,
+}
@@ -2,12 +2,16 @@ use anchor_lang::prelude::*;
 
 pub const MAX_WINNERS: u8 = 200;
 
+// Winners are declared in batches, since a transaction only fits a few of them
 #[account]
 pub struct Winners {
     pub game: Pubkey,             // Game this winners account belongs to
     pub num_winners: u8,          // Actual number of winners
     pub winners: Vec<WinnerInfo>, // Vector of winners and their info
     pub bump: u8,                 // PDA bump
+    pub total_pot: u64,           // What the prizes are split from - fixed by the first batch
+    pub last_score: i32,          // Last declared winner's score - the next batch ranks below it
+    pub last_finished_time: i64,  // Last declared winner's finish time
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
@@ -24,7 +28,15 @@ impl Winners {
         1 +                              // num_winners
         1 +                              // bump
         4 +                              // vec len
-        (32 + 1 + 8 + 1) * MAX_WINNERS as usize; // max possible winners (pub key + rank + amount + claimed)
+        (32 + 1 + 8 + 1) * MAX_WINNERS as usize + // max possible winners (pub key + rank + amount + claimed)
+        8 +                               // total pot
+        4 +                               // last score
+        8;                                // last finished time
+
+    // Prizes can only be paid out once every winner is in
+    pub fn is_complete(&self) -> bool {
+        self.winners.len() == self.num_winners as usize
+    }
 }
 
 #[event]
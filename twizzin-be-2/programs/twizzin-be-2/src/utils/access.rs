@@ -0,0 +1,216 @@
+use crate::errors::ErrorCode;
+use crate::state::AccessMode;
+use crate::utils::merkle::LEAF_PREFIX;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use solana_program::{ed25519_program, instruction::Instruction};
+
+// Layout of the ed25519 program instruction data
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+const PUBKEY_SIZE: usize = 32;
+const SIGNATURE_SIZE: usize = 64;
+
+pub fn validate_access_mode(access_mode: &AccessMode) -> Result<()> {
+    if let AccessMode::Allowlist { root } = access_mode {
+        require!(*root != [0; 32], ErrorCode::InvalidAccessMode);
+    }
+    Ok(())
+}
+
+// Leaf for a wallet in a game's allowlist merkle tree, which is built like a v2
+// answer tree
+pub fn create_allowlist_leaf(player: &Pubkey) -> [u8; 32] {
+    hashv(&[&[LEAF_PREFIX], player.as_ref()]).to_bytes()
+}
+
+// Message the invite signer signs to let a player into a game
+pub fn create_invite_message(game: &Pubkey, player: &Pubkey, expiry: i64) -> Vec<u8> {
+    let mut message = Vec::with_capacity(32 + 32 + 8);
+    message.extend_from_slice(game.as_ref());
+    message.extend_from_slice(player.as_ref());
+    message.extend_from_slice(&expiry.to_le_bytes());
+    message
+}
+
+// Checks that an ed25519 program instruction verified exactly one signature
+// over `message` by `signer`. The runtime has already rejected the transaction
+// if the signature itself is bad, so only the signed data needs checking.
+pub fn verify_ed25519_instruction(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    require!(
+        ix.program_id == ed25519_program::ID,
+        ErrorCode::InvalidInvite
+    );
+    require!(ix.accounts.is_empty(), ErrorCode::InvalidInvite);
+
+    let data = &ix.data;
+    require!(
+        data.len() >= SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE,
+        ErrorCode::InvalidInvite
+    );
+    require!(data[0] == 1, ErrorCode::InvalidInvite);
+
+    let read_u16 = |index: usize| -> usize {
+        let start = SIGNATURE_OFFSETS_START + index * 2;
+        u16::from_le_bytes([data[start], data[start + 1]]) as usize
+    };
+
+    let signature_offset = read_u16(0);
+    let signature_instruction_index = read_u16(1);
+    let public_key_offset = read_u16(2);
+    let public_key_instruction_index = read_u16(3);
+    let message_data_offset = read_u16(4);
+    let message_data_size = read_u16(5);
+    let message_instruction_index = read_u16(6);
+
+    // All of the signed data has to live in this instruction
+    let this_instruction = u16::MAX as usize;
+    require!(
+        signature_instruction_index == this_instruction
+            && public_key_instruction_index == this_instruction
+            && message_instruction_index == this_instruction,
+        ErrorCode::InvalidInvite
+    );
+
+    require!(
+        data.len() >= signature_offset + SIGNATURE_SIZE,
+        ErrorCode::InvalidInvite
+    );
+
+    let public_key = data
+        .get(public_key_offset..public_key_offset + PUBKEY_SIZE)
+        .ok_or(ErrorCode::InvalidInvite)?;
+    require!(public_key == signer.as_ref(), ErrorCode::InvalidInvite);
+
+    let signed_message = data
+        .get(message_data_offset..message_data_offset + message_data_size)
+        .ok_or(ErrorCode::InvalidInvite)?;
+    require!(signed_message == message, ErrorCode::InvalidInvite);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::merkle::{compute_merkle_root_v2, hash_node, verify_merkle_proof_v2};
+
+    // Builds ed25519 instruction data the same way the web3.js helper does
+    fn ed25519_ix(signer: &Pubkey, message: &[u8]) -> Instruction {
+        let public_key_offset = SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE;
+        let signature_offset = public_key_offset + PUBKEY_SIZE;
+        let message_data_offset = signature_offset + SIGNATURE_SIZE;
+
+        let mut data = vec![1u8, 0u8];
+        for value in [
+            signature_offset,
+            u16::MAX as usize,
+            public_key_offset,
+            u16::MAX as usize,
+            message_data_offset,
+            message.len(),
+            u16::MAX as usize,
+        ] {
+            data.extend_from_slice(&(value as u16).to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[7u8; SIGNATURE_SIZE]);
+        data.extend_from_slice(message);
+
+        Instruction {
+            program_id: ed25519_program::ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    #[test]
+    fn test_allowlist_proof() {
+        let players: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = players.iter().map(create_allowlist_leaf).collect();
+
+        let left = hash_node(leaves[0], leaves[1]);
+        let right = hash_node(leaves[2], leaves[3]);
+        let root = hash_node(left, right);
+        assert_eq!(compute_merkle_root_v2(&leaves), Some(root));
+
+        assert!(verify_merkle_proof_v2(
+            leaves[2],
+            &[leaves[3], left],
+            2,
+            root
+        ));
+        assert!(!verify_merkle_proof_v2(
+            create_allowlist_leaf(&Pubkey::new_unique()),
+            &[leaves[3], left],
+            2,
+            root
+        ));
+
+        // The proof only holds at the player's own position
+        assert!(!verify_merkle_proof_v2(
+            leaves[2],
+            &[leaves[3], left],
+            3,
+            root
+        ));
+    }
+
+    #[test]
+    fn test_validate_access_mode() {
+        assert!(validate_access_mode(&AccessMode::Public).is_ok());
+        assert!(validate_access_mode(&AccessMode::Allowlist { root: [1; 32] }).is_ok());
+        assert!(validate_access_mode(&AccessMode::Allowlist { root: [0; 32] }).is_err());
+        // A blank invite signer leaves the admin as the only signer
+        assert!(validate_access_mode(&AccessMode::SignedInvite {
+            invite_signer: Pubkey::default()
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn test_invite_message_layout() {
+        let game = Pubkey::new_unique();
+        let player = Pubkey::new_unique();
+        let message = create_invite_message(&game, &player, 1_700_000_000_000);
+
+        assert_eq!(message.len(), 72);
+        assert_eq!(&message[..32], game.as_ref());
+        assert_eq!(&message[32..64], player.as_ref());
+        assert_eq!(message[64..], 1_700_000_000_000i64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_verify_ed25519_instruction() {
+        let signer = Pubkey::new_unique();
+        let message = create_invite_message(&Pubkey::new_unique(), &Pubkey::new_unique(), 5);
+        let ix = ed25519_ix(&signer, &message);
+
+        assert!(verify_ed25519_instruction(&ix, &signer, &message).is_ok());
+
+        // Wrong signer
+        assert!(verify_ed25519_instruction(&ix, &Pubkey::new_unique(), &message).is_err());
+
+        // Wrong message
+        let other = create_invite_message(&Pubkey::new_unique(), &Pubkey::new_unique(), 5);
+        assert!(verify_ed25519_instruction(&ix, &signer, &other).is_err());
+
+        // Not the ed25519 program
+        let mut wrong_program = ix.clone();
+        wrong_program.program_id = Pubkey::new_unique();
+        assert!(verify_ed25519_instruction(&wrong_program, &signer, &message).is_err());
+
+        // Signed data pulled from another instruction
+        let mut other_ix_data = ix.clone();
+        other_ix_data.data[SIGNATURE_OFFSETS_START + 6] = 0;
+        other_ix_data.data[SIGNATURE_OFFSETS_START + 7] = 0;
+        assert!(verify_ed25519_instruction(&other_ix_data, &signer, &message).is_err());
+
+        // Truncated data
+        let mut truncated = ix.clone();
+        truncated.data.truncate(20);
+        assert!(verify_ed25519_instruction(&truncated, &signer, &message).is_err());
+    }
+}
+
+// cargo test access -- --nocapture
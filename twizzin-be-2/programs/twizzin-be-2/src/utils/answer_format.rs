@@ -0,0 +1,136 @@
+use crate::state::AnswerType;
+
+// Tags each answer type in its leaf so one type can't pass for another
+pub fn answer_type_tag(answer_type: &AnswerType) -> u8 {
+    match answer_type {
+        AnswerType::Choice => 0,
+        AnswerType::MultiSelect => 1,
+        AnswerType::FreeText => 2,
+        AnswerType::Numeric { .. } => 3,
+    }
+}
+
+// Options in any order, repeated or padded with spaces encode the same way: "c, a,a" -> "a,c"
+pub fn canonicalize_multi_select(answer: &str) -> String {
+    let mut options: Vec<&str> = answer
+        .split(',')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .collect();
+    options.sort_unstable();
+    options.dedup();
+    options.join(",")
+}
+
+// Trimmed, runs of whitespace collapsed to a single space and lowercased with
+// str::to_lowercase: "  New   YORK " -> "new york". No unicode normalization is applied.
+pub fn canonicalize_free_text(answer: &str) -> String {
+    answer
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// Whole numbers only - questions needing decimals scale them into their units
+pub fn parse_numeric(answer: &str) -> Option<i64> {
+    answer.trim().parse().ok()
+}
+
+// The form of an answer that goes into its leaf. Numeric questions commit their
+// tolerance range instead, so the answer itself isn't part of the leaf.
+pub fn canonicalize_answer(answer_type: &AnswerType, answer: &str) -> String {
+    match answer_type {
+        AnswerType::Choice => answer.to_string(),
+        AnswerType::MultiSelect => canonicalize_multi_select(answer),
+        AnswerType::FreeText => canonicalize_free_text(answer),
+        AnswerType::Numeric { .. } => String::new(),
+    }
+}
+
+// The part of a submitted answer the leaf can't vouch for - a numeric answer
+// has to land inside the committed range
+pub fn answer_in_range(answer_type: &AnswerType, answer: &str) -> bool {
+    match answer_type {
+        AnswerType::Numeric { min, max } => {
+            parse_numeric(answer).is_some_and(|value| (*min..=*max).contains(&value))
+        }
+        _ => true,
+    }
+}
+
+// Checks a submitted answer against a revealed answer key entry
+pub fn answer_matches(answer_type: &AnswerType, expected: &str, submitted: &str) -> bool {
+    answer_in_range(answer_type, submitted)
+        && canonicalize_answer(answer_type, expected) == canonicalize_answer(answer_type, submitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choice() {
+        assert!(answer_matches(&AnswerType::Choice, "a", "a"));
+        assert!(!answer_matches(&AnswerType::Choice, "a", "b"));
+        // Options are ids, so they're compared exactly
+        assert!(!answer_matches(&AnswerType::Choice, "a", "A"));
+        assert!(!answer_matches(&AnswerType::Choice, "a", " a"));
+    }
+
+    #[test]
+    fn test_multi_select() {
+        assert_eq!(canonicalize_multi_select("c, a,a"), "a,c");
+        assert_eq!(canonicalize_multi_select("a,c"), "a,c");
+        assert_eq!(canonicalize_multi_select(" , "), "");
+
+        assert!(answer_matches(&AnswerType::MultiSelect, "a,c", "c,a"));
+        assert!(answer_matches(&AnswerType::MultiSelect, "a,c", " a , c ,"));
+        // It's all or nothing
+        assert!(!answer_matches(&AnswerType::MultiSelect, "a,c", "a"));
+        assert!(!answer_matches(&AnswerType::MultiSelect, "a,c", "a,b,c"));
+    }
+
+    #[test]
+    fn test_free_text() {
+        assert_eq!(canonicalize_free_text("  New   YORK "), "new york");
+        assert_eq!(canonicalize_free_text("new\tyork\n"), "new york");
+        assert_eq!(canonicalize_free_text("ÉCOLE"), "école");
+
+        assert!(answer_matches(&AnswerType::FreeText, "Paris", " paris"));
+        assert!(!answer_matches(
+            &AnswerType::FreeText,
+            "new york",
+            "newyork"
+        ));
+    }
+
+    #[test]
+    fn test_numeric() {
+        let answer_type = AnswerType::Numeric { min: 95, max: 105 };
+        assert_eq!(parse_numeric(" 100 "), Some(100));
+        assert_eq!(parse_numeric("-7"), Some(-7));
+        assert_eq!(parse_numeric("1.5"), None);
+
+        assert!(answer_in_range(&answer_type, "95"));
+        assert!(answer_in_range(&answer_type, "105"));
+        assert!(!answer_in_range(&answer_type, "94"));
+        assert!(!answer_in_range(&answer_type, "106"));
+        assert!(!answer_in_range(&answer_type, "one hundred"));
+        assert!(!answer_in_range(&answer_type, ""));
+
+        // The range is what's committed, so whatever the key lists as its answer is ignored
+        assert!(answer_matches(&answer_type, "", "101"));
+        assert!(answer_matches(&answer_type, "100", "99"));
+        assert!(!answer_matches(&answer_type, "100", "200"));
+    }
+
+    #[test]
+    fn test_other_types_are_always_in_range() {
+        assert!(answer_in_range(&AnswerType::Choice, "anything"));
+        assert!(answer_in_range(&AnswerType::MultiSelect, "a,b"));
+        assert!(answer_in_range(&AnswerType::FreeText, "anything"));
+    }
+}
+
+// cargo test answer_format -- --nocapture
@@ -0,0 +1,409 @@
+use crate::errors::ErrorCode;
+use crate::state::{
+    AnswerKeyEntry, SubmittedAnswer, MAX_ANSWER_LENGTH, MAX_QUESTIONS, MAX_QUESTION_ID_LENGTH,
+};
+use crate::utils::answer_format::answer_matches;
+use crate::utils::merkle::{compute_versioned_root, create_versioned_leaf_hash, leaf_covers};
+use crate::utils::scoring::ScoringRules;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+// Checks the revealed key is complete and rebuilds to the committed root.
+// Entries must be in display order since that's how the tree was built.
+pub fn verify_answer_key(
+    entries: &[AnswerKeyEntry],
+    answer_hash: [u8; 32],
+    merkle_version: u8,
+) -> Result<()> {
+    require!(
+        !entries.is_empty() && entries.len() <= MAX_QUESTIONS,
+        ErrorCode::InvalidAnswerKey
+    );
+
+    let mut leaves = Vec::with_capacity(entries.len());
+    for (i, entry) in entries.iter().enumerate() {
+        require!(
+            entry.display_order as usize == i,
+            ErrorCode::InvalidAnswerKey
+        );
+        require!(
+            entry.answer.len() <= MAX_ANSWER_LENGTH
+                && entry.question_id.len() <= MAX_QUESTION_ID_LENGTH,
+            ErrorCode::InvalidAnswerKey
+        );
+        require!(
+            leaf_covers(merkle_version, &entry.answer_type, entry.points),
+            ErrorCode::InvalidAnswerKey
+        );
+        leaves.push(create_versioned_leaf_hash(
+            merkle_version,
+            entry.display_order,
+            &entry.answer_type,
+            &entry.answer,
+            &entry.question_id,
+            entry.points,
+        ));
+    }
+
+    require!(
+        compute_versioned_root(merkle_version, &leaves) == Some(answer_hash),
+        ErrorCode::AnswerKeyMismatch
+    );
+    Ok(())
+}
+
+// Commits to exactly what a player submitted so it can be re-scored later
+pub fn create_submission_hash<'a>(answers: impl IntoIterator<Item = (u8, &'a str)>) -> [u8; 32] {
+    let answers: Vec<(u8, &str)> = answers.into_iter().collect();
+    let headers: Vec<[u8; 5]> = answers
+        .iter()
+        .map(|(display_order, answer)| {
+            let mut header = [*display_order, 0, 0, 0, 0];
+            header[1..].copy_from_slice(&(answer.len() as u32).to_le_bytes());
+            header
+        })
+        .collect();
+    let vals: Vec<&[u8]> = headers
+        .iter()
+        .zip(&answers)
+        .flat_map(|(header, (_, answer))| [header.as_slice(), answer.as_bytes()])
+        .collect();
+    hashv(&vals).to_bytes()
+}
+
+pub fn submitted_answer_pairs(answers: &[SubmittedAnswer]) -> impl Iterator<Item = (u8, &str)> {
+    answers.iter().map(|a| (a.display_order, a.answer.as_str()))
+}
+
+// Marks each answer against the key for its question type, returning the number
+// correct and the points they're worth under the game's scoring rules. Only the
+// first answer to each question can count. Empty answers are skips and score
+// nothing, anything else wrong costs the penalty.
+pub fn score_submission(
+    answers: &mut [SubmittedAnswer],
+    entries: &[AnswerKeyEntry],
+    rules: &ScoringRules,
+    player_start: i64,
+    finished_time: i64,
+) -> Result<(u8, i32)> {
+    let durations = rules.answer_durations(
+        player_start,
+        finished_time,
+        answers.iter().map(|answer| answer.answered_at),
+    )?;
+    let mut answered = [false; 256];
+    let mut num_correct: u8 = 0;
+    let mut score: i32 = 0;
+
+    for (answer, time_taken) in answers.iter_mut().zip(durations) {
+        let first = !answered[answer.display_order as usize];
+        answered[answer.display_order as usize] = true;
+
+        let entry = entries.get(answer.display_order as usize);
+        answer.is_correct = first
+            && entry.is_some_and(|entry| {
+                answer_matches(&entry.answer_type, &entry.answer, &answer.answer)
+            });
+        if let (true, Some(entry)) = (answer.is_correct, entry) {
+            num_correct = num_correct.saturating_add(1);
+            score = score
+                .checked_add(rules.correct_answer_points(entry.points, time_taken))
+                .ok_or(ErrorCode::NumericOverflow)?;
+        } else if first && !answer.answer.is_empty() {
+            score = score
+                .checked_sub(rules.wrong_answer_penalty as i32)
+                .ok_or(ErrorCode::NumericOverflow)?;
+        }
+    }
+
+    Ok((num_correct, score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::AnswerType;
+    use crate::utils::merkle::{
+        compute_merkle_root, compute_merkle_root_v2, create_leaf_hash, create_leaf_hash_v0,
+        create_leaf_hash_v2, LEGACY_POINTS, MERKLE_V0, MERKLE_V1, MERKLE_V2,
+    };
+
+    // Questions get harder, and are worth more, as the game goes on
+    fn entries() -> Vec<AnswerKeyEntry> {
+        [("a", 1), ("c", 2), ("b", 3), ("d", 5)]
+            .iter()
+            .enumerate()
+            .map(|(i, (answer, points))| AnswerKeyEntry {
+                display_order: i as u8,
+                answer_type: AnswerType::Choice,
+                answer: answer.to_string(),
+                question_id: format!("7f3c2a10-0000-4000-8000-00000000000{}", i),
+                points: *points,
+            })
+            .collect()
+    }
+
+    fn root(entries: &[AnswerKeyEntry]) -> [u8; 32] {
+        let leaves: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|e| {
+                create_leaf_hash(
+                    e.display_order,
+                    &e.answer_type,
+                    &e.answer,
+                    &e.question_id,
+                    e.points,
+                )
+            })
+            .collect();
+        compute_merkle_root(&leaves).unwrap()
+    }
+
+    fn root_v2(entries: &[AnswerKeyEntry]) -> [u8; 32] {
+        let leaves: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|e| {
+                create_leaf_hash_v2(
+                    e.display_order,
+                    &e.answer_type,
+                    &e.answer,
+                    &e.question_id,
+                    e.points,
+                )
+            })
+            .collect();
+        compute_merkle_root_v2(&leaves).unwrap()
+    }
+
+    fn submitted(answers: &[(u8, &str)]) -> Vec<SubmittedAnswer> {
+        answers
+            .iter()
+            .map(|(display_order, answer)| SubmittedAnswer {
+                display_order: *display_order,
+                answer: answer.to_string(),
+                is_correct: false,
+                answered_at: 0,
+            })
+            .collect()
+    }
+
+    fn score(
+        answers: &mut [SubmittedAnswer],
+        entries: &[AnswerKeyEntry],
+        wrong_answer_penalty: u16,
+    ) -> (u8, i32) {
+        let rules = ScoringRules {
+            wrong_answer_penalty,
+            ..Default::default()
+        };
+        score_submission(answers, entries, &rules, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_verify_answer_key() {
+        let entries = entries();
+        let answer_hash = root(&entries);
+        assert!(verify_answer_key(&entries, answer_hash, MERKLE_V1).is_ok());
+
+        // Wrong answer
+        let mut tampered = entries.clone();
+        tampered[2].answer = "a".to_string();
+        assert!(verify_answer_key(&tampered, answer_hash, MERKLE_V1).is_err());
+
+        // Wrong weight
+        let mut reweighted = entries.clone();
+        reweighted[0].points = 5;
+        assert!(verify_answer_key(&reweighted, answer_hash, MERKLE_V1).is_err());
+
+        // Missing question
+        assert!(verify_answer_key(&entries[..3], answer_hash, MERKLE_V1).is_err());
+
+        // Out of order
+        let mut swapped = entries.clone();
+        swapped.swap(0, 1);
+        assert!(verify_answer_key(&swapped, answer_hash, MERKLE_V1).is_err());
+
+        assert!(verify_answer_key(&[], answer_hash, MERKLE_V1).is_err());
+    }
+
+    #[test]
+    fn test_verify_answer_key_v2() {
+        let entries = entries();
+        let answer_hash = root_v2(&entries);
+        assert!(verify_answer_key(&entries, answer_hash, MERKLE_V2).is_ok());
+
+        // Keys are only checked against the game's own tree format
+        assert!(verify_answer_key(&entries, answer_hash, MERKLE_V1).is_err());
+        assert!(verify_answer_key(&entries, root(&entries), MERKLE_V2).is_err());
+
+        let mut tampered = entries.clone();
+        tampered[3].answer = "a".to_string();
+        assert!(verify_answer_key(&tampered, answer_hash, MERKLE_V2).is_err());
+    }
+
+    #[test]
+    fn test_verify_answer_key_v0() {
+        let mut entries = entries();
+        for entry in &mut entries {
+            entry.points = LEGACY_POINTS;
+        }
+        let leaves: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                create_leaf_hash_v0(entry.display_order, &entry.answer, &entry.question_id)
+            })
+            .collect();
+        let answer_hash = compute_merkle_root(&leaves).unwrap();
+        assert!(verify_answer_key(&entries, answer_hash, MERKLE_V0).is_ok());
+
+        // The leaves don't commit weights or types, so a key can't claim any
+        let mut reweighted = entries.clone();
+        reweighted[0].points = 5;
+        assert!(verify_answer_key(&reweighted, answer_hash, MERKLE_V0).is_err());
+        let mut retyped = entries.clone();
+        retyped[1].answer_type = AnswerType::FreeText;
+        assert!(verify_answer_key(&retyped, answer_hash, MERKLE_V0).is_err());
+    }
+
+    #[test]
+    fn test_score_submission() {
+        let entries = entries();
+
+        let mut all_correct = submitted(&[(0, "a"), (1, "c"), (2, "b"), (3, "d")]);
+        assert_eq!(score(&mut all_correct, &entries, 0), (4, 11));
+        assert!(all_correct.iter().all(|answer| answer.is_correct));
+
+        let mut some_correct = submitted(&[(0, "a"), (1, "b"), (3, "d")]);
+        assert_eq!(score(&mut some_correct, &entries, 0), (2, 6));
+        let flags: Vec<bool> = some_correct.iter().map(|a| a.is_correct).collect();
+        assert_eq!(flags, vec![true, false, true]);
+
+        // Repeating a question or answering one that doesn't exist doesn't score
+        let mut gamed = submitted(&[(0, "a"), (0, "a"), (0, "a"), (9, "a")]);
+        assert_eq!(score(&mut gamed, &entries, 0), (1, 1));
+
+        // A wrong first answer can't be fixed by answering again
+        let mut retried = submitted(&[(1, "a"), (1, "c")]);
+        assert_eq!(score(&mut retried, &entries, 0), (0, 0));
+    }
+
+    #[test]
+    fn test_fewer_hard_answers_outscore_many_easy_ones() {
+        let entries = entries();
+
+        let mut easy = submitted(&[(0, "a"), (1, "c"), (2, "x"), (3, "x")]);
+        let mut hard = submitted(&[(0, "x"), (1, "x"), (2, "x"), (3, "d")]);
+        let (easy_correct, easy_score) = score(&mut easy, &entries, 0);
+        let (hard_correct, hard_score) = score(&mut hard, &entries, 0);
+
+        assert!(easy_correct > hard_correct);
+        assert!(hard_score > easy_score);
+    }
+
+    #[test]
+    fn test_negative_marking() {
+        let entries = entries();
+
+        // Two right (1 + 5), one wrong, one skipped
+        let mut answers = submitted(&[(0, "a"), (1, "b"), (2, ""), (3, "d")]);
+        assert_eq!(score(&mut answers, &entries, 2), (2, 4));
+        let flags: Vec<bool> = answers.iter().map(|a| a.is_correct).collect();
+        assert_eq!(flags, vec![true, false, false, true]);
+
+        // Skipping everything scores zero rather than a penalty
+        let mut skipped = submitted(&[(0, ""), (1, ""), (2, ""), (3, "")]);
+        assert_eq!(score(&mut skipped, &entries, 2), (0, 0));
+
+        // Guessing badly can take the total below zero
+        let mut guessed = submitted(&[(0, "x"), (1, "x"), (2, "x"), (3, "x")]);
+        assert_eq!(score(&mut guessed, &entries, 2), (0, -8));
+
+        // Repeats of a wrong answer are only penalised once, answers to
+        // questions that don't exist are still wrong
+        let mut repeated = submitted(&[(1, "x"), (1, "x"), (9, "x")]);
+        assert_eq!(score(&mut repeated, &entries, 2), (0, -4));
+
+        // Without a penalty wrong answers are just worth nothing
+        let mut unpenalised = submitted(&[(0, "x"), (1, "c")]);
+        assert_eq!(score(&mut unpenalised, &entries, 0), (1, 2));
+    }
+
+    #[test]
+    fn test_mixed_answer_types() {
+        let entry = |display_order: u8, answer_type: AnswerType, answer: &str| AnswerKeyEntry {
+            display_order,
+            answer_type,
+            answer: answer.to_string(),
+            question_id: format!("question-{}", display_order),
+            points: 1,
+        };
+        let entries = vec![
+            entry(0, AnswerType::Choice, "b"),
+            entry(1, AnswerType::MultiSelect, "a,c"),
+            entry(2, AnswerType::FreeText, "New York"),
+            entry(3, AnswerType::Numeric { min: 95, max: 105 }, ""),
+        ];
+        assert!(verify_answer_key(&entries, root(&entries), MERKLE_V1).is_ok());
+
+        let mut answers = submitted(&[(0, "b"), (1, "c, a"), (2, "new  york"), (3, "101")]);
+        assert_eq!(score(&mut answers, &entries, 0), (4, 4));
+
+        let mut answers = submitted(&[(0, "B"), (1, "a"), (2, "newyork"), (3, "110")]);
+        assert_eq!(score(&mut answers, &entries, 0), (0, 0));
+
+        // Changing a question's type changes the root
+        let mut retyped = entries.clone();
+        retyped[1].answer_type = AnswerType::FreeText;
+        assert!(verify_answer_key(&retyped, root(&entries), MERKLE_V1).is_err());
+    }
+
+    #[test]
+    fn test_speed_bonus() {
+        let entries = entries();
+        let rules = ScoringRules {
+            wrong_answer_penalty: 0,
+            speed_bonus: 10,
+            speed_bonus_window: 10_000,
+        };
+
+        // Player starts at 1s - 1s on the first (1 + 9), 5s on the second (2 + 5),
+        // instantly wrong on the third and 20s on the last (5 + 0)
+        let mut answers = submitted(&[(0, "a"), (1, "c"), (2, "x"), (3, "d")]);
+        for (answer, answered_at) in answers.iter_mut().zip([2_000, 7_000, 7_000, 27_000]) {
+            answer.answered_at = answered_at;
+        }
+        assert_eq!(
+            score_submission(&mut answers, &entries, &rules, 1_000, 30_000).unwrap(),
+            (3, 22)
+        );
+
+        // Times outside the player's window are rejected
+        assert!(score_submission(&mut answers, &entries, &rules, 1_000, 20_000).is_err());
+        assert!(score_submission(&mut answers, &entries, &rules, 3_000, 30_000).is_err());
+    }
+
+    #[test]
+    fn test_submission_hash() {
+        let hash = |answers: &[(u8, &str)]| create_submission_hash(answers.iter().copied());
+
+        let answers = submitted(&[(0, "a"), (1, "c")]);
+        assert_eq!(
+            create_submission_hash(submitted_answer_pairs(&answers)),
+            hash(&[(0, "a"), (1, "c")])
+        );
+        assert_ne!(hash(&[(0, "a"), (1, "c")]), hash(&[(0, "a"), (1, "b")]));
+        // Length prefix keeps answer boundaries unambiguous
+        assert_ne!(hash(&[(0, "ab"), (1, "")]), hash(&[(0, "a"), (1, "b")]));
+        // Stored hashes predate the syscall, so the bytes hashed can't change
+        let hash: String = hash(&[(0, "a"), (1, "c")])
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        assert_eq!(
+            hash,
+            "4923a9170f1eeb56ad6ed6085778a38d1e530e595fd4b16c35451efdd96438f3"
+        );
+    }
+}
+
+// cargo test answer_key -- --nocapture
@@ -1,5 +1,7 @@
 use crate::errors::ErrorCode;
+use crate::state::{FeeRecipient, MAX_FEE_RECIPIENTS};
 use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
 
 pub fn calculate_fees(
     total_pot: u64,
@@ -50,10 +52,172 @@ pub fn calculate_fees(
     Ok((treasury_fee, admin_commission))
 }
 
+pub fn validate_fee_recipients(fee_recipients: &[FeeRecipient]) -> Result<()> {
+    require!(
+        fee_recipients.len() <= MAX_FEE_RECIPIENTS,
+        ErrorCode::TooManyFeeRecipients
+    );
+
+    // An empty list means the whole treasury fee goes to the treasury
+    if fee_recipients.is_empty() {
+        return Ok(());
+    }
+
+    let mut total_weight: u32 = 0;
+    for fee_recipient in fee_recipients {
+        require!(
+            !fee_recipient.recipient.eq(&Pubkey::default()),
+            ErrorCode::TreasuryAddressBlank
+        );
+        require!(
+            fee_recipient.weight_bps > 0,
+            ErrorCode::InvalidFeeRecipientWeights
+        );
+        total_weight += fee_recipient.weight_bps as u32;
+    }
+
+    require!(total_weight == 10000, ErrorCode::InvalidFeeRecipientWeights);
+
+    Ok(())
+}
+
+pub fn split_treasury_fee(treasury_fee: u64, weights_bps: &[u16]) -> Result<Vec<u64>> {
+    require!(
+        !weights_bps.is_empty() && weights_bps.len() <= MAX_FEE_RECIPIENTS,
+        ErrorCode::InvalidFeeRecipientWeights
+    );
+    require!(
+        weights_bps.iter().map(|w| *w as u32).sum::<u32>() == 10000,
+        ErrorCode::InvalidFeeRecipientWeights
+    );
+
+    let mut shares = Vec::with_capacity(weights_bps.len());
+    let mut remaining = treasury_fee;
+
+    for weight in weights_bps {
+        let share = (treasury_fee as u128)
+            .checked_mul(*weight as u128)
+            .ok_or(ErrorCode::NumericOverflow)?
+            .checked_div(10000)
+            .ok_or(ErrorCode::NumericOverflow)? as u64;
+
+        shares.push(share);
+        remaining = remaining
+            .checked_sub(share)
+            .ok_or(ErrorCode::NumericOverflow)?;
+    }
+
+    // Add any remaining dust to the first recipient due to integer division
+    if remaining > 0 {
+        shares[0] = shares[0]
+            .checked_add(remaining)
+            .ok_or(ErrorCode::NumericOverflow)?;
+    }
+
+    Ok(shares)
+}
+
+// A vault PDA paying out of its pot - lamports from the vault itself for SOL, tokens
+// from its token account for SPL
+pub struct VaultPayer<'a, 'info> {
+    pub is_native: bool,
+    pub vault: AccountInfo<'info>,
+    pub vault_token_account: Option<AccountInfo<'info>>,
+    pub signer: &'a [&'a [&'a [u8]]], // The vault's seeds
+    pub token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> VaultPayer<'_, 'info> {
+    pub fn transfer(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        if self.is_native {
+            let transfer_ctx = CpiContext::new_with_signer(
+                self.system_program.clone(),
+                anchor_lang::system_program::Transfer {
+                    from: self.vault.clone(),
+                    to,
+                },
+                self.signer,
+            );
+            anchor_lang::system_program::transfer(transfer_ctx, amount)
+        } else {
+            let vault_token_account = self
+                .vault_token_account
+                .clone()
+                .ok_or(ErrorCode::VaultTokenAccountNotProvided)?;
+
+            let transfer_ctx = CpiContext::new_with_signer(
+                self.token_program.clone(),
+                anchor_spl::token::Transfer {
+                    from: vault_token_account,
+                    to,
+                    authority: self.vault.clone(),
+                },
+                self.signer,
+            );
+            anchor_spl::token::transfer(transfer_ctx, amount)
+        }
+    }
+
+    // Pays each configured fee recipient its share of the treasury fee, returning
+    // what each was paid. Recipient accounts are passed as remaining accounts in
+    // config order - wallets for SOL, token accounts owned by the recipients for SPL.
+    pub fn pay_fee_recipients(
+        &self,
+        treasury_fee: u64,
+        fee_recipients: &[FeeRecipient],
+        token_mint: &Pubkey,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Vec<(Pubkey, u64)>> {
+        require!(
+            remaining_accounts.len() == fee_recipients.len(),
+            ErrorCode::InvalidFeeRecipient
+        );
+
+        let weights: Vec<u16> = fee_recipients.iter().map(|r| r.weight_bps).collect();
+        let shares = split_treasury_fee(treasury_fee, &weights)?;
+
+        let mut paid = Vec::with_capacity(shares.len());
+        for ((fee_recipient, account), share) in fee_recipients
+            .iter()
+            .zip(remaining_accounts.iter())
+            .zip(shares)
+        {
+            require!(account.is_writable, ErrorCode::InvalidFeeRecipient);
+
+            if self.is_native {
+                require!(
+                    account.key() == fee_recipient.recipient,
+                    ErrorCode::InvalidFeeRecipient
+                );
+            } else {
+                let token_account = Account::<TokenAccount>::try_from(account)?;
+                require!(
+                    token_account.owner == fee_recipient.recipient,
+                    ErrorCode::InvalidFeeRecipient
+                );
+                require!(
+                    token_account.mint == *token_mint,
+                    ErrorCode::InvalidFeeRecipient
+                );
+            }
+
+            if share > 0 {
+                self.transfer(account.clone(), share)?;
+                paid.push((fee_recipient.recipient, share));
+            }
+        }
+
+        Ok(paid)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use proptest::prelude::*;
+
     const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
     const MOCK_RENT_EXEMPTION: u64 = 890_880;
 
@@ -74,9 +238,9 @@ mod tests {
 
         // Test with token amount (no rent exemption)
         let (treasury_fee, admin_commission) = calculate_fees(
-            1000_000, // 1M tokens
-            500,      // 5%
-            200,      // 2%
+            1_000_000, // 1M tokens
+            500,       // 5%
+            200,       // 2%
             MOCK_RENT_EXEMPTION,
             false,
         )
@@ -176,6 +340,145 @@ mod tests {
         assert_eq!(treasury_fee, 0);
         assert_eq!(admin_commission, 0);
     }
+
+    #[test]
+    fn test_split_treasury_fee() {
+        // 50% ops, 30% DAO, 20% referral pool
+        let shares = split_treasury_fee(1_000_000, &[5000, 3000, 2000]).unwrap();
+        assert_eq!(shares, vec![500_000, 300_000, 200_000]);
+
+        // Single recipient gets everything
+        let shares = split_treasury_fee(LAMPORTS_PER_SOL, &[10000]).unwrap();
+        assert_eq!(shares, vec![LAMPORTS_PER_SOL]);
+
+        // Zero fee splits to zero
+        let shares = split_treasury_fee(0, &[5000, 5000]).unwrap();
+        assert_eq!(shares, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_split_treasury_fee_dust() {
+        // 100 / 3 doesn't divide evenly - dust goes to the first recipient
+        let shares = split_treasury_fee(100, &[3334, 3333, 3333]).unwrap();
+        assert_eq!(shares, vec![34, 33, 33]);
+        assert_eq!(shares.iter().sum::<u64>(), 100);
+
+        // Large amounts shouldn't overflow
+        let shares = split_treasury_fee(u64::MAX, &[9999, 1]).unwrap();
+        assert_eq!(shares.iter().sum::<u64>(), u64::MAX);
+    }
+
+    #[test]
+    fn test_split_treasury_fee_invalid_weights() {
+        assert!(split_treasury_fee(1_000, &[]).is_err());
+        assert!(split_treasury_fee(1_000, &[5000, 4000]).is_err());
+        assert!(split_treasury_fee(1_000, &[5000, 6000]).is_err());
+        assert!(split_treasury_fee(1_000, &[2000; 6]).is_err());
+    }
+
+    #[test]
+    fn test_validate_fee_recipients() {
+        let recipient = |weight_bps| FeeRecipient {
+            recipient: Pubkey::new_unique(),
+            weight_bps,
+        };
+
+        // Empty list is allowed - treasury gets the whole fee
+        assert!(validate_fee_recipients(&[]).is_ok());
+        assert!(validate_fee_recipients(&[recipient(10000)]).is_ok());
+        assert!(validate_fee_recipients(&[recipient(7000), recipient(3000)]).is_ok());
+
+        // Weights must sum to exactly 10_000
+        assert!(validate_fee_recipients(&[recipient(7000), recipient(2000)]).is_err());
+        // Zero weight recipients aren't allowed
+        assert!(validate_fee_recipients(&[recipient(10000), recipient(0)]).is_err());
+        // Blank recipient isn't allowed
+        assert!(validate_fee_recipients(&[FeeRecipient {
+            recipient: Pubkey::default(),
+            weight_bps: 10000,
+        }])
+        .is_err());
+        // Too many recipients
+        assert!(validate_fee_recipients(&[
+            recipient(2000),
+            recipient(2000),
+            recipient(2000),
+            recipient(2000),
+            recipient(1000),
+            recipient(1000),
+        ])
+        .is_err());
+    }
+
+    // Weights that sum to 10_000, from one recipient up to the maximum
+    fn weights() -> impl Strategy<Value = Vec<u16>> {
+        prop::collection::btree_set(1u16..10_000, 0..MAX_FEE_RECIPIENTS).prop_map(|cuts| {
+            let mut previous = 0;
+            cuts.into_iter()
+                .chain([10_000])
+                .map(|cut| {
+                    let weight = cut - previous;
+                    previous = cut;
+                    weight
+                })
+                .collect()
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn fees_are_floored_shares_of_the_distributable_pot(
+            total_pot in any::<u64>(),
+            treasury_fee_bps in 0u16..=1000,
+            commission_bps in 0u16..=1000,
+            rent_exemption in 0..=2 * MOCK_RENT_EXEMPTION,
+            is_native in any::<bool>(),
+        ) {
+            let (treasury_fee, admin_commission) = calculate_fees(
+                total_pot,
+                treasury_fee_bps,
+                commission_bps,
+                rent_exemption,
+                is_native,
+            )
+            .unwrap();
+
+            let distributable = if is_native {
+                total_pot.saturating_sub(rent_exemption)
+            } else {
+                total_pot
+            } as u128;
+            prop_assert_eq!(treasury_fee as u128, distributable * treasury_fee_bps as u128 / 10000);
+            prop_assert_eq!(admin_commission as u128, distributable * commission_bps as u128 / 10000);
+            // Both fees together never take more than 20% of the pot
+            prop_assert!(treasury_fee as u128 + admin_commission as u128 <= distributable / 5);
+        }
+
+        #[test]
+        fn fees_over_ten_percent_are_rejected(
+            total_pot in any::<u64>(),
+            too_high in 1001u16..,
+            bps in 0u16..=1000,
+        ) {
+            prop_assert!(calculate_fees(total_pot, too_high, bps, 0, false).is_err());
+            prop_assert!(calculate_fees(total_pot, bps, too_high, 0, false).is_err());
+        }
+
+        #[test]
+        fn treasury_fee_splits_add_up(treasury_fee in any::<u64>(), weights in weights()) {
+            let shares = split_treasury_fee(treasury_fee, &weights).unwrap();
+
+            prop_assert_eq!(shares.len(), weights.len());
+            prop_assert_eq!(shares.iter().map(|s| *s as u128).sum::<u128>(), treasury_fee as u128);
+            // Each recipient gets at least its weight, and only the first gets the dust
+            let floors: Vec<u64> = weights
+                .iter()
+                .map(|w| (treasury_fee as u128 * *w as u128 / 10000) as u64)
+                .collect();
+            prop_assert_eq!(&shares[1..], &floors[1..]);
+            prop_assert!(shares[0] - floors[0] < weights.len() as u64);
+        }
+    }
 }
 
 // cargo test fees -- --nocapture
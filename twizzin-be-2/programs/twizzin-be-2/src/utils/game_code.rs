@@ -0,0 +1,35 @@
+// Codes are matched case-insensitively and without surrounding whitespace.
+// ASCII-only upper-casing keeps the seed the same length as the input.
+pub fn normalize_game_code(game_code: &str) -> String {
+    game_code.trim().to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_game_code() {
+        assert_eq!(normalize_game_code("QUIZ1"), "QUIZ1");
+        assert_eq!(normalize_game_code("quiz1"), "QUIZ1");
+        assert_eq!(normalize_game_code("  Quiz1\t"), "QUIZ1");
+        assert_eq!(normalize_game_code("   "), "");
+    }
+
+    #[test]
+    fn test_normalized_codes_collide() {
+        let codes = ["quiz1", "QUIZ1", " Quiz1 ", "qUiZ1\n"];
+        for code in codes {
+            assert_eq!(normalize_game_code(code), normalize_game_code(codes[0]));
+        }
+        assert_ne!(normalize_game_code("QUIZ1"), normalize_game_code("QUIZ 1"));
+    }
+
+    #[test]
+    fn test_non_ascii_codes_keep_length() {
+        let code = "straße";
+        assert_eq!(normalize_game_code(code).len(), code.len());
+    }
+}
+
+// cargo test game_code -- --nocapture
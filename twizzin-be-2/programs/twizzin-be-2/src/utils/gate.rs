@@ -0,0 +1,205 @@
+use crate::errors::ErrorCode;
+use crate::state::Gate;
+use crate::utils::merkle::LEAF_PREFIX;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+pub fn validate_gate(gate: &Gate) -> Result<()> {
+    match gate {
+        Gate::Open => {}
+        Gate::Token { mint, min_amount } => {
+            require!(*mint != Pubkey::default(), ErrorCode::InvalidGate);
+            require!(*min_amount > 0, ErrorCode::InvalidGate);
+        }
+        Gate::NftCollection { collection } => {
+            require!(*collection != Pubkey::default(), ErrorCode::InvalidGate);
+        }
+        Gate::NftMintList { root } => {
+            require!(*root != [0; 32], ErrorCode::InvalidGate);
+        }
+    }
+    Ok(())
+}
+
+// Leaf for an NFT mint in a gate's mint list merkle tree
+pub fn create_mint_list_leaf(mint: &Pubkey) -> [u8; 32] {
+    hashv(&[&[LEAF_PREFIX], mint.as_ref()]).to_bytes()
+}
+
+// Reads the mint and verified collection out of a Metaplex metadata account.
+// Returns None if the data is malformed or the collection isn't verified.
+pub fn read_verified_collection(data: &[u8]) -> Option<(Pubkey, Pubkey)> {
+    let mut reader = MetadataReader { data, offset: 0 };
+
+    reader.skip(1)?; // key
+    reader.skip(32)?; // update authority
+    let mint = reader.pubkey()?;
+    reader.string()?; // name
+    reader.string()?; // symbol
+    reader.string()?; // uri
+    reader.skip(2)?; // seller fee basis points
+
+    // creators: Option<Vec<Creator { address, verified, share }>>
+    if reader.u8()? == 1 {
+        let num_creators = reader.u32()? as usize;
+        reader.skip(num_creators.checked_mul(32 + 1 + 1)?)?;
+    }
+
+    reader.skip(1)?; // primary sale happened
+    reader.skip(1)?; // is mutable
+
+    // edition nonce and token standard: Option<u8>
+    for _ in 0..2 {
+        if reader.u8()? == 1 {
+            reader.skip(1)?;
+        }
+    }
+
+    // collection: Option<Collection { verified, key }>
+    if reader.u8()? != 1 {
+        return None;
+    }
+    let verified = reader.u8()? == 1;
+    let collection = reader.pubkey()?;
+
+    verified.then_some((mint, collection))
+}
+
+struct MetadataReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> MetadataReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let end = self.offset.checked_add(len)?;
+        let bytes = self.data.get(self.offset..end)?;
+        self.offset = end;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn pubkey(&mut self) -> Option<Pubkey> {
+        self.take(32).map(|b| Pubkey::try_from(b).unwrap())
+    }
+
+    fn string(&mut self) -> Option<()> {
+        let len = self.u32()? as usize;
+        self.skip(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(mint: &Pubkey, creators: usize, collection: Option<(bool, Pubkey)>) -> Vec<u8> {
+        let mut data = vec![4u8]; // MetadataV1 key
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(mint.as_ref());
+        for field in ["Twizzin Pass", "TWZ", "https://example.com/pass.json"] {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        data.extend_from_slice(&500u16.to_le_bytes());
+        if creators > 0 {
+            data.push(1);
+            data.extend_from_slice(&(creators as u32).to_le_bytes());
+            for _ in 0..creators {
+                data.extend_from_slice(Pubkey::new_unique().as_ref());
+                data.push(1);
+                data.push(100 / creators as u8);
+            }
+        } else {
+            data.push(0);
+        }
+        data.push(1); // primary sale happened
+        data.push(1); // is mutable
+        data.extend_from_slice(&[1, 255]); // edition nonce
+        data.extend_from_slice(&[1, 0]); // token standard
+        match collection {
+            Some((verified, key)) => {
+                data.push(1);
+                data.push(verified as u8);
+                data.extend_from_slice(key.as_ref());
+            }
+            None => data.push(0),
+        }
+        // Trailing fields the gate doesn't read
+        data.extend_from_slice(&[0; 16]);
+        data
+    }
+
+    #[test]
+    fn test_validate_gate() {
+        assert!(validate_gate(&Gate::Open).is_ok());
+        assert!(validate_gate(&Gate::Token {
+            mint: Pubkey::new_unique(),
+            min_amount: 1
+        })
+        .is_ok());
+        assert!(validate_gate(&Gate::Token {
+            mint: Pubkey::new_unique(),
+            min_amount: 0
+        })
+        .is_err());
+        assert!(validate_gate(&Gate::Token {
+            mint: Pubkey::default(),
+            min_amount: 1
+        })
+        .is_err());
+        assert!(validate_gate(&Gate::NftCollection {
+            collection: Pubkey::default()
+        })
+        .is_err());
+        assert!(validate_gate(&Gate::NftMintList { root: [0; 32] }).is_err());
+    }
+
+    #[test]
+    fn test_read_verified_collection() {
+        let mint = Pubkey::new_unique();
+        let collection = Pubkey::new_unique();
+
+        let data = metadata(&mint, 2, Some((true, collection)));
+        assert_eq!(read_verified_collection(&data), Some((mint, collection)));
+
+        let no_creators = metadata(&mint, 0, Some((true, collection)));
+        assert_eq!(
+            read_verified_collection(&no_creators),
+            Some((mint, collection))
+        );
+    }
+
+    #[test]
+    fn test_read_unverified_or_missing_collection() {
+        let mint = Pubkey::new_unique();
+
+        let unverified = metadata(&mint, 1, Some((false, Pubkey::new_unique())));
+        assert_eq!(read_verified_collection(&unverified), None);
+
+        let missing = metadata(&mint, 1, None);
+        assert_eq!(read_verified_collection(&missing), None);
+    }
+
+    #[test]
+    fn test_read_truncated_metadata() {
+        let data = metadata(&Pubkey::new_unique(), 3, Some((true, Pubkey::new_unique())));
+        for len in [0, 1, 40, 100, 200] {
+            assert_eq!(read_verified_collection(&data[..len.min(data.len())]), None);
+        }
+    }
+}
+
+// cargo test gate -- --nocapture
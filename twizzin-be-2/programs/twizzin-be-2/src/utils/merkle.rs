@@ -1,4 +1,37 @@
-use sha2::{Digest, Sha256};
+use crate::errors::ErrorCode;
+use crate::state::AnswerType;
+use crate::utils::answer_format::{answer_in_range, answer_type_tag, canonicalize_answer};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+// Bumped whenever the layout of a leaf changes
+pub const LEAF_FORMAT_VERSION: u8 = 1;
+
+// Tree formats a game's answer_hash can be built with
+pub const MERKLE_V0: u8 = 0; // The original untyped leaves on a v1 tree - only migrated games use it
+pub const MERKLE_V1: u8 = 1; // Sorted pairs with no domain separation
+pub const MERKLE_V2: u8 = 2; // Prefixed leaves and nodes, proofs bound to the leaf's position
+
+// The original scoring counted correct answers, so every v0 question is worth a point
+pub const LEGACY_POINTS: u16 = 1;
+
+// v2 domain separation - an internal node can never be presented as a leaf
+pub const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+// v2 pads odd layers with this so every level of a proof has a sibling
+pub const EMPTY_NODE: [u8; 32] = [0; 32];
+
+// Display orders are a u8, so no answer tree is taller than this
+pub const MAX_TREE_DEPTH: u8 = 8;
+
+pub fn validate_merkle_version(merkle_version: u8) -> Result<()> {
+    require!(
+        merkle_version == MERKLE_V1 || merkle_version == MERKLE_V2,
+        ErrorCode::InvalidMerkleVersion
+    );
+    Ok(())
+}
 
 pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> bool {
     let mut current = leaf;
@@ -12,129 +45,887 @@ pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -
     current == root
 }
 
-// Helper function to create leaf node from answer data
-pub fn create_leaf_hash(display_order: u8, answer: &str, salt: &str) -> [u8; 32] {
-    let mut hasher = Sha256::new();
-    hasher.update([display_order]);
-    hasher.update(answer.as_bytes());
-    hasher.update(salt.as_bytes());
-    hasher.finalize().into()
+// Helper function to create leaf node from answer data.
+// Leaves are tagged with the format version and answer type. The answer is
+// canonicalized for its type, and numeric questions commit their tolerance range
+// in its place. Points are what the question is worth, so weights are committed
+// with the answers.
+pub fn create_leaf_hash(
+    display_order: u8,
+    answer_type: &AnswerType,
+    answer: &str,
+    salt: &str,
+    points: u16,
+) -> [u8; 32] {
+    leaf_hash(&[], display_order, answer_type, answer, salt, points)
+}
+
+// The leaf games were built with before answer types and points -
+// sha256(display_order || answer || salt), with the answer compared exactly
+pub fn create_leaf_hash_v0(display_order: u8, answer: &str, salt: &str) -> [u8; 32] {
+    sha256(&[&[display_order], answer.as_bytes(), salt.as_bytes()])
+}
+
+// The v2 leaf - the same fields behind the leaf prefix
+pub fn create_leaf_hash_v2(
+    display_order: u8,
+    answer_type: &AnswerType,
+    answer: &str,
+    salt: &str,
+    points: u16,
+) -> [u8; 32] {
+    leaf_hash(
+        &[LEAF_PREFIX],
+        display_order,
+        answer_type,
+        answer,
+        salt,
+        points,
+    )
+}
+
+fn leaf_hash(
+    prefix: &[u8],
+    display_order: u8,
+    answer_type: &AnswerType,
+    answer: &str,
+    salt: &str,
+    points: u16,
+) -> [u8; 32] {
+    let header = [
+        LEAF_FORMAT_VERSION,
+        answer_type_tag(answer_type),
+        display_order,
+    ];
+    let points = points.to_le_bytes();
+    match answer_type {
+        AnswerType::Numeric { min, max } => sha256(&[
+            prefix,
+            &header,
+            &min.to_le_bytes(),
+            &max.to_le_bytes(),
+            salt.as_bytes(),
+            &points,
+        ]),
+        _ => {
+            let answer = canonicalize_answer(answer_type, answer);
+            sha256(&[
+                prefix,
+                &header,
+                &(answer.len() as u32).to_le_bytes(),
+                answer.as_bytes(),
+                salt.as_bytes(),
+                &points,
+            ])
+        }
+    }
+}
+
+// Rebuilds the root the way the client builds its tree - leaves paired
+// left to right, with an odd node carried up to the next layer unchanged
+pub fn compute_merkle_root(leaves: &[[u8; 32]]) -> Option<[u8; 32]> {
+    let mut layer = leaves.to_vec();
+    if layer.is_empty() {
+        return None;
+    }
+
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [first, second] => hash_pair(*first, *second),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    Some(layer[0])
+}
+
+// Walks up from the leaf at `index`, whose bits say which side each sibling sits on.
+// The index has to fit within the proof so there's only one proof per position.
+pub fn verify_merkle_proof_v2(
+    leaf: [u8; 32],
+    proof: &[[u8; 32]],
+    index: u32,
+    root: [u8; 32],
+) -> bool {
+    if index.checked_shr(proof.len() as u32).unwrap_or(0) != 0 {
+        return false;
+    }
+
+    let mut current = leaf;
+    let mut index = index;
+    for sibling in proof {
+        current = if index & 1 == 0 {
+            hash_node(current, *sibling)
+        } else {
+            hash_node(*sibling, current)
+        };
+        index >>= 1;
+    }
+
+    current == root
+}
+
+// Builds a v2 root - leaves stay in position and odd layers are padded with EMPTY_NODE
+pub fn compute_merkle_root_v2(leaves: &[[u8; 32]]) -> Option<[u8; 32]> {
+    let mut layer = leaves.to_vec();
+    if layer.is_empty() {
+        return None;
+    }
+
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => hash_node(*left, *right),
+                [left] => hash_node(*left, EMPTY_NODE),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    Some(layer[0])
+}
+
+// Verifies a set of v2 leaves against the root with a single proof, so shared
+// siblings are sent and hashed once. `leaves` are (index, leaf) pairs in strictly
+// increasing index order and `proof` holds the siblings they can't rebuild, bottom
+// layer first and left to right within a layer. `depth` is the height of the tree,
+// which is at most MAX_TREE_DEPTH.
+pub fn verify_multiproof_v2(
+    leaves: &[(u32, [u8; 32])],
+    proof: &[[u8; 32]],
+    depth: u8,
+    root: [u8; 32],
+) -> bool {
+    let in_order = leaves.windows(2).all(|pair| pair[0].0 < pair[1].0);
+    let in_tree = match leaves.last() {
+        Some((index, _)) => (*index as u64) >> depth == 0,
+        None => false,
+    };
+    if depth > MAX_TREE_DEPTH || !in_order || !in_tree {
+        return false;
+    }
+
+    let mut layer = leaves.to_vec();
+    let mut proof = proof.iter();
+    for _ in 0..depth {
+        let mut next = Vec::with_capacity(layer.len());
+        let mut nodes = layer.iter().peekable();
+        while let Some(&(index, node)) = nodes.next() {
+            let parent = if index & 1 == 1 {
+                // A known left sibling would already have been paired with this node
+                match proof.next() {
+                    Some(sibling) => hash_node(*sibling, node),
+                    None => return false,
+                }
+            } else if let Some(&(_, sibling)) = nodes.next_if(|(next, _)| *next == index + 1) {
+                hash_node(node, sibling)
+            } else {
+                match proof.next() {
+                    Some(sibling) => hash_node(node, *sibling),
+                    None => return false,
+                }
+            };
+            next.push((index >> 1, parent));
+        }
+        layer = next;
+    }
+
+    proof.next().is_none() && layer == [(0, root)]
+}
+
+// Picks the leaf format for a game's merkle version
+pub fn create_versioned_leaf_hash(
+    merkle_version: u8,
+    display_order: u8,
+    answer_type: &AnswerType,
+    answer: &str,
+    salt: &str,
+    points: u16,
+) -> [u8; 32] {
+    match merkle_version {
+        MERKLE_V0 => create_leaf_hash_v0(display_order, answer, salt),
+        MERKLE_V2 => create_leaf_hash_v2(display_order, answer_type, answer, salt, points),
+        _ => create_leaf_hash(display_order, answer_type, answer, salt, points),
+    }
+}
+
+// A v0 leaf commits neither the answer type nor the points, so it can only vouch
+// for what every question was back then - a choice worth LEGACY_POINTS
+pub fn leaf_covers(merkle_version: u8, answer_type: &AnswerType, points: u16) -> bool {
+    merkle_version != MERKLE_V0 || (*answer_type == AnswerType::Choice && points == LEGACY_POINTS)
+}
+
+// Answer leaves sit at their display order, which v2 proofs are bound to
+pub fn verify_versioned_proof(
+    merkle_version: u8,
+    leaf: [u8; 32],
+    proof: &[[u8; 32]],
+    display_order: u8,
+    root: [u8; 32],
+) -> bool {
+    match merkle_version {
+        MERKLE_V2 => verify_merkle_proof_v2(leaf, proof, display_order as u32, root),
+        _ => verify_merkle_proof(leaf, proof, root),
+    }
+}
+
+pub fn compute_versioned_root(merkle_version: u8, leaves: &[[u8; 32]]) -> Option<[u8; 32]> {
+    match merkle_version {
+        MERKLE_V2 => compute_merkle_root_v2(leaves),
+        _ => compute_merkle_root(leaves),
+    }
+}
+
+// Helper to hash two v2 nodes together, in position order
+pub fn hash_node(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    sha256(&[&[NODE_PREFIX], &left, &right])
 }
 
 // Helper to hash two nodes together
-fn hash_pair(first: [u8; 32], second: [u8; 32]) -> [u8; 32] {
-    let mut hasher = Sha256::new();
+pub fn hash_pair(first: [u8; 32], second: [u8; 32]) -> [u8; 32] {
     // Sort to ensure consistent ordering
     if first <= second {
-        hasher.update(first);
-        hasher.update(second);
+        sha256(&[&first, &second])
     } else {
-        hasher.update(second);
-        hasher.update(first);
+        sha256(&[&second, &first])
     }
-    hasher.finalize().into()
+}
+
+// Every merkle hash goes through the sol_sha256 syscall, which is far cheaper
+// in compute units than hashing in the program itself
+fn sha256(vals: &[&[u8]]) -> [u8; 32] {
+    hashv(vals).to_bytes()
 }
 
 // Example usage in an instruction
 pub fn verify_answer(
     display_order: u8,
+    answer_type: &AnswerType,
     answer: &str,
     salt: &str,
+    points: u16,
     proof: &[[u8; 32]],
     root: [u8; 32],
 ) -> bool {
-    let leaf = create_leaf_hash(display_order, answer, salt);
-    verify_merkle_proof(leaf, proof, root)
-}
-
-// how to use in typescript:
-// import { createHash } from 'crypto';
-
-// interface Answer {
-//     display_order: number;
-//     answer: string;
-//     salt: string;
-// }
-
-// class MerkleTree {
-//     private layers: Buffer[][];
-
-//     constructor(answers: Answer[]) {
-//         // Create leaf nodes
-//         const leaves = answers.map(answer => this.createLeaf(answer));
-//         this.layers = [leaves];
-
-//         // Build tree layers
-//         while (this.layers[this.layers.length - 1].length > 1) {
-//             this.layers.push(this.createNextLayer(this.layers[this.layers.length - 1]));
-//         }
-//     }
-
-//     private createLeaf(answer: Answer): Buffer {
-//         const hash = createHash('sha256');
-//         hash.update(Buffer.from([answer.display_order]));
-//         hash.update(answer.answer);
-//         hash.update(answer.salt);
-//         return hash.digest();
-//     }
-
-//     private createNextLayer(nodes: Buffer[]): Buffer[] {
-//         const layerNodes: Buffer[] = [];
-//         for (let i = 0; i < nodes.length; i += 2) {
-//             if (i + 1 === nodes.length) {
-//                 layerNodes.push(nodes[i]);
-//             } else {
-//                 const hash = createHash('sha256');
-//                 if (Buffer.compare(nodes[i], nodes[i + 1]) <= 0) {
-//                     hash.update(nodes[i]);
-//                     hash.update(nodes[i + 1]);
-//                 } else {
-//                     hash.update(nodes[i + 1]);
-//                     hash.update(nodes[i]);
-//                 }
-//                 layerNodes.push(hash.digest());
-//             }
-//         }
-//         return layerNodes;
-//     }
-
-//     getRoot(): Buffer {
-//         return this.layers[this.layers.length - 1][0];
-//     }
-
-//     getProof(display_order: number): Buffer[] {
-//         let index = this.layers[0].findIndex((leaf, idx) =>
-//             idx === display_order
-//         );
-//         if (index === -1) return [];
-
-//         const proof: Buffer[] = [];
-//         for (let layerIndex = 0; layerIndex < this.layers.length - 1; layerIndex++) {
-//             const isRight = index % 2 === 0;
-//             const pairIndex = isRight ? index + 1 : index - 1;
-
-//             if (pairIndex < this.layers[layerIndex].length) {
-//                 proof.push(this.layers[layerIndex][pairIndex]);
-//             }
-
-//             index = Math.floor(index / 2);
-//         }
-
-//         return proof;
-//     }
-// }
-
-// // Example usage:
-// const answers: Answer[] = [
-//     { display_order: 0, answer: 'a', salt: 'q1' },
-//     { display_order: 1, answer: 'b', salt: 'q2' },
-//     { display_order: 2, answer: 'c', salt: 'q3' },
-// ];
-
-// const tree = new MerkleTree(answers);
-// const root = tree.getRoot();
-// const proofForAnswer0 = tree.getProof(0);
-
-// // These values would be used in your Solana program:
-// console.log('Root to store on-chain:', root);
-// console.log('Proof for answer verification:', proofForAnswer0);
+    let leaf = create_leaf_hash(display_order, answer_type, answer, salt, points);
+    answer_in_range(answer_type, answer) && verify_merkle_proof(leaf, proof, root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // What the runtime charges for sol_sha256 (solana 1.18 ComputeBudget defaults)
+    const SHA256_BASE_COST: u64 = 85;
+    const SHA256_BYTE_COST: u64 = 1;
+    const MEM_OP_BASE_COST: u64 = 10;
+
+    // Compute units one sol_sha256 call over `vals` costs
+    fn sha256_units(vals: &[&[u8]]) -> u64 {
+        vals.iter().fold(SHA256_BASE_COST, |units, val| {
+            units + MEM_OP_BASE_COST.max(SHA256_BYTE_COST * (val.len() as u64 / 2))
+        })
+    }
+
+    fn leaves(count: u8) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| create_leaf_hash(i, &AnswerType::Choice, "a", &format!("question-{}", i), 1))
+            .collect()
+    }
+
+    // Harder questions are worth more
+    fn weighted_leaves(points: &[u16]) -> Vec<[u8; 32]> {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, points)| {
+                create_leaf_hash(
+                    i as u8,
+                    &AnswerType::Choice,
+                    "b",
+                    &format!("question-{}", i),
+                    *points,
+                )
+            })
+            .collect()
+    }
+
+    // Mirrors MerkleTree.getProof from the typescript example below
+    fn proof(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+        let mut layer = leaves.to_vec();
+        let mut proof = Vec::new();
+        while layer.len() > 1 {
+            let pair_index = index ^ 1;
+            if pair_index < layer.len() {
+                proof.push(layer[pair_index]);
+            }
+            layer = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [first, second] => hash_pair(*first, *second),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            index /= 2;
+        }
+        proof
+    }
+
+    #[test]
+    fn test_compute_merkle_root_matches_proofs() {
+        for count in 1..=9 {
+            let leaves = leaves(count);
+            let root = compute_merkle_root(&leaves).unwrap();
+            for (i, leaf) in leaves.iter().enumerate() {
+                assert!(verify_merkle_proof(*leaf, &proof(&leaves, i), root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_compute_merkle_root_detects_changes() {
+        let mut leaves = leaves(5);
+        let root = compute_merkle_root(&leaves).unwrap();
+
+        leaves[3] = create_leaf_hash(3, &AnswerType::Choice, "b", "question-3", 1);
+        assert_ne!(compute_merkle_root(&leaves).unwrap(), root);
+
+        leaves.pop();
+        assert_ne!(compute_merkle_root(&leaves).unwrap(), root);
+    }
+
+    #[test]
+    fn test_weighted_tree_proofs() {
+        let points = [1, 2, 3, 5, 8, 13, 100];
+        let leaves = weighted_leaves(&points);
+        let root = compute_merkle_root(&leaves).unwrap();
+
+        for (i, points) in points.iter().enumerate() {
+            let salt = format!("question-{}", i);
+            let proof = proof(&leaves, i);
+            assert!(verify_answer(
+                i as u8,
+                &AnswerType::Choice,
+                "b",
+                &salt,
+                *points,
+                &proof,
+                root
+            ));
+            // Claiming a question is worth more than committed fails
+            assert!(!verify_answer(
+                i as u8,
+                &AnswerType::Choice,
+                "b",
+                &salt,
+                points + 1,
+                &proof,
+                root
+            ));
+        }
+    }
+
+    #[test]
+    fn test_points_change_the_leaf() {
+        assert_ne!(
+            create_leaf_hash(0, &AnswerType::Choice, "a", "question-0", 1),
+            create_leaf_hash(0, &AnswerType::Choice, "a", "question-0", 2)
+        );
+        assert_ne!(
+            compute_merkle_root(&weighted_leaves(&[1, 1, 1])),
+            compute_merkle_root(&weighted_leaves(&[1, 1, 3]))
+        );
+    }
+
+    #[test]
+    fn test_answer_types_tag_the_leaf() {
+        let leaf = |answer_type: AnswerType, answer: &str| {
+            create_leaf_hash(0, &answer_type, answer, "question-0", 1)
+        };
+
+        // The same answer under a different type is a different leaf
+        assert_ne!(
+            leaf(AnswerType::Choice, "a"),
+            leaf(AnswerType::MultiSelect, "a")
+        );
+        assert_ne!(
+            leaf(AnswerType::Choice, "a"),
+            leaf(AnswerType::FreeText, "a")
+        );
+
+        // Equivalent answers share a leaf
+        assert_eq!(
+            leaf(AnswerType::MultiSelect, "c,a"),
+            leaf(AnswerType::MultiSelect, "a, c")
+        );
+        assert_eq!(
+            leaf(AnswerType::FreeText, "Paris"),
+            leaf(AnswerType::FreeText, " paris ")
+        );
+        assert_ne!(leaf(AnswerType::Choice, "A"), leaf(AnswerType::Choice, "a"));
+
+        // Numeric leaves commit the range, not the answer
+        let range = AnswerType::Numeric { min: 95, max: 105 };
+        assert_eq!(leaf(range, "100"), leaf(range, ""));
+        assert_ne!(
+            leaf(range, "100"),
+            leaf(AnswerType::Numeric { min: 95, max: 106 }, "100")
+        );
+    }
+
+    #[test]
+    fn test_numeric_answers_must_be_in_range() {
+        let range = AnswerType::Numeric { min: 95, max: 105 };
+        let leaves = vec![
+            create_leaf_hash(0, &AnswerType::Choice, "a", "question-0", 1),
+            create_leaf_hash(1, &range, "", "question-1", 2),
+        ];
+        let root = compute_merkle_root(&leaves).unwrap();
+        let proof = proof(&leaves, 1);
+
+        assert!(verify_answer(
+            1,
+            &range,
+            "100",
+            "question-1",
+            2,
+            &proof,
+            root
+        ));
+        assert!(!verify_answer(
+            1,
+            &range,
+            "106",
+            "question-1",
+            2,
+            &proof,
+            root
+        ));
+        // Widening the range to fit the answer breaks the proof
+        let widened = AnswerType::Numeric { min: 95, max: 110 };
+        assert!(!verify_answer(
+            1,
+            &widened,
+            "106",
+            "question-1",
+            2,
+            &proof,
+            root
+        ));
+    }
+
+    #[test]
+    fn test_compute_merkle_root_empty() {
+        assert_eq!(compute_merkle_root(&[]), None);
+        assert_eq!(compute_merkle_root_v2(&[]), None);
+    }
+
+    fn leaves_v2(count: u8) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|i| {
+                create_leaf_hash_v2(i, &AnswerType::Choice, "a", &format!("question-{}", i), 1)
+            })
+            .collect()
+    }
+
+    // Mirrors MerkleTreeV2.getProof in tests/utils.ts
+    fn proof_v2(leaves: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+        let mut layer = leaves.to_vec();
+        let mut proof = Vec::new();
+        while layer.len() > 1 {
+            proof.push(layer.get(index ^ 1).copied().unwrap_or(EMPTY_NODE));
+            layer = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(*left, *right),
+                    [left] => hash_node(*left, EMPTY_NODE),
+                    _ => unreachable!(),
+                })
+                .collect();
+            index /= 2;
+        }
+        proof
+    }
+
+    #[test]
+    fn test_v2_proofs() {
+        for count in 1..=9 {
+            let leaves = leaves_v2(count);
+            let root = compute_merkle_root_v2(&leaves).unwrap();
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = proof_v2(&leaves, i);
+                assert!(verify_merkle_proof_v2(*leaf, &proof, i as u32, root));
+                // v1 verification doesn't understand the v2 tree
+                assert!(count == 1 || !verify_merkle_proof(*leaf, &proof, root));
+            }
+        }
+    }
+
+    #[test]
+    fn test_v2_proofs_are_bound_to_position() {
+        let leaves = leaves_v2(6);
+        let root = compute_merkle_root_v2(&leaves).unwrap();
+        let proof = proof_v2(&leaves, 2);
+
+        assert!(verify_merkle_proof_v2(leaves[2], &proof, 2, root));
+        for index in [0, 1, 3, 4, 5] {
+            assert!(!verify_merkle_proof_v2(leaves[2], &proof, index, root));
+        }
+        // Bits past the end of the proof would give one leaf several positions
+        assert!(!verify_merkle_proof_v2(leaves[2], &proof, 2 + 8, root));
+        assert!(!verify_merkle_proof_v2(leaves[2], &proof, u32::MAX, root));
+    }
+
+    #[test]
+    fn test_leaves_and_nodes_are_domain_separated() {
+        let leaves = leaves(2);
+        let (first, second) = (leaves[0].min(leaves[1]), leaves[0].max(leaves[1]));
+        let children = [first, second].concat();
+
+        // A v1 node is the plain hash of its children - the same as hashing
+        // those 64 bytes as leaf data, so a node could pass for a leaf
+        let as_leaf = hashv(&[&children]).to_bytes();
+        assert_eq!(as_leaf, hash_pair(leaves[0], leaves[1]));
+
+        // v2 hashes leaf data and nodes behind different prefixes
+        let as_leaf = hashv(&[&[LEAF_PREFIX], &children]).to_bytes();
+        assert_ne!(as_leaf, hash_node(first, second));
+        assert_ne!(LEAF_PREFIX, NODE_PREFIX);
+    }
+
+    #[test]
+    fn test_versioned_helpers() {
+        let leaf =
+            |version| create_versioned_leaf_hash(version, 0, &AnswerType::Choice, "a", "q", 1);
+        assert_eq!(
+            leaf(MERKLE_V1),
+            create_leaf_hash(0, &AnswerType::Choice, "a", "q", 1)
+        );
+        assert_eq!(
+            leaf(MERKLE_V2),
+            create_leaf_hash_v2(0, &AnswerType::Choice, "a", "q", 1)
+        );
+
+        let leaves = leaves_v2(3);
+        let root = compute_versioned_root(MERKLE_V2, &leaves).unwrap();
+        assert!(verify_versioned_proof(
+            MERKLE_V2,
+            leaves[1],
+            &proof_v2(&leaves, 1),
+            1,
+            root
+        ));
+        assert!(!verify_versioned_proof(
+            MERKLE_V1,
+            leaves[1],
+            &proof_v2(&leaves, 1),
+            1,
+            root
+        ));
+
+        assert!(validate_merkle_version(MERKLE_V1).is_ok());
+        assert!(validate_merkle_version(MERKLE_V2).is_ok());
+        // Only migrate_game sets v0
+        assert!(validate_merkle_version(MERKLE_V0).is_err());
+        assert!(validate_merkle_version(3).is_err());
+    }
+
+    // Built the way games were before answer types and points - the vectors come
+    // from hashing by hand, not from this module
+    #[test]
+    fn test_v0_matches_baseline_tree() {
+        let answers = [
+            ("a", "q1"),
+            ("c", "q2"),
+            ("b", "q3"),
+            ("d", "q4"),
+            ("a", "q5"),
+        ];
+        let leaves: Vec<[u8; 32]> = answers
+            .iter()
+            .enumerate()
+            .map(|(i, (answer, salt))| {
+                create_versioned_leaf_hash(MERKLE_V0, i as u8, &AnswerType::Choice, answer, salt, 1)
+            })
+            .collect();
+        assert_eq!(
+            to_hex(leaves[0]),
+            "51d97d26bc27e14799b3a5daf58ce4eb08e7ac6f1a69e6d8b18283c2ddd3e6c8"
+        );
+        let root = compute_versioned_root(MERKLE_V0, &leaves).unwrap();
+        assert_eq!(
+            to_hex(root),
+            "fd95d52e6825d577a81cc39d463ac7d1163ad6bd879bc316e612738cb7dc1aad"
+        );
+        for i in 0..leaves.len() {
+            assert!(verify_versioned_proof(
+                MERKLE_V0,
+                leaves[i],
+                &proof(&leaves, i),
+                i as u8,
+                root
+            ));
+        }
+
+        // The leaf ignores the type and points, so only a one point choice is vouched for
+        assert!(leaf_covers(MERKLE_V0, &AnswerType::Choice, LEGACY_POINTS));
+        assert!(!leaf_covers(MERKLE_V0, &AnswerType::Choice, 2));
+        assert!(!leaf_covers(
+            MERKLE_V0,
+            &AnswerType::FreeText,
+            LEGACY_POINTS
+        ));
+        assert!(leaf_covers(MERKLE_V1, &AnswerType::FreeText, 2));
+    }
+
+    fn to_hex(bytes: [u8; 32]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    // The same vectors are checked against the TypeScript builders in
+    // tests/merkleVectors.ts - change both together
+    fn vector_leaves(create: fn(u8, &AnswerType, &str, &str, u16) -> [u8; 32]) -> Vec<[u8; 32]> {
+        vec![
+            create(0, &AnswerType::Choice, "a", "q1", 1),
+            create(1, &AnswerType::MultiSelect, "c, a", "q2", 2),
+            create(2, &AnswerType::FreeText, " New  York", "q3", 3),
+            create(3, &AnswerType::Numeric { min: -5, max: 105 }, "", "q4", 5),
+            create(4, &AnswerType::Choice, "b", "q5", 8),
+        ]
+    }
+
+    #[test]
+    fn test_cross_language_vectors() {
+        let leaves = vector_leaves(create_leaf_hash);
+        assert_eq!(
+            to_hex(leaves[0]),
+            "ed4087381daf3a41bfc20653259b17a8a01be7a34337831be515cb8365d3bddb"
+        );
+        assert_eq!(
+            to_hex(leaves[3]),
+            "5fc6006f278403d6beca755af6fadf8d098b42f7c5a1221b5fceb70c7b9f8fae"
+        );
+        assert_eq!(
+            to_hex(compute_merkle_root(&leaves).unwrap()),
+            "3dbad17fa49bb519347f206314a503295b45ed267282f76abee9bf2402367601"
+        );
+
+        let leaves = vector_leaves(create_leaf_hash_v2);
+        assert_eq!(
+            to_hex(leaves[0]),
+            "bc29b8f9cd665cdb4d1f5941f2d22db9507e4d012a76828d044412aa6c2f7042"
+        );
+        assert_eq!(
+            to_hex(leaves[3]),
+            "b036dbb434b038ef6d2002d7a784758af53dd9daa82130d48d7999563117d71d"
+        );
+        let root = compute_merkle_root_v2(&leaves).unwrap();
+        assert_eq!(
+            to_hex(root),
+            "a9a4ad2809f047af2ec029096549f758c41d030cb55c4d95086044cf6c7c35f2"
+        );
+        let proof: Vec<String> = proof_v2(&leaves, 4).into_iter().map(to_hex).collect();
+        assert_eq!(
+            proof,
+            vec![
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "3e1ceabf41165ef55276e95eda494a37b6835379a028d63ec233da53837907b3"
+            ]
+        );
+    }
+
+    // Mirrors MerkleTree.getMultiproof in tests/utils.ts
+    fn multiproof_v2(leaves: &[[u8; 32]], indices: &[u32]) -> (Vec<[u8; 32]>, u8) {
+        let mut layer = leaves.to_vec();
+        let mut known: Vec<u32> = indices.to_vec();
+        let mut proof = Vec::new();
+        let mut depth = 0;
+        while layer.len() > 1 {
+            for index in &known {
+                let sibling = index ^ 1;
+                if !known.contains(&sibling) {
+                    proof.push(layer.get(sibling as usize).copied().unwrap_or(EMPTY_NODE));
+                }
+            }
+            known = known.iter().map(|index| index >> 1).collect();
+            known.dedup();
+            layer = layer
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => hash_node(*left, *right),
+                    [left] => hash_node(*left, EMPTY_NODE),
+                    _ => unreachable!(),
+                })
+                .collect();
+            depth += 1;
+        }
+        (proof, depth)
+    }
+
+    fn claimed(leaves: &[[u8; 32]], indices: &[u32]) -> Vec<(u32, [u8; 32])> {
+        indices
+            .iter()
+            .map(|index| (*index, leaves[*index as usize]))
+            .collect()
+    }
+
+    #[test]
+    fn test_multiproofs() {
+        for count in 1..=12u8 {
+            let leaves = leaves_v2(count);
+            let root = compute_merkle_root_v2(&leaves).unwrap();
+            let all: Vec<u32> = (0..count as u32).collect();
+            let subsets = [
+                all.clone(),
+                all.iter().copied().filter(|i| i % 2 == 0).collect(),
+                all.iter().copied().filter(|i| i % 3 == 1).collect(),
+                vec![count as u32 - 1],
+            ];
+            for indices in subsets.iter().filter(|indices| !indices.is_empty()) {
+                let (proof, depth) = multiproof_v2(&leaves, indices);
+                assert!(verify_multiproof_v2(
+                    &claimed(&leaves, indices),
+                    &proof,
+                    depth,
+                    root
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_multiproof_rejects_tampering() {
+        let leaves = leaves_v2(7);
+        let root = compute_merkle_root_v2(&leaves).unwrap();
+        let indices = [1, 2, 5];
+        let (proof, depth) = multiproof_v2(&leaves, &indices);
+        let claimed = claimed(&leaves, &indices);
+        assert!(verify_multiproof_v2(&claimed, &proof, depth, root));
+
+        // A wrong answer anywhere breaks the whole proof
+        let mut wrong = claimed.clone();
+        wrong[1].1 = create_leaf_hash_v2(2, &AnswerType::Choice, "b", "question-2", 1);
+        assert!(!verify_multiproof_v2(&wrong, &proof, depth, root));
+
+        // Leaves are bound to their positions
+        let mut moved = claimed.clone();
+        moved[2].0 = 6;
+        assert!(!verify_multiproof_v2(&moved, &proof, depth, root));
+        let mut outside = claimed.clone();
+        outside[2].0 = 5 + 8;
+        assert!(!verify_multiproof_v2(&outside, &proof, depth, root));
+
+        // Leaves have to be sorted and can't repeat
+        let unsorted = vec![claimed[1], claimed[0], claimed[2]];
+        assert!(!verify_multiproof_v2(&unsorted, &proof, depth, root));
+        let repeated = vec![claimed[0], claimed[0], claimed[1], claimed[2]];
+        assert!(!verify_multiproof_v2(&repeated, &proof, depth, root));
+
+        // Every sibling has to be used, and the tree height has to be right
+        assert!(!verify_multiproof_v2(&claimed, &proof[1..], depth, root));
+        let extra = [proof.clone(), vec![EMPTY_NODE]].concat();
+        assert!(!verify_multiproof_v2(&claimed, &extra, depth, root));
+        assert!(!verify_multiproof_v2(&claimed, &proof, depth - 1, root));
+        assert!(!verify_multiproof_v2(&claimed, &proof, depth + 1, root));
+        assert!(!verify_multiproof_v2(&[], &proof, depth, root));
+
+        // Padding the root out to a taller tree stops at MAX_TREE_DEPTH
+        let padding = (MAX_TREE_DEPTH - depth) as usize;
+        let mut tall_proof = [proof.clone(), vec![EMPTY_NODE; padding]].concat();
+        let mut tall_root = (0..padding).fold(root, |node, _| hash_node(node, EMPTY_NODE));
+        assert!(verify_multiproof_v2(
+            &claimed,
+            &tall_proof,
+            MAX_TREE_DEPTH,
+            tall_root
+        ));
+        tall_proof.push(EMPTY_NODE);
+        tall_root = hash_node(tall_root, EMPTY_NODE);
+        let too_tall = MAX_TREE_DEPTH + 1;
+        assert!(!verify_multiproof_v2(
+            &claimed,
+            &tall_proof,
+            too_tall,
+            tall_root
+        ));
+    }
+
+    // What answer verification spends on sha256 syscalls - leaf hashes plus the
+    // proofs, with every answer correct. Only the hashing is counted, so this
+    // compares the proof formats; tests/integration/compute.rs measures whole
+    // submissions on the SBF build. Multiproofs hash each shared node once and send
+    // far fewer proof bytes, which is what limits large games per transaction.
+    #[test]
+    fn test_verification_hash_cost() {
+        // Every v2 node hashes the prefix and two children
+        let node_units = sha256_units(&[&[NODE_PREFIX], &EMPTY_NODE, &EMPTY_NODE]);
+        let leaf_units = |i: u8| {
+            let salt = format!("question-{}", i);
+            let header = [LEAF_FORMAT_VERSION, answer_type_tag(&AnswerType::Choice), i];
+            sha256_units(&[
+                &[LEAF_PREFIX],
+                &header,
+                &1u32.to_le_bytes(),
+                b"a",
+                salt.as_bytes(),
+                &1u16.to_le_bytes(),
+            ])
+        };
+
+        println!("questions | per-answer hashing | multiproof hashing | per-answer bytes | multiproof bytes");
+        for count in [10u8, 50, 100] {
+            let leaves = leaves_v2(count);
+            let root = compute_merkle_root_v2(&leaves).unwrap();
+            let indices: Vec<u32> = (0..count as u32).collect();
+            let proofs: Vec<_> = indices
+                .iter()
+                .map(|index| proof_v2(&leaves, *index as usize))
+                .collect();
+            let (multiproof, depth) = multiproof_v2(&leaves, &indices);
+            assert!(indices
+                .iter()
+                .zip(&proofs)
+                .all(|(index, proof)| verify_merkle_proof_v2(
+                    leaves[*index as usize],
+                    proof,
+                    *index,
+                    root
+                )));
+            let claimed = claimed(&leaves, &indices);
+            assert!(verify_multiproof_v2(&claimed, &multiproof, depth, root));
+
+            let leaf_hashing: u64 = (0..count).map(leaf_units).sum();
+            // A per-answer proof hashes once per sibling
+            let per_answer_nodes: usize = proofs.iter().map(Vec::len).sum();
+            let per_answer_hashing = leaf_hashing + per_answer_nodes as u64 * node_units;
+            // Each multiproof hash turns two known nodes into one, from the claimed
+            // leaves and the proof's siblings down to the root
+            let multiproof_nodes = claimed.len() + multiproof.len() - 1;
+            let multiproof_hashing = leaf_hashing + multiproof_nodes as u64 * node_units;
+
+            let per_answer_bytes: usize = proofs.iter().map(|proof| 4 + 32 * proof.len()).sum();
+            let multiproof_bytes = 1 + 4 + 32 * multiproof.len();
+            println!(
+                "{:>9} | {:>18} | {:>18} | {:>16} | {:>16}",
+                count, per_answer_hashing, multiproof_hashing, per_answer_bytes, multiproof_bytes
+            );
+
+            assert!(multiproof_hashing < per_answer_hashing);
+            assert!(multiproof_bytes < per_answer_bytes);
+        }
+    }
+}
+
+// cargo test merkle -- --nocapture
+
+// Off-chain trees are built by twizzin_client::merkle::MerkleTree in Rust and by
+// MerkleTree in tests/utils.ts - both take the merkle version and build either tree.
+// tests/merkleVectors.ts checks the typescript builder against the same vectors as
+// test_cross_language_vectors.
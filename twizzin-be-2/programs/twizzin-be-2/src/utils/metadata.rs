@@ -0,0 +1,29 @@
+use crate::errors::ErrorCode;
+use crate::state::MAX_METADATA_URI_LENGTH;
+use anchor_lang::prelude::*;
+
+pub fn validate_metadata_uri(metadata_uri: &Option<String>) -> Result<()> {
+    if let Some(uri) = metadata_uri {
+        require!(
+            !uri.is_empty() && uri.len() <= MAX_METADATA_URI_LENGTH,
+            ErrorCode::InvalidMetadataUri
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_metadata_uri() {
+        assert!(validate_metadata_uri(&None).is_ok());
+        assert!(validate_metadata_uri(&Some("ipfs://bafy/game.json".to_string())).is_ok());
+        assert!(validate_metadata_uri(&Some("x".repeat(MAX_METADATA_URI_LENGTH))).is_ok());
+        assert!(validate_metadata_uri(&Some(String::new())).is_err());
+        assert!(validate_metadata_uri(&Some("x".repeat(MAX_METADATA_URI_LENGTH + 1))).is_err());
+    }
+}
+
+// cargo test metadata -- --nocapture
@@ -9,3 +9,45 @@ pub use prize::*;
 
 pub mod convert_pubkey_to_bytes;
 pub use convert_pubkey_to_bytes::*;
+
+pub mod referral;
+pub use referral::*;
+
+pub mod profile;
+pub use profile::*;
+
+pub mod season;
+pub use season::*;
+
+pub mod access;
+pub use access::*;
+
+pub mod gate;
+pub use gate::*;
+
+pub mod operator;
+pub use operator::*;
+
+pub mod game_code;
+pub use game_code::*;
+
+pub mod metadata;
+pub use metadata::*;
+
+pub mod answer_key;
+pub use answer_key::*;
+
+pub mod answer_format;
+pub use answer_format::*;
+
+pub mod scoring;
+pub use scoring::*;
+
+pub mod submission;
+pub use submission::*;
+
+pub mod migrate;
+pub use migrate::*;
+
+pub mod account;
+pub use account::*;
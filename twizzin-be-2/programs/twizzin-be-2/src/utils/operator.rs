@@ -0,0 +1,109 @@
+use crate::errors::ErrorCode;
+use crate::state::{GameOperator, OPERATOR_ALL_PERMISSIONS};
+use anchor_lang::prelude::*;
+
+pub fn validate_operator_permissions(permissions: u8) -> Result<()> {
+    require!(
+        permissions != 0 && permissions & !OPERATOR_ALL_PERMISSIONS == 0,
+        ErrorCode::InvalidOperatorPermissions
+    );
+    Ok(())
+}
+
+// Checks the signer is either the game admin or an operator the current admin
+// granted `permission`. Returns true when the signer is the admin.
+pub fn check_game_authority(
+    game_admin: &Pubkey,
+    authority: &Pubkey,
+    game_operator: Option<&GameOperator>,
+    permission: u8,
+) -> Result<bool> {
+    if authority == game_admin {
+        return Ok(true);
+    }
+
+    let game_operator = game_operator.ok_or(ErrorCode::InvalidAuthority)?;
+    require!(
+        game_operator.operator == *authority && game_operator.granted_by == *game_admin,
+        ErrorCode::InvalidAuthority
+    );
+    require!(
+        game_operator.permissions & permission == permission,
+        ErrorCode::OperatorNotPermitted
+    );
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{OPERATOR_DECLARE_WINNERS, OPERATOR_START_END, OPERATOR_UPDATE_GAME};
+
+    fn operator(admin: Pubkey, key: Pubkey, permissions: u8) -> GameOperator {
+        GameOperator {
+            game: Pubkey::new_unique(),
+            operator: key,
+            permissions,
+            bump: 255,
+            granted_by: admin,
+        }
+    }
+
+    #[test]
+    fn test_validate_operator_permissions() {
+        assert!(validate_operator_permissions(OPERATOR_START_END).is_ok());
+        assert!(validate_operator_permissions(OPERATOR_ALL_PERMISSIONS).is_ok());
+        assert!(validate_operator_permissions(0).is_err());
+        assert!(validate_operator_permissions(1 << 5).is_err());
+    }
+
+    #[test]
+    fn test_admin_is_always_allowed() {
+        let admin = Pubkey::new_unique();
+        assert!(check_game_authority(&admin, &admin, None, OPERATOR_UPDATE_GAME).unwrap());
+    }
+
+    #[test]
+    fn test_operator_permissions() {
+        let admin = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let game_operator = operator(admin, key, OPERATOR_START_END | OPERATOR_DECLARE_WINNERS);
+
+        assert!(
+            !check_game_authority(&admin, &key, Some(&game_operator), OPERATOR_START_END).unwrap()
+        );
+        assert!(
+            check_game_authority(&admin, &key, Some(&game_operator), OPERATOR_DECLARE_WINNERS)
+                .is_ok()
+        );
+        assert!(
+            check_game_authority(&admin, &key, Some(&game_operator), OPERATOR_UPDATE_GAME).is_err()
+        );
+    }
+
+    #[test]
+    fn test_previous_admins_operator_rejected() {
+        let (previous, admin) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let key = Pubkey::new_unique();
+        let game_operator = operator(previous, key, OPERATOR_ALL_PERMISSIONS);
+
+        assert!(
+            check_game_authority(&admin, &key, Some(&game_operator), OPERATOR_START_END).is_err()
+        );
+    }
+
+    #[test]
+    fn test_non_operator_rejected() {
+        let admin = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let someone_else = operator(admin, Pubkey::new_unique(), OPERATOR_ALL_PERMISSIONS);
+
+        assert!(check_game_authority(&admin, &key, None, OPERATOR_START_END).is_err());
+        assert!(
+            check_game_authority(&admin, &key, Some(&someone_else), OPERATOR_START_END).is_err()
+        );
+    }
+}
+
+// cargo test operator -- --nocapture
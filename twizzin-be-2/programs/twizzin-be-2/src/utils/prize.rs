@@ -27,7 +27,14 @@ pub fn calculate_prizes(
         let prize_amount = distributable_pot
             .checked_div(num_winners as u64)
             .ok_or(ErrorCode::NumericOverflow)?;
-        return Ok(vec![prize_amount; num_winners as usize]);
+        let mut prizes = vec![prize_amount; num_winners as usize];
+
+        // First place takes the dust, as with the geometric split - left in a SOL
+        // vault it would be less than rent, and the last claim would fail
+        prizes[0] = prizes[0]
+            .checked_add(distributable_pot % num_winners as u64)
+            .ok_or(ErrorCode::NumericOverflow)?;
+        return Ok(prizes);
     }
 
     // Geometric distribution
@@ -63,6 +70,9 @@ pub fn calculate_prizes(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::MAX_WINNERS;
+    use crate::utils::fees::calculate_fees;
+    use proptest::prelude::*;
     const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
     const MOCK_RENT_EXEMPTION: u64 = 890_880;
 
@@ -200,6 +210,10 @@ mod tests {
         // First place should get the dust
         assert_eq!(prizes.iter().sum::<u64>(), 1001);
         assert_eq!(prizes.len(), 3);
+
+        // Even splits pay the dust to first place as well
+        let prizes = calculate_prizes(1001, 3, true, false, 0).unwrap();
+        assert_eq!(prizes, vec![335, 333, 333]);
     }
 
     #[test]
@@ -210,34 +224,75 @@ mod tests {
         assert_eq!(prizes.len(), 4);
         assert!(prizes[0] > 0); // First place should get something
                                 // Verify some places get 0 due to small pot
-        assert!(prizes.iter().any(|&x| x == 0));
+        assert!(prizes.contains(&0));
+    }
+
+    proptest! {
+        #[test]
+        fn prizes_pay_out_the_distributable_pot(
+            total_pot in any::<u64>(),
+            num_winners in 1..=MAX_WINNERS,
+            even_split in any::<bool>(),
+            is_native in any::<bool>(),
+            rent_exemption in 0..=2 * MOCK_RENT_EXEMPTION,
+        ) {
+            let result =
+                calculate_prizes(total_pot, num_winners, even_split, is_native, rent_exemption);
+            if is_native && total_pot < rent_exemption {
+                prop_assert!(result.is_err());
+                return Ok(());
+            }
+            let prizes = result.unwrap();
+            let distributable = if is_native { total_pot - rent_exemption } else { total_pot };
+            let total = prizes.iter().map(|p| *p as u128).sum::<u128>();
+
+            // Either way first place takes the dust, so the whole pot is paid out
+            prop_assert_eq!(prizes.len(), num_winners as usize);
+            prop_assert_eq!(total, distributable as u128);
+            if even_split {
+                prop_assert!(prizes[1..].iter().all(|p| *p == prizes[num_winners as usize - 1]));
+                prop_assert!(prizes[0] - prizes[num_winners as usize - 1] < num_winners as u64);
+            } else {
+                // Geometric prizes never grow
+                prop_assert!(prizes.windows(2).all(|pair| pair[0] >= pair[1]));
+            }
+        }
+
+        // The vault pays fees when the game ends and prizes from what's left
+        #[test]
+        fn payouts_never_exceed_the_pot(
+            total_pot in any::<u64>(),
+            treasury_fee_bps in 0u16..=1000,
+            commission_bps in 0u16..=1000,
+            num_winners in 1..=MAX_WINNERS,
+            even_split in any::<bool>(),
+            is_native in any::<bool>(),
+        ) {
+            let rent_exemption = if is_native { MOCK_RENT_EXEMPTION } else { 0 };
+            prop_assume!(total_pot >= rent_exemption);
+            let (treasury_fee, admin_commission) = calculate_fees(
+                total_pot,
+                treasury_fee_bps,
+                commission_bps,
+                rent_exemption,
+                is_native,
+            )
+            .unwrap();
+            let remaining = total_pot - treasury_fee - admin_commission;
+            let prizes =
+                calculate_prizes(remaining, num_winners, even_split, is_native, rent_exemption)
+                    .unwrap();
+
+            let paid = treasury_fee as u128
+                + admin_commission as u128
+                + prizes.iter().map(|p| *p as u128).sum::<u128>();
+            prop_assert!(paid <= (total_pot - rent_exemption) as u128);
+        }
     }
 }
 
 // cargo test prize -- --nocapture
 
-// Client-side winner determination
-// async function determineWinners(gameAddress: PublicKey, maxWinners: number) {
-//     // Find all player accounts for this game
-//     const playerAccounts = await program.account.playerAccount.all([
-//       {
-//         memcmp: {
-//           offset: 8, // after discriminator
-//           bytes: gameAddress.toBase58(),
-//         },
-//       },
-//     ]);
-
-//     // Sort by num_correct (descending) and finish_time (ascending)
-//     const sortedPlayers = playerAccounts
-//       .filter(p => p.account.finishedTime > 0) // Only include players who submitted
-//       .sort((a, b) => {
-//         if (b.account.numCorrect !== a.account.numCorrect) {
-//           return b.account.numCorrect - a.account.numCorrect;
-//         }
-//         return a.account.finishedTime - b.account.finishedTime;
-//       });
-
-//     // Return top winners
-//     return sortedPlayers.slice(0, maxWinners);
-//   }
+// Winners are picked off-chain by twizzin_client::ranking::determine_winners -
+// players who submitted, highest score first then earliest finish then lowest
+// pubkey, in the order declare_winners checks.
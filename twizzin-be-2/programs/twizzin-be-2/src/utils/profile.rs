@@ -0,0 +1,217 @@
+use crate::errors::ErrorCode;
+use crate::state::{MintWinnings, PlayerProfile, ProfileUpdated, MAX_PROFILE_MINTS};
+use anchor_lang::prelude::*;
+
+// Called when a player submits answers for a game
+pub fn record_game_played(
+    profile: &mut PlayerProfile,
+    game: Pubkey,
+    num_correct: u8,
+) -> Result<()> {
+    // A streak only carries on if the previous game was won
+    if profile.last_game != Pubkey::default() && !profile.last_game_won {
+        profile.current_streak = 0;
+    }
+
+    profile.games_played = profile
+        .games_played
+        .checked_add(1)
+        .ok_or(ErrorCode::NumericOverflow)?;
+    profile.total_correct = profile
+        .total_correct
+        .checked_add(num_correct as u32)
+        .ok_or(ErrorCode::NumericOverflow)?;
+    profile.last_game = game;
+    profile.last_game_won = false;
+
+    Ok(())
+}
+
+// Called when a winner claims their prize
+pub fn record_prize_claimed(
+    profile: &mut PlayerProfile,
+    game: Pubkey,
+    rank: u8,
+    mint: Pubkey,
+    prize_amount: u64,
+) -> Result<()> {
+    if rank == 1 {
+        profile.wins = profile
+            .wins
+            .checked_add(1)
+            .ok_or(ErrorCode::NumericOverflow)?;
+    }
+    if rank <= 3 {
+        profile.podium_finishes = profile
+            .podium_finishes
+            .checked_add(1)
+            .ok_or(ErrorCode::NumericOverflow)?;
+    }
+
+    // Claims for older games still count as wins but can't extend the streak
+    if profile.last_game == game && !profile.last_game_won {
+        profile.last_game_won = true;
+        profile.current_streak = profile
+            .current_streak
+            .checked_add(1)
+            .ok_or(ErrorCode::NumericOverflow)?;
+        profile.best_streak = std::cmp::max(profile.best_streak, profile.current_streak);
+    }
+
+    if prize_amount > 0 {
+        let num_mints = profile.winnings.len();
+        match profile.winnings.iter_mut().find(|w| w.mint == mint) {
+            Some(winnings) => {
+                winnings.amount = winnings
+                    .amount
+                    .checked_add(prize_amount)
+                    .ok_or(ErrorCode::NumericOverflow)?;
+            }
+            None if num_mints < MAX_PROFILE_MINTS => {
+                profile.winnings.push(MintWinnings {
+                    mint,
+                    amount: prize_amount,
+                });
+            }
+            // Don't block the claim if the profile is already tracking the max mints
+            None => msg!("Profile winnings full, not tracking mint: {}", mint),
+        }
+    }
+
+    Ok(())
+}
+
+pub fn emit_profile_updated(profile: &PlayerProfile) {
+    emit!(ProfileUpdated {
+        player: profile.player,
+        games_played: profile.games_played,
+        total_correct: profile.total_correct,
+        wins: profile.wins,
+        podium_finishes: profile.podium_finishes,
+        current_streak: profile.current_streak,
+    });
+}
+
+// The profile PDA is always passed, so a player who made a profile can't leave
+// it out to keep a loss off their record. None if they never made one.
+pub fn load_profile(profile: &AccountInfo) -> Result<Option<PlayerProfile>> {
+    if profile.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(
+        *profile.owner,
+        crate::ID,
+        anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+    );
+    let data = profile.try_borrow_data()?;
+    Ok(Some(PlayerProfile::try_deserialize(&mut &data[..])?))
+}
+
+pub fn save_profile(profile: &AccountInfo, stats: &PlayerProfile) -> Result<()> {
+    let mut data = profile.try_borrow_mut_data()?;
+    stats.try_serialize(&mut &mut data[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_profile() -> PlayerProfile {
+        PlayerProfile {
+            player: Pubkey::new_unique(),
+            games_played: 0,
+            total_correct: 0,
+            wins: 0,
+            podium_finishes: 0,
+            current_streak: 0,
+            best_streak: 0,
+            winnings: vec![],
+            last_game: Pubkey::default(),
+            last_game_won: false,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_record_game_played() {
+        let mut profile = new_profile();
+        let game = Pubkey::new_unique();
+
+        record_game_played(&mut profile, game, 7).unwrap();
+        record_game_played(&mut profile, Pubkey::new_unique(), 3).unwrap();
+
+        assert_eq!(profile.games_played, 2);
+        assert_eq!(profile.total_correct, 10);
+        assert!(!profile.last_game_won);
+    }
+
+    #[test]
+    fn test_wins_and_podiums() {
+        let mut profile = new_profile();
+        let mint = Pubkey::new_unique();
+
+        for rank in 1..=4 {
+            let game = Pubkey::new_unique();
+            record_game_played(&mut profile, game, 5).unwrap();
+            record_prize_claimed(&mut profile, game, rank, mint, 100).unwrap();
+        }
+
+        assert_eq!(profile.wins, 1);
+        assert_eq!(profile.podium_finishes, 3);
+        assert_eq!(profile.winnings.len(), 1);
+        assert_eq!(profile.winnings[0].amount, 400);
+    }
+
+    #[test]
+    fn test_streaks() {
+        let mut profile = new_profile();
+        let mint = Pubkey::new_unique();
+
+        // Win two in a row
+        for _ in 0..2 {
+            let game = Pubkey::new_unique();
+            record_game_played(&mut profile, game, 5).unwrap();
+            record_prize_claimed(&mut profile, game, 1, mint, 100).unwrap();
+        }
+        assert_eq!(profile.current_streak, 2);
+
+        // Claiming twice for the same game doesn't extend the streak
+        let last_game = profile.last_game;
+        record_prize_claimed(&mut profile, last_game, 1, mint, 0).unwrap();
+        assert_eq!(profile.current_streak, 2);
+
+        // Lose one - the streak resets on the next game
+        record_game_played(&mut profile, Pubkey::new_unique(), 0).unwrap();
+        let game = Pubkey::new_unique();
+        record_game_played(&mut profile, game, 5).unwrap();
+        assert_eq!(profile.current_streak, 0);
+
+        record_prize_claimed(&mut profile, game, 2, mint, 50).unwrap();
+        assert_eq!(profile.current_streak, 1);
+        assert_eq!(profile.best_streak, 2);
+    }
+
+    #[test]
+    fn test_winnings_per_mint() {
+        let mut profile = new_profile();
+        let game = Pubkey::new_unique();
+
+        let mints: Vec<Pubkey> = (0..MAX_PROFILE_MINTS + 1)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        for mint in mints.iter() {
+            record_prize_claimed(&mut profile, game, 5, *mint, 10).unwrap();
+        }
+        // Same mint accumulates
+        record_prize_claimed(&mut profile, game, 5, mints[0], 15).unwrap();
+
+        assert_eq!(profile.winnings.len(), MAX_PROFILE_MINTS);
+        assert_eq!(profile.winnings[0].amount, 25);
+        // Zero prizes aren't tracked
+        let mut profile = new_profile();
+        record_prize_claimed(&mut profile, game, 5, mints[0], 0).unwrap();
+        assert!(profile.winnings.is_empty());
+    }
+}
+
+// cargo test profile -- --nocapture
@@ -0,0 +1,75 @@
+use crate::errors::ErrorCode;
+use anchor_lang::prelude::*;
+
+// Returns the reward paid to a referrer for each player they referred.
+// The reward is referral_fee_bps of the entry fee, scaled down if the
+// source cut (treasury fee or admin commission) can't cover every referral.
+pub fn calculate_referral_reward(
+    entry_fee: u64,
+    referral_fee_bps: u16,
+    referred_players: u32,
+    source_cut: u64,
+) -> Result<u64> {
+    require!(referral_fee_bps <= 1000, ErrorCode::InvalidBasisPoints);
+
+    if referred_players == 0 || entry_fee == 0 || referral_fee_bps == 0 {
+        return Ok(0);
+    }
+
+    let reward = (entry_fee as u128)
+        .checked_mul(referral_fee_bps as u128)
+        .ok_or(ErrorCode::NumericOverflow)?
+        .checked_div(10000)
+        .ok_or(ErrorCode::NumericOverflow)? as u64;
+
+    // Never pay out more than the source cut
+    let max_reward = source_cut
+        .checked_div(referred_players as u64)
+        .ok_or(ErrorCode::NumericOverflow)?;
+
+    Ok(std::cmp::min(reward, max_reward))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_basic_referral_reward() {
+        // 2% of a 1 SOL entry fee, treasury cut easily covers it
+        let reward = calculate_referral_reward(LAMPORTS_PER_SOL, 200, 3, LAMPORTS_PER_SOL).unwrap();
+        assert_eq!(reward, LAMPORTS_PER_SOL / 50);
+
+        // Token amounts round down
+        let reward = calculate_referral_reward(1_001, 500, 1, 1_000).unwrap();
+        assert_eq!(reward, 50);
+    }
+
+    #[test]
+    fn test_reward_capped_by_source_cut() {
+        // 10% of 1 SOL for 10 players = 1 SOL, but only 0.5 SOL available
+        let reward =
+            calculate_referral_reward(LAMPORTS_PER_SOL, 1000, 10, LAMPORTS_PER_SOL / 2).unwrap();
+        assert_eq!(reward, LAMPORTS_PER_SOL / 20);
+        assert!(reward * 10 <= LAMPORTS_PER_SOL / 2);
+
+        // Nothing to pay from
+        let reward = calculate_referral_reward(LAMPORTS_PER_SOL, 1000, 10, 0).unwrap();
+        assert_eq!(reward, 0);
+    }
+
+    #[test]
+    fn test_zero_cases() {
+        assert_eq!(calculate_referral_reward(0, 500, 5, 1_000).unwrap(), 0);
+        assert_eq!(calculate_referral_reward(1_000, 0, 5, 1_000).unwrap(), 0);
+        assert_eq!(calculate_referral_reward(1_000, 500, 0, 1_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_bps_validation() {
+        assert!(calculate_referral_reward(1_000, 1001, 1, 1_000).is_err());
+    }
+}
+
+// cargo test referral -- --nocapture
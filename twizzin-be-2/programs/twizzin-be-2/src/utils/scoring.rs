@@ -0,0 +1,161 @@
+use crate::errors::ErrorCode;
+use crate::state::Game;
+use anchor_lang::prelude::*;
+
+// How a game turns answers into points
+#[derive(Clone, Copy, Default)]
+pub struct ScoringRules {
+    pub wrong_answer_penalty: u16,
+    pub speed_bonus: u16, // Most extra points a correct answer can earn - 0 turns it off
+    pub speed_bonus_window: u32, // Milliseconds for the bonus to decay to nothing
+}
+
+impl ScoringRules {
+    pub fn for_game(game: &Game) -> Self {
+        Self {
+            wrong_answer_penalty: game.wrong_answer_penalty,
+            speed_bonus: game.speed_bonus,
+            speed_bonus_window: game.speed_bonus_window,
+        }
+    }
+
+    pub fn has_speed_bonus(&self) -> bool {
+        self.speed_bonus > 0
+    }
+
+    // Full bonus for an instant answer, decaying linearly to nothing by the end of the window
+    pub fn speed_bonus(&self, time_taken: i64) -> u32 {
+        if !self.has_speed_bonus() || self.speed_bonus_window == 0 {
+            return 0;
+        }
+        let window = self.speed_bonus_window as i64;
+        let remaining = window - time_taken.clamp(0, window);
+        (self.speed_bonus as i64 * remaining / window) as u32
+    }
+
+    // What a correct answer is worth - its base points plus any speed bonus
+    pub fn correct_answer_points(&self, points: u16, time_taken: i64) -> i32 {
+        points as i32 + self.speed_bonus(time_taken) as i32
+    }
+
+    // Time spent on each answer - the gap since the previous answer, or since the
+    // player started for the first. Answer times have to fall between the start and
+    // finish in the order they were given, so a client can shift time between
+    // questions but never claim to have answered before it could have.
+    // Times aren't looked at without a speed bonus.
+    pub fn answer_durations(
+        &self,
+        start: i64,
+        finish: i64,
+        answered_at: impl ExactSizeIterator<Item = i64>,
+    ) -> Result<Vec<i64>> {
+        if !self.has_speed_bonus() {
+            return Ok(vec![0; answered_at.len()]);
+        }
+
+        let mut previous = start;
+        answered_at
+            .map(|time| {
+                require!(
+                    time >= previous && time <= finish,
+                    ErrorCode::InvalidAnswerTime
+                );
+                let duration = time - previous;
+                previous = time;
+                Ok(duration)
+            })
+            .collect()
+    }
+}
+
+pub fn validate_speed_bonus(speed_bonus: u16, speed_bonus_window: u32) -> Result<()> {
+    require!(
+        speed_bonus == 0 || speed_bonus_window > 0,
+        ErrorCode::InvalidSpeedBonus
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(speed_bonus: u16, speed_bonus_window: u32) -> ScoringRules {
+        ScoringRules {
+            wrong_answer_penalty: 0,
+            speed_bonus,
+            speed_bonus_window,
+        }
+    }
+
+    #[test]
+    fn test_speed_bonus_decays() {
+        let rules = rules(10, 10_000);
+        assert_eq!(rules.speed_bonus(0), 10);
+        assert_eq!(rules.speed_bonus(2_500), 7);
+        assert_eq!(rules.speed_bonus(5_000), 5);
+        assert_eq!(rules.speed_bonus(9_999), 0);
+        assert_eq!(rules.speed_bonus(10_000), 0);
+        assert_eq!(rules.speed_bonus(60_000), 0);
+        // Can't earn more than the full bonus
+        assert_eq!(rules.speed_bonus(-5_000), 10);
+
+        let mut previous = u32::MAX;
+        for time_taken in (0..=10_000).step_by(500) {
+            let bonus = rules.speed_bonus(time_taken);
+            assert!(bonus <= previous);
+            previous = bonus;
+        }
+    }
+
+    #[test]
+    fn test_correct_answer_points() {
+        assert_eq!(rules(10, 10_000).correct_answer_points(3, 5_000), 8);
+        assert_eq!(rules(10, 10_000).correct_answer_points(3, 20_000), 3);
+        // Without a speed bonus only the base points count
+        assert_eq!(rules(0, 0).correct_answer_points(3, 0), 3);
+        assert_eq!(
+            rules(u16::MAX, 1).correct_answer_points(u16::MAX, 0),
+            2 * u16::MAX as i32
+        );
+    }
+
+    #[test]
+    fn test_answer_durations() {
+        let rules = rules(10, 10_000);
+        let durations = rules
+            .answer_durations(1_000, 9_000, [2_000, 2_500, 8_000].into_iter())
+            .unwrap();
+        assert_eq!(durations, vec![1_000, 500, 5_500]);
+
+        // Before the player started
+        assert!(rules
+            .answer_durations(1_000, 9_000, [500].into_iter())
+            .is_err());
+        // After they finished
+        assert!(rules
+            .answer_durations(1_000, 9_000, [9_500].into_iter())
+            .is_err());
+        // Out of order
+        assert!(rules
+            .answer_durations(1_000, 9_000, [3_000, 2_000].into_iter())
+            .is_err());
+    }
+
+    #[test]
+    fn test_answer_times_ignored_without_speed_bonus() {
+        let durations = rules(0, 0)
+            .answer_durations(1_000, 9_000, [0, 50_000].into_iter())
+            .unwrap();
+        assert_eq!(durations, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_validate_speed_bonus() {
+        assert!(validate_speed_bonus(0, 0).is_ok());
+        assert!(validate_speed_bonus(10, 5_000).is_ok());
+        assert!(validate_speed_bonus(10, 0).is_err());
+    }
+}
+
+// cargo test scoring -- --nocapture
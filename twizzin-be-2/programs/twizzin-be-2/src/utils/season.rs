@@ -0,0 +1,129 @@
+use crate::errors::ErrorCode;
+use crate::state::{Game, SeasonPlayer, SeasonStanding, MAX_SEASON_WINNERS};
+use anchor_lang::prelude::*;
+
+// Points awarded for finishing ranks 1-10 in a season game
+pub const SEASON_POINTS: [u32; 10] = [25, 18, 15, 12, 10, 8, 6, 4, 2, 1];
+
+pub fn season_points_for_rank(rank: u8) -> u32 {
+    if rank == 0 {
+        return 0;
+    }
+    SEASON_POINTS.get(rank as usize - 1).copied().unwrap_or(0)
+}
+
+// Sets up the player's season account the first time they finish one of the
+// season's games, so declare_winners has somewhere to add their points
+pub fn open_season_player(
+    game: &Game,
+    season_player: Option<&mut Account<SeasonPlayer>>,
+    player: Pubkey,
+    bump: Option<u8>,
+) -> Result<()> {
+    let Some(season) = game.season else {
+        return Ok(());
+    };
+    let season_player = season_player.ok_or(ErrorCode::SeasonAccountsNotProvided)?;
+    if season_player.season == Pubkey::default() {
+        season_player.season = season;
+        season_player.player = player;
+        season_player.bump = bump.ok_or(ErrorCode::SeasonAccountsNotProvided)?;
+    }
+    Ok(())
+}
+
+// Keeps the leaderboard ordered by points (descending) then by the time the
+// points were reached (ascending), capped at MAX_SEASON_WINNERS entries
+pub fn update_leaderboard(
+    leaderboard: &mut Vec<SeasonStanding>,
+    player: Pubkey,
+    points: u32,
+    updated_at: i64,
+) {
+    leaderboard.retain(|standing| standing.player != player);
+
+    let position = leaderboard
+        .iter()
+        .position(|standing| {
+            points > standing.points
+                || (points == standing.points && updated_at < standing.updated_at)
+        })
+        .unwrap_or(leaderboard.len());
+
+    if position >= MAX_SEASON_WINNERS as usize {
+        return;
+    }
+
+    leaderboard.insert(
+        position,
+        SeasonStanding {
+            player,
+            points,
+            updated_at,
+        },
+    );
+    leaderboard.truncate(MAX_SEASON_WINNERS as usize);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_points_for_rank() {
+        assert_eq!(season_points_for_rank(0), 0);
+        assert_eq!(season_points_for_rank(1), 25);
+        assert_eq!(season_points_for_rank(2), 18);
+        assert_eq!(season_points_for_rank(10), 1);
+        assert_eq!(season_points_for_rank(11), 0);
+        assert_eq!(season_points_for_rank(200), 0);
+    }
+
+    #[test]
+    fn test_leaderboard_ordering() {
+        let mut leaderboard = vec![];
+        let (a, b, c) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        update_leaderboard(&mut leaderboard, a, 18, 100);
+        update_leaderboard(&mut leaderboard, b, 25, 200);
+        update_leaderboard(&mut leaderboard, c, 18, 50);
+
+        let players: Vec<Pubkey> = leaderboard.iter().map(|s| s.player).collect();
+        // c reached 18 before a
+        assert_eq!(players, vec![b, c, a]);
+
+        // a moves up once they gain points
+        update_leaderboard(&mut leaderboard, a, 43, 300);
+        let players: Vec<Pubkey> = leaderboard.iter().map(|s| s.player).collect();
+        assert_eq!(players, vec![a, b, c]);
+        assert_eq!(leaderboard.len(), 3);
+    }
+
+    #[test]
+    fn test_leaderboard_capped() {
+        let mut leaderboard = vec![];
+        for i in 0..MAX_SEASON_WINNERS as u32 {
+            update_leaderboard(&mut leaderboard, Pubkey::new_unique(), 100 + i, 0);
+        }
+        assert_eq!(leaderboard.len(), MAX_SEASON_WINNERS as usize);
+
+        // Too few points to make the board
+        let low = Pubkey::new_unique();
+        update_leaderboard(&mut leaderboard, low, 1, 0);
+        assert!(leaderboard.iter().all(|s| s.player != low));
+
+        // Enough points pushes the last place off
+        let last = leaderboard.last().unwrap().player;
+        let high = Pubkey::new_unique();
+        update_leaderboard(&mut leaderboard, high, 500, 0);
+        assert_eq!(leaderboard[0].player, high);
+        assert_eq!(leaderboard.len(), MAX_SEASON_WINNERS as usize);
+        assert!(leaderboard.iter().all(|s| s.player != last));
+    }
+}
+
+// cargo test season -- --nocapture
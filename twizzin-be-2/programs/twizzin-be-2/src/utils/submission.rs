@@ -0,0 +1,257 @@
+use crate::errors::ErrorCode;
+use crate::state::{
+    AnswerInput, AnswersSubmitted, Game, PlayerAccount, PlayerProfile, SubmittedAnswer,
+    MAX_ANSWER_LENGTH,
+};
+use crate::utils::answer_format::answer_in_range;
+use crate::utils::answer_key::{create_submission_hash, submitted_answer_pairs};
+use crate::utils::profile::{emit_profile_updated, record_game_played};
+use crate::utils::scoring::ScoringRules;
+use anchor_lang::prelude::*;
+
+// Checks a player's claimed finish time against the game and the clock (all in ms)
+pub fn validate_finish_time(game: &Game, client_finish_time: i64, current_time: i64) -> Result<()> {
+    require!(
+        client_finish_time >= game.start_time,
+        ErrorCode::GameNotStarted
+    );
+    require!(client_finish_time <= game.end_time, ErrorCode::GameEnded);
+    // Ensure client_finish_time isn't in the future relative to blockchain time
+    require!(
+        client_finish_time < current_time,
+        ErrorCode::InvalidFinishTime
+    );
+    Ok(())
+}
+
+// Scores answers into the player's running totals and appends them to their submission.
+// Answers can arrive over several chunks, so the player's answered bitmap decides which
+// answer is a question's first - only that one counts. Skipped questions score nothing
+// and wrong answers cost the game's penalty. `is_proven` checks an answer against the key.
+pub fn record_answers(
+    player_account: &mut PlayerAccount,
+    submitted: &mut Vec<SubmittedAnswer>,
+    answers: Vec<AnswerInput>,
+    rules: &ScoringRules,
+    player_start: i64,
+    latest: i64,
+    is_proven: impl Fn(&AnswerInput) -> bool,
+) -> Result<()> {
+    // Answer times carry on from the last answer already stored
+    let previous = submitted
+        .last()
+        .map_or(player_start, |answer| answer.answered_at);
+    let durations = rules.answer_durations(
+        previous,
+        latest,
+        answers.iter().map(|answer| answer.answered_at),
+    )?;
+
+    submitted.reserve(answers.len());
+    for (answer, time_taken) in answers.into_iter().zip(durations) {
+        require!(
+            answer.answer.len() <= MAX_ANSWER_LENGTH,
+            ErrorCode::AnswerTooLong
+        );
+        let first = player_account.mark_answered(answer.display_order);
+        let is_correct =
+            first && answer_in_range(&answer.answer_type, &answer.answer) && is_proven(&answer);
+
+        if is_correct {
+            player_account.num_correct = player_account
+                .num_correct
+                .checked_add(1)
+                .ok_or(ErrorCode::NumericOverflow)?;
+            player_account.score = player_account
+                .score
+                .checked_add(rules.correct_answer_points(answer.points, time_taken))
+                .ok_or(ErrorCode::NumericOverflow)?;
+        } else if first && !answer.answer.is_empty() {
+            player_account.score = player_account
+                .score
+                .checked_sub(rules.wrong_answer_penalty as i32)
+                .ok_or(ErrorCode::NumericOverflow)?;
+        }
+
+        submitted.push(SubmittedAnswer {
+            display_order: answer.display_order,
+            answer: answer.answer,
+            is_correct,
+            answered_at: answer.answered_at,
+        });
+    }
+
+    Ok(())
+}
+
+// Locks in the player's finish time once all of their answers are in
+pub fn finish_submission(
+    game: &mut Account<Game>,
+    player_account: &mut PlayerAccount,
+    submitted: &[SubmittedAnswer],
+    profile: Option<&mut PlayerProfile>,
+    client_finish_time: i64,
+) -> Result<()> {
+    let game_key = game.key();
+    player_account.finished_time = client_finish_time;
+    game.finished_players = game
+        .finished_players
+        .checked_add(1)
+        .ok_or(ErrorCode::PlayerCountOverflow)?;
+    // Lets players prove exactly what they submitted in a dispute
+    player_account.answer_hash = create_submission_hash(submitted_answer_pairs(submitted));
+
+    if let Some(profile) = profile {
+        record_game_played(profile, game_key, player_account.num_correct)?;
+        emit_profile_updated(profile);
+    }
+
+    emit!(AnswersSubmitted {
+        game: game_key,
+        player: player_account.player,
+        num_correct: player_account.num_correct,
+        score: player_account.score,
+        finished_time: client_finish_time,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::AnswerType;
+
+    fn player() -> PlayerAccount {
+        PlayerAccount {
+            game: Pubkey::default(),
+            player: Pubkey::default(),
+            join_time: 0,
+            finished_time: 0,
+            num_correct: 0,
+            answer_hash: [0; 32],
+            bump: 0,
+            referrer: None,
+            score: 0,
+            answered: [0; 32],
+        }
+    }
+
+    fn answer(display_order: u8, answer: &str, answered_at: i64) -> AnswerInput {
+        AnswerInput {
+            display_order,
+            answer_type: AnswerType::Choice,
+            answer: answer.to_string(),
+            question_id: format!("question-{}", display_order),
+            points: 2,
+            answered_at,
+            proof: vec![],
+        }
+    }
+
+    // Answers of 'a' are the correct ones
+    fn record(
+        player: &mut PlayerAccount,
+        submitted: &mut Vec<SubmittedAnswer>,
+        answers: Vec<AnswerInput>,
+        rules: &ScoringRules,
+    ) -> Result<()> {
+        record_answers(player, submitted, answers, rules, 0, 10_000, |answer| {
+            answer.answer == "a"
+        })
+    }
+
+    #[test]
+    fn test_chunks_accumulate() {
+        let rules = ScoringRules {
+            wrong_answer_penalty: 1,
+            ..Default::default()
+        };
+        let mut player = player();
+        let mut submitted = Vec::new();
+
+        record(
+            &mut player,
+            &mut submitted,
+            vec![answer(0, "a", 0), answer(1, "b", 0)],
+            &rules,
+        )
+        .unwrap();
+        record(
+            &mut player,
+            &mut submitted,
+            vec![answer(2, "a", 0), answer(3, "", 0)],
+            &rules,
+        )
+        .unwrap();
+
+        assert_eq!(player.num_correct, 2);
+        assert_eq!(player.score, 2 - 1 + 2);
+        assert_eq!(submitted.len(), 4);
+        assert_eq!(player.answered[0], 0b1111);
+    }
+
+    #[test]
+    fn test_questions_only_count_once_across_chunks() {
+        let rules = ScoringRules::default();
+        let mut player = player();
+        let mut submitted = Vec::new();
+
+        record(&mut player, &mut submitted, vec![answer(7, "b", 0)], &rules).unwrap();
+        // A correct answer in a later chunk can't replace the first one
+        record(&mut player, &mut submitted, vec![answer(7, "a", 0)], &rules).unwrap();
+        record(
+            &mut player,
+            &mut submitted,
+            vec![answer(255, "a", 0), answer(255, "a", 0)],
+            &rules,
+        )
+        .unwrap();
+
+        assert_eq!(player.num_correct, 1);
+        assert_eq!(player.score, 2);
+        let correct: Vec<bool> = submitted.iter().map(|answer| answer.is_correct).collect();
+        assert_eq!(correct, [false, false, true, false]);
+        assert_eq!(player.answered[0], 0b1000_0000);
+        assert_eq!(player.answered[31], 0b1000_0000);
+    }
+
+    #[test]
+    fn test_answer_times_carry_across_chunks() {
+        let rules = ScoringRules {
+            wrong_answer_penalty: 0,
+            speed_bonus: 10,
+            speed_bonus_window: 10_000,
+        };
+        let mut player = player();
+        let mut submitted = Vec::new();
+
+        record(
+            &mut player,
+            &mut submitted,
+            vec![answer(0, "a", 5_000)],
+            &rules,
+        )
+        .unwrap();
+        // The second chunk's first answer is timed from the end of the first chunk
+        record(
+            &mut player,
+            &mut submitted,
+            vec![answer(1, "a", 7_500)],
+            &rules,
+        )
+        .unwrap();
+        assert_eq!(player.score, (2 + 5) + (2 + 7));
+
+        // An answer from before the last chunk is out of order
+        assert!(record(
+            &mut player,
+            &mut submitted,
+            vec![answer(2, "a", 7_000)],
+            &rules
+        )
+        .is_err());
+    }
+}
+
+// cargo test submission -- --nocapture
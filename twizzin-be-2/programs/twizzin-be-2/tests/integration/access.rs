@@ -0,0 +1,394 @@
+//! Private games (allowlists and signed invites) and gated games (a token
+//! balance, an NFT collection or an NFT mint list).
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey};
+use solana_sdk::account::Account;
+use solana_sdk::ed25519_instruction::new_ed25519_instruction;
+use twizzin_be_2::constants::TOKEN_METADATA_PROGRAM_ID;
+use twizzin_be_2::errors::ErrorCode;
+use twizzin_be_2::state::{AccessMode, AccessProof, Gate, MintListProof};
+use twizzin_be_2::utils::{
+    create_allowlist_leaf, create_invite_message, create_mint_list_leaf, hash_node,
+};
+
+use crate::setup::*;
+
+pub fn keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+pub fn pubkey(keypair: &Keypair) -> Pubkey {
+    Pubkey::new_from_array(keypair.public.to_bytes())
+}
+
+fn join(
+    env: &mut Env,
+    game: &TestGame,
+    player: Pubkey,
+    options: JoinOptions,
+) -> crate::svm::TxResult {
+    env.send(game.join_ix(player, options), player)
+}
+
+#[test]
+fn allowlist() {
+    let mut env = Env::new();
+    let (alice, bob, carol) = (env.wallet(), env.wallet(), env.wallet());
+    let (alice_leaf, bob_leaf) = (create_allowlist_leaf(&alice), create_allowlist_leaf(&bob));
+    let game = env.create_game(GameParams {
+        access_mode: AccessMode::Allowlist {
+            root: hash_node(alice_leaf, bob_leaf),
+        },
+        ..GameParams::native()
+    });
+    let proof = |leaf, index| JoinOptions {
+        access_proof: Some(AccessProof::Allowlist {
+            proof: vec![leaf],
+            index,
+        }),
+        ..JoinOptions::default()
+    };
+
+    assert_error(
+        join(&mut env, &game, alice, JoinOptions::default()),
+        ErrorCode::AccessProofNotProvided,
+    );
+    assert_error(
+        join(&mut env, &game, carol, proof(bob_leaf, 0)),
+        ErrorCode::NotOnAllowlist,
+    );
+    // A proof only holds at the player's own position
+    assert_error(
+        join(&mut env, &game, alice, proof(bob_leaf, 1)),
+        ErrorCode::NotOnAllowlist,
+    );
+    join(&mut env, &game, alice, proof(bob_leaf, 0)).unwrap();
+    join(&mut env, &game, bob, proof(alice_leaf, 1)).unwrap();
+}
+
+#[test]
+fn signed_invites() {
+    let mut env = Env::new();
+    let (admin, invite_signer, stranger) = (keypair(1), keypair(2), keypair(3));
+    env.svm.airdrop(&pubkey(&admin), 100 * SOL);
+    let game = env.create_game_as(
+        pubkey(&admin),
+        GameParams {
+            access_mode: AccessMode::SignedInvite {
+                invite_signer: pubkey(&invite_signer),
+            },
+            ..GameParams::native()
+        },
+    );
+    let expiry = env.svm.now_ms() + 60_000;
+    let options = |expiry| JoinOptions {
+        access_proof: Some(AccessProof::SignedInvite { expiry }),
+        instructions_sysvar: true,
+        ..JoinOptions::default()
+    };
+    // An invite is the ed25519 instruction in front of the join
+    let invite = |signer: &Keypair, player: Pubkey, expiry| -> Instruction {
+        new_ed25519_instruction(
+            signer,
+            &create_invite_message(&game.address, &player, expiry),
+        )
+    };
+    let join_with = |env: &mut Env, signer: &Keypair, expiry, signed_expiry| {
+        let player = env.wallet();
+        let join = game.join_ix(player, options(expiry));
+        env.svm
+            .process(&[invite(signer, player, signed_expiry), join], &[player])
+    };
+
+    join_with(&mut env, &invite_signer, expiry, expiry).unwrap();
+    join_with(&mut env, &admin, expiry, expiry).unwrap();
+    assert_error(
+        join_with(&mut env, &stranger, expiry, expiry),
+        ErrorCode::InvalidInvite,
+    );
+    // The expiry is part of the signed message, so it can't be stretched
+    assert_error(
+        join_with(&mut env, &invite_signer, expiry + 1, expiry),
+        ErrorCode::InvalidInvite,
+    );
+
+    let player = env.wallet();
+    assert_error(
+        join(&mut env, &game, player, options(expiry)),
+        ErrorCode::InvalidInvite,
+    );
+    assert_error(
+        join(
+            &mut env,
+            &game,
+            player,
+            JoinOptions {
+                instructions_sysvar: false,
+                ..options(expiry)
+            },
+        ),
+        ErrorCode::AccessProofNotProvided,
+    );
+
+    env.svm.advance_ms(60_000);
+    assert_error(
+        join_with(&mut env, &invite_signer, expiry, expiry),
+        ErrorCode::InviteExpired,
+    );
+}
+
+#[test]
+fn token_gate() {
+    let mut env = Env::new();
+    let gate_mint = env.mint();
+    let game = env.create_game(GameParams {
+        gate: Gate::Token {
+            mint: gate_mint,
+            min_amount: 5,
+        },
+        ..GameParams::native()
+    });
+    let holding = |token_account| JoinOptions {
+        gate_token_account: Some(token_account),
+        ..JoinOptions::default()
+    };
+
+    let (holder, short, other) = (env.wallet(), env.wallet(), env.wallet());
+    let holder_account = env.fund_tokens(&holder, &gate_mint, 5);
+    let short_account = env.fund_tokens(&short, &gate_mint, 4);
+    let other_mint = env.mint();
+    let other_account = env.fund_tokens(&other, &other_mint, 5);
+
+    assert_error(
+        join(&mut env, &game, holder, JoinOptions::default()),
+        ErrorCode::GateTokenAccountNotProvided,
+    );
+    assert_error(
+        join(&mut env, &game, short, holding(short_account)),
+        ErrorCode::GateInsufficientBalance,
+    );
+    assert_error(
+        join(&mut env, &game, other, holding(other_account)),
+        ErrorCode::GateInvalidTokenAccount,
+    );
+    // Borrowing someone else's tokens doesn't count
+    assert_error(
+        join(&mut env, &game, short, holding(holder_account)),
+        ErrorCode::GateInvalidTokenAccount,
+    );
+    join(&mut env, &game, holder, holding(holder_account)).unwrap();
+}
+
+// A Metaplex metadata account for `mint`, up to and including its collection
+fn metadata(mint: &Pubkey, collection: Option<(bool, Pubkey)>) -> Vec<u8> {
+    let mut data = vec![4u8]; // MetadataV1 key
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(mint.as_ref());
+    for field in ["Twizzin Pass", "TWZ", "https://example.com/pass.json"] {
+        data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    data.extend_from_slice(&500u16.to_le_bytes());
+    data.push(0); // no creators
+    data.extend_from_slice(&[1, 1]); // primary sale happened, is mutable
+    data.extend_from_slice(&[1, 255, 1, 0]); // edition nonce, token standard
+    match collection {
+        Some((verified, key)) => {
+            data.push(1);
+            data.push(verified as u8);
+            data.extend_from_slice(key.as_ref());
+        }
+        None => data.push(0),
+    }
+    data
+}
+
+fn metadata_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+// Mints an NFT to a new wallet and returns the wallet, its token account and the metadata
+pub fn nft_holder(
+    env: &mut Env,
+    collection: Option<(bool, Pubkey)>,
+    owner: Pubkey,
+) -> (Pubkey, Pubkey, Pubkey) {
+    let holder = env.wallet();
+    let mint = Pubkey::new_unique();
+    env.svm.set_mint(mint, 0);
+    let token_account = env.fund_tokens(&holder, &mint, 1);
+    let address = metadata_pda(&mint);
+    env.svm.set_account(
+        address,
+        Account {
+            lamports: SOL,
+            data: metadata(&mint, collection),
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    (holder, token_account, address)
+}
+
+#[test]
+fn collection_gate() {
+    let mut env = Env::new();
+    let collection = Pubkey::new_unique();
+    let game = env.create_game(GameParams {
+        gate: Gate::NftCollection { collection },
+        ..GameParams::native()
+    });
+    let showing = |token_account, metadata| JoinOptions {
+        gate_token_account: Some(token_account),
+        gate_metadata: Some(metadata),
+        ..JoinOptions::default()
+    };
+    let verified = Some((true, collection));
+
+    let (holder, token_account, metadata) =
+        nft_holder(&mut env, verified, TOKEN_METADATA_PROGRAM_ID);
+    assert_error(
+        join(
+            &mut env,
+            &game,
+            holder,
+            JoinOptions {
+                gate_metadata: None,
+                ..showing(token_account, metadata)
+            },
+        ),
+        ErrorCode::GateInvalidMetadata,
+    );
+
+    // Metadata has to be the real Metaplex account for the NFT's mint
+    let (_, _, other_metadata) = nft_holder(&mut env, verified, TOKEN_METADATA_PROGRAM_ID);
+    assert_error(
+        join(
+            &mut env,
+            &game,
+            holder,
+            showing(token_account, other_metadata),
+        ),
+        ErrorCode::GateInvalidMetadata,
+    );
+    let (forger, forged_account, forged_metadata) =
+        nft_holder(&mut env, verified, Pubkey::new_unique());
+    assert_error(
+        join(
+            &mut env,
+            &game,
+            forger,
+            showing(forged_account, forged_metadata),
+        ),
+        ErrorCode::GateInvalidMetadata,
+    );
+
+    for collection in [
+        Some((false, collection)),
+        Some((true, Pubkey::new_unique())),
+        None,
+    ] {
+        let (player, token_account, metadata) =
+            nft_holder(&mut env, collection, TOKEN_METADATA_PROGRAM_ID);
+        assert_error(
+            join(&mut env, &game, player, showing(token_account, metadata)),
+            ErrorCode::GateNftNotInCollection,
+        );
+    }
+
+    join(&mut env, &game, holder, showing(token_account, metadata)).unwrap();
+}
+
+#[test]
+fn mint_list_gate() {
+    let mut env = Env::new();
+    let (listed, also_listed) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (leaf, sibling) = (
+        create_mint_list_leaf(&listed),
+        create_mint_list_leaf(&also_listed),
+    );
+    let game = env.create_game(GameParams {
+        gate: Gate::NftMintList {
+            root: hash_node(leaf, sibling),
+        },
+        ..GameParams::native()
+    });
+    let showing = |token_account, proof: Option<MintListProof>| JoinOptions {
+        gate_token_account: Some(token_account),
+        gate_proof: proof,
+        ..JoinOptions::default()
+    };
+    // The listed mint is the tree's first leaf
+    let listed_proof = |index| {
+        Some(MintListProof {
+            proof: vec![sibling],
+            index,
+        })
+    };
+
+    let holder = env.wallet();
+    env.svm.set_mint(listed, 0);
+    let token_account = env.fund_tokens(&holder, &listed, 1);
+    assert_error(
+        join(&mut env, &game, holder, showing(token_account, None)),
+        ErrorCode::GateNftNotInList,
+    );
+
+    let sold = env.wallet();
+    let empty_account = env.fund_tokens(&sold, &listed, 0);
+    assert_error(
+        join(
+            &mut env,
+            &game,
+            sold,
+            showing(empty_account, listed_proof(0)),
+        ),
+        ErrorCode::GateInsufficientBalance,
+    );
+
+    let unlisted = env.wallet();
+    let unlisted_mint = Pubkey::new_unique();
+    env.svm.set_mint(unlisted_mint, 0);
+    let unlisted_account = env.fund_tokens(&unlisted, &unlisted_mint, 1);
+    assert_error(
+        join(
+            &mut env,
+            &game,
+            unlisted,
+            showing(unlisted_account, listed_proof(0)),
+        ),
+        ErrorCode::GateNftNotInList,
+    );
+
+    // A proof for the wrong position doesn't verify
+    assert_error(
+        join(
+            &mut env,
+            &game,
+            holder,
+            showing(token_account, listed_proof(1)),
+        ),
+        ErrorCode::GateNftNotInList,
+    );
+
+    join(
+        &mut env,
+        &game,
+        holder,
+        showing(token_account, listed_proof(0)),
+    )
+    .unwrap();
+}
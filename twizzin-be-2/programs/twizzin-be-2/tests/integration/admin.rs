@@ -0,0 +1,330 @@
+//! Operators acting for the admin, and handing a game to a new admin.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use twizzin_be_2::errors::ErrorCode;
+use twizzin_be_2::state::{
+    AccessMode, Game, GameOperator, OPERATOR_ALL_PERMISSIONS, OPERATOR_DECLARE_WINNERS,
+    OPERATOR_START_END, OPERATOR_UPDATE_GAME,
+};
+use twizzin_be_2::utils::merkle::MERKLE_V1;
+use twizzin_be_2::{accounts, instruction};
+use twizzin_client::{find_game_operator_pda, instructions};
+
+use crate::setup::*;
+
+pub fn set_operator_ix(game: &TestGame, operator: Pubkey, permissions: u8) -> Instruction {
+    instructions::set_game_operator(
+        accounts::SetGameOperator {
+            admin: game.admin,
+            game: game.address,
+            game_operator: find_game_operator_pda(&game.address, &operator).0,
+            system_program: system_program::ID,
+        },
+        instruction::SetGameOperator {
+            operator,
+            permissions,
+        },
+    )
+}
+
+pub fn rename(name: &str) -> instruction::UpdateGame {
+    instruction::UpdateGame {
+        new_name: Some(name.to_string()),
+        ..no_changes()
+    }
+}
+
+// The update instruction signed by `operator` with its operator account attached
+pub fn operator_update_ix(
+    game: &TestGame,
+    operator: Pubkey,
+    args: instruction::UpdateGame,
+) -> Instruction {
+    let mut ix = game.update_ix(operator, args);
+    // game_operator is the third account; None is passed as the program id
+    ix.accounts[2].pubkey = find_game_operator_pda(&game.address, &operator).0;
+    ix
+}
+
+#[test]
+fn operator_runs_the_game() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams {
+        starts_in: 60_000,
+        ..GameParams::native()
+    });
+    let operator = env.wallet();
+    let operator_pda = find_game_operator_pda(&game.address, &operator).0;
+
+    env.send(
+        set_operator_ix(
+            &game,
+            operator,
+            OPERATOR_START_END | OPERATOR_DECLARE_WINNERS,
+        ),
+        game.admin,
+    )
+    .unwrap();
+    let stored: GameOperator = env.svm.get(&operator_pda).unwrap();
+    assert_eq!(
+        stored.permissions,
+        OPERATOR_START_END | OPERATOR_DECLARE_WINNERS
+    );
+
+    let result = env.send(
+        operator_update_ix(&game, operator, rename("Mine")),
+        operator,
+    );
+    assert_error(result, ErrorCode::OperatorNotPermitted);
+
+    // Granting the update permission later reuses the same account
+    env.send(
+        set_operator_ix(&game, operator, OPERATOR_ALL_PERMISSIONS),
+        game.admin,
+    )
+    .unwrap();
+    env.send(
+        operator_update_ix(&game, operator, rename("Mine")),
+        operator,
+    )
+    .unwrap();
+    assert_eq!(env.svm.get::<Game>(&game.address).unwrap().name, "Mine");
+
+    // So is the schedule
+    let reschedule = instruction::UpdateGame {
+        new_end_time: Some(game.end_time + 60_000),
+        ..no_changes()
+    };
+    env.send(operator_update_ix(&game, operator, reschedule), operator)
+        .unwrap();
+
+    // Money, answers, access and scoring are still the admin's call
+    let admin_only = [
+        instruction::UpdateGame {
+            new_entry_fee: Some(2 * game.entry_fee),
+            ..no_changes()
+        },
+        instruction::UpdateGame {
+            new_answer_hash: Some([7; 32]),
+            ..no_changes()
+        },
+        instruction::UpdateGame {
+            new_max_winners: Some(1),
+            ..no_changes()
+        },
+        instruction::UpdateGame {
+            new_even_split: Some(true),
+            ..no_changes()
+        },
+        instruction::UpdateGame {
+            new_access_mode: Some(AccessMode::Public),
+            ..no_changes()
+        },
+        instruction::UpdateGame {
+            new_wrong_answer_penalty: Some(1),
+            ..no_changes()
+        },
+        instruction::UpdateGame {
+            new_merkle_version: Some(MERKLE_V1),
+            ..no_changes()
+        },
+    ];
+    for args in admin_only {
+        let result = env.send(operator_update_ix(&game, operator, args), operator);
+        assert_error(result, ErrorCode::OperatorNotPermitted);
+    }
+    let rehash = instruction::UpdateGame {
+        new_answer_hash: Some(game.tree.root()),
+        ..no_changes()
+    };
+    env.send(game.update_ix(game.admin, rehash), game.admin)
+        .unwrap();
+
+    let player = env.player(&game);
+    let start = instructions::start_game(
+        accounts::StartGame {
+            authority: operator,
+            game: game.address,
+            game_operator: Some(operator_pda),
+        },
+        instruction::StartGame { total_time: 60_000 },
+    );
+    env.send(start, operator).unwrap();
+    assert!(env.svm.get::<Game>(&game.address).unwrap().started);
+
+    // Started early, the game can't be pushed back to reopen what the start froze
+    let postpone = instruction::UpdateGame {
+        new_start_time: Some(game.start_time + 60_000),
+        ..no_changes()
+    };
+    let result = env.send(operator_update_ix(&game, operator, postpone), operator);
+    assert_error(result, ErrorCode::ScheduleFrozen);
+
+    // The end can only move later while the game runs
+    let end_time = env.svm.get::<Game>(&game.address).unwrap().end_time;
+    let cut_short = instruction::UpdateGame {
+        new_end_time: Some(end_time - 1),
+        ..no_changes()
+    };
+    let result = env.send(operator_update_ix(&game, operator, cut_short), operator);
+    assert_error(result, ErrorCode::ScheduleFrozen);
+    let extend = instruction::UpdateGame {
+        new_end_time: Some(end_time + 60_000),
+        ..no_changes()
+    };
+    env.send(operator_update_ix(&game, operator, extend), operator)
+        .unwrap();
+    let stored: Game = env.svm.get(&game.address).unwrap();
+    assert_eq!(stored.end_time, end_time + 60_000);
+    env.submit(&game, player, &ANSWERS);
+
+    // The commission still goes to the admin when an operator ends the game
+    let admin_before = env.svm.lamports(&game.admin);
+    let end = instructions::end_game(
+        accounts::EndGame {
+            game_operator: Some(operator_pda),
+            ..game.end_accounts(operator)
+        },
+        instruction::EndGame {},
+        Vec::new(),
+    );
+    env.send(end, operator).unwrap();
+    assert!(env.svm.lamports(&game.admin) > admin_before);
+
+    let mut declare = game.declare_ix(operator, vec![player]);
+    declare.accounts[2].pubkey = operator_pda;
+    env.send(declare, operator).unwrap();
+
+    let rent = env.svm.lamports(&operator_pda);
+    let admin_before = env.svm.lamports(&game.admin);
+    let remove = instructions::remove_game_operator(
+        accounts::RemoveGameOperator {
+            admin: game.admin,
+            game: game.address,
+            game_operator: operator_pda,
+        },
+        instruction::RemoveGameOperator {},
+    );
+    env.send(remove, game.admin).unwrap();
+    assert!(env.svm.account(&operator_pda).is_none());
+    assert_eq!(env.svm.lamports(&game.admin), admin_before + rent);
+
+    let result = env.send(game.update_ix(operator, rename("Gone")), operator);
+    assert_error(result, ErrorCode::InvalidAuthority);
+}
+
+#[test]
+fn operators_are_checked() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let operator = env.wallet();
+
+    let result = env.send(
+        set_operator_ix(&game, game.admin, OPERATOR_UPDATE_GAME),
+        game.admin,
+    );
+    assert_error(result, ErrorCode::InvalidAuthority);
+    let result = env.send(set_operator_ix(&game, operator, 1 << 5), game.admin);
+    assert_error(result, ErrorCode::InvalidOperatorPermissions);
+
+    // Only the admin can hand out permissions
+    let mut ix = set_operator_ix(&game, operator, OPERATOR_UPDATE_GAME);
+    ix.accounts[0].pubkey = operator;
+    assert_error(env.send(ix, operator), ErrorCode::InvalidAdmin);
+
+    // Someone else's operator account doesn't stand in for your own
+    let other = env.wallet();
+    env.send(
+        set_operator_ix(&game, other, OPERATOR_UPDATE_GAME),
+        game.admin,
+    )
+    .unwrap();
+    let mut ix = game.update_ix(operator, rename("Mine"));
+    ix.accounts[2].pubkey = find_game_operator_pda(&game.address, &other).0;
+    let failure = env.send(ix, operator).unwrap_err();
+    assert_eq!(
+        failure.custom_code(),
+        Some(anchor_lang::error::ErrorCode::ConstraintSeeds.into())
+    );
+}
+
+#[test]
+fn admin_transfer_needs_acceptance() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let new_admin = env.wallet();
+
+    let transfer = |new| {
+        instructions::transfer_game_admin(
+            accounts::TransferGameAdmin {
+                admin: game.admin,
+                game: game.address,
+            },
+            instruction::TransferGameAdmin { new_admin: new },
+        )
+    };
+    let accept = |signer| {
+        instructions::accept_game_admin(
+            accounts::AcceptGameAdmin {
+                new_admin: signer,
+                game: game.address,
+            },
+            instruction::AcceptGameAdmin {},
+        )
+    };
+
+    assert_error(
+        env.send(transfer(Pubkey::default()), game.admin),
+        ErrorCode::InvalidNewAdmin,
+    );
+
+    // Transferring back to the current admin cancels
+    env.send(transfer(new_admin), game.admin).unwrap();
+    env.send(transfer(game.admin), game.admin).unwrap();
+    assert_eq!(
+        env.svm.get::<Game>(&game.address).unwrap().pending_admin,
+        None
+    );
+    assert_error(
+        env.send(accept(new_admin), new_admin),
+        ErrorCode::NotPendingAdmin,
+    );
+
+    let operator = env.wallet();
+    env.send(
+        set_operator_ix(&game, operator, OPERATOR_UPDATE_GAME),
+        game.admin,
+    )
+    .unwrap();
+    env.send(transfer(new_admin), game.admin).unwrap();
+    let stranger = env.wallet();
+    assert_error(
+        env.send(accept(stranger), stranger),
+        ErrorCode::NotPendingAdmin,
+    );
+    env.send(accept(new_admin), new_admin).unwrap();
+
+    let stored: Game = env.svm.get(&game.address).unwrap();
+    assert_eq!((stored.admin, stored.pending_admin), (new_admin, None));
+    let result = env.send(game.update_ix(game.admin, rename("Old")), game.admin);
+    assert_error(result, ErrorCode::InvalidAuthority);
+    env.send(game.update_ix(new_admin, rename("New")), new_admin)
+        .unwrap();
+
+    // The old admin's operators go with them until the new admin grants them again
+    let result = env.send(
+        operator_update_ix(&game, operator, rename("Mine")),
+        operator,
+    );
+    assert_error(result, ErrorCode::InvalidAuthority);
+    let mut grant = set_operator_ix(&game, operator, OPERATOR_UPDATE_GAME);
+    grant.accounts[0].pubkey = new_admin;
+    env.send(grant, new_admin).unwrap();
+    env.send(
+        operator_update_ix(&game, operator, rename("Mine")),
+        operator,
+    )
+    .unwrap();
+}
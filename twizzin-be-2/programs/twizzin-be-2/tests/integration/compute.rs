@@ -0,0 +1,54 @@
+//! Compute units for large submissions, measured on the program's SBF build.
+//!
+//! anchor build && cargo test -p twizzin-be-2 --test integration compute -- --ignored --nocapture
+
+use twizzin_be_2::state::PlayerAccount;
+
+use crate::setup::*;
+use crate::svm::Svm;
+
+const SBF_BUILD: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../target/deploy/twizzin_be_2.so"
+);
+
+// Every answer right and proven with one multiproof. The larger games don't fit
+// in one transaction, so this shows what compute alone would allow.
+#[test]
+#[ignore = "needs the SBF build from anchor build"]
+fn submit_answers_batch_units() {
+    let elf = std::fs::read(SBF_BUILD).expect("read the SBF build");
+    println!("questions | compute units | instruction bytes");
+    for questions in [10u8, 50, 100] {
+        let mut env = Env::with_svm(Svm::sbf(&elf));
+        let game = env.create_game(GameParams {
+            questions,
+            ..GameParams::native()
+        });
+        let player = env.player(&game);
+        env.start(&game, 60_000);
+        env.svm.advance_ms(20_000);
+        let finish = env.svm.now_ms();
+        env.svm.advance_ms(1000);
+
+        let picks: Vec<&str> = game
+            .entries
+            .iter()
+            .map(|entry| entry.answer.as_str())
+            .collect();
+        let mut answers = game.answers(&picks, finish);
+        answers.iter_mut().for_each(|answer| answer.proof.clear());
+        let claimed: Vec<u8> = (0..questions).collect();
+        let multiproof = game.tree.multiproof(&claimed).unwrap();
+        let ix = game.submit_batch_ix(player, answers, multiproof, finish);
+        let bytes = ix.data.len();
+        let units = env
+            .svm
+            .measure(&[ix], &[player])
+            .expect("submit answers batch");
+
+        let stored: PlayerAccount = env.svm.get(&game.player_pda(&player)).unwrap();
+        assert_eq!(stored.score, 10 * questions as i32);
+        println!("{:>9} | {:>13} | {:>17}", questions, units, bytes);
+    }
+}
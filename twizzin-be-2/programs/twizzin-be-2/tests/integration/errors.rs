@@ -0,0 +1,1329 @@
+//! Every `ErrorCode` variant, each with the smallest transaction that raises
+//! it - or the reason no transaction can.
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use anchor_spl::token;
+use twizzin_be_2::constants::{PROGRAM_AUTHORITY, TOKEN_METADATA_PROGRAM_ID};
+use twizzin_be_2::errors::ErrorCode;
+use twizzin_be_2::state::{
+    AccessMode, AccessProof, AnswerInput, AnswerMultiproof, FeeRecipient, Game, Gate,
+    MintListProof, ReferralFeeSource, MAX_SEASON_HOSTS, OPERATOR_START_END,
+};
+use twizzin_be_2::utils::create_allowlist_leaf;
+use twizzin_be_2::utils::merkle::MERKLE_V1;
+use twizzin_be_2::{accounts, instruction};
+use twizzin_client::{
+    find_config_pda, find_referral_balance_pda, find_season_player_pda, instructions,
+};
+
+use crate::access::{keypair, nft_holder, pubkey};
+use crate::admin::{operator_update_ix, rename, set_operator_ix};
+use crate::seasons::{create_season, season_game, TestSeason, SEASON_LENGTH};
+use crate::setup::*;
+use crate::svm::TxResult;
+use crate::tournaments::{
+    create_tournament, entrant, init_tournament, TestTournament, REGISTRATION,
+};
+
+// Every variant in declaration order, so each sits at 6000 + its index
+const ALL: [ErrorCode; 113] = {
+    use ErrorCode::*;
+    [
+        UnauthorizedProgramAuthority,
+        InvalidAuthority,
+        InvalidFee,
+        TreasuryFeeTooHigh,
+        TreasuryAddressBlank,
+        AuthorityAddressBlank,
+        NameTooLong,
+        GameCodeTooLong,
+        MaxWinnersTooLow,
+        MaxWinnersTooHigh,
+        InvalidTimeRange,
+        TokenMintRequired,
+        VaultRequired,
+        AdminTokenAccountNotProvided,
+        InvalidVaultAccount,
+        InvalidTokenAccount,
+        GameEnded,
+        PlayerTokenAccountNotProvided,
+        PlayerCountOverflow,
+        InvalidPlayer,
+        InvalidGame,
+        AlreadySubmitted,
+        GameNotStarted,
+        InvalidFinishTime,
+        InvalidAdmin,
+        NumericOverflow,
+        VaultTokenAccountNotProvided,
+        TreasuryTokenAccountNotProvided,
+        InvalidTreasury,
+        GameNotEnded,
+        InvalidBasisPoints,
+        InvalidWinnerCount,
+        InvalidWinnerOrder,
+        PlayerNotFinished,
+        DuplicateWinner,
+        WinnerNotPlayer,
+        NotAWinner,
+        PrizeAlreadyClaimed,
+        UnclaimedPrizes,
+        CannotCloseWinnerAccount,
+        TooManyFeeRecipients,
+        InvalidFeeRecipientWeights,
+        InvalidFeeRecipient,
+        ReferralFeeTooHigh,
+        SelfReferral,
+        ReferrerNotPlayer,
+        ReferralAccountsNotProvided,
+        InvalidReferralAccount,
+        NothingToWithdraw,
+        SeasonSettled,
+        SeasonNotEnded,
+        GameOutsideSeason,
+        SeasonFull,
+        SeasonAccountsNotProvided,
+        InvalidSeason,
+        EmptySeasonLeaderboard,
+        InvalidSeasonWinner,
+        TournamentRoundEntryFee,
+        TournamentFull,
+        TournamentRegistrationClosed,
+        TournamentAccountsNotProvided,
+        InvalidTournament,
+        NotAdvancedToRound,
+        TournamentNotComplete,
+        TournamentSettled,
+        TournamentNotSettled,
+        InvalidTournamentRounds,
+        InvalidAccessMode,
+        AccessProofNotProvided,
+        NotOnAllowlist,
+        InvalidInvite,
+        InviteExpired,
+        InvalidGate,
+        GateLocked,
+        GateTokenAccountNotProvided,
+        GateInvalidTokenAccount,
+        GateInsufficientBalance,
+        GateInvalidMetadata,
+        GateNftNotInCollection,
+        GateNftNotInList,
+        InvalidOperatorPermissions,
+        OperatorNotPermitted,
+        InvalidNewAdmin,
+        NotPendingAdmin,
+        GameCodeTaken,
+        InvalidGameCodeRegistry,
+        InvalidMetadataUri,
+        GameMetadataFrozen,
+        InvalidAnswerKey,
+        AnswerKeyMismatch,
+        AnswerTooLong,
+        ScoringFrozen,
+        InvalidSpeedBonus,
+        InvalidAnswerTime,
+        InvalidMerkleVersion,
+        MultiproofRequiresV2,
+        InvalidMultiproof,
+        NoAnswersSubmitted,
+        WinnersNotDeclared,
+        AlreadyMigrated,
+        ReferralAlreadySettled,
+        ReferralsNotSettled,
+        HostNotApproved,
+        TooManySeasonHosts,
+        GameCodeRegistryNotProvided,
+        ScheduleFrozen,
+        AnswerHashFrozen,
+        WinnersAlreadyDeclared,
+        AnswerKeyNotRevealed,
+        AnswerKeyAlreadyRevealed,
+        ScoresFrozen,
+        SeasonGamesPending,
+        DonationFrozen,
+    ]
+};
+
+const SECOND: [&str; 3] = ["a", "b", "x"];
+const THIRD: [&str; 3] = ["a", "x", "x"];
+
+enum Case {
+    Raised(fn() -> TxResult),
+    Unreachable(&'static str),
+}
+
+#[test]
+fn every_error_code() {
+    for (i, code) in ALL.into_iter().enumerate() {
+        assert_eq!(u32::from(code), 6000 + i as u32, "{code:?} is out of order");
+        match case(code) {
+            Case::Raised(scenario) => assert_error(scenario(), code),
+            Case::Unreachable(reason) => println!("{code:?} can't be raised: {reason}"),
+        }
+    }
+}
+
+// Passes None for every account at `key`
+fn omit(mut ix: Instruction, key: Pubkey) -> Instruction {
+    for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == key) {
+        *meta = AccountMeta::new_readonly(twizzin_be_2::ID, false);
+    }
+    ix
+}
+
+// Passes `to` in place of every account at `from`
+fn swap(mut ix: Instruction, from: Pubkey, to: Pubkey) -> Instruction {
+    for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == from) {
+        meta.pubkey = to;
+    }
+    ix
+}
+
+// Creates a game for a new admin, for games that are meant to fail
+fn create(env: &mut Env, params: GameParams) -> TxResult {
+    let admin = env.wallet();
+    create_as(env, admin, params)
+}
+
+fn create_as(env: &mut Env, admin: Pubkey, params: GameParams) -> TxResult {
+    let ix = TestGame::new(admin, &params, env.treasury, env.svm.now_ms()).init_ix(&params);
+    env.send(ix, admin)
+}
+
+fn invalid_game(params: GameParams) -> TxResult {
+    create(&mut Env::new(), params)
+}
+
+fn config_changes() -> instruction::UpdateConfig {
+    instruction::UpdateConfig {
+        new_treasury: None,
+        new_treasury_fee: None,
+        new_fee_recipients: None,
+        new_referral_fee: None,
+        new_referral_fee_source: None,
+    }
+}
+
+fn update_config(authority: Pubkey, args: instruction::UpdateConfig) -> TxResult {
+    let mut env = Env::new();
+    let ix = instructions::update_config(
+        accounts::UpdateConfig {
+            authority,
+            config: find_config_pda().0,
+        },
+        args,
+    );
+    env.send(ix, authority)
+}
+
+fn update(params: GameParams, args: instruction::UpdateGame) -> TxResult {
+    let mut env = Env::new();
+    let game = env.create_game(params);
+    env.send(game.update_ix(game.admin, args), game.admin)
+}
+
+// A started game whose players have each submitted `picks`, in order
+fn finished_game(env: &mut Env, params: GameParams, picks: &[&[&str]]) -> (TestGame, Vec<Pubkey>) {
+    let game = env.create_game(params);
+    let players: Vec<Pubkey> = picks.iter().map(|_| env.player(&game)).collect();
+    env.start(&game, 60_000);
+    for (player, picks) in players.iter().zip(picks) {
+        env.submit(&game, *player, picks);
+    }
+    (game, players)
+}
+
+fn ended_game(env: &mut Env, params: GameParams, picks: &[&[&str]]) -> (TestGame, Vec<Pubkey>) {
+    let (game, players) = finished_game(env, params, picks);
+    env.end(&game);
+    (game, players)
+}
+
+fn declare(winners: impl Fn(&[Pubkey]) -> Vec<Pubkey>) -> TxResult {
+    let mut env = Env::new();
+    let (game, players) = ended_game(&mut env, GameParams::native(), &[&ANSWERS, &SECOND]);
+    env.send(game.declare_ix(game.admin, winners(&players)), game.admin)
+}
+
+// A game whose two winners have been declared, with a third player who lost
+fn declared_game(env: &mut Env, params: GameParams) -> (TestGame, Vec<Pubkey>) {
+    let game = env.create_game(params);
+    let players: Vec<Pubkey> = (0..3).map(|_| env.player(&game)).collect();
+    env.play(
+        &game,
+        &[
+            (players[0], &ANSWERS),
+            (players[1], &SECOND),
+            (players[2], &THIRD),
+        ],
+    );
+    (game, players)
+}
+
+fn submit(params: GameParams, answers: impl Fn(&TestGame, i64) -> Vec<AnswerInput>) -> TxResult {
+    let mut env = Env::new();
+    let game = env.create_game(params);
+    let player = env.player(&game);
+    env.svm.advance_ms(1000);
+    let finish = env.svm.now_ms();
+    env.svm.advance_ms(1000);
+    env.send(
+        game.submit_ix(player, answers(&game, finish), finish),
+        player,
+    )
+}
+
+fn submit_at(finish: impl Fn(&Game, i64) -> i64) -> TxResult {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let player = env.player(&game);
+    env.svm.advance_ms(1000);
+    let stored: Game = env.svm.get(&game.address).unwrap();
+    let finish = finish(&stored, env.svm.now_ms());
+    let ix = game.submit_ix(player, game.answers(&ANSWERS, finish), finish);
+    env.send(ix, player)
+}
+
+fn join(params: GameParams, options: impl Fn(&mut Env) -> (Pubkey, JoinOptions)) -> TxResult {
+    let mut env = Env::new();
+    let game = env.create_game(params);
+    let (player, options) = options(&mut env);
+    env.send(game.join_ix(player, options), player)
+}
+
+// A game that needs 5 of a new mint to join, and that mint
+fn token_gated(env: &mut Env) -> (TestGame, Pubkey) {
+    let gate_mint = env.mint();
+    let game = env.create_game(gated(Gate::Token {
+        mint: gate_mint,
+        min_amount: 5,
+    }));
+    (game, gate_mint)
+}
+
+fn gated(gate: Gate) -> GameParams {
+    GameParams {
+        gate,
+        ..GameParams::native()
+    }
+}
+
+fn reveal(entries: impl Fn(&TestGame) -> Vec<twizzin_be_2::state::AnswerKeyEntry>) -> TxResult {
+    let mut env = Env::new();
+    let (game, _) = ended_game(&mut env, GameParams::native(), &[&ANSWERS]);
+    env.send(game.reveal_ix(entries(&game)), game.admin)
+}
+
+// A game on a config that takes a referral fee, with bob referred by alice
+fn referred_game(env: &mut Env) -> (TestGame, Pubkey) {
+    let game = env.create_game(GameParams::native());
+    let alice = env.player(&game);
+    let bob = env.wallet();
+    let options = JoinOptions {
+        referrer: Some(alice),
+        ..JoinOptions::default()
+    };
+    env.send(game.join_ix(bob, options), bob).unwrap();
+    (game, alice)
+}
+
+fn referral_env() -> Env {
+    let mut env = Env::without_config();
+    env.init_config(Vec::new(), 1000, ReferralFeeSource::Commission);
+    env
+}
+
+fn approved_host(env: &mut Env, season: &TestSeason) -> Pubkey {
+    let host = env.wallet();
+    env.send(season.approve_host_ix(host), PROGRAM_AUTHORITY)
+        .unwrap();
+    host
+}
+
+// A season with one game, won and claimed by a single player
+fn played_season(env: &mut Env) -> (TestSeason, Pubkey) {
+    let season = create_season(env, NATIVE_MINT, SOL);
+    let game = season_game(env, &season);
+    let player = env.player(&game);
+    env.play(&game, &[(player, &ANSWERS)]);
+    env.claim(&game, player);
+    env.svm.warp_to_ms(season.end_time);
+    (season, player)
+}
+
+// A season game's submission, without the player's season account
+fn submit_without_season_player() -> TxResult {
+    let mut env = Env::new();
+    let season = create_season(&mut env, NATIVE_MINT, SOL);
+    let game = season_game(&mut env, &season);
+    let player = env.player(&game);
+    env.svm.advance_ms(1000);
+    let finish = env.svm.now_ms();
+    env.svm.advance_ms(1000);
+    let ix = game.submit_ix(player, game.answers(&ANSWERS, finish), finish);
+    let season_player = find_season_player_pda(&season.address, &player).0;
+    env.send(omit(ix, season_player), player)
+}
+
+// Declares a game that isn't in the season as if it were
+fn declare_with_season() -> TxResult {
+    let mut env = Env::new();
+    let season = create_season(&mut env, NATIVE_MINT, SOL);
+    let mut game = env.create_game(GameParams::native());
+    let player = env.player(&game);
+    env.start(&game, 60_000);
+    env.submit(&game, player, &ANSWERS);
+    env.end(&game);
+    game.season = Some(season.address);
+    env.send(game.declare_ix(game.admin, vec![player]), game.admin)
+}
+
+// A one-round tournament whose round has been played and won by a single entrant
+fn played_tournament(env: &mut Env) -> (TestTournament, TestGame, Pubkey) {
+    let tournament = create_tournament(env, NATIVE_MINT, 1);
+    let player = entrant(env, &tournament);
+    let round = env.create_game_as(tournament.admin, tournament.params());
+    env.send(tournament.join_round_ix(&round, player, None), player)
+        .unwrap();
+    env.play(&round, &[(player, &ANSWERS)]);
+    (tournament, round, player)
+}
+
+fn case(code: ErrorCode) -> Case {
+    use Case::*;
+    use ErrorCode::*;
+    match code {
+        UnauthorizedProgramAuthority => Raised(|| {
+            let mut env = Env::without_config();
+            let stranger = env.wallet();
+            let ix = env.init_config_ix(stranger, 500, Vec::new(), 0, ReferralFeeSource::Treasury);
+            env.send(ix, stranger)
+        }),
+        InvalidAuthority => Raised(|| update_config(Pubkey::new_unique(), config_changes())),
+        TreasuryFeeTooHigh => Raised(|| {
+            update_config(
+                PROGRAM_AUTHORITY,
+                instruction::UpdateConfig {
+                    new_treasury_fee: Some(1001),
+                    ..config_changes()
+                },
+            )
+        }),
+        TreasuryAddressBlank => Raised(|| {
+            update_config(
+                PROGRAM_AUTHORITY,
+                instruction::UpdateConfig {
+                    new_treasury: Some(Pubkey::default()),
+                    ..config_changes()
+                },
+            )
+        }),
+        TooManyFeeRecipients => Raised(|| {
+            let recipient = |_| FeeRecipient {
+                recipient: Pubkey::new_unique(),
+                weight_bps: 2000,
+            };
+            update_config(
+                PROGRAM_AUTHORITY,
+                instruction::UpdateConfig {
+                    new_fee_recipients: Some((0..6).map(recipient).collect()),
+                    ..config_changes()
+                },
+            )
+        }),
+        InvalidFeeRecipientWeights => Raised(|| {
+            update_config(
+                PROGRAM_AUTHORITY,
+                instruction::UpdateConfig {
+                    new_fee_recipients: Some(vec![FeeRecipient {
+                        recipient: Pubkey::new_unique(),
+                        weight_bps: 5000,
+                    }]),
+                    ..config_changes()
+                },
+            )
+        }),
+        ReferralFeeTooHigh => Raised(|| {
+            update_config(
+                PROGRAM_AUTHORITY,
+                instruction::UpdateConfig {
+                    new_referral_fee: Some(1001),
+                    ..config_changes()
+                },
+            )
+        }),
+        InvalidFeeRecipient => Raised(|| {
+            // The config splits the treasury fee, but no recipients are passed
+            let mut env = Env::without_config();
+            let recipient = FeeRecipient {
+                recipient: Pubkey::new_unique(),
+                weight_bps: 10_000,
+            };
+            env.init_config(vec![recipient], 0, ReferralFeeSource::Treasury);
+            let (game, _) = finished_game(&mut env, GameParams::native(), &[&ANSWERS]);
+            env.send(game.end_ix(game.admin, Vec::new()), game.admin)
+        }),
+
+        NameTooLong => Raised(|| {
+            invalid_game(GameParams {
+                name: String::new(),
+                ..GameParams::native()
+            })
+        }),
+        GameCodeTooLong => Raised(|| {
+            invalid_game(GameParams {
+                code: "G".repeat(17),
+                ..GameParams::native()
+            })
+        }),
+        MaxWinnersTooLow => Raised(|| {
+            invalid_game(GameParams {
+                max_winners: 0,
+                ..GameParams::native()
+            })
+        }),
+        MaxWinnersTooHigh => Raised(|| {
+            invalid_game(GameParams {
+                max_winners: 201,
+                ..GameParams::native()
+            })
+        }),
+        InvalidTimeRange => Raised(|| {
+            invalid_game(GameParams {
+                duration: 0,
+                ..GameParams::native()
+            })
+        }),
+        InvalidAccessMode => Raised(|| {
+            invalid_game(GameParams {
+                access_mode: AccessMode::Allowlist { root: [0; 32] },
+                ..GameParams::native()
+            })
+        }),
+        InvalidGate => Raised(|| {
+            invalid_game(gated(Gate::Token {
+                mint: Pubkey::new_unique(),
+                min_amount: 0,
+            }))
+        }),
+        InvalidMetadataUri => Raised(|| {
+            invalid_game(GameParams {
+                metadata_uri: Some(String::new()),
+                ..GameParams::native()
+            })
+        }),
+        InvalidSpeedBonus => Raised(|| {
+            invalid_game(GameParams {
+                speed_bonus: 100,
+                speed_bonus_window: 0,
+                ..GameParams::native()
+            })
+        }),
+        InvalidMerkleVersion => Raised(|| {
+            // The client won't build a tree it doesn't know, so only the argument changes
+            let mut env = Env::new();
+            let (admin, params) = (env.wallet(), GameParams::native());
+            let game = TestGame::new(admin, &params, env.treasury, env.svm.now_ms());
+            let ix = game.init_ix(&GameParams {
+                merkle_version: 9,
+                ..params
+            });
+            env.send(ix, admin)
+        }),
+        GameCodeTaken => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            create(
+                &mut env,
+                GameParams {
+                    code: game.code,
+                    ..GameParams::native()
+                },
+            )
+        }),
+        AdminTokenAccountNotProvided => Raised(|| {
+            let mut env = Env::new();
+            let params = GameParams {
+                donation: SOL,
+                ..GameParams::spl(env.mint())
+            };
+            let admin = env.wallet();
+            let game = TestGame::new(admin, &params, env.treasury, env.svm.now_ms());
+            let ix = omit(game.init_ix(&params), game.token_account(&admin).unwrap());
+            env.send(ix, admin)
+        }),
+
+        GameMetadataFrozen => Raised(|| {
+            update(
+                GameParams::native(),
+                instruction::UpdateGame {
+                    new_metadata_uri: Some("https://example.com/game.json".to_string()),
+                    ..no_changes()
+                },
+            )
+        }),
+        ScoringFrozen => Raised(|| {
+            update(
+                GameParams::native(),
+                instruction::UpdateGame {
+                    new_speed_bonus: Some(100),
+                    ..no_changes()
+                },
+            )
+        }),
+        ScheduleFrozen => Raised(|| {
+            update(
+                GameParams::native(),
+                instruction::UpdateGame {
+                    new_start_time: Some(0),
+                    ..no_changes()
+                },
+            )
+        }),
+        AnswerHashFrozen => Raised(|| {
+            update(
+                GameParams::native(),
+                instruction::UpdateGame {
+                    new_answer_hash: Some([7; 32]),
+                    ..no_changes()
+                },
+            )
+        }),
+        DonationFrozen => Raised(|| {
+            update(
+                GameParams::native(),
+                instruction::UpdateGame {
+                    new_donation_amount: Some(SOL),
+                    ..no_changes()
+                },
+            )
+        }),
+        GateLocked => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            env.player(&game);
+            let lock = instruction::UpdateGame {
+                new_gate: Some(Gate::Token {
+                    mint: Pubkey::new_unique(),
+                    min_amount: 1,
+                }),
+                ..no_changes()
+            };
+            env.send(game.update_ix(game.admin, lock), game.admin)
+        }),
+        InvalidOperatorPermissions => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let operator = env.wallet();
+            env.send(set_operator_ix(&game, operator, 1 << 5), game.admin)
+        }),
+        OperatorNotPermitted => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let operator = env.wallet();
+            env.send(
+                set_operator_ix(&game, operator, OPERATOR_START_END),
+                game.admin,
+            )
+            .unwrap();
+            env.send(
+                operator_update_ix(&game, operator, rename("Mine")),
+                operator,
+            )
+        }),
+        InvalidAdmin => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let stranger = env.wallet();
+            let ix = instructions::transfer_game_admin(
+                accounts::TransferGameAdmin {
+                    admin: stranger,
+                    game: game.address,
+                },
+                instruction::TransferGameAdmin {
+                    new_admin: stranger,
+                },
+            );
+            env.send(ix, stranger)
+        }),
+        InvalidNewAdmin => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let ix = instructions::transfer_game_admin(
+                accounts::TransferGameAdmin {
+                    admin: game.admin,
+                    game: game.address,
+                },
+                instruction::TransferGameAdmin {
+                    new_admin: Pubkey::default(),
+                },
+            );
+            env.send(ix, game.admin)
+        }),
+        NotPendingAdmin => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let stranger = env.wallet();
+            let ix = instructions::accept_game_admin(
+                accounts::AcceptGameAdmin {
+                    new_admin: stranger,
+                    game: game.address,
+                },
+                instruction::AcceptGameAdmin {},
+            );
+            env.send(ix, stranger)
+        }),
+
+        GameEnded => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            env.svm.warp_to_ms(game.end_time);
+            let player = env.wallet();
+            env.send(game.join_ix(player, JoinOptions::default()), player)
+        }),
+        PlayerTokenAccountNotProvided => Raised(|| {
+            let mut env = Env::new();
+            let mint = env.mint();
+            let game = env.create_game(GameParams::spl(mint));
+            let player = env.wallet();
+            let ix = game.join_ix(player, JoinOptions::default());
+            env.send(omit(ix, game.token_account(&player).unwrap()), player)
+        }),
+        InvalidTokenAccount => Raised(|| {
+            // Paying with someone else's tokens
+            let mut env = Env::new();
+            let mint = env.mint();
+            let game = env.create_game(GameParams::spl(mint));
+            let (owner, player) = (env.wallet(), env.wallet());
+            let token_account = env.fund_tokens(&owner, &game.mint, SOL);
+            let ix = game.join_ix(player, JoinOptions::default());
+            let ix = swap(ix, game.token_account(&player).unwrap(), token_account);
+            env.send(ix, player)
+        }),
+        SelfReferral => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let player = env.wallet();
+            let options = JoinOptions {
+                referrer: Some(player),
+                ..JoinOptions::default()
+            };
+            let mut ix = game.join_ix(player, options);
+            // The referrer's player account is the player's own, which doesn't exist yet
+            let referrer = ix
+                .accounts
+                .iter()
+                .rposition(|meta| meta.pubkey == game.player_pda(&player))
+                .unwrap();
+            ix.accounts[referrer] = AccountMeta::new_readonly(twizzin_be_2::ID, false);
+            env.send(ix, player)
+        }),
+        ReferrerNotPlayer => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let (referrer, player) = (env.wallet(), env.wallet());
+            let options = JoinOptions {
+                referrer: Some(referrer),
+                ..JoinOptions::default()
+            };
+            let ix = omit(game.join_ix(player, options), game.player_pda(&referrer));
+            env.send(ix, player)
+        }),
+        AccessProofNotProvided => Raised(|| {
+            join(
+                GameParams {
+                    access_mode: AccessMode::Allowlist {
+                        root: create_allowlist_leaf(&Pubkey::new_unique()),
+                    },
+                    ..GameParams::native()
+                },
+                |env| (env.wallet(), JoinOptions::default()),
+            )
+        }),
+        NotOnAllowlist => Raised(|| {
+            join(
+                GameParams {
+                    access_mode: AccessMode::Allowlist {
+                        root: create_allowlist_leaf(&Pubkey::new_unique()),
+                    },
+                    ..GameParams::native()
+                },
+                |env| {
+                    let options = JoinOptions {
+                        access_proof: Some(AccessProof::Allowlist {
+                            proof: Vec::new(),
+                            index: 0,
+                        }),
+                        ..JoinOptions::default()
+                    };
+                    (env.wallet(), options)
+                },
+            )
+        }),
+        InvalidInvite => Raised(|| {
+            // The invite has to be signed in an ed25519 instruction ahead of the join
+            join(
+                GameParams {
+                    access_mode: AccessMode::SignedInvite {
+                        invite_signer: pubkey(&keypair(2)),
+                    },
+                    ..GameParams::native()
+                },
+                |env| {
+                    let options = JoinOptions {
+                        access_proof: Some(AccessProof::SignedInvite {
+                            expiry: env.svm.now_ms() + 60_000,
+                        }),
+                        instructions_sysvar: true,
+                        ..JoinOptions::default()
+                    };
+                    (env.wallet(), options)
+                },
+            )
+        }),
+        InviteExpired => Raised(|| {
+            join(
+                GameParams {
+                    access_mode: AccessMode::SignedInvite {
+                        invite_signer: pubkey(&keypair(2)),
+                    },
+                    ..GameParams::native()
+                },
+                |env| {
+                    let options = JoinOptions {
+                        access_proof: Some(AccessProof::SignedInvite {
+                            expiry: env.svm.now_ms(),
+                        }),
+                        instructions_sysvar: true,
+                        ..JoinOptions::default()
+                    };
+                    (env.wallet(), options)
+                },
+            )
+        }),
+        GateTokenAccountNotProvided => Raised(|| {
+            let mut env = Env::new();
+            let (game, _) = token_gated(&mut env);
+            let player = env.wallet();
+            env.send(game.join_ix(player, JoinOptions::default()), player)
+        }),
+        GateInvalidTokenAccount => Raised(|| {
+            let mut env = Env::new();
+            let (game, _) = token_gated(&mut env);
+            let (player, other_mint) = (env.wallet(), env.mint());
+            let options = JoinOptions {
+                gate_token_account: Some(env.fund_tokens(&player, &other_mint, 5)),
+                ..JoinOptions::default()
+            };
+            env.send(game.join_ix(player, options), player)
+        }),
+        GateInsufficientBalance => Raised(|| {
+            let mut env = Env::new();
+            let (game, gate_mint) = token_gated(&mut env);
+            let player = env.wallet();
+            let options = JoinOptions {
+                gate_token_account: Some(env.fund_tokens(&player, &gate_mint, 4)),
+                ..JoinOptions::default()
+            };
+            env.send(game.join_ix(player, options), player)
+        }),
+        GateInvalidMetadata => Raised(|| {
+            let mut env = Env::new();
+            let collection = Pubkey::new_unique();
+            let game = env.create_game(gated(Gate::NftCollection { collection }));
+            let (player, token_account, _) = nft_holder(
+                &mut env,
+                Some((true, collection)),
+                TOKEN_METADATA_PROGRAM_ID,
+            );
+            let options = JoinOptions {
+                gate_token_account: Some(token_account),
+                ..JoinOptions::default()
+            };
+            env.send(game.join_ix(player, options), player)
+        }),
+        GateNftNotInCollection => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(gated(Gate::NftCollection {
+                collection: Pubkey::new_unique(),
+            }));
+            let (player, token_account, metadata) =
+                nft_holder(&mut env, None, TOKEN_METADATA_PROGRAM_ID);
+            let options = JoinOptions {
+                gate_token_account: Some(token_account),
+                gate_metadata: Some(metadata),
+                ..JoinOptions::default()
+            };
+            env.send(game.join_ix(player, options), player)
+        }),
+        GateNftNotInList => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(gated(Gate::NftMintList { root: [1; 32] }));
+            let (player, token_account, _) = nft_holder(&mut env, None, TOKEN_METADATA_PROGRAM_ID);
+            let options = JoinOptions {
+                gate_token_account: Some(token_account),
+                gate_proof: Some(MintListProof {
+                    proof: Vec::new(),
+                    index: 0,
+                }),
+                ..JoinOptions::default()
+            };
+            env.send(game.join_ix(player, options), player)
+        }),
+
+        AlreadySubmitted => Raised(|| {
+            // A second submit_answers trips over its submission account's init first
+            let mut env = Env::new();
+            let (game, players) = finished_game(&mut env, GameParams::native(), &[&ANSWERS]);
+            let ix = game.chunk_ix(players[0], game.answers(&ANSWERS, env.svm.now_ms()));
+            env.send(ix, players[0])
+        }),
+        GameNotStarted => Raised(|| submit_at(|game, _| game.start_time - 1)),
+        InvalidFinishTime => Raised(|| submit_at(|_, now| now)),
+        AnswerTooLong => Raised(|| {
+            submit(GameParams::native(), |game, finish| {
+                let mut answers = game.answers(&ANSWERS, finish);
+                answers[0].answer = "a".repeat(33);
+                answers
+            })
+        }),
+        InvalidAnswerTime => Raised(|| {
+            let params = GameParams {
+                speed_bonus: 100,
+                speed_bonus_window: 10_000,
+                ..GameParams::native()
+            };
+            submit(params, |game, finish| {
+                let mut answers = game.answers(&ANSWERS, finish);
+                answers[0].answered_at = finish + 1;
+                answers
+            })
+        }),
+        MultiproofRequiresV2 => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams {
+                merkle_version: MERKLE_V1,
+                ..GameParams::native()
+            });
+            let player = env.player(&game);
+            env.svm.advance_ms(1000);
+            let finish = env.svm.now_ms() - 1;
+            let multiproof = AnswerMultiproof {
+                claimed: Vec::new(),
+                proof: Vec::new(),
+                depth: 0,
+            };
+            let answers = game.answers(&ANSWERS, finish);
+            let ix = game.submit_batch_ix(player, answers, multiproof, finish);
+            env.send(ix, player)
+        }),
+        InvalidMultiproof => Raised(|| {
+            // Batched answers carry no proofs of their own
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let player = env.player(&game);
+            env.svm.advance_ms(1000);
+            let finish = env.svm.now_ms() - 1;
+            let multiproof = game.tree.multiproof(&[0, 1, 2]).unwrap();
+            let answers = game.answers(&ANSWERS, finish);
+            let ix = game.submit_batch_ix(player, answers, multiproof, finish);
+            env.send(ix, player)
+        }),
+        NoAnswersSubmitted => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            let player = env.player(&game);
+            env.svm.advance_ms(1000);
+            env.send(game.chunk_ix(player, Vec::new()), player).unwrap();
+            let finish = env.svm.now_ms() - 1;
+            env.send(game.finalize_ix(player, finish), player)
+        }),
+        WinnersNotDeclared => Raised(|| {
+            // A finisher could still be a winner, so can't leave before the declaration
+            let mut env = Env::new();
+            let (game, players) = ended_game(&mut env, GameParams::native(), &[&ANSWERS]);
+            let ix = omit(game.close_player_ix(players[0], true), game.winners_pda());
+            env.send(ix, players[0])
+        }),
+        AlreadyMigrated => Raised(|| {
+            let mut env = Env::new();
+            let ix = instructions::migrate_config(
+                accounts::MigrateConfig {
+                    authority: PROGRAM_AUTHORITY,
+                    config: find_config_pda().0,
+                    system_program: system_program::ID,
+                },
+                instruction::MigrateConfig {},
+            );
+            env.send(ix, PROGRAM_AUTHORITY)
+        }),
+        ReferralAlreadySettled => Raised(|| {
+            let mut env = referral_env();
+            let (game, alice) = referred_game(&mut env);
+            env.start(&game, 60_000);
+            env.end(&game);
+            env.send(game.settle_referral_ix(&alice), game.admin)
+                .unwrap();
+            env.send(game.settle_referral_ix(&alice), game.admin)
+        }),
+        ReferralsNotSettled => Raised(|| {
+            let mut env = referral_env();
+            let (game, alice) = referred_game(&mut env);
+            let winners = env.play(&game, &[(alice, &ANSWERS)]);
+            env.claim(&game, winners[0]);
+            env.send(game.close_ix(), game.admin)
+        }),
+
+        InvalidTreasury => Raised(|| {
+            let mut env = Env::new();
+            let (game, _) = finished_game(&mut env, GameParams::native(), &[&ANSWERS]);
+            let stranger = env.wallet();
+            let ix = swap(game.end_ix(game.admin, Vec::new()), env.treasury, stranger);
+            env.send(ix, game.admin)
+        }),
+        TreasuryTokenAccountNotProvided => Raised(|| {
+            let mut env = Env::new();
+            let params = GameParams::spl(env.mint());
+            let (game, _) = finished_game(&mut env, params, &[&ANSWERS]);
+            let treasury_account = game.token_account(&env.treasury).unwrap();
+            let ix = omit(game.end_ix(game.admin, Vec::new()), treasury_account);
+            env.send(ix, game.admin)
+        }),
+        ReferralAccountsNotProvided => Raised(|| {
+            let mut env = referral_env();
+            let game = env.create_game(GameParams::native());
+            let alice = env.player(&game);
+            let bob = env.wallet();
+            let options = JoinOptions {
+                referrer: Some(alice),
+                ..JoinOptions::default()
+            };
+            let balance = find_referral_balance_pda(&alice, &game.mint).0;
+            env.send(omit(game.join_ix(bob, options), balance), bob)
+        }),
+        NothingToWithdraw => Raised(|| {
+            // Referral rewards only arrive when the game ends
+            let mut env = referral_env();
+            let (game, alice) = referred_game(&mut env);
+            let balance = find_referral_balance_pda(&alice, &game.mint).0;
+            let ix = instructions::withdraw_referral_balance(
+                accounts::WithdrawReferralBalance {
+                    referrer: alice,
+                    referral_balance: balance,
+                    referral_token_account: None,
+                    referrer_token_account: None,
+                    token_program: token::ID,
+                    system_program: system_program::ID,
+                },
+                instruction::WithdrawReferralBalance {},
+            );
+            env.send(ix, alice)
+        }),
+
+        GameNotEnded => Raised(|| {
+            let mut env = Env::new();
+            let (game, players) = finished_game(&mut env, GameParams::native(), &[&ANSWERS]);
+            env.send(game.declare_ix(game.admin, players), game.admin)
+        }),
+        VaultTokenAccountNotProvided => Raised(|| {
+            let mut env = Env::new();
+            let params = GameParams::spl(env.mint());
+            let (game, players) = ended_game(&mut env, params, &[&ANSWERS]);
+            let ix = game.declare_ix(game.admin, players);
+            env.send(omit(ix, game.vault_token_account().unwrap()), game.admin)
+        }),
+        InvalidWinnerCount => {
+            Raised(|| declare(|players| vec![players[0], players[1], Pubkey::new_unique()]))
+        }
+        InvalidWinnerOrder => Raised(|| declare(|players| vec![players[1], players[0]])),
+        DuplicateWinner => Raised(|| declare(|players| vec![players[0], players[0]])),
+        WinnersAlreadyDeclared => Raised(|| {
+            let mut env = Env::new();
+            let (game, players) = declared_game(&mut env, GameParams::native());
+            env.send(game.declare_ix(game.admin, vec![players[2]]), game.admin)
+        }),
+        PlayerNotFinished => Raised(|| {
+            // A player who never submitted, declared in place of one who did
+            let mut env = Env::new();
+            let (game, players) =
+                finished_game(&mut env, GameParams::native(), &[&ANSWERS, &SECOND]);
+            let idle = env.wallet();
+            env.send(game.join_ix(idle, JoinOptions::default()), idle)
+                .unwrap();
+            env.end(&game);
+            env.send(
+                game.declare_ix(game.admin, vec![players[0], idle]),
+                game.admin,
+            )
+        }),
+        WinnerNotPlayer => Raised(|| {
+            // A stranger declared with another player's account in its place
+            let mut env = Env::new();
+            let (game, players) = ended_game(&mut env, GameParams::native(), &[&ANSWERS, &SECOND]);
+            let stranger = env.wallet();
+            let ix = game.declare_ix(game.admin, vec![players[0], stranger]);
+            let ix = swap(ix, game.player_pda(&stranger), game.player_pda(&players[1]));
+            env.send(ix, game.admin)
+        }),
+
+        NotAWinner => Raised(|| {
+            let mut env = Env::new();
+            let (game, players) = declared_game(&mut env, GameParams::native());
+            env.send(game.claim_ix(players[2]), players[2])
+        }),
+        UnclaimedPrizes => Raised(|| {
+            let mut env = Env::new();
+            let (game, _) = declared_game(&mut env, GameParams::native());
+            env.send(game.close_ix(), game.admin)
+        }),
+        GameCodeRegistryNotProvided => Raised(|| {
+            let mut env = Env::new();
+            let game = env.create_game(GameParams::native());
+            env.start(&game, 60_000);
+            env.end(&game);
+            env.declare(&game, &[]);
+            env.send(omit(game.close_ix(), game.registry_pda()), game.admin)
+        }),
+        CannotCloseWinnerAccount => Raised(|| {
+            let mut env = Env::new();
+            let (game, players) = declared_game(&mut env, GameParams::native());
+            env.send(game.close_player_ix(players[0], true), players[0])
+        }),
+        PrizeAlreadyClaimed => Raised(|| {
+            // Game claims close the player account, so only tournament prizes can be claimed twice
+            let mut env = Env::new();
+            let (tournament, round, player) = played_tournament(&mut env);
+            env.send(tournament.settle_ix(&round), tournament.admin)
+                .unwrap();
+            env.send(tournament.claim_ix(&round, player), player)
+                .unwrap();
+            env.send(tournament.claim_ix(&round, player), player)
+        }),
+
+        SeasonSettled => Raised(|| {
+            let mut env = Env::new();
+            let (season, player) = played_season(&mut env);
+            env.send(season.settle_ix(&[player]), PROGRAM_AUTHORITY)
+                .unwrap();
+            env.send(season.settle_ix(&[player]), PROGRAM_AUTHORITY)
+        }),
+        SeasonNotEnded => Raised(|| {
+            let mut env = Env::new();
+            let season = create_season(&mut env, NATIVE_MINT, SOL);
+            env.send(season.settle_ix(&[]), PROGRAM_AUTHORITY)
+        }),
+        GameOutsideSeason => Raised(|| {
+            let mut env = Env::new();
+            let season = create_season(&mut env, NATIVE_MINT, SOL);
+            let host = approved_host(&mut env, &season);
+            let params = GameParams {
+                season: Some(season.address),
+                duration: 3 * SEASON_LENGTH,
+                ..GameParams::native()
+            };
+            create_as(&mut env, host, params)
+        }),
+        SeasonFull => Raised(|| {
+            let mut env = Env::new();
+            let season = create_season(&mut env, NATIVE_MINT, SOL);
+            season_game(&mut env, &season);
+            season_game(&mut env, &season);
+            let host = approved_host(&mut env, &season);
+            let params = GameParams {
+                season: Some(season.address),
+                ..GameParams::native()
+            };
+            create_as(&mut env, host, params)
+        }),
+        SeasonAccountsNotProvided => Raised(submit_without_season_player),
+        InvalidSeason => Raised(declare_with_season),
+        HostNotApproved => Raised(|| {
+            let mut env = Env::new();
+            let season = create_season(&mut env, NATIVE_MINT, SOL);
+            let params = GameParams {
+                season: Some(season.address),
+                ..GameParams::native()
+            };
+            create(&mut env, params)
+        }),
+        TooManySeasonHosts => Raised(|| {
+            let mut env = Env::new();
+            let season = create_season(&mut env, NATIVE_MINT, SOL);
+            for _ in 0..MAX_SEASON_HOSTS {
+                approved_host(&mut env, &season);
+            }
+            let host = env.wallet();
+            env.send(season.approve_host_ix(host), PROGRAM_AUTHORITY)
+        }),
+        SeasonGamesPending => Raised(|| {
+            // Its one game never declared winners
+            let mut env = Env::new();
+            let season = create_season(&mut env, NATIVE_MINT, SOL);
+            season_game(&mut env, &season);
+            env.svm.warp_to_ms(season.end_time);
+            env.send(season.settle_ix(&[]), PROGRAM_AUTHORITY)
+        }),
+        EmptySeasonLeaderboard => Raised(|| {
+            let mut env = Env::new();
+            let season = create_season(&mut env, NATIVE_MINT, SOL);
+            env.svm.warp_to_ms(season.end_time);
+            env.send(season.settle_ix(&[]), PROGRAM_AUTHORITY)
+        }),
+        InvalidSeasonWinner => Raised(|| {
+            let mut env = Env::new();
+            let (season, _) = played_season(&mut env);
+            let stranger = env.wallet();
+            env.send(season.settle_ix(&[stranger]), PROGRAM_AUTHORITY)
+        }),
+
+        InvalidBasisPoints => Raised(|| {
+            let mut env = Env::new();
+            let (tournament, ix) = init_tournament(&mut env, NATIVE_MINT, 1, 1001);
+            env.send(ix, tournament.admin)
+        }),
+        InvalidTournamentRounds => Raised(|| {
+            let mut env = Env::new();
+            let (tournament, ix) = init_tournament(&mut env, NATIVE_MINT, 0, 1000);
+            env.send(ix, tournament.admin)
+        }),
+        TournamentRoundEntryFee => Raised(|| {
+            let mut env = Env::new();
+            let tournament = create_tournament(&mut env, NATIVE_MINT, 1);
+            let paid = GameParams {
+                entry_fee: 1,
+                ..tournament.params()
+            };
+            env.send(tournament.round_ix(&env, &paid), tournament.admin)
+        }),
+        TournamentFull => Raised(|| {
+            let mut env = Env::new();
+            let tournament = create_tournament(&mut env, NATIVE_MINT, 1);
+            env.create_game_as(tournament.admin, tournament.params());
+            env.send(
+                tournament.round_ix(&env, &tournament.params()),
+                tournament.admin,
+            )
+        }),
+        TournamentRegistrationClosed => Raised(|| {
+            let mut env = Env::new();
+            let tournament = create_tournament(&mut env, NATIVE_MINT, 1);
+            env.svm.advance_ms(REGISTRATION);
+            let player = env.wallet();
+            env.send(tournament.join_ix(player), player)
+        }),
+        TournamentAccountsNotProvided => Raised(|| {
+            let mut env = Env::new();
+            let tournament = create_tournament(&mut env, NATIVE_MINT, 1);
+            let round = env.create_game_as(tournament.admin, tournament.params());
+            let player = entrant(&mut env, &tournament);
+            env.send(round.join_ix(player, JoinOptions::default()), player)
+        }),
+        InvalidTournament => Raised(|| {
+            // Entered in one tournament, joining another's round
+            let mut env = Env::new();
+            let (tournament, other) = (
+                create_tournament(&mut env, NATIVE_MINT, 1),
+                create_tournament(&mut env, NATIVE_MINT, 1),
+            );
+            let round = env.create_game_as(tournament.admin, tournament.params());
+            let player = entrant(&mut env, &other);
+            env.send(other.join_round_ix(&round, player, None), player)
+        }),
+        NotAdvancedToRound => Raised(|| {
+            let mut env = Env::new();
+            let tournament = create_tournament(&mut env, NATIVE_MINT, 2);
+            let players: Vec<Pubkey> = (0..3).map(|_| entrant(&mut env, &tournament)).collect();
+            let first = env.create_game_as(tournament.admin, tournament.params());
+            for player in &players {
+                env.send(tournament.join_round_ix(&first, *player, None), *player)
+                    .unwrap();
+            }
+            env.play(
+                &first,
+                &[
+                    (players[0], &ANSWERS),
+                    (players[1], &SECOND),
+                    (players[2], &THIRD),
+                ],
+            );
+            let last = env.create_game_as(tournament.admin, tournament.params());
+            let ix = tournament.join_round_ix(&last, players[2], Some(&first));
+            env.send(ix, players[2])
+        }),
+        TournamentNotComplete => Raised(|| {
+            let mut env = Env::new();
+            let tournament = create_tournament(&mut env, NATIVE_MINT, 2);
+            let player = entrant(&mut env, &tournament);
+            let first = env.create_game_as(tournament.admin, tournament.params());
+            env.send(tournament.join_round_ix(&first, player, None), player)
+                .unwrap();
+            env.play(&first, &[(player, &ANSWERS)]);
+            env.send(tournament.settle_ix(&first), tournament.admin)
+        }),
+        TournamentSettled => Raised(|| {
+            let mut env = Env::new();
+            let (tournament, round, _) = played_tournament(&mut env);
+            env.send(tournament.settle_ix(&round), tournament.admin)
+                .unwrap();
+            env.send(tournament.settle_ix(&round), tournament.admin)
+        }),
+        TournamentNotSettled => Raised(|| {
+            let mut env = Env::new();
+            let (tournament, round, player) = played_tournament(&mut env);
+            env.send(tournament.claim_ix(&round, player), player)
+        }),
+
+        InvalidAnswerKey => Raised(|| reveal(|_| Vec::new())),
+        AnswerKeyMismatch => Raised(|| {
+            reveal(|game| {
+                let mut entries = game.entries.clone();
+                entries[0].answer = "b".to_string();
+                entries
+            })
+        }),
+        AnswerKeyNotRevealed => Raised(|| {
+            // The whole key is up but hasn't been checked
+            let mut env = Env::new();
+            let (game, players) = ended_game(&mut env, GameParams::native(), &[&ANSWERS]);
+            env.send(game.reveal_chunk_ix(game.entries.clone()), game.admin)
+                .unwrap();
+            env.send(game.rescore_ix(players[0], players[0]), players[0])
+        }),
+        AnswerKeyAlreadyRevealed => Raised(|| {
+            let mut env = Env::new();
+            let (game, _) = ended_game(&mut env, GameParams::native(), &[&ANSWERS]);
+            env.send(game.reveal_ix(game.entries.clone()), game.admin)
+                .unwrap();
+            env.send(game.reveal_chunk_ix(game.entries.clone()), game.admin)
+        }),
+        ScoresFrozen => Raised(|| {
+            let mut env = Env::new();
+            let (game, players) = declared_game(&mut env, GameParams::native());
+            env.send(game.reveal_ix(game.entries.clone()), game.admin)
+                .unwrap();
+            env.send(game.rescore_ix(players[2], players[2]), players[2])
+        }),
+
+        InvalidFee
+        | AuthorityAddressBlank
+        | TokenMintRequired
+        | VaultRequired
+        | InvalidVaultAccount => Unreachable("no instruction raises it"),
+        NumericOverflow => {
+            Unreachable("amounts are bounded by real balances long before checked math overflows")
+        }
+        PlayerCountOverflow => Unreachable("a game would need u32::MAX players"),
+        InvalidPlayer | InvalidGame => {
+            Unreachable("the player account's seeds already pin its player and game")
+        }
+        InvalidReferralAccount => {
+            Unreachable("the referral accounts' seeds already pin their game and referrer")
+        }
+        InvalidGameCodeRegistry => {
+            Unreachable("a code only moves to another game after its current game has closed")
+        }
+    }
+}
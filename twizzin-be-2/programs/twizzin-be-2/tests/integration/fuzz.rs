@@ -0,0 +1,450 @@
+//! Random interleavings of a game's instructions, for SOL and SPL games.
+//! Most actions fail in most states - what matters is that after every step
+//! the vault can still pay what it owes, and that once the game settles the
+//! vault is empty and nothing the game created is left behind.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::rent::Rent;
+use proptest::prelude::*;
+use twizzin_be_2::instruction;
+use twizzin_be_2::state::{Game, GameReferral, PlayerAccount, ReferralFeeSource, Winners};
+use twizzin_client::ranking::determine_winners;
+use twizzin_client::{find_game_referral_pda, find_referral_balance_pda};
+
+use crate::setup::*;
+
+const PLAYERS: usize = 5;
+const MAX_BATCH: usize = 3;
+
+#[derive(Debug, Clone)]
+enum Action {
+    Join {
+        player: usize,
+        referrer: Option<usize>,
+    },
+    Update {
+        donation: Option<u64>,
+        extend_seconds: Option<i64>,
+    },
+    Start {
+        seconds: i64,
+    },
+    Submit {
+        player: usize,
+        correct: [bool; 3],
+    },
+    Wait {
+        seconds: i64,
+    },
+    End,
+    Declare {
+        batch: usize,
+    }, // Up to this many of the winners not yet declared
+    SettleReferral {
+        referrer: usize,
+    },
+    Claim {
+        player: usize,
+    },
+    ClosePlayer {
+        player: usize,
+    },
+    CloseGame,
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    let player = 0..PLAYERS;
+    prop_oneof![
+        4 => (player.clone(), prop::option::of(player.clone()))
+            .prop_map(|(player, referrer)| Action::Join { player, referrer })
+            .boxed(),
+        2 => (prop::option::of(amount()), prop::option::of(1..120i64))
+            .prop_map(|(donation, extend_seconds)| Action::Update { donation, extend_seconds })
+            .boxed(),
+        1 => (10..120i64).prop_map(|seconds| Action::Start { seconds }).boxed(),
+        4 => (player.clone(), any::<[bool; 3]>())
+            .prop_map(|(player, correct)| Action::Submit { player, correct })
+            .boxed(),
+        2 => (1..90i64).prop_map(|seconds| Action::Wait { seconds }).boxed(),
+        1 => Just(Action::End).boxed(),
+        2 => (1..=MAX_BATCH).prop_map(|batch| Action::Declare { batch }).boxed(),
+        2 => player.clone().prop_map(|referrer| Action::SettleReferral { referrer }).boxed(),
+        2 => player.clone().prop_map(|player| Action::Claim { player }).boxed(),
+        2 => player.prop_map(|player| Action::ClosePlayer { player }).boxed(),
+        1 => Just(Action::CloseGame).boxed(),
+    ]
+}
+
+#[derive(Debug, Clone)]
+struct Scenario {
+    spl: bool,
+    max_winners: u8,
+    all_are_winners: bool,
+    even_split: bool,
+    entry_fee: u64,
+    donation: u64,
+    scheduled: bool, // Opens a minute out, leaving time to change the donation
+    referral_fee: u16,
+    referrals_from_commission: bool,
+    actions: Vec<Action>,
+}
+
+// Zero half the time, so free games and games without a donation come up
+fn amount() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(0), 1..SOL]
+}
+
+fn scenario() -> impl Strategy<Value = Scenario> {
+    (
+        any::<bool>(),
+        1..=4u8,
+        any::<bool>(),
+        any::<bool>(),
+        amount(),
+        amount(),
+        any::<bool>(),
+        prop_oneof![Just(0), 1..=1000u16],
+        any::<bool>(),
+        prop::collection::vec(action(), 1..40),
+    )
+        .prop_map(
+            |(
+                spl,
+                max_winners,
+                all_are_winners,
+                even_split,
+                entry_fee,
+                donation,
+                scheduled,
+                referral_fee,
+                referrals_from_commission,
+                actions,
+            )| Scenario {
+                spl,
+                max_winners,
+                all_are_winners,
+                even_split,
+                entry_fee,
+                donation,
+                scheduled,
+                referral_fee,
+                referrals_from_commission,
+                actions,
+            },
+        )
+}
+
+struct Run {
+    env: Env,
+    game: TestGame,
+    players: Vec<Pubkey>,
+    supply: u64,
+}
+
+impl Run {
+    fn new(scenario: &Scenario) -> Self {
+        let mut env = Env::without_config();
+        let referral_fee_source = if scenario.referrals_from_commission {
+            ReferralFeeSource::Commission
+        } else {
+            ReferralFeeSource::Treasury
+        };
+        env.init_config(Vec::new(), scenario.referral_fee, referral_fee_source);
+        let params = if scenario.spl {
+            GameParams::spl(env.mint())
+        } else {
+            GameParams::native()
+        };
+        let game = env.create_game(GameParams {
+            max_winners: scenario.max_winners,
+            all_are_winners: scenario.all_are_winners,
+            even_split: scenario.even_split,
+            entry_fee: scenario.entry_fee,
+            donation: scenario.donation,
+            starts_in: if scenario.scheduled { 60_000 } else { 0 },
+            ..params
+        });
+        let players = (0..PLAYERS)
+            .map(|_| {
+                let player = env.wallet();
+                if !game.is_native {
+                    env.fund_tokens(&player, &game.mint, 100 * SOL);
+                }
+                player
+            })
+            .collect();
+        let mut run = Self {
+            env,
+            game,
+            players,
+            supply: 0,
+        };
+        run.supply = run.token_supply();
+        run
+    }
+
+    // Results are ignored - the invariants are checked whether a step lands or not
+    fn step(&mut self, action: &Action) {
+        let game = &self.game;
+        let _ = match *action {
+            Action::Join { player, referrer } => {
+                let options = JoinOptions {
+                    referrer: referrer.map(|referrer| self.players[referrer]),
+                    ..JoinOptions::default()
+                };
+                let player = self.players[player];
+                self.env.send(game.join_ix(player, options), player)
+            }
+            Action::Update {
+                donation,
+                extend_seconds,
+            } => {
+                let end_time = self
+                    .env
+                    .svm
+                    .get::<Game>(&game.address)
+                    .map_or(game.end_time, |state| state.end_time);
+                let args = instruction::UpdateGame {
+                    new_donation_amount: donation,
+                    new_end_time: extend_seconds.map(|seconds| end_time + seconds * 1000),
+                    ..no_changes()
+                };
+                self.env.send(game.update_ix(game.admin, args), game.admin)
+            }
+            Action::Start { seconds } => self
+                .env
+                .send(game.start_ix(game.admin, seconds * 1000), game.admin),
+            Action::Submit { player, correct } => {
+                let player = self.players[player];
+                let picks: Vec<&str> = ANSWERS
+                    .iter()
+                    .zip(correct)
+                    .map(|(answer, correct)| if correct { *answer } else { "x" })
+                    .collect();
+                self.env.svm.advance_ms(1000);
+                let finish = self.env.svm.now_ms();
+                self.env.svm.advance_ms(1000);
+                let ix = game.submit_ix(player, game.answers(&picks, finish), finish);
+                self.env.send(ix, player)
+            }
+            Action::Wait { seconds } => {
+                self.env.svm.advance_ms(seconds * 1000);
+                return;
+            }
+            Action::End => self
+                .env
+                .send(game.end_ix(game.admin, Vec::new()), game.admin),
+            Action::Declare { batch } => match self.winners() {
+                Some(winners) => {
+                    let declared = self.declared();
+                    let end = winners.len().min(declared + batch);
+                    let mut ixs = game.declare_ixs(game.admin, &winners[..end], declared);
+                    match ixs.pop() {
+                        Some(ix) => self.env.send(ix, game.admin),
+                        None => return,
+                    }
+                }
+                None => return,
+            },
+            Action::SettleReferral { referrer } => {
+                let referrer = self.players[referrer];
+                self.env
+                    .send(game.settle_referral_ix(&referrer), game.admin)
+            }
+            Action::Claim { player } => {
+                let player = self.players[player];
+                self.env.send(game.claim_ix(player), player)
+            }
+            Action::ClosePlayer { player } => self.close_player(player),
+            Action::CloseGame => self.env.send(game.close_ix(), game.admin),
+        };
+    }
+
+    // The ranked winners the admin would declare, if the game is still open
+    fn winners(&self) -> Option<Vec<Pubkey>> {
+        let game = self.env.svm.get::<Game>(&self.game.address)?;
+        let players: Vec<PlayerAccount> = self
+            .players
+            .iter()
+            .filter_map(|player| self.env.svm.get(&self.game.player_pda(player)))
+            .collect();
+        determine_winners(&game, &players).ok()
+    }
+
+    // Winners declared so far
+    fn declared(&self) -> usize {
+        self.env
+            .svm
+            .get::<Winners>(&self.game.winners_pda())
+            .map_or(0, |winners| winners.winners.len())
+    }
+
+    // Closes with the winners account once it exists and the submission if there is one
+    fn close_player(&mut self, player: usize) -> crate::svm::TxResult {
+        let player = self.players[player];
+        let submitted = self
+            .env
+            .svm
+            .account(&self.game.submission_pda(&player))
+            .is_some();
+        let mut ix = self.game.close_player_ix(player, submitted);
+        if self.env.svm.account(&self.game.winners_pda()).is_none() {
+            let winners = self.game.winners_pda();
+            for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == winners) {
+                meta.pubkey = twizzin_be_2::ID;
+                meta.is_writable = false;
+            }
+        }
+        self.env.send(ix, player)
+    }
+
+    // Every token of the game's mint, wherever it sits
+    fn token_supply(&self) -> u64 {
+        let referral_balances = self
+            .players
+            .iter()
+            .map(|player| find_referral_balance_pda(player, &self.game.mint).0);
+        let owners = self
+            .players
+            .iter()
+            .copied()
+            .chain([self.game.admin, self.env.treasury, self.game.vault])
+            .chain(referral_balances);
+        owners
+            .filter_map(|owner| self.game.token_account(&owner))
+            .map(|account| self.env.svm.token_balance(&account))
+            .sum()
+    }
+
+    fn check(&self) {
+        let svm = &self.env.svm;
+        if !self.game.is_native {
+            assert_eq!(
+                self.token_supply(),
+                self.supply,
+                "tokens were minted or burned"
+            );
+        }
+
+        // Referral rewards held back for settle_referral sit on the game account
+        // for SOL games and next to the prizes in the vault for SPL games
+        let escrow = svm
+            .get::<Game>(&self.game.address)
+            .map_or(0, |game| game.referral_escrow);
+        let mut owed = if self.game.is_native { 0 } else { escrow };
+        if self.game.is_native {
+            if let Some(account) = svm.account(&self.game.address) {
+                let rent = Rent::default().minimum_balance(account.data.len());
+                assert!(
+                    account.lamports >= rent + escrow,
+                    "game holds {} but escrows {escrow}",
+                    account.lamports
+                );
+            }
+        }
+
+        // Declared prizes must stay covered until they're claimed
+        if let Some(winners) = svm.get::<Winners>(&self.game.winners_pda()) {
+            owed += winners
+                .winners
+                .iter()
+                .filter(|winner| !winner.claimed)
+                .map(|winner| winner.prize_amount)
+                .sum::<u64>();
+        }
+        let pot = self.game.pot(svm);
+        assert!(pot >= owed, "vault holds {pot} but owes {owed}");
+    }
+
+    // Plays the game out from wherever the actions left it, as an admin and
+    // players who want their money would
+    fn settle(&mut self) {
+        let game = &self.game;
+        if let Some(state) = self.env.svm.get::<Game>(&game.address) {
+            if !state.ended {
+                let end = state.end_time.max(self.env.svm.now_ms());
+                self.env.svm.warp_to_ms(end);
+                self.env.end(game);
+            }
+            let complete = self
+                .env
+                .svm
+                .get::<Winners>(&game.winners_pda())
+                .is_some_and(|winners| winners.is_complete());
+            if !complete {
+                let winners = self.winners().expect("rank the players");
+                for ix in game.declare_ixs(game.admin, &winners, self.declared()) {
+                    self.env.send(ix, game.admin).expect("declare winners");
+                }
+            }
+            let winners = self.env.svm.get::<Winners>(&game.winners_pda()).unwrap();
+            for winner in winners.winners.iter().filter(|winner| !winner.claimed) {
+                self.env.claim(game, winner.player);
+                self.check();
+            }
+
+            // Every referrer is paid before the game can close
+            for referrer in &self.players {
+                let referral = find_game_referral_pda(&game.address, referrer).0;
+                if let Some(referral) = self.env.svm.get::<GameReferral>(&referral) {
+                    if !referral.settled {
+                        self.env
+                            .send(game.settle_referral_ix(referrer), game.admin)
+                            .expect("settle referral");
+                        self.check();
+                    }
+                }
+            }
+        }
+
+        for player in 0..PLAYERS {
+            if self
+                .env
+                .svm
+                .account(&self.game.player_pda(&self.players[player]))
+                .is_some()
+            {
+                self.close_player(player).expect("close player account");
+            }
+        }
+
+        let game = &self.game;
+        if self.env.svm.account(&game.address).is_some() {
+            self.env
+                .send(game.close_ix(), game.admin)
+                .expect("close game");
+        }
+        self.check();
+
+        // Nothing is left in the vault and no account the game made survives
+        let svm = &self.env.svm;
+        assert_eq!(
+            svm.lamports(&game.vault),
+            0,
+            "lamports stranded in the vault"
+        );
+        let mut leftovers = vec![game.address, game.winners_pda(), game.registry_pda()];
+        leftovers.extend(game.vault_token_account());
+        for player in &self.players {
+            leftovers.push(game.player_pda(player));
+            leftovers.push(game.submission_pda(player));
+        }
+        for account in leftovers {
+            assert!(svm.account(&account).is_none(), "{account} was left open");
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    #[test]
+    fn random_games_pay_out_and_clean_up(scenario in scenario()) {
+        let mut run = Run::new(&scenario);
+        for action in &scenario.actions {
+            run.step(action);
+            run.check();
+        }
+        run.settle();
+    }
+}
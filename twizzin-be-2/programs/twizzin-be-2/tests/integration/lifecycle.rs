@@ -0,0 +1,896 @@
+//! A whole game from creation to close, for SOL and SPL games, plus the
+//! config-driven payouts: fee recipients and referrals.
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::rent::Rent;
+use anchor_lang::{system_program, AnchorSerialize, Discriminator, Space};
+use anchor_spl::token;
+use solana_sdk::account::Account;
+use twizzin_be_2::errors::ErrorCode;
+use twizzin_be_2::state::{
+    AnswerKey, FeeRecipient, Game, PlayerAccount, PlayerProfile, ProgramConfig, ReferralBalance,
+    ReferralFeeSource, Winners, LEGACY_GAME_SPACE, LEGACY_PLAYER_SPACE,
+};
+use twizzin_be_2::utils::merkle::{LEGACY_POINTS, MERKLE_V0};
+use twizzin_be_2::{accounts, constants::PROGRAM_AUTHORITY, instruction};
+use twizzin_client::{
+    find_config_pda, find_game_pda, find_player_pda, find_profile_pda, find_referral_balance_pda,
+    find_vault_pda, instructions, parse_events, MerkleTree, ProgramEvent,
+};
+
+use crate::setup::*;
+
+fn rent(space: usize) -> u64 {
+    Rent::default().minimum_balance(space)
+}
+
+fn events(logs: &[String]) -> Vec<ProgramEvent> {
+    parse_events(logs).expect("decode events")
+}
+
+// Prizes for two winners with the geometric split - first place takes the dust
+fn geometric_pair(pot: u64) -> (u64, u64) {
+    let second = (pot - pot / 2) / 2;
+    (pot - second, second)
+}
+
+fn init_profile_ix(player: Pubkey) -> anchor_lang::solana_program::instruction::Instruction {
+    instructions::init_profile(
+        accounts::InitProfile {
+            player,
+            profile: find_profile_pda(&player).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitProfile {},
+    )
+}
+
+// Runs one game through every step of its life: donations, all three ways to
+// submit answers, fees, prizes, the answer key reveal and the cleanup
+fn full_game(mut env: Env, params: GameParams) {
+    let game = env.create_game(GameParams {
+        donation: SOL,
+        starts_in: 60_000,
+        ..params
+    });
+    assert_eq!(game.pot(&env.svm), SOL);
+
+    // Topping up the donation before the start moves the difference into the vault
+    let update = instruction::UpdateGame {
+        new_name: Some("Renamed".to_string()),
+        new_donation_amount: Some(2 * SOL),
+        ..no_changes()
+    };
+    env.send(game.update_ix(game.admin, update), game.admin)
+        .unwrap();
+    let stored: Game = env.svm.get(&game.address).unwrap();
+    assert_eq!(stored.name, "Renamed");
+    assert_eq!(stored.donation_amount, 2 * SOL);
+    assert_eq!(game.pot(&env.svm), 2 * SOL);
+
+    let alice = env.player(&game);
+    let bob = env.player(&game);
+    let carol = env.player(&game);
+    assert_eq!(game.pot(&env.svm), 2 * SOL + 3 * game.entry_fee);
+    env.send(init_profile_ix(alice), alice).unwrap();
+
+    env.start(&game, 60_000);
+    env.svm.advance_ms(1000);
+    let finish = env.svm.now_ms();
+    env.svm.advance_ms(1000);
+
+    // Alice proves each answer on its own and has a profile to update
+    let submit = game.submit_ix(alice, game.answers(&ANSWERS, finish), finish);
+    env.send(submit, alice).unwrap();
+
+    // Bob proves his two right answers with one multiproof
+    let mut answers = game.answers(&["a", "b", "x"], finish + 1);
+    answers.iter_mut().for_each(|answer| answer.proof.clear());
+    let multiproof = game.tree.multiproof(&[0, 1]).unwrap();
+    env.send(
+        game.submit_batch_ix(bob, answers, multiproof, finish + 1),
+        bob,
+    )
+    .unwrap();
+
+    // Carol sends her answers over two chunks
+    let mut answers = game.answers(&["a", "x", "x"], finish + 2);
+    let rest = answers.split_off(1);
+    let logs = env.send(game.chunk_ix(carol, answers), carol).unwrap();
+    assert!(events(&logs).iter().any(
+        |event| matches!(event, ProgramEvent::AnswerChunkSubmitted(chunk) if chunk.score == 10)
+    ));
+    env.send(game.chunk_ix(carol, rest), carol).unwrap();
+    env.send(game.finalize_ix(carol, finish + 2), carol)
+        .unwrap();
+
+    let scores: Vec<i32> = [alice, bob, carol]
+        .iter()
+        .map(|player| {
+            env.svm
+                .get::<PlayerAccount>(&game.player_pda(player))
+                .unwrap()
+                .score
+        })
+        .collect();
+    assert_eq!(scores, vec![30, 20, 10]);
+
+    // Fees come off the pot, less the game's rent for SOL games
+    let pot = game.pot(&env.svm);
+    let held_back = if game.is_native {
+        rent(Game::INIT_SPACE)
+    } else {
+        0
+    };
+    let distributable = pot - held_back;
+    let treasury_fee = distributable * TREASURY_FEE_BPS as u64 / 10_000;
+    let commission = distributable * 1000 / 10_000;
+    let treasury_before = game.balance(&env.svm, &game.treasury);
+    let admin_before = game.balance(&env.svm, &game.admin);
+
+    let logs = env
+        .send(game.end_ix(game.admin, Vec::new()), game.admin)
+        .unwrap();
+    assert_eq!(
+        game.balance(&env.svm, &game.treasury),
+        treasury_before + treasury_fee
+    );
+    assert_eq!(
+        game.balance(&env.svm, &game.admin),
+        admin_before + commission
+    );
+    let ended = events(&logs)
+        .into_iter()
+        .find_map(|event| match event {
+            ProgramEvent::GameEnded(ended) => Some(ended),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!((ended.total_pot, ended.treasury_fee), (pot, treasury_fee));
+    assert_eq!(ended.admin_commission, commission);
+
+    let prize_pool = game.pot(&env.svm);
+    assert_eq!(prize_pool, pot - treasury_fee - commission);
+
+    // Once the key is public anyone can re-score a player from their submission
+    env.send(game.reveal_ix(game.entries.clone()), game.admin)
+        .unwrap();
+    let rescore = game.rescore_ix(bob, carol);
+    let logs = env.send(rescore.clone(), bob).unwrap();
+    assert!(events(&logs).iter().any(|event| matches!(
+        event,
+        ProgramEvent::PlayerRescored(rescored) if rescored.previous_score == 10 && rescored.score == 10
+    )));
+
+    env.declare(&game, &[alice, bob]);
+    // Scores are final once they've been ranked
+    assert_error(env.send(rescore, bob), ErrorCode::ScoresFrozen);
+    let winners: Winners = env.svm.get(&game.winners_pda()).unwrap();
+    let (first, second) = geometric_pair(prize_pool);
+    let prizes: Vec<u64> = winners.winners.iter().map(|w| w.prize_amount).collect();
+    assert_eq!(prizes, vec![first, second]);
+
+    // Claiming pays the prize and closes the player and submission accounts back to the winner
+    for (player, prize) in [(alice, first), (bob, second)] {
+        let account_rent = env.svm.lamports(&game.player_pda(&player))
+            + env.svm.lamports(&game.submission_pda(&player));
+        let before = game.balance(&env.svm, &player);
+        env.send(game.claim_ix(player), player).unwrap();
+        let refund = if game.is_native { account_rent } else { 0 };
+        assert_eq!(game.balance(&env.svm, &player), before + prize + refund);
+        assert!(env.svm.account(&game.player_pda(&player)).is_none());
+        assert!(env.svm.account(&game.submission_pda(&player)).is_none());
+    }
+    assert_eq!(game.pot(&env.svm), 0);
+    let profile: PlayerProfile = env.svm.get(&find_profile_pda(&alice).0).unwrap();
+    assert_eq!((profile.games_played, profile.wins), (1, 1));
+
+    // Carol didn't win, so she can take her rent back straight away
+    let reclaimable =
+        env.svm.lamports(&game.player_pda(&carol)) + env.svm.lamports(&game.submission_pda(&carol));
+    let before = env.svm.lamports(&carol);
+    env.send(game.close_player_ix(carol, true), carol).unwrap();
+    assert_eq!(env.svm.lamports(&carol), before + reclaimable);
+
+    // Closing the game hands the admin every account's rent and frees the code
+    let mut reclaimable = [game.address, game.winners_pda(), game.registry_pda()]
+        .iter()
+        .map(|account| env.svm.lamports(account))
+        .sum::<u64>();
+    if let Some(vault_token_account) = game.vault_token_account() {
+        reclaimable += env.svm.lamports(&vault_token_account);
+    }
+    let before = env.svm.lamports(&game.admin);
+    env.send(game.close_ix(), game.admin).unwrap();
+    assert_eq!(env.svm.lamports(&game.admin), before + reclaimable);
+    assert!(env.svm.account(&game.address).is_none());
+    assert!(env.svm.account(&game.registry_pda()).is_none());
+
+    let reused = GameParams {
+        code: game.code.clone(),
+        ..GameParams::spl(game.mint)
+    };
+    env.create_game(reused);
+}
+
+#[test]
+fn native_game_lifecycle() {
+    full_game(Env::new(), GameParams::native());
+}
+
+#[test]
+fn spl_game_lifecycle() {
+    let mut env = Env::new();
+    let mint = env.mint();
+    full_game(env, GameParams::spl(mint));
+}
+
+#[test]
+fn leftover_tokens_go_to_the_admin() {
+    let mut env = Env::new();
+    let params = GameParams::spl(env.mint());
+    let game = env.create_game(params);
+    let player = env.player(&game);
+    env.play(&game, &[(player, &ANSWERS)]);
+    env.claim(&game, player);
+
+    // Tokens sent to the vault once the prizes are paid still reach the admin on close
+    let donor = env.wallet();
+    let source = env.fund_tokens(&donor, &game.mint, 5);
+    let vault_token_account = game.vault_token_account().unwrap();
+    let transfer = token::spl_token::instruction::transfer(
+        &token::ID,
+        &source,
+        &vault_token_account,
+        &donor,
+        &[],
+        5,
+    )
+    .unwrap();
+    env.send(transfer, donor).unwrap();
+
+    let before = game.balance(&env.svm, &game.admin);
+    env.send(game.close_ix(), game.admin).unwrap();
+    assert_eq!(game.balance(&env.svm, &game.admin), before + 5);
+    assert!(env.svm.account(&vault_token_account).is_none());
+}
+
+#[test]
+fn only_finished_players_count_towards_the_winners() {
+    // One of two winners' places is taken - the player who only joined can't fill the other
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let finisher = env.player(&game);
+    env.player(&game);
+    env.play(&game, &[(finisher, &ANSWERS)]);
+    let winners: Winners = env.svm.get(&game.winners_pda()).unwrap();
+    assert_eq!(winners.num_winners, 1);
+    env.claim(&game, finisher);
+    env.send(game.close_ix(), game.admin).unwrap();
+
+    // Nobody finished, so nobody wins and the pot goes back to the admin
+    let game = env.create_game(GameParams::native());
+    env.player(&game);
+    env.start(&game, 60_000);
+    env.end(&game);
+    env.declare(&game, &[]);
+    let pot = game.pot(&env.svm);
+    let before = env.svm.lamports(&game.admin);
+    env.send(game.close_ix(), game.admin).unwrap();
+    assert!(env.svm.lamports(&game.admin) >= before + pot);
+    assert_eq!(env.svm.lamports(&game.vault), 0);
+}
+
+#[test]
+fn exact_ties_go_to_the_lower_pubkey() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let (first, second) = (env.player(&game), env.player(&game));
+    env.start(&game, 60_000);
+    env.svm.advance_ms(1000);
+    let finish = env.svm.now_ms();
+    env.svm.advance_ms(1000);
+    for player in [first, second] {
+        let ix = game.submit_ix(player, game.answers(&ANSWERS, finish), finish);
+        env.send(ix, player).unwrap();
+    }
+    env.end(&game);
+
+    let (lower, higher) = (first.min(second), first.max(second));
+    let declare = game.declare_ix(game.admin, vec![higher, lower]);
+    assert_error(env.send(declare, game.admin), ErrorCode::InvalidWinnerOrder);
+    env.declare(&game, &[lower, higher]);
+    for player in [lower, higher] {
+        env.claim(&game, player);
+    }
+}
+
+#[test]
+fn answer_key_reveals_in_chunks() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let player = env.player(&game);
+    env.start(&game, 60_000);
+    env.submit(&game, player, &["a", "x", "c"]);
+    env.end(&game);
+
+    // Chunks carry on in display order
+    let (first, rest) = game.entries.split_at(1);
+    env.send(game.reveal_chunk_ix(first.to_vec()), game.admin)
+        .unwrap();
+    let skipped = game.reveal_chunk_ix(rest[1..].to_vec());
+    assert_error(env.send(skipped, game.admin), ErrorCode::InvalidAnswerKey);
+    env.send(game.reveal_chunk_ix(rest.to_vec()), game.admin)
+        .unwrap();
+
+    // The key only counts once the whole of it matches the game's answer hash
+    let rescore = game.rescore_ix(player, player);
+    assert_error(
+        env.send(rescore.clone(), player),
+        ErrorCode::AnswerKeyNotRevealed,
+    );
+    let logs = env.send(game.finalize_key_ix(), game.admin).unwrap();
+    assert!(events(&logs).iter().any(|event| matches!(
+        event,
+        ProgramEvent::AnswerKeyRevealed(revealed) if revealed.num_questions == 3
+    )));
+    let key: AnswerKey = env.svm.get(&game.answer_key_pda()).unwrap();
+    assert!(key.revealed);
+    assert_eq!(key.entries.len(), game.entries.len());
+
+    let logs = env.send(rescore, player).unwrap();
+    assert!(events(&logs).iter().any(|event| matches!(
+        event,
+        ProgramEvent::PlayerRescored(rescored) if rescored.num_correct == 2 && rescored.score == 20
+    )));
+}
+
+#[test]
+fn games_end_once() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let player = env.player(&game);
+    env.start(&game, 60_000);
+    env.submit(&game, player, &ANSWERS);
+
+    // Fees come out when the game ends, so winners can't be declared until then
+    env.svm.warp_to_ms(game.start_time + 3_600_000);
+    let declare = game.declare_ix(game.admin, vec![player]);
+    assert_error(env.send(declare, game.admin), ErrorCode::GameNotEnded);
+    env.end(&game);
+    env.declare(&game, &[player]);
+
+    // Ending or restarting again would take the fees out of the prizes a second time
+    let end = game.end_ix(game.admin, Vec::new());
+    assert_error(env.send(end, game.admin), ErrorCode::GameEnded);
+    let start = game.start_ix(game.admin, 60_000);
+    assert_error(env.send(start, game.admin), ErrorCode::GameEnded);
+
+    env.claim(&game, player);
+    env.send(game.close_ix(), game.admin).unwrap();
+}
+
+#[test]
+fn games_ended_before_their_start_stay_ended() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams {
+        donation: SOL,
+        starts_in: 60_000,
+        ..GameParams::native()
+    });
+    env.end(&game);
+
+    // Moving the end out again would keep players from leaving, and the donation
+    // is already counted in the pot
+    let reopen = instruction::UpdateGame {
+        new_end_time: Some(game.end_time),
+        ..no_changes()
+    };
+    let result = env.send(game.update_ix(game.admin, reopen), game.admin);
+    assert_error(result, ErrorCode::ScheduleFrozen);
+    let withdraw = instruction::UpdateGame {
+        new_donation_amount: Some(0),
+        ..no_changes()
+    };
+    let result = env.send(game.update_ix(game.admin, withdraw), game.admin);
+    assert_error(result, ErrorCode::DonationFrozen);
+}
+
+#[test]
+fn profiles_record_every_game() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let alice = env.player(&game);
+    env.send(init_profile_ix(alice), alice).unwrap();
+    env.start(&game, 60_000);
+
+    // Leaving the profile out to keep a loss off the record doesn't work
+    env.svm.advance_ms(1000);
+    let finish = env.svm.now_ms();
+    let profile = find_profile_pda(&alice).0;
+    let mut submit = game.submit_ix(alice, game.answers(&["x", "x", "x"], finish), finish);
+    for meta in submit
+        .accounts
+        .iter_mut()
+        .filter(|meta| meta.pubkey == profile)
+    {
+        *meta = AccountMeta::new_readonly(twizzin_be_2::ID, false);
+    }
+    let failure = env.send(submit, alice).unwrap_err();
+    assert_eq!(
+        failure.custom_code(),
+        Some(anchor_lang::error::ErrorCode::ConstraintSeeds.into())
+    );
+
+    env.submit(&game, alice, &["x", "x", "x"]);
+    let stored: PlayerProfile = env.svm.get(&profile).unwrap();
+    assert_eq!((stored.games_played, stored.last_game), (1, game.address));
+    assert!(!stored.last_game_won);
+}
+
+#[test]
+fn even_split_pays_every_player() {
+    for native in [true, false] {
+        let mut env = Env::new();
+        let params = if native {
+            GameParams::native()
+        } else {
+            GameParams::spl(env.mint())
+        };
+        let game = env.create_game(GameParams {
+            all_are_winners: true,
+            even_split: true,
+            commission: 0,
+            ..params
+        });
+        let players: Vec<_> = (0..4).map(|_| env.player(&game)).collect();
+        let picks: Vec<(Pubkey, &[&str])> = players.iter().map(|p| (*p, &ANSWERS[..])).collect();
+        env.play(&game, &picks);
+
+        let pool = game.pot(&env.svm);
+        let winners: Winners = env.svm.get(&game.winners_pda()).unwrap();
+        assert_eq!(winners.num_winners, 4);
+        let prizes: Vec<u64> = winners.winners.iter().map(|w| w.prize_amount).collect();
+        assert_eq!(prizes, [pool / 4 + pool % 4, pool / 4, pool / 4, pool / 4]);
+        for player in &players {
+            env.claim(&game, *player);
+        }
+        assert_eq!(game.pot(&env.svm), 0);
+    }
+}
+
+#[test]
+fn fee_recipients_split_the_treasury_fee() {
+    for native in [true, false] {
+        let mut env = Env::without_config();
+        let recipients = [env.wallet(), env.wallet()];
+        env.init_config(
+            vec![
+                FeeRecipient {
+                    recipient: recipients[0],
+                    weight_bps: 7000,
+                },
+                FeeRecipient {
+                    recipient: recipients[1],
+                    weight_bps: 3000,
+                },
+            ],
+            0,
+            ReferralFeeSource::Treasury,
+        );
+        let params = if native {
+            GameParams::native()
+        } else {
+            GameParams::spl(env.mint())
+        };
+        let game = env.create_game(params);
+        let accounts: Vec<Pubkey> = recipients
+            .iter()
+            .map(|recipient| match game.token_account(recipient) {
+                Some(_) => env.fund_tokens(recipient, &game.mint, 0),
+                None => *recipient,
+            })
+            .collect();
+        let player = env.player(&game);
+        env.player(&game);
+        env.start(&game, 60_000);
+        env.submit(&game, player, &ANSWERS);
+
+        let before: Vec<u64> = recipients
+            .iter()
+            .map(|r| game.balance(&env.svm, r))
+            .collect();
+        let treasury_before = game.balance(&env.svm, &game.treasury);
+        let remaining = accounts
+            .iter()
+            .map(|a| AccountMeta::new(*a, false))
+            .collect();
+        let logs = env
+            .send(game.end_ix(game.admin, remaining), game.admin)
+            .unwrap();
+
+        let splits: Vec<u64> = events(&logs)
+            .into_iter()
+            .filter_map(|event| match event {
+                ProgramEvent::TreasuryFeeSplit(split) => Some(split.amount),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(splits.len(), 2);
+        assert!(splits[0] > splits[1]);
+        for ((recipient, before), split) in recipients.iter().zip(before).zip(&splits) {
+            assert_eq!(game.balance(&env.svm, recipient), before + split);
+        }
+        assert_eq!(game.balance(&env.svm, &game.treasury), treasury_before);
+    }
+}
+
+#[test]
+fn referrers_are_paid_and_withdraw() {
+    for native in [true, false] {
+        let mut env = Env::without_config();
+        env.init_config(Vec::new(), 1000, ReferralFeeSource::Commission);
+        let params = if native {
+            GameParams::native()
+        } else {
+            GameParams::spl(env.mint())
+        };
+        let game = env.create_game(params);
+        let alice = env.player(&game);
+
+        let bob = env.wallet();
+        if !native {
+            env.fund_tokens(&bob, &game.mint, 100 * SOL);
+        }
+        let join = game.join_ix(
+            bob,
+            JoinOptions {
+                referrer: Some(alice),
+                ..JoinOptions::default()
+            },
+        );
+        env.send(join, bob).unwrap();
+
+        env.start(&game, 60_000);
+        env.submit(&game, alice, &ANSWERS);
+        env.end(&game);
+
+        // end_game only holds the rewards back, whoever settles pays them in
+        let reward = game.entry_fee * 1000 / 10_000;
+        let held: Game = env.svm.get(&game.address).unwrap();
+        assert_eq!(
+            (held.referral_reward, held.referral_escrow),
+            (reward, reward)
+        );
+        let balance = find_referral_balance_pda(&alice, &game.mint).0;
+        let stranger = env.wallet();
+        env.send(game.settle_referral_ix(&alice), stranger).unwrap();
+
+        let stored: ReferralBalance = env.svm.get(&balance).unwrap();
+        assert_eq!((stored.balance, stored.total_earned), (reward, reward));
+        assert_eq!(
+            env.svm.get::<Game>(&game.address).unwrap().referral_escrow,
+            0
+        );
+
+        let before = game.balance(&env.svm, &alice);
+        let withdraw = instructions::withdraw_referral_balance(
+            accounts::WithdrawReferralBalance {
+                referrer: alice,
+                referral_balance: balance,
+                referral_token_account: game.token_account(&balance),
+                referrer_token_account: game.token_account(&alice),
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::WithdrawReferralBalance {},
+        );
+        env.send(withdraw, alice).unwrap();
+        assert_eq!(game.balance(&env.svm, &alice), before + reward);
+        assert_eq!(env.svm.get::<ReferralBalance>(&balance).unwrap().balance, 0);
+    }
+}
+
+#[test]
+fn referrers_settle_one_at_a_time() {
+    // More referrers than would fit in one transaction's accounts
+    let mut env = Env::without_config();
+    env.init_config(Vec::new(), 1000, ReferralFeeSource::Commission);
+    let game = env.create_game(GameParams::native());
+    let referrers: Vec<Pubkey> = (0..16).map(|_| env.player(&game)).collect();
+    for referrer in &referrers {
+        let player = env.wallet();
+        let options = JoinOptions {
+            referrer: Some(*referrer),
+            ..JoinOptions::default()
+        };
+        env.send(game.join_ix(player, options), player).unwrap();
+    }
+
+    let winners = env.play(&game, &[(referrers[0], &ANSWERS)]);
+    env.claim(&game, winners[0]);
+    let held: Game = env.svm.get(&game.address).unwrap();
+    assert_eq!(held.referral_escrow, held.referral_reward * 16);
+    assert!(held.referral_reward > 0);
+    assert_error(
+        env.send(game.close_ix(), game.admin),
+        ErrorCode::ReferralsNotSettled,
+    );
+
+    for referrer in &referrers {
+        env.send(game.settle_referral_ix(referrer), game.admin)
+            .unwrap();
+        let balance = find_referral_balance_pda(referrer, &game.mint).0;
+        let stored: ReferralBalance = env.svm.get(&balance).unwrap();
+        assert_eq!(stored.balance, held.referral_reward);
+    }
+    env.send(game.close_ix(), game.admin).unwrap();
+}
+
+#[test]
+fn losers_leave_after_the_game_closes() {
+    let mut env = Env::new();
+    let game = env.create_game(GameParams::native());
+    let players: Vec<_> = (0..3).map(|_| env.player(&game)).collect();
+    let winners = env.play(
+        &game,
+        &[
+            (players[0], &ANSWERS),
+            (players[1], &ANSWERS),
+            (players[2], &["x", "x", "x"]),
+        ],
+    );
+
+    for winner in winners {
+        env.claim(&game, winner);
+    }
+    env.send(game.close_ix(), game.admin).unwrap();
+
+    // The winners account went with the game, so the loser leaves without it
+    let loser = players[2];
+    let reclaimable =
+        env.svm.lamports(&game.player_pda(&loser)) + env.svm.lamports(&game.submission_pda(&loser));
+    let close = instructions::close_player_account(
+        accounts::ClosePlayerAccount {
+            player: loser,
+            game: game.address,
+            winners: None,
+            player_account: game.player_pda(&loser),
+            submission: Some(game.submission_pda(&loser)),
+            system_program: system_program::ID,
+        },
+        instruction::ClosePlayerAccount {},
+    );
+    let before = env.svm.lamports(&loser);
+    env.send(close, loser).unwrap();
+    assert_eq!(env.svm.lamports(&loser), before + reclaimable);
+}
+
+#[test]
+fn authority_updates_config() {
+    let mut env = Env::new();
+    let update = instructions::update_config(
+        accounts::UpdateConfig {
+            authority: PROGRAM_AUTHORITY,
+            config: find_config_pda().0,
+        },
+        instruction::UpdateConfig {
+            new_treasury: None,
+            new_treasury_fee: Some(250),
+            new_fee_recipients: None,
+            new_referral_fee: Some(500),
+            new_referral_fee_source: Some(ReferralFeeSource::Commission),
+        },
+    );
+    env.send(update, PROGRAM_AUTHORITY).unwrap();
+    let config: ProgramConfig = env.svm.get(&find_config_pda().0).unwrap();
+    assert_eq!((config.treasury_fee, config.referral_fee), (250, 500));
+    assert!(config.referral_fee_source == ReferralFeeSource::Commission);
+}
+
+#[test]
+fn legacy_config_migrates() {
+    // A config from before fee recipients and referral fees: treasury, authority and fee
+    let mut env = Env::without_config();
+    let config = find_config_pda().0;
+    let mut data = ProgramConfig::DISCRIMINATOR.to_vec();
+    data.extend(env.treasury.to_bytes());
+    data.extend(PROGRAM_AUTHORITY.to_bytes());
+    data.extend(TREASURY_FEE_BPS.to_le_bytes());
+    env.svm.set_account(
+        config,
+        Account {
+            lamports: rent(data.len()),
+            data,
+            owner: twizzin_be_2::ID,
+            ..Account::default()
+        },
+    );
+
+    let migrate = instructions::migrate_config(
+        accounts::MigrateConfig {
+            authority: PROGRAM_AUTHORITY,
+            config,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateConfig {},
+    );
+    env.send(migrate.clone(), PROGRAM_AUTHORITY).unwrap();
+    let migrated: ProgramConfig = env.svm.get(&config).unwrap();
+    assert_eq!(migrated.treasury_pubkey, env.treasury);
+    assert_eq!(migrated.treasury_fee, TREASURY_FEE_BPS);
+    assert!(migrated.fee_recipients.is_empty());
+    assert_eq!(migrated.referral_fee, 0);
+    assert!(migrated.referral_fee_source == ReferralFeeSource::Treasury);
+    assert_eq!(env.svm.lamports(&config), rent(ProgramConfig::INIT_SPACE));
+
+    // Games run against the migrated config, and it only migrates once
+    let game = env.create_game(GameParams::native());
+    let player = env.player(&game);
+    env.play(&game, &[(player, &ANSWERS)]);
+    env.claim(&game, player);
+    assert_error(
+        env.send(migrate, PROGRAM_AUTHORITY),
+        ErrorCode::AlreadyMigrated,
+    );
+}
+
+// A game and its players as init_game and submit_answers wrote them before admin
+// transfers, referrals and scoring
+fn legacy_game(env: &mut Env, game: &TestGame, players: &[(Pubkey, u8)]) {
+    let mut data = Game::DISCRIMINATOR.to_vec();
+    data.extend(game.admin.to_bytes());
+    "Legacy".to_string().serialize(&mut data).unwrap();
+    game.code.serialize(&mut data).unwrap();
+    data.extend(game.mint.to_bytes());
+    data.extend(game.entry_fee.to_le_bytes());
+    data.extend(0u16.to_le_bytes()); // commission
+    data.push(find_game_pda(&game.admin, &game.code).1);
+    data.push(find_vault_pda(&game.admin, &game.code).1);
+    data.extend(game.start_time.to_le_bytes());
+    data.extend(game.end_time.to_le_bytes());
+    data.push(game.max_winners);
+    data.extend((players.len() as u32).to_le_bytes());
+    data.extend(game.tree.root());
+    data.extend(0u64.to_le_bytes()); // donation
+    data.extend([1, 0, 0]); // native, not all winners, geometric split
+    data.resize(LEGACY_GAME_SPACE, 0);
+    env.svm.set_account(
+        game.address,
+        Account {
+            lamports: rent(data.len()),
+            data,
+            owner: twizzin_be_2::ID,
+            ..Account::default()
+        },
+    );
+    env.svm
+        .airdrop(&game.vault, players.len() as u64 * game.entry_fee);
+
+    for (i, (player, num_correct)) in players.iter().enumerate() {
+        let mut data = PlayerAccount::DISCRIMINATOR.to_vec();
+        data.extend(game.address.to_bytes());
+        data.extend(player.to_bytes());
+        data.extend(game.start_time.to_le_bytes());
+        data.extend((game.start_time + 1000 * (i as i64 + 1)).to_le_bytes());
+        data.push(*num_correct);
+        data.extend([0; 32]);
+        data.push(find_player_pda(&game.address, player).1);
+        assert_eq!(data.len(), LEGACY_PLAYER_SPACE);
+        env.svm.set_account(
+            game.player_pda(player),
+            Account {
+                lamports: rent(data.len()),
+                data,
+                owner: twizzin_be_2::ID,
+                ..Account::default()
+            },
+        );
+    }
+}
+
+#[test]
+fn legacy_game_migrates() {
+    let mut env = Env::new();
+    let admin = env.wallet();
+    let mut game = TestGame::new(admin, &GameParams::native(), env.treasury, env.svm.now_ms());
+    // Its answers were committed with the original leaves, every question worth a point
+    for entry in &mut game.entries {
+        entry.points = LEGACY_POINTS;
+    }
+    game.tree = MerkleTree::new(&game.entries, MERKLE_V0).unwrap();
+    let (alice, bob) = (env.wallet(), env.wallet());
+    legacy_game(&mut env, &game, &[(alice, 2), (bob, 3)]);
+
+    let migrate_game = |signer| {
+        instructions::migrate_game(
+            accounts::MigrateGame {
+                admin: signer,
+                game: game.address,
+                winners: game.winners_pda(),
+                system_program: system_program::ID,
+            },
+            instruction::MigrateGame {},
+        )
+    };
+    let migrate_player = |player: &Pubkey| {
+        instructions::migrate_player_account(
+            accounts::MigratePlayerAccount {
+                payer: admin,
+                game: game.address,
+                player_account: game.player_pda(player),
+                system_program: system_program::ID,
+            },
+            instruction::MigratePlayerAccount {},
+        )
+    };
+
+    // Only the admin migrates the game, and players follow it
+    let stranger = env.wallet();
+    assert_error(
+        env.send(migrate_game(stranger), stranger),
+        ErrorCode::InvalidAdmin,
+    );
+    assert!(env.send(migrate_player(&alice), admin).is_err());
+    env.send(migrate_game(admin), admin).unwrap();
+    let migrated: Game = env.svm.get(&game.address).unwrap();
+    assert_eq!((migrated.creator, migrated.admin), (admin, admin));
+    assert_eq!(migrated.merkle_version, MERKLE_V0);
+    assert_eq!((migrated.total_players, migrated.finished_players), (2, 0));
+    assert!(migrated.started && !migrated.ended);
+    assert_eq!(env.svm.lamports(&game.address), rent(Game::INIT_SPACE));
+
+    for player in [alice, bob] {
+        env.send(migrate_player(&player), admin).unwrap();
+    }
+    let stored: PlayerAccount = env.svm.get(&game.player_pda(&bob)).unwrap();
+    assert_eq!((stored.num_correct, stored.score), (3, 3));
+    let migrated: Game = env.svm.get(&game.address).unwrap();
+    assert_eq!(migrated.finished_players, 2);
+    assert_error(
+        env.send(migrate_game(admin), admin),
+        ErrorCode::AlreadyMigrated,
+    );
+    assert_error(
+        env.send(migrate_player(&bob), admin),
+        ErrorCode::AlreadyMigrated,
+    );
+
+    // Late players prove their answers against the original tree
+    let carol = env.player(&game);
+    env.submit(&game, carol, &["a", "c", "b"]);
+    let stored: PlayerAccount = env.svm.get(&game.player_pda(&carol)).unwrap();
+    assert_eq!((stored.num_correct, stored.score), (1, 1));
+
+    // The migrated game finishes like any other
+    env.svm.warp_to_ms(game.end_time);
+    env.end(&game);
+    env.declare(&game, &[bob, alice]);
+    let prize = env.svm.get::<Winners>(&game.winners_pda()).unwrap().winners[0].prize_amount;
+    let before = env.svm.lamports(&bob);
+    for player in [bob, alice] {
+        let claim = instructions::claim(
+            accounts::Claim {
+                submission: None,
+                ..game.claim_accounts(player)
+            },
+            instruction::Claim {},
+        );
+        env.send(claim, player).unwrap();
+    }
+    assert_eq!(
+        env.svm.lamports(&bob),
+        before + prize + rent(PlayerAccount::INIT_SPACE)
+    );
+
+    // It never had a code registry to free
+    let close = instructions::close_game(
+        accounts::CloseGame {
+            game_code_registry: None,
+            ..game.close_accounts()
+        },
+        instruction::CloseGame {},
+    );
+    env.send(close, admin).unwrap();
+    assert!(env.svm.account(&game.address).is_none());
+}
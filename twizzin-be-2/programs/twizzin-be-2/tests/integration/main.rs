@@ -0,0 +1,15 @@
+//! End-to-end tests that run the program natively on a solana-runtime bank.
+//!
+//! cargo test -p twizzin-be-2 --test integration -- --nocapture
+
+mod setup;
+mod svm;
+
+mod access;
+mod admin;
+mod compute;
+mod errors;
+mod fuzz;
+mod lifecycle;
+mod seasons;
+mod tournaments;
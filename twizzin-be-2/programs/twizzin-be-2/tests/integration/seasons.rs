@@ -0,0 +1,440 @@
+//! Seasons: approved hosts' games opt in, winners earn points when they're declared and
+//! the leaderboard splits the season's prize pool once it ends.
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use anchor_spl::{associated_token, token};
+use twizzin_be_2::constants::PROGRAM_AUTHORITY;
+use twizzin_be_2::errors::ErrorCode;
+use twizzin_be_2::state::{Season, SeasonPlayer, Winners, MAX_WINNERS, OPERATOR_DECLARE_WINNERS};
+use twizzin_be_2::utils::prize::calculate_prizes;
+use twizzin_be_2::utils::season::SEASON_POINTS;
+use twizzin_be_2::{accounts, instruction};
+use twizzin_client::{
+    find_game_operator_pda, find_season_pda, find_season_player_pda, find_season_vault_pda,
+    instructions,
+};
+
+use crate::admin::set_operator_ix;
+use crate::setup::*;
+
+const SEASON_ID: u32 = 7;
+pub const SEASON_LENGTH: i64 = 2 * 60 * 60 * 1000;
+
+pub struct TestSeason {
+    pub address: Pubkey,
+    vault: Pubkey,
+    mint: Pubkey,
+    is_native: bool,
+    pub end_time: i64,
+}
+
+impl TestSeason {
+    fn vault_token_account(&self) -> Option<Pubkey> {
+        (!self.is_native)
+            .then(|| associated_token::get_associated_token_address(&self.vault, &self.mint))
+    }
+
+    pub fn approve_host_ix(&self, host: Pubkey) -> Instruction {
+        instructions::approve_season_host(
+            accounts::ApproveSeasonHost {
+                authority: PROGRAM_AUTHORITY,
+                season: self.address,
+            },
+            instruction::ApproveSeasonHost { host },
+        )
+    }
+
+    fn remove_host_ix(&self, host: Pubkey) -> Instruction {
+        instructions::remove_season_host(
+            accounts::RemoveSeasonHost {
+                authority: PROGRAM_AUTHORITY,
+                season: self.address,
+            },
+            instruction::RemoveSeasonHost { host },
+        )
+    }
+
+    pub fn settle_ix(&self, winners: &[Pubkey]) -> Instruction {
+        let remaining = winners
+            .iter()
+            .map(|winner| {
+                let account = if self.is_native {
+                    *winner
+                } else {
+                    associated_token::get_associated_token_address(winner, &self.mint)
+                };
+                AccountMeta::new(account, false)
+            })
+            .collect();
+        instructions::settle_season(
+            accounts::SettleSeason {
+                payer: PROGRAM_AUTHORITY,
+                season: self.address,
+                season_vault: self.vault,
+                season_vault_token_account: self.vault_token_account(),
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::SettleSeason {},
+            remaining,
+        )
+    }
+}
+
+pub fn create_season(env: &mut Env, mint: Pubkey, prize_pool: u64) -> TestSeason {
+    create_season_with(env, mint, prize_pool, false)
+}
+
+fn create_season_with(
+    env: &mut Env,
+    mint: Pubkey,
+    prize_pool: u64,
+    even_split: bool,
+) -> TestSeason {
+    let address = find_season_pda(SEASON_ID).0;
+    let season = TestSeason {
+        address,
+        vault: find_season_vault_pda(&address).0,
+        mint,
+        is_native: mint == NATIVE_MINT,
+        end_time: env.svm.now_ms() + SEASON_LENGTH,
+    };
+    let authority_token_account =
+        (!season.is_native).then(|| env.fund_tokens(&PROGRAM_AUTHORITY, &mint, prize_pool));
+    let ix = instructions::create_season(
+        accounts::CreateSeason {
+            authority: PROGRAM_AUTHORITY,
+            season: season.address,
+            token_mint: mint,
+            season_vault: season.vault,
+            season_vault_token_account: season.vault_token_account(),
+            authority_token_account,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateSeason {
+            season_id: SEASON_ID,
+            name: "Season 7".to_string(),
+            start_time: env.svm.now_ms(),
+            end_time: season.end_time,
+            num_winners: 2,
+            even_split,
+            max_games: 2,
+            prize_pool,
+        },
+    );
+    env.send(ix, PROGRAM_AUTHORITY).unwrap();
+    season
+}
+
+pub fn season_game(env: &mut Env, season: &TestSeason) -> TestGame {
+    let params = if season.is_native {
+        GameParams::native()
+    } else {
+        GameParams::spl(season.mint)
+    };
+    let admin = env.wallet();
+    env.send(season.approve_host_ix(admin), PROGRAM_AUTHORITY)
+        .unwrap();
+    env.create_game_as(
+        admin,
+        GameParams {
+            season: Some(season.address),
+            ..params
+        },
+    )
+}
+
+fn points(env: &Env, season: &TestSeason, player: &Pubkey) -> u32 {
+    env.svm
+        .get::<SeasonPlayer>(&find_season_player_pda(&season.address, player).0)
+        .unwrap()
+        .points
+}
+
+fn season_pays_its_leaderboard(native: bool) {
+    let mut env = Env::new();
+    let mint = if native { NATIVE_MINT } else { env.mint() };
+    let season = create_season(&mut env, mint, 3 * SOL);
+
+    let first = season_game(&mut env, &season);
+    let (alice, bob) = (env.player(&first), env.player(&first));
+    env.play(&first, &[(alice, &ANSWERS), (bob, &["a", "b", "x"])]);
+    // Points land with the winners, whether or not they claim
+    assert_eq!(points(&env, &season, &alice), 25);
+    assert_eq!(points(&env, &season, &bob), 18);
+    env.claim(&first, alice);
+
+    let second = season_game(&mut env, &season);
+    env.send(second.join_ix(bob, JoinOptions::default()), bob)
+        .unwrap();
+    let carol = env.player(&second);
+    env.play(&second, &[(bob, &ANSWERS), (carol, &["x", "x", "x"])]);
+    env.claim(&second, bob);
+
+    assert_eq!(points(&env, &season, &alice), 25);
+    assert_eq!(points(&env, &season, &bob), 43);
+    assert_eq!(points(&env, &season, &carol), 18);
+    let stored: Season = env.svm.get(&season.address).unwrap();
+    let order: Vec<Pubkey> = stored.leaderboard.iter().map(|s| s.player).collect();
+    assert_eq!(order, vec![bob, alice, carol]);
+    assert_eq!(stored.num_games, 2);
+
+    // The season is full
+    env.send(season.approve_host_ix(PROGRAM_AUTHORITY), PROGRAM_AUTHORITY)
+        .unwrap();
+    let params = GameParams {
+        season: Some(season.address),
+        ..GameParams::native()
+    };
+    let result = env.send(init_ix(&env, &params), PROGRAM_AUTHORITY);
+    assert_error(result, ErrorCode::SeasonFull);
+
+    let settle = season.settle_ix(&[bob, alice]);
+    assert_error(
+        env.send(settle.clone(), PROGRAM_AUTHORITY),
+        ErrorCode::SeasonNotEnded,
+    );
+    env.svm.warp_to_ms(season.end_time);
+    assert_error(
+        env.send(season.settle_ix(&[bob]), PROGRAM_AUTHORITY),
+        ErrorCode::InvalidSeasonWinner,
+    );
+    assert_error(
+        env.send(season.settle_ix(&[alice, bob]), PROGRAM_AUTHORITY),
+        ErrorCode::InvalidSeasonWinner,
+    );
+
+    let pot = if native {
+        env.svm.lamports(&season.vault)
+    } else {
+        env.svm
+            .token_balance(&season.vault_token_account().unwrap())
+    };
+    let prizes = calculate_prizes(pot, 2, false, native, 0).unwrap();
+    let before = [bob, alice].map(|player| first.balance(&env.svm, &player));
+    env.send(settle, PROGRAM_AUTHORITY).unwrap();
+    for ((player, before), prize) in [bob, alice].iter().zip(before).zip(&prizes) {
+        assert_eq!(first.balance(&env.svm, player), before + prize);
+    }
+    assert!(env.svm.get::<Season>(&season.address).unwrap().settled);
+
+    let result = env.send(season.settle_ix(&[bob, alice]), PROGRAM_AUTHORITY);
+    assert_error(result, ErrorCode::SeasonSettled);
+}
+
+// An even split that leaves a lamport over still empties the vault
+#[test]
+fn even_split_season_pays_the_dust() {
+    let mut env = Env::new();
+    let season = create_season_with(&mut env, NATIVE_MINT, SOL + 1, true);
+    let game = season_game(&mut env, &season);
+    let (alice, bob) = (env.player(&game), env.player(&game));
+    env.play(&game, &[(alice, &ANSWERS), (bob, &["a", "b", "x"])]);
+    env.claim(&game, alice);
+    env.claim(&game, bob);
+
+    env.svm.warp_to_ms(season.end_time);
+    let before = [alice, bob].map(|player| env.svm.lamports(&player));
+    env.send(season.settle_ix(&[alice, bob]), PROGRAM_AUTHORITY)
+        .unwrap();
+    assert_eq!(env.svm.lamports(&alice), before[0] + SOL / 2 + 1);
+    assert_eq!(env.svm.lamports(&bob), before[1] + SOL / 2);
+    assert_eq!(env.svm.lamports(&season.vault), 0);
+}
+
+// Creates a game as the program authority, for games that are meant to fail
+fn init_ix(env: &Env, params: &GameParams) -> Instruction {
+    TestGame::new(PROGRAM_AUTHORITY, params, env.treasury, env.svm.now_ms()).init_ix(params)
+}
+
+#[test]
+fn native_season() {
+    season_pays_its_leaderboard(true);
+}
+
+#[test]
+fn spl_season() {
+    season_pays_its_leaderboard(false);
+}
+
+#[test]
+fn season_rules() {
+    let mut env = Env::new();
+    let season = create_season(&mut env, NATIVE_MINT, SOL);
+
+    // Only the program authority runs seasons
+    let stranger = env.wallet();
+    let mut ix = season.settle_ix(&[]);
+    ix.accounts[0].pubkey = stranger;
+    let address = find_season_pda(SEASON_ID + 1).0;
+    let create = instructions::create_season(
+        accounts::CreateSeason {
+            authority: stranger,
+            season: address,
+            token_mint: NATIVE_MINT,
+            season_vault: find_season_vault_pda(&address).0,
+            season_vault_token_account: None,
+            authority_token_account: None,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateSeason {
+            season_id: SEASON_ID + 1,
+            name: "Mine".to_string(),
+            start_time: 0,
+            end_time: 1,
+            num_winners: 1,
+            even_split: true,
+            max_games: 1,
+            prize_pool: 0,
+        },
+    );
+    assert_error(env.send(create, stranger), ErrorCode::InvalidAuthority);
+    let mut approve = season.approve_host_ix(stranger);
+    approve.accounts[0].pubkey = stranger;
+    assert_error(env.send(approve, stranger), ErrorCode::InvalidAuthority);
+
+    // Only approved hosts can add games, and only while they're approved
+    let params = GameParams {
+        season: Some(season.address),
+        ..GameParams::native()
+    };
+    let result = env.send(init_ix(&env, &params), PROGRAM_AUTHORITY);
+    assert_error(result, ErrorCode::HostNotApproved);
+    env.send(season.approve_host_ix(PROGRAM_AUTHORITY), PROGRAM_AUTHORITY)
+        .unwrap();
+    env.send(season.remove_host_ix(PROGRAM_AUTHORITY), PROGRAM_AUTHORITY)
+        .unwrap();
+    let result = env.send(init_ix(&env, &params), PROGRAM_AUTHORITY);
+    assert_error(result, ErrorCode::HostNotApproved);
+    let stored: Season = env.svm.get(&season.address).unwrap();
+    assert!(stored.approved_hosts.is_empty());
+    env.send(season.approve_host_ix(PROGRAM_AUTHORITY), PROGRAM_AUTHORITY)
+        .unwrap();
+
+    // Games have to fit inside the season
+    let params = GameParams {
+        season: Some(season.address),
+        duration: 3 * SEASON_LENGTH,
+        ..GameParams::native()
+    };
+    let result = env.send(init_ix(&env, &params), PROGRAM_AUTHORITY);
+    assert_error(result, ErrorCode::GameOutsideSeason);
+
+    // and stay inside it when they're rescheduled
+    let game = env.create_game_as(
+        PROGRAM_AUTHORITY,
+        GameParams {
+            season: Some(season.address),
+            starts_in: 60_000,
+            ..GameParams::native()
+        },
+    );
+    let postpone = instruction::UpdateGame {
+        new_end_time: Some(season.end_time + 1),
+        ..no_changes()
+    };
+    let result = env.send(game.update_ix(game.admin, postpone), game.admin);
+    assert_error(result, ErrorCode::GameOutsideSeason);
+
+    // Points go to the season accounts of the declared winners
+    let (alice, bob) = (env.player(&game), env.player(&game));
+    env.start(&game, 60_000);
+    env.submit(&game, alice, &ANSWERS);
+    env.end(&game);
+    let mut declare = game.declare_ix(game.admin, vec![alice]);
+    let bobs = find_season_player_pda(&season.address, &bob).0;
+    declare.accounts.last_mut().unwrap().pubkey = bobs;
+    assert_error(env.send(declare, game.admin), ErrorCode::InvalidSeason);
+
+    // The season can't settle until every game in it has declared
+    env.svm.warp_to_ms(season.end_time);
+    assert_error(env.send(ix, stranger), ErrorCode::SeasonGamesPending);
+    env.declare(&game, &[alice]);
+    let mut ix = season.settle_ix(&[alice]);
+    ix.accounts[0].pubkey = stranger;
+    env.send(ix, stranger).unwrap();
+    assert_eq!(points(&env, &season, &alice), 25);
+}
+
+// The most winners a game can have are declared a batch per transaction, with an
+// operator signing for an SPL season game - the most accounts a batch can carry
+#[test]
+fn most_winners_declare_in_batches() {
+    let mut env = Env::new();
+    let mint = env.mint();
+    let season = create_season(&mut env, mint, 3 * SOL);
+    let admin = env.wallet();
+    env.send(season.approve_host_ix(admin), PROGRAM_AUTHORITY)
+        .unwrap();
+    let game = env.create_game_as(
+        admin,
+        GameParams {
+            all_are_winners: true,
+            season: Some(season.address),
+            ..GameParams::spl(mint)
+        },
+    );
+    let operator = env.wallet();
+    env.send(
+        set_operator_ix(&game, operator, OPERATOR_DECLARE_WINNERS),
+        admin,
+    )
+    .unwrap();
+
+    // Everyone is right, so they rank in the order they finished
+    let players: Vec<Pubkey> = (0..MAX_WINNERS).map(|_| env.player(&game)).collect();
+    env.start(&game, 3_600_000);
+    for player in &players {
+        env.submit(&game, *player, &ANSWERS);
+    }
+    env.svm.advance_ms(3_600_000);
+    env.end(&game);
+
+    let declare = |winners: &[Pubkey], declared| -> Vec<Instruction> {
+        game.declare_ixs(operator, winners, declared)
+            .into_iter()
+            .map(|mut ix| {
+                ix.accounts[2].pubkey = find_game_operator_pda(&game.address, &operator).0;
+                ix
+            })
+            .collect()
+    };
+    let batches = declare(&players, 0);
+    assert!(batches.len() > 1);
+    let (last, rest) = batches.split_last().unwrap();
+    env.send(rest[0].clone(), operator).unwrap();
+
+    // A batch picks up below the last one, so nobody is declared twice
+    let again = declare(&[players[0], players[0]], 1).remove(0);
+    assert_error(env.send(again, operator), ErrorCode::InvalidWinnerOrder);
+    for ix in &rest[1..] {
+        env.send(ix.clone(), operator).unwrap();
+    }
+
+    // Nobody is paid until everyone is in
+    assert_error(
+        env.send(game.claim_ix(players[0]), players[0]),
+        ErrorCode::WinnersNotDeclared,
+    );
+    env.send(last.clone(), operator).unwrap();
+    let winners: Winners = env.svm.get(&game.winners_pda()).unwrap();
+    assert!(winners.is_complete());
+    assert_eq!(winners.winners.len(), MAX_WINNERS as usize);
+    assert_error(
+        env.send(last.clone(), operator),
+        ErrorCode::WinnersAlreadyDeclared,
+    );
+
+    // Points went to the top of the whole declaration, not of each batch
+    for (rank, player) in players.iter().enumerate() {
+        let expected = SEASON_POINTS.get(rank).copied().unwrap_or(0);
+        assert_eq!(points(&env, &season, player), expected);
+    }
+    env.claim(&game, players[0]);
+}
@@ -0,0 +1,818 @@
+//! Shared fixtures: a configured program, funded wallets and mints, a small
+//! answer key, and builders for a game's core instructions.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::{self, spl_token};
+use twizzin_be_2::constants::PROGRAM_AUTHORITY;
+use twizzin_be_2::errors::ErrorCode;
+use twizzin_be_2::state::{
+    AccessMode, AccessProof, AnswerInput, AnswerKeyEntry, AnswerMultiproof, AnswerType,
+    FeeRecipient, Gate, MintListProof, ReferralFeeSource,
+};
+use twizzin_be_2::utils::merkle::MERKLE_V2;
+use twizzin_be_2::{accounts, instruction};
+use twizzin_client::{
+    find_answer_key_pda, find_config_pda, find_game_code_registry_pda, find_game_pda,
+    find_game_referral_pda, find_player_pda, find_profile_pda, find_referral_balance_pda,
+    find_season_player_pda, find_submission_pda, find_vault_pda, find_winners_pda, instructions,
+    MerkleTree,
+};
+
+use crate::svm::{Svm, TxResult};
+
+pub const SOL: u64 = 1_000_000_000;
+pub const TREASURY_FEE_BPS: u16 = 500;
+pub const NATIVE_MINT: Pubkey = spl_token::native_mint::ID;
+
+// Games use the same three-question key unless they ask for more: the right
+// picks are a, b, c, then round again
+pub const ANSWERS: [&str; 3] = ["a", "b", "c"];
+
+pub fn answer_key(questions: u8) -> Vec<AnswerKeyEntry> {
+    (0..questions)
+        .map(|i| AnswerKeyEntry {
+            display_order: i,
+            answer_type: AnswerType::Choice,
+            answer: ANSWERS[i as usize % ANSWERS.len()].to_string(),
+            question_id: format!("6a1e0c2e-0000-4000-8000-{:012x}", i),
+            points: 10,
+        })
+        .collect()
+}
+
+// Fails the test unless the transaction failed with the program's `code`
+#[track_caller]
+pub fn assert_error(result: TxResult, code: ErrorCode) {
+    let expected = u32::from(code);
+    match result {
+        Ok(logs) => panic!("expected {code:?} ({expected}) but succeeded: {logs:#?}"),
+        Err(failure) => assert_eq!(
+            failure.custom_code(),
+            Some(expected),
+            "expected {code:?}, got {:?}: {:#?}",
+            failure.error,
+            failure.logs
+        ),
+    }
+}
+
+pub struct Env {
+    pub svm: Svm,
+    pub treasury: Pubkey,
+}
+
+impl Env {
+    // A program with its config set up - 5% treasury fee, no referral fee
+    pub fn new() -> Self {
+        Self::with_svm(Svm::new())
+    }
+
+    pub fn without_config() -> Self {
+        Self::on(Svm::new())
+    }
+
+    // A configured program running on `svm`
+    pub fn with_svm(svm: Svm) -> Self {
+        let mut env = Self::on(svm);
+        env.init_config(Vec::new(), 0, ReferralFeeSource::Treasury);
+        env
+    }
+
+    fn on(mut svm: Svm) -> Self {
+        svm.airdrop(&PROGRAM_AUTHORITY, 1_000 * SOL);
+        let treasury = Pubkey::new_unique();
+        svm.airdrop(&treasury, SOL);
+        Self { svm, treasury }
+    }
+
+    pub fn init_config(
+        &mut self,
+        fee_recipients: Vec<FeeRecipient>,
+        referral_fee: u16,
+        referral_fee_source: ReferralFeeSource,
+    ) {
+        let ix = self.init_config_ix(
+            PROGRAM_AUTHORITY,
+            TREASURY_FEE_BPS,
+            fee_recipients,
+            referral_fee,
+            referral_fee_source,
+        );
+        self.send(ix, PROGRAM_AUTHORITY).expect("init config");
+    }
+
+    pub fn init_config_ix(
+        &self,
+        admin: Pubkey,
+        treasury_fee: u16,
+        fee_recipients: Vec<FeeRecipient>,
+        referral_fee: u16,
+        referral_fee_source: ReferralFeeSource,
+    ) -> Instruction {
+        instructions::init_config(
+            accounts::InitConfig {
+                admin,
+                config: find_config_pda().0,
+                system_program: system_program::ID,
+            },
+            instruction::InitConfig {
+                treasury_pubkey: self.treasury,
+                treasury_fee,
+                fee_recipients,
+                referral_fee,
+                referral_fee_source,
+            },
+        )
+    }
+
+    pub fn wallet(&mut self) -> Pubkey {
+        let wallet = Pubkey::new_unique();
+        self.svm.airdrop(&wallet, 100 * SOL);
+        wallet
+    }
+
+    pub fn mint(&mut self) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        self.svm.set_mint(mint, 6);
+        mint
+    }
+
+    // Gives `owner` an associated token account holding `amount` of `mint`
+    pub fn fund_tokens(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+        let address = get_associated_token_address(owner, mint);
+        self.svm.set_token_account(address, *mint, *owner, amount);
+        address
+    }
+
+    pub fn send(&mut self, ix: Instruction, signer: Pubkey) -> TxResult {
+        self.svm.process(&[ix], &[signer])
+    }
+
+    pub fn create_game(&mut self, params: GameParams) -> TestGame {
+        let admin = self.wallet();
+        self.create_game_as(admin, params)
+    }
+
+    pub fn create_game_as(&mut self, admin: Pubkey, params: GameParams) -> TestGame {
+        let game = TestGame::new(admin, &params, self.treasury, self.svm.now_ms());
+        if !game.is_native {
+            self.fund_tokens(&admin, &game.mint, 1_000 * SOL);
+            let treasury = self.treasury;
+            if self
+                .svm
+                .account(&game.token_account(&treasury).unwrap())
+                .is_none()
+            {
+                self.fund_tokens(&treasury, &game.mint, 0);
+            }
+        }
+        let ix = game.init_ix(&params);
+        self.send(ix, admin).expect("init game");
+        game
+    }
+
+    // A wallet that has joined `game`, with tokens to pay for SPL games
+    pub fn player(&mut self, game: &TestGame) -> Pubkey {
+        let player = self.wallet();
+        if !game.is_native {
+            self.fund_tokens(&player, &game.mint, 100 * SOL);
+        }
+        let ix = game.join_ix(player, JoinOptions::default());
+        self.send(ix, player).expect("join game");
+        player
+    }
+
+    pub fn start(&mut self, game: &TestGame, total_time: i64) {
+        let ix = game.start_ix(game.admin, total_time);
+        self.send(ix, game.admin).expect("start game");
+    }
+
+    // Submits `picks` finishing a second from now, each answered 100ms apart
+    pub fn submit(&mut self, game: &TestGame, player: Pubkey, picks: &[&str]) {
+        self.svm.advance_ms(1000);
+        let finish = self.svm.now_ms();
+        self.svm.advance_ms(1000);
+        let ix = game.submit_ix(player, game.answers(picks, finish), finish);
+        self.send(ix, player).expect("submit answers");
+    }
+
+    pub fn end(&mut self, game: &TestGame) {
+        let ix = game.end_ix(game.admin, Vec::new());
+        self.send(ix, game.admin).expect("end game");
+    }
+
+    pub fn declare(&mut self, game: &TestGame, winners: &[Pubkey]) {
+        for ix in game.declare_ixs(game.admin, winners, 0) {
+            self.send(ix, game.admin).expect("declare winners");
+        }
+    }
+
+    pub fn claim(&mut self, game: &TestGame, player: Pubkey) {
+        let ix = game.claim_ix(player);
+        self.send(ix, player).expect("claim");
+    }
+
+    // Runs a game through to declared winners. Each player's picks are
+    // submitted in order, so earlier players win ties.
+    pub fn play(&mut self, game: &TestGame, players: &[(Pubkey, &[&str])]) -> Vec<Pubkey> {
+        self.start(game, 60_000);
+        for (player, picks) in players {
+            self.submit(game, *player, picks);
+        }
+        self.end(game);
+
+        let mut ranked: Vec<_> = players
+            .iter()
+            .enumerate()
+            .map(|(i, (player, _))| {
+                let score = self
+                    .svm
+                    .get::<twizzin_be_2::state::PlayerAccount>(&game.player_pda(player))
+                    .unwrap()
+                    .score;
+                (score, i, *player)
+            })
+            .collect();
+        ranked.sort_by_key(|(score, i, _)| (-score, *i));
+        let count = if game.all_are_winners {
+            ranked.len()
+        } else {
+            ranked.len().min(game.max_winners as usize)
+        };
+        let winners: Vec<_> = ranked
+            .iter()
+            .take(count)
+            .map(|(_, _, player)| *player)
+            .collect();
+        self.declare(game, &winners);
+        winners
+    }
+}
+
+#[derive(Clone)]
+pub struct GameParams {
+    pub name: String,
+    pub code: String,
+    pub mint: Pubkey,
+    pub entry_fee: u64,
+    pub commission: u16,
+    pub max_winners: u8,
+    pub donation: u64,
+    pub all_are_winners: bool,
+    pub even_split: bool,
+    pub access_mode: AccessMode,
+    pub gate: Gate,
+    pub metadata_uri: Option<String>,
+    pub wrong_answer_penalty: u16,
+    pub speed_bonus: u16,
+    pub speed_bonus_window: u32,
+    pub merkle_version: u8,
+    pub questions: u8,
+    pub starts_in: i64,
+    pub duration: i64,
+    pub season: Option<Pubkey>,
+    pub tournament: Option<Pubkey>,
+}
+
+impl GameParams {
+    // A public SOL game: 0.1 SOL to enter, 2 winners, 10% commission
+    pub fn native() -> Self {
+        Self {
+            name: "Integration".to_string(),
+            code: unique_code(),
+            mint: NATIVE_MINT,
+            entry_fee: SOL / 10,
+            commission: 1000,
+            max_winners: 2,
+            donation: 0,
+            all_are_winners: false,
+            even_split: false,
+            access_mode: AccessMode::Public,
+            gate: Gate::Open,
+            metadata_uri: None,
+            wrong_answer_penalty: 0,
+            speed_bonus: 0,
+            speed_bonus_window: 0,
+            merkle_version: MERKLE_V2,
+            questions: ANSWERS.len() as u8,
+            starts_in: 0,
+            duration: 3_600_000,
+            season: None,
+            tournament: None,
+        }
+    }
+
+    pub fn spl(mint: Pubkey) -> Self {
+        Self {
+            mint,
+            ..Self::native()
+        }
+    }
+}
+
+// update_game args that leave everything as it is
+pub fn no_changes() -> instruction::UpdateGame {
+    instruction::UpdateGame {
+        new_name: None,
+        new_entry_fee: None,
+        new_commission: None,
+        new_start_time: None,
+        new_end_time: None,
+        new_max_winners: None,
+        new_answer_hash: None,
+        new_donation_amount: None,
+        new_all_are_winners: None,
+        new_even_split: None,
+        new_access_mode: None,
+        new_gate: None,
+        new_metadata_uri: None,
+        new_question_set_hash: None,
+        new_wrong_answer_penalty: None,
+        new_speed_bonus: None,
+        new_speed_bonus_window: None,
+        new_merkle_version: None,
+    }
+}
+
+// Game codes are global, so every test game gets its own
+pub fn unique_code() -> String {
+    static NEXT: AtomicU32 = AtomicU32::new(0);
+    format!("G{:08X}", NEXT.fetch_add(1, Ordering::Relaxed))
+}
+
+#[derive(Default)]
+pub struct JoinOptions {
+    pub referrer: Option<Pubkey>,
+    pub access_proof: Option<AccessProof>,
+    pub gate_proof: Option<MintListProof>,
+    pub gate_token_account: Option<Pubkey>,
+    pub gate_metadata: Option<Pubkey>,
+    pub instructions_sysvar: bool,
+    pub tournament: Option<(Pubkey, Pubkey)>, // Tournament and the player's entry
+    pub previous_winners: Option<Pubkey>,
+}
+
+pub struct TestGame {
+    pub admin: Pubkey,
+    pub code: String,
+    pub address: Pubkey,
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    pub is_native: bool,
+    pub treasury: Pubkey,
+    pub entry_fee: u64,
+    pub max_winners: u8,
+    pub all_are_winners: bool,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub season: Option<Pubkey>,
+    pub entries: Vec<AnswerKeyEntry>,
+    pub tree: MerkleTree,
+}
+
+impl TestGame {
+    pub fn new(admin: Pubkey, params: &GameParams, treasury: Pubkey, now: i64) -> Self {
+        let entries = answer_key(params.questions);
+        let tree = MerkleTree::new(&entries, params.merkle_version).unwrap();
+        Self {
+            admin,
+            code: params.code.clone(),
+            address: find_game_pda(&admin, &params.code).0,
+            vault: find_vault_pda(&admin, &params.code).0,
+            mint: params.mint,
+            is_native: params.mint == NATIVE_MINT,
+            treasury,
+            entry_fee: params.entry_fee,
+            max_winners: params.max_winners,
+            all_are_winners: params.all_are_winners,
+            start_time: now + params.starts_in,
+            end_time: now + params.starts_in + params.duration,
+            season: params.season,
+            entries,
+            tree,
+        }
+    }
+
+    // The owner's associated token account for SPL games, None for SOL games
+    pub fn token_account(&self, owner: &Pubkey) -> Option<Pubkey> {
+        (!self.is_native).then(|| get_associated_token_address(owner, &self.mint))
+    }
+
+    pub fn vault_token_account(&self) -> Option<Pubkey> {
+        self.token_account(&self.vault)
+    }
+
+    // What the vault holds in the game's currency
+    pub fn pot(&self, svm: &Svm) -> u64 {
+        match self.vault_token_account() {
+            Some(account) => svm.token_balance(&account),
+            None => svm.lamports(&self.vault),
+        }
+    }
+
+    // What `owner` holds in the game's currency
+    pub fn balance(&self, svm: &Svm, owner: &Pubkey) -> u64 {
+        match self.token_account(owner) {
+            Some(account) => svm.token_balance(&account),
+            None => svm.lamports(owner),
+        }
+    }
+
+    pub fn player_pda(&self, player: &Pubkey) -> Pubkey {
+        find_player_pda(&self.address, player).0
+    }
+
+    pub fn submission_pda(&self, player: &Pubkey) -> Pubkey {
+        find_submission_pda(&self.address, player).0
+    }
+
+    pub fn winners_pda(&self) -> Pubkey {
+        find_winners_pda(&self.address).0
+    }
+
+    pub fn answer_key_pda(&self) -> Pubkey {
+        find_answer_key_pda(&self.address).0
+    }
+
+    // One answer per pick, in display order, each answered 100ms before `finish`
+    pub fn answers(&self, picks: &[&str], finish: i64) -> Vec<AnswerInput> {
+        picks
+            .iter()
+            .enumerate()
+            .map(|(i, pick)| {
+                let answered_at = finish - 100 * (picks.len() - i) as i64;
+                self.tree
+                    .answer_input(&self.entries, i, pick, answered_at)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    pub fn init_ix(&self, params: &GameParams) -> Instruction {
+        instructions::init_game(
+            accounts::InitGame {
+                admin: self.admin,
+                game: self.address,
+                game_code_registry: find_game_code_registry_pda(&params.code).0,
+                token_mint: params.mint,
+                vault: self.vault,
+                vault_token_account: self.vault_token_account(),
+                admin_token_account: self.token_account(&self.admin),
+                season: params.season,
+                tournament: params.tournament,
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::InitGame {
+                name: params.name.clone(),
+                game_code: params.code.clone(),
+                entry_fee: params.entry_fee,
+                commission: params.commission,
+                start_time: self.start_time,
+                end_time: self.end_time,
+                max_winners: params.max_winners,
+                answer_hash: self.tree.root(),
+                donation_amount: params.donation,
+                all_are_winners: params.all_are_winners,
+                even_split: params.even_split,
+                access_mode: params.access_mode,
+                gate: params.gate,
+                metadata_uri: params.metadata_uri.clone(),
+                question_set_hash: [7; 32],
+                wrong_answer_penalty: params.wrong_answer_penalty,
+                speed_bonus: params.speed_bonus,
+                speed_bonus_window: params.speed_bonus_window,
+                merkle_version: params.merkle_version,
+            },
+        )
+    }
+
+    pub fn update_ix(&self, authority: Pubkey, args: instruction::UpdateGame) -> Instruction {
+        instructions::update_game(
+            accounts::UpdateGame {
+                authority,
+                game: self.address,
+                game_operator: None,
+                vault: self.vault,
+                vault_token_account: self.vault_token_account(),
+                token_mint: self.mint,
+                admin_token_account: self.token_account(&self.admin),
+                season: self.season,
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            args,
+        )
+    }
+
+    pub fn join_ix(&self, player: Pubkey, options: JoinOptions) -> Instruction {
+        let referral = options.referrer.map(|referrer| {
+            let balance = find_referral_balance_pda(&referrer, &self.mint).0;
+            (referrer, balance)
+        });
+        instructions::join_game(
+            accounts::JoinGame {
+                player,
+                game: self.address,
+                player_account: self.player_pda(&player),
+                vault: self.vault,
+                vault_token_account: self.vault_token_account(),
+                player_token_account: (self.entry_fee > 0)
+                    .then(|| self.token_account(&player))
+                    .flatten(),
+                referrer_player_account: referral.map(|(referrer, _)| self.player_pda(&referrer)),
+                game_referral: referral
+                    .map(|(referrer, _)| find_game_referral_pda(&self.address, &referrer).0),
+                referral_balance: referral.map(|(_, balance)| balance),
+                referral_token_account: referral
+                    .and_then(|(_, balance)| self.token_account(&balance)),
+                token_mint: referral.map(|_| self.mint),
+                tournament: options.tournament.map(|(tournament, _)| tournament),
+                tournament_entry: options.tournament.map(|(_, entry)| entry),
+                previous_winners: options.previous_winners,
+                gate_token_account: options.gate_token_account,
+                gate_metadata: options.gate_metadata,
+                instructions_sysvar: options
+                    .instructions_sysvar
+                    .then_some(anchor_lang::solana_program::sysvar::instructions::ID),
+                token_program: token::ID,
+                associated_token_program: associated_token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::JoinGame {
+                referrer: options.referrer,
+                access_proof: options.access_proof,
+                gate_proof: options.gate_proof,
+            },
+        )
+    }
+
+    pub fn start_ix(&self, authority: Pubkey, total_time: i64) -> Instruction {
+        instructions::start_game(
+            accounts::StartGame {
+                authority,
+                game: self.address,
+                game_operator: None,
+            },
+            instruction::StartGame { total_time },
+        )
+    }
+
+    pub fn submit_accounts(&self, player: Pubkey) -> accounts::SubmitAnswers {
+        accounts::SubmitAnswers {
+            player,
+            game: self.address,
+            player_account: self.player_pda(&player),
+            submission: self.submission_pda(&player),
+            profile: find_profile_pda(&player).0,
+            season_player: self
+                .season
+                .map(|season| find_season_player_pda(&season, &player).0),
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn submit_ix(&self, player: Pubkey, answers: Vec<AnswerInput>, finish: i64) -> Instruction {
+        instructions::submit_answers(
+            self.submit_accounts(player),
+            instruction::SubmitAnswers {
+                answers,
+                client_finish_time: finish,
+            },
+        )
+    }
+
+    pub fn submit_batch_ix(
+        &self,
+        player: Pubkey,
+        answers: Vec<AnswerInput>,
+        multiproof: AnswerMultiproof,
+        finish: i64,
+    ) -> Instruction {
+        instructions::submit_answers_batch(
+            self.submit_accounts(player),
+            instruction::SubmitAnswersBatch {
+                answers,
+                multiproof,
+                client_finish_time: finish,
+            },
+        )
+    }
+
+    pub fn chunk_ix(&self, player: Pubkey, answers: Vec<AnswerInput>) -> Instruction {
+        instructions::submit_answer_chunk(
+            accounts::SubmitAnswerChunk {
+                player,
+                game: self.address,
+                player_account: self.player_pda(&player),
+                submission: self.submission_pda(&player),
+                system_program: system_program::ID,
+            },
+            instruction::SubmitAnswerChunk { answers },
+        )
+    }
+
+    pub fn finalize_ix(&self, player: Pubkey, finish: i64) -> Instruction {
+        instructions::finalize_submission(
+            accounts::FinalizeSubmission {
+                player,
+                game: self.address,
+                player_account: self.player_pda(&player),
+                submission: self.submission_pda(&player),
+                profile: find_profile_pda(&player).0,
+                season_player: self
+                    .season
+                    .map(|season| find_season_player_pda(&season, &player).0),
+                system_program: system_program::ID,
+            },
+            instruction::FinalizeSubmission {
+                client_finish_time: finish,
+            },
+        )
+    }
+
+    pub fn reveal_ix(&self, entries: Vec<AnswerKeyEntry>) -> Instruction {
+        instructions::reveal_answer_key(
+            accounts::RevealAnswerKey {
+                admin: self.admin,
+                game: self.address,
+                answer_key: self.answer_key_pda(),
+                system_program: system_program::ID,
+            },
+            instruction::RevealAnswerKey { entries },
+        )
+    }
+
+    pub fn reveal_chunk_ix(&self, entries: Vec<AnswerKeyEntry>) -> Instruction {
+        instructions::reveal_answer_key_chunk(
+            accounts::RevealAnswerKeyChunk {
+                admin: self.admin,
+                game: self.address,
+                answer_key: self.answer_key_pda(),
+                system_program: system_program::ID,
+            },
+            instruction::RevealAnswerKeyChunk { entries },
+        )
+    }
+
+    pub fn finalize_key_ix(&self) -> Instruction {
+        instructions::finalize_answer_key(
+            accounts::FinalizeAnswerKey {
+                admin: self.admin,
+                game: self.address,
+                answer_key: self.answer_key_pda(),
+            },
+            instruction::FinalizeAnswerKey {},
+        )
+    }
+
+    pub fn rescore_ix(&self, signer: Pubkey, player: Pubkey) -> Instruction {
+        instructions::rescore_player(
+            accounts::RescorePlayer {
+                signer,
+                game: self.address,
+                answer_key: self.answer_key_pda(),
+                player_account: self.player_pda(&player),
+                submission: self.submission_pda(&player),
+                winners: self.winners_pda(),
+            },
+            instruction::RescorePlayer {},
+        )
+    }
+
+    pub fn end_accounts(&self, authority: Pubkey) -> accounts::EndGame {
+        accounts::EndGame {
+            authority,
+            admin: self.admin,
+            game: self.address,
+            game_operator: None,
+            vault: self.vault,
+            vault_token_account: self.vault_token_account(),
+            admin_token_account: self.token_account(&self.admin),
+            treasury_token_account: self.token_account(&self.treasury),
+            config: find_config_pda().0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            treasury: self.treasury,
+        }
+    }
+
+    pub fn end_ix(&self, authority: Pubkey, remaining: Vec<AccountMeta>) -> Instruction {
+        instructions::end_game(
+            self.end_accounts(authority),
+            instruction::EndGame {},
+            remaining,
+        )
+    }
+
+    pub fn settle_referral_ix(&self, referrer: &Pubkey) -> Instruction {
+        let referral_balance = find_referral_balance_pda(referrer, &self.mint).0;
+        instructions::settle_referral(
+            accounts::SettleReferral {
+                game: self.address,
+                game_referral: find_game_referral_pda(&self.address, referrer).0,
+                referral_balance,
+                vault: self.vault,
+                vault_token_account: self.vault_token_account(),
+                referral_token_account: self.token_account(&referral_balance),
+                token_program: token::ID,
+            },
+            instruction::SettleReferral {},
+        )
+    }
+
+    // Every batch after the `declared` winners
+    pub fn declare_ixs(
+        &self,
+        authority: Pubkey,
+        winners: &[Pubkey],
+        declared: usize,
+    ) -> Vec<Instruction> {
+        instructions::declare_winners(
+            accounts::DeclareWinners {
+                authority,
+                game: self.address,
+                game_operator: None,
+                vault: self.vault,
+                vault_token_account: self.vault_token_account(),
+                winners: self.winners_pda(),
+                season: self.season,
+                system_program: system_program::ID,
+            },
+            winners,
+            declared,
+        )
+    }
+
+    // A declaration that fits one batch
+    pub fn declare_ix(&self, authority: Pubkey, winners: Vec<Pubkey>) -> Instruction {
+        let mut batches = self.declare_ixs(authority, &winners, 0);
+        assert_eq!(batches.len(), 1, "winners fit one batch");
+        batches.remove(0)
+    }
+
+    pub fn claim_accounts(&self, player: Pubkey) -> accounts::Claim {
+        accounts::Claim {
+            player,
+            game: self.address,
+            winners: self.winners_pda(),
+            player_account: self.player_pda(&player),
+            submission: Some(self.submission_pda(&player)),
+            vault: self.vault,
+            vault_token_account: self.vault_token_account(),
+            player_token_account: self.token_account(&player),
+            profile: find_profile_pda(&player).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn claim_ix(&self, player: Pubkey) -> Instruction {
+        instructions::claim(self.claim_accounts(player), instruction::Claim {})
+    }
+
+    pub fn close_accounts(&self) -> accounts::CloseGame {
+        accounts::CloseGame {
+            admin: self.admin,
+            game: self.address,
+            winners: self.winners_pda(),
+            game_code_registry: Some(self.registry_pda()),
+            vault: self.vault,
+            vault_token_account: self.vault_token_account(),
+            admin_token_account: self.token_account(&self.admin),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn close_ix(&self) -> Instruction {
+        instructions::close_game(self.close_accounts(), instruction::CloseGame {})
+    }
+
+    pub fn close_player_ix(&self, player: Pubkey, submission: bool) -> Instruction {
+        instructions::close_player_account(
+            accounts::ClosePlayerAccount {
+                player,
+                game: self.address,
+                winners: Some(self.winners_pda()),
+                player_account: self.player_pda(&player),
+                submission: submission.then(|| self.submission_pda(&player)),
+                system_program: system_program::ID,
+            },
+            instruction::ClosePlayerAccount {},
+        )
+    }
+
+    pub fn registry_pda(&self) -> Pubkey {
+        find_game_code_registry_pda(&self.code).0
+    }
+}
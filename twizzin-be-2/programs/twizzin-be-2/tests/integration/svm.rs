@@ -0,0 +1,551 @@
+//! The runtime the integration tests run on: a `solana-runtime` bank with
+//! the twizzin program loaded as a native builtin through
+//! `solana-program-test`, behind the small synchronous facade the tests
+//! were written against.
+//!
+//! The bank does the account, rent and CPI privilege checks a validator
+//! does, and runs the SPL Token and Associated Token Account builds bundled
+//! with program-test. Fees are zero, and the facade's own genesis account
+//! pays for every transaction so a test's first signer needn't exist.
+//!
+//! The tests sign with bare pubkeys, so transactions go to the bank without
+//! signature verification. The facade checks the parts of sigverify the
+//! tests rely on before sending:
+//! - Every signer an instruction asks for is in the signer set.
+//! - ed25519 precompile instructions verify.
+//! - The packet size and account-lock limits, as for a legacy message.
+//!
+//! Two things differ from a plain `ProgramTestContext`:
+//! - The facade owns the bank, because a 1.18 context leaks its bank and
+//!   the accounts-db threads behind it when dropped.
+//! - program-test prints `sol_log_data` to stdout instead of the
+//!   transaction's logs, which loses Anchor's events. The facade routes it
+//!   through `sol_log` and turns those lines back into `Program data:` logs.
+//!
+//! A native builtin isn't metered. `Svm::sbf` loads the program's SBF build
+//! instead, so compute units come out as a validator would charge them.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Once, RwLock};
+
+use anchor_lang::AccountDeserialize;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use solana_accounts_db::transaction_results::TransactionExecutionResult;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    hash::Hash,
+    instruction::{Instruction, InstructionError},
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs::{self, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use solana_program_runtime::compute_budget_processor::MAX_COMPUTE_UNIT_LIMIT;
+use solana_program_runtime::invoke_context::BuiltinFunctionWithContext;
+use solana_program_test::{processor, programs::spl_programs, ProgramTest};
+use solana_runtime::{bank::Bank, bank_forks::BankForks, genesis_utils::create_genesis_config};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    bpf_loader,
+    compute_budget::ComputeBudgetInstruction,
+    ed25519_program,
+    feature_set::FeatureSet,
+    message::Message,
+    native_token::LAMPORTS_PER_SOL,
+    packet::PACKET_DATA_SIZE,
+    signature::{Signature, Signer},
+    transaction::{Transaction, TransactionError},
+};
+
+use anchor_spl::token::spl_token;
+
+/// Unix time the clock starts at. Game timestamps are this in milliseconds.
+pub const GENESIS_UNIX_TIMESTAMP: i64 = 1_750_000_000;
+
+// What an event log line looks like once it has been through `sol_log`
+const LOGGED_PROGRAM_DATA: &str = "Program log: Program data: ";
+
+/// Accounts a transaction can lock. The SDK's MAX_TX_ACCOUNT_LOCKS is the
+/// raised limit, which mainnet validators don't enforce yet.
+pub const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+
+// What the runtime charges the compute budget program for an instruction
+const COMPUTE_BUDGET_PROGRAM_UNITS: u64 = 150;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxError {
+    /// The serialized transaction doesn't fit in a packet.
+    TooLarge(usize),
+    TooManyAccountLocks(usize),
+    MissingSignature(Pubkey),
+    Precompile {
+        index: usize,
+    },
+    /// Instruction `index` returned `error`. A failed CPI reports the
+    /// callee's error, the same as a validator would.
+    Program {
+        index: usize,
+        error: ProgramError,
+    },
+    /// The bank rejected what instruction `index` did to its accounts.
+    Runtime {
+        index: usize,
+        error: InstructionError,
+    },
+    /// The bank rejected the transaction as a whole.
+    Transaction(TransactionError),
+}
+
+impl From<TransactionError> for TxError {
+    fn from(error: TransactionError) -> Self {
+        match error {
+            TransactionError::InstructionError(index, error) => {
+                let index = index as usize;
+                match ProgramError::try_from(error.clone()) {
+                    Ok(error) => Self::Program { index, error },
+                    Err(_) => Self::Runtime { index, error },
+                }
+            }
+            error => Self::Transaction(error),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Failure {
+    pub error: TxError,
+    pub logs: Vec<String>,
+}
+
+impl Failure {
+    pub fn custom_code(&self) -> Option<u32> {
+        match &self.error {
+            TxError::Program {
+                error: ProgramError::Custom(code),
+                ..
+            } => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+pub type TxResult = Result<Vec<String>, Failure>;
+
+pub struct Svm {
+    bank: Arc<Bank>,
+    bank_forks: Arc<RwLock<BankForks>>,
+    // Pays for every transaction, so a test's first signer needn't exist
+    payer: Pubkey,
+    clock: Clock,
+    // The bank drops a message it has seen as already processed, so a repeat
+    // gets a new blockhash
+    sent: HashSet<Hash>,
+    // What the last transaction to execute used - a native builtin isn't
+    // metered, so this only counts the program's own work on `Svm::sbf`
+    units: u64,
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for Svm {
+    // The program cache points back at the bank forks that own it, so the
+    // bank never drops until that cycle is cut
+    fn drop(&mut self) {
+        self.bank_forks
+            .read()
+            .unwrap()
+            .root_bank()
+            .loaded_programs_cache
+            .write()
+            .unwrap()
+            .fork_graph = None;
+    }
+}
+
+// Anchor's entrypoint ties the account slice to the accounts' lifetime,
+// which program-test's processor signature doesn't
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    twizzin_be_2::entry(program_id, accounts, data)
+}
+
+// A native builtin makes CPIs through program-test's syscall stubs, which
+// it only installs while starting its first bank
+fn install_syscall_stubs() {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("tokio runtime")
+            .block_on(ProgramTest::default().start());
+        // ProgramTest turns on debug logging for every instruction
+        solana_logger::setup_with_default("error");
+        let program_test_stubs = program_stubs::set_syscall_stubs(Box::new(NoStubs));
+        program_stubs::set_syscall_stubs(Box::new(LogDataStubs(program_test_stubs)));
+    });
+}
+
+impl Svm {
+    pub fn new() -> Self {
+        Self::with_program(None)
+    }
+
+    /// Runs the program from its SBF build, `elf`, rather than natively.
+    pub fn sbf(elf: &[u8]) -> Self {
+        Self::with_program(Some(elf))
+    }
+
+    fn with_program(elf: Option<&[u8]>) -> Self {
+        install_syscall_stubs();
+
+        let genesis = create_genesis_config(1_000_000 * LAMPORTS_PER_SOL);
+        let payer = genesis.mint_keypair.pubkey();
+        let mut genesis = genesis.genesis_config;
+        genesis.rent = Rent::default();
+        for (key, account) in spl_programs(&genesis.rent) {
+            genesis.add_account(key, account);
+        }
+        let (genesis_bank, bank_forks) = match elf {
+            Some(elf) => {
+                let program = Account {
+                    lamports: genesis.rent.minimum_balance(elf.len()),
+                    data: elf.to_vec(),
+                    owner: bpf_loader::ID,
+                    executable: true,
+                    rent_epoch: 0,
+                };
+                genesis.add_account(twizzin_be_2::ID, program.into());
+                Bank::new_with_bank_forks_for_tests(&genesis)
+            }
+            None => {
+                let builtin: Option<BuiltinFunctionWithContext> = processor!(process_instruction);
+                Bank::new_with_mockup_builtin_for_tests(
+                    &genesis,
+                    twizzin_be_2::ID,
+                    builtin.unwrap(),
+                )
+            }
+        };
+        // Programs deployed in a slot can only be called from the next one
+        genesis_bank.fill_bank_with_ticks_for_tests();
+        let bank = Bank::new_from_parent(genesis_bank.clone(), genesis_bank.collector_id(), 1);
+        let bank = bank_forks
+            .write()
+            .unwrap()
+            .insert(bank)
+            .clone_without_scheduler();
+
+        let mut clock: Clock = bank.clock();
+        clock.unix_timestamp = GENESIS_UNIX_TIMESTAMP;
+        let mut svm = Self {
+            bank,
+            bank_forks,
+            payer,
+            clock,
+            sent: HashSet::new(),
+            units: 0,
+        };
+        svm.sync_clock();
+        svm.set_mint(spl_token::native_mint::ID, 9);
+        svm
+    }
+
+    pub fn now_ms(&self) -> i64 {
+        self.clock.unix_timestamp * 1000
+    }
+
+    /// Moves the clock forward, rounding up to the next whole second since
+    /// the program only sees second resolution.
+    pub fn advance_ms(&mut self, ms: i64) {
+        self.clock.unix_timestamp += (ms + 999) / 1000;
+        self.sync_clock();
+    }
+
+    /// Moves the clock to the first whole second at or after `ms`.
+    pub fn warp_to_ms(&mut self, ms: i64) {
+        let target = (ms + 999) / 1000;
+        assert!(
+            target >= self.clock.unix_timestamp,
+            "the clock can't go back"
+        );
+        self.clock.unix_timestamp = target;
+        self.sync_clock();
+    }
+
+    fn sync_clock(&self) {
+        self.bank.set_sysvar_for_tests(&self.clock);
+    }
+
+    pub fn account(&self, key: &Pubkey) -> Option<Account> {
+        self.bank
+            .get_account(key)
+            .map(Account::from)
+            .filter(|account| account.lamports > 0)
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, account: Account) {
+        self.bank
+            .store_account(&key, &AccountSharedData::from(account));
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| account.lamports)
+    }
+
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let mut account = self.account(key).unwrap_or_else(|| Account {
+            owner: system_program::ID,
+            ..Account::default()
+        });
+        account.lamports += lamports;
+        self.set_account(*key, account);
+    }
+
+    /// Decodes an Anchor account, returning `None` if it doesn't exist.
+    pub fn get<T: AccountDeserialize>(&self, key: &Pubkey) -> Option<T> {
+        let account = self.account(key)?;
+        Some(T::try_deserialize(&mut account.data.as_slice()).expect("decode account"))
+    }
+
+    pub fn set_mint(&mut self, key: Pubkey, decimals: u8) {
+        let mint = spl_token::state::Mint {
+            mint_authority: Some(Pubkey::new_unique()).into(),
+            supply: u64::MAX / 2,
+            decimals,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        self.set_packed(key, spl_token::ID, mint, 0);
+    }
+
+    pub fn set_token_account(&mut self, key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+        let is_native = mint == spl_token::native_mint::ID;
+        let rent = Rent::default().minimum_balance(spl_token::state::Account::LEN);
+        let account = spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            is_native: if is_native {
+                Some(rent).into()
+            } else {
+                None.into()
+            },
+            ..spl_token::state::Account::default()
+        };
+        let wrapped = if is_native { amount } else { 0 };
+        self.set_packed(key, spl_token::ID, account, wrapped);
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        self.account(key).map_or(0, |account| {
+            spl_token::state::Account::unpack(&account.data)
+                .expect("decode token account")
+                .amount
+        })
+    }
+
+    fn set_packed<T: Pack>(&mut self, key: Pubkey, owner: Pubkey, state: T, extra_lamports: u64) {
+        let mut data = vec![0; T::LEN];
+        state.pack_into_slice(&mut data);
+        self.set_account(
+            key,
+            Account {
+                lamports: Rent::default().minimum_balance(T::LEN) + extra_lamports,
+                data,
+                owner,
+                ..Account::default()
+            },
+        );
+    }
+
+    /// Runs `instructions` as one atomic transaction signed by `signers`.
+    pub fn process(&mut self, instructions: &[Instruction], signers: &[Pubkey]) -> TxResult {
+        check_limits(instructions, signers)
+            .and_then(|()| check_signatures(instructions, signers))
+            .map_err(|error| Failure {
+                error,
+                logs: Vec::new(),
+            })?;
+        self.execute(instructions)
+    }
+
+    /// Runs `instructions` with the most compute a transaction can ask for and
+    /// without the packet or account-lock limits, returning the units they
+    /// used. For sizing work that doesn't fit in one transaction yet. A
+    /// failure's instruction index counts the compute budget instruction.
+    pub fn measure(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> Result<u64, Failure> {
+        check_signatures(instructions, signers).map_err(|error| Failure {
+            error,
+            logs: Vec::new(),
+        })?;
+        let budget = ComputeBudgetInstruction::set_compute_unit_limit(MAX_COMPUTE_UNIT_LIMIT);
+        let instructions = [&[budget], instructions].concat();
+        self.execute(&instructions)?;
+        // The budget instruction is charged like any builtin
+        Ok(self.units - COMPUTE_BUDGET_PROGRAM_UNITS)
+    }
+
+    fn execute(&mut self, instructions: &[Instruction]) -> TxResult {
+        let mut message = Message::new(instructions, Some(&self.payer));
+        message.recent_blockhash = self.bank.last_blockhash();
+        if !self.sent.insert(message.hash()) {
+            self.bank.register_unique_recent_blockhash_for_test();
+            self.sent.clear();
+            message.recent_blockhash = self.bank.last_blockhash();
+            self.sent.insert(message.hash());
+        }
+        let signatures = message.header.num_required_signatures as usize;
+        let transaction = Transaction {
+            signatures: vec![Signature::default(); signatures],
+            message,
+        };
+
+        let details = match self.bank.process_transaction_with_metadata(transaction) {
+            TransactionExecutionResult::Executed { details, .. } => details,
+            TransactionExecutionResult::NotExecuted(error) => {
+                return Err(Failure {
+                    error: error.into(),
+                    logs: Vec::new(),
+                })
+            }
+        };
+        self.units = details.executed_units;
+        let logs = details
+            .log_messages
+            .unwrap_or_default()
+            .into_iter()
+            .map(|log| match log.strip_prefix(LOGGED_PROGRAM_DATA) {
+                Some(data) => format!("Program data: {data}"),
+                None => log,
+            })
+            .collect();
+        match details.status {
+            Ok(()) => Ok(logs),
+            Err(error) => Err(Failure {
+                error: error.into(),
+                logs,
+            }),
+        }
+    }
+}
+
+// The checks a validator makes before a transaction reaches the bank
+fn check_limits(instructions: &[Instruction], signers: &[Pubkey]) -> Result<(), TxError> {
+    // The first signer pays, as the test helpers all sign with the payer first
+    let message = Message::new(instructions, signers.first());
+    if message.account_keys.len() > MAX_TX_ACCOUNT_LOCKS {
+        return Err(TxError::TooManyAccountLocks(message.account_keys.len()));
+    }
+    let signatures = message.header.num_required_signatures as usize;
+    let size = short_vec_len(signatures) + 64 * signatures + message.serialize().len();
+    if size > PACKET_DATA_SIZE {
+        return Err(TxError::TooLarge(size));
+    }
+    Ok(())
+}
+
+fn check_signatures(instructions: &[Instruction], signers: &[Pubkey]) -> Result<(), TxError> {
+    for meta in instructions.iter().flat_map(|ix| &ix.accounts) {
+        if meta.is_signer && !signers.contains(&meta.pubkey) {
+            return Err(TxError::MissingSignature(meta.pubkey));
+        }
+    }
+
+    let datas: Vec<&[u8]> = instructions.iter().map(|ix| ix.data.as_slice()).collect();
+    for (index, ix) in instructions.iter().enumerate() {
+        if ix.program_id == ed25519_program::ID {
+            solana_sdk::ed25519_instruction::verify(&ix.data, &datas, &FeatureSet::all_enabled())
+                .map_err(|_| TxError::Precompile { index })?;
+        }
+    }
+    Ok(())
+}
+
+fn short_vec_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+struct NoStubs;
+
+impl SyscallStubs for NoStubs {}
+
+// program-test's stubs, except that `sol_log_data` lands in the logs
+struct LogDataStubs(Box<dyn SyscallStubs>);
+
+impl SyscallStubs for LogDataStubs {
+    fn sol_log(&self, message: &str) {
+        self.0.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.0.sol_log_compute_units()
+    }
+    fn sol_remaining_compute_units(&self) -> u64 {
+        self.0.sol_remaining_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.0
+            .sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_rent_sysvar(var_addr)
+    }
+    fn sol_get_epoch_rewards_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_epoch_rewards_sysvar(var_addr)
+    }
+    fn sol_get_last_restart_slot(&self, var_addr: *mut u8) -> u64 {
+        self.0.sol_get_last_restart_slot(var_addr)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.0.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.0.sol_set_return_data(data)
+    }
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let fields: Vec<String> = fields.iter().map(|field| STANDARD.encode(field)).collect();
+        self.0
+            .sol_log(&format!("Program data: {}", fields.join(" ")))
+    }
+    fn sol_get_processed_sibling_instruction(&self, index: usize) -> Option<Instruction> {
+        self.0.sol_get_processed_sibling_instruction(index)
+    }
+    fn sol_get_stack_height(&self) -> u64 {
+        self.0.sol_get_stack_height()
+    }
+}
@@ -0,0 +1,382 @@
+//! Tournaments: one entry fee, a run of free round games where only the top
+//! finishers move on, and a prize pool for the final round's winners.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::rent::Rent;
+use anchor_lang::system_program;
+use anchor_spl::{associated_token, token};
+use twizzin_be_2::errors::ErrorCode;
+use twizzin_be_2::state::{Tournament, TournamentEntry};
+use twizzin_be_2::utils::fees::calculate_fees;
+use twizzin_be_2::utils::prize::calculate_prizes;
+use twizzin_be_2::{accounts, instruction};
+use twizzin_client::{
+    find_config_pda, find_tournament_entry_pda, find_tournament_pda, find_tournament_vault_pda,
+    instructions,
+};
+
+use crate::setup::*;
+
+const ENTRY_FEE: u64 = SOL;
+const COMMISSION: u16 = 1000;
+pub const REGISTRATION: i64 = 10 * 60 * 1000;
+
+pub struct TestTournament {
+    pub admin: Pubkey,
+    pub address: Pubkey,
+    vault: Pubkey,
+    mint: Pubkey,
+    is_native: bool,
+    treasury: Pubkey,
+}
+
+impl TestTournament {
+    fn token_account(&self, owner: &Pubkey) -> Option<Pubkey> {
+        (!self.is_native).then(|| associated_token::get_associated_token_address(owner, &self.mint))
+    }
+
+    pub fn entry_pda(&self, player: &Pubkey) -> Pubkey {
+        find_tournament_entry_pda(&self.address, player).0
+    }
+
+    fn balance(&self, env: &Env, owner: &Pubkey) -> u64 {
+        match self.token_account(owner) {
+            Some(token_account) => env.svm.token_balance(&token_account),
+            None => env.svm.lamports(owner),
+        }
+    }
+
+    fn pot(&self, env: &Env) -> u64 {
+        self.balance(env, &self.vault)
+    }
+
+    pub fn params(&self) -> GameParams {
+        let params = if self.is_native {
+            GameParams::native()
+        } else {
+            GameParams::spl(self.mint)
+        };
+        GameParams {
+            entry_fee: 0,
+            tournament: Some(self.address),
+            ..params
+        }
+    }
+
+    // Creates a round game, for rounds that are meant to fail
+    pub fn round_ix(&self, env: &Env, params: &GameParams) -> Instruction {
+        TestGame::new(self.admin, params, self.treasury, env.svm.now_ms()).init_ix(params)
+    }
+
+    pub fn join_ix(&self, player: Pubkey) -> Instruction {
+        instructions::join_tournament(
+            accounts::JoinTournament {
+                player,
+                tournament: self.address,
+                tournament_entry: self.entry_pda(&player),
+                tournament_vault: self.vault,
+                tournament_vault_token_account: self.token_account(&self.vault),
+                player_token_account: self.token_account(&player),
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::JoinTournament {},
+        )
+    }
+
+    // Joins a round game, passing the previous round's winners after the first round
+    pub fn join_round_ix(
+        &self,
+        game: &TestGame,
+        player: Pubkey,
+        previous: Option<&TestGame>,
+    ) -> Instruction {
+        game.join_ix(
+            player,
+            JoinOptions {
+                tournament: Some((self.address, self.entry_pda(&player))),
+                previous_winners: previous.map(TestGame::winners_pda),
+                ..JoinOptions::default()
+            },
+        )
+    }
+
+    pub fn settle_ix(&self, final_round: &TestGame) -> Instruction {
+        instructions::settle_tournament(
+            accounts::SettleTournament {
+                admin: self.admin,
+                tournament: self.address,
+                final_winners: final_round.winners_pda(),
+                tournament_vault: self.vault,
+                tournament_vault_token_account: self.token_account(&self.vault),
+                admin_token_account: self.token_account(&self.admin),
+                treasury_token_account: self.token_account(&self.treasury),
+                config: find_config_pda().0,
+                treasury: self.treasury,
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::SettleTournament {},
+            Vec::new(),
+        )
+    }
+
+    pub fn claim_ix(&self, final_round: &TestGame, player: Pubkey) -> Instruction {
+        instructions::claim_tournament_prize(
+            accounts::ClaimTournamentPrize {
+                player,
+                tournament: self.address,
+                tournament_entry: self.entry_pda(&player),
+                final_winners: final_round.winners_pda(),
+                tournament_vault: self.vault,
+                tournament_vault_token_account: self.token_account(&self.vault),
+                player_token_account: self.token_account(&player),
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::ClaimTournamentPrize {},
+        )
+    }
+
+    pub fn close_ix(&self) -> Instruction {
+        instructions::close_tournament(
+            accounts::CloseTournament {
+                admin: self.admin,
+                tournament: self.address,
+                tournament_vault: self.vault,
+                tournament_vault_token_account: self.token_account(&self.vault),
+                admin_token_account: self.token_account(&self.admin),
+                token_program: token::ID,
+                system_program: system_program::ID,
+            },
+            instruction::CloseTournament {},
+        )
+    }
+}
+
+// A tournament and the instruction that creates it, for tournaments that are meant to fail
+pub fn init_tournament(
+    env: &mut Env,
+    mint: Pubkey,
+    num_rounds: u8,
+    commission: u16,
+) -> (TestTournament, Instruction) {
+    let admin = env.wallet();
+    let code = unique_code();
+    let address = find_tournament_pda(&admin, &code).0;
+    let tournament = TestTournament {
+        admin,
+        address,
+        vault: find_tournament_vault_pda(&address).0,
+        mint,
+        is_native: mint == NATIVE_MINT,
+        treasury: env.treasury,
+    };
+    let ix = instructions::init_tournament(
+        accounts::InitTournament {
+            admin,
+            tournament: address,
+            token_mint: mint,
+            tournament_vault: tournament.vault,
+            tournament_vault_token_account: tournament.token_account(&tournament.vault),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitTournament {
+            tournament_code: code,
+            name: "Cup".to_string(),
+            entry_fee: ENTRY_FEE,
+            commission,
+            num_rounds,
+            advance_count: 2,
+            even_split: false,
+            registration_end: env.svm.now_ms() + REGISTRATION,
+        },
+    );
+    (tournament, ix)
+}
+
+pub fn create_tournament(env: &mut Env, mint: Pubkey, num_rounds: u8) -> TestTournament {
+    let (tournament, ix) = init_tournament(env, mint, num_rounds, COMMISSION);
+    env.send(ix, tournament.admin).unwrap();
+    tournament
+}
+
+pub fn entrant(env: &mut Env, tournament: &TestTournament) -> Pubkey {
+    let player = env.wallet();
+    if !tournament.is_native {
+        env.fund_tokens(&player, &tournament.mint, 10 * SOL);
+    }
+    env.send(tournament.join_ix(player), player).unwrap();
+    player
+}
+
+fn tournament_pays_the_final(native: bool) {
+    let mut env = Env::new();
+    let mint = if native { NATIVE_MINT } else { env.mint() };
+    let tournament = create_tournament(&mut env, mint, 2);
+    let players: Vec<Pubkey> = (0..3).map(|_| entrant(&mut env, &tournament)).collect();
+    let (ann, ben, cat) = (players[0], players[1], players[2]);
+    let stored: Tournament = env.svm.get(&tournament.address).unwrap();
+    assert_eq!(stored.total_players, 3);
+    assert_eq!(tournament.pot(&env), 3 * ENTRY_FEE);
+
+    let first = env.create_game_as(tournament.admin, tournament.params());
+
+    // Rounds are free - the entry fee was paid once
+    let paid_round = GameParams {
+        entry_fee: 1,
+        ..tournament.params()
+    };
+    let result = env.send(tournament.round_ix(&env, &paid_round), tournament.admin);
+    assert_error(result, ErrorCode::TournamentRoundEntryFee);
+
+    let outsider = env.wallet();
+    assert_error(
+        env.send(first.join_ix(outsider, JoinOptions::default()), outsider),
+        ErrorCode::TournamentAccountsNotProvided,
+    );
+    for player in &players {
+        env.send(tournament.join_round_ix(&first, *player, None), *player)
+            .unwrap();
+    }
+    let advanced = env.play(
+        &first,
+        &[
+            (ann, &ANSWERS),
+            (ben, &["a", "b", "x"]),
+            (cat, &["x", "x", "x"]),
+        ],
+    );
+    assert_eq!(advanced, vec![ann, ben]);
+
+    // Settling before the last round exists is too early
+    let result = env.send(tournament.settle_ix(&first), tournament.admin);
+    assert_error(result, ErrorCode::TournamentNotComplete);
+
+    let last = env.create_game_as(tournament.admin, tournament.params());
+    assert_error(
+        env.send(tournament.join_round_ix(&last, cat, Some(&first)), cat),
+        ErrorCode::NotAdvancedToRound,
+    );
+    for player in [ann, ben] {
+        env.send(
+            tournament.join_round_ix(&last, player, Some(&first)),
+            player,
+        )
+        .unwrap();
+    }
+    let entry: TournamentEntry = env.svm.get(&tournament.entry_pda(&ann)).unwrap();
+    assert_eq!(entry.rounds_joined, 2);
+    let result = env.send(
+        tournament.round_ix(&env, &tournament.params()),
+        tournament.admin,
+    );
+    assert_error(result, ErrorCode::TournamentFull);
+
+    let winners = env.play(&last, &[(ben, &ANSWERS), (ann, &["a", "x", "x"])]);
+    assert_eq!(winners, vec![ben, ann]);
+    assert_error(
+        env.send(tournament.claim_ix(&last, ben), ben),
+        ErrorCode::TournamentNotSettled,
+    );
+
+    let pot = tournament.pot(&env);
+    let held_back = if native {
+        Rent::default().minimum_balance(0)
+    } else {
+        0
+    };
+    let (treasury_fee, commission) =
+        calculate_fees(pot, TREASURY_FEE_BPS, COMMISSION, held_back, native).unwrap();
+    let treasury_before = tournament.balance(&env, &tournament.treasury);
+    let admin_before = tournament.balance(&env, &tournament.admin);
+    env.send(tournament.settle_ix(&last), tournament.admin)
+        .unwrap();
+    assert_eq!(
+        tournament.balance(&env, &tournament.treasury),
+        treasury_before + treasury_fee
+    );
+    assert_eq!(
+        tournament.balance(&env, &tournament.admin),
+        admin_before + commission
+    );
+    let prize_pool = pot - held_back - treasury_fee - commission;
+    let stored: Tournament = env.svm.get(&tournament.address).unwrap();
+    assert_eq!(stored.prize_pool, prize_pool);
+    assert_error(
+        env.send(tournament.settle_ix(&last), tournament.admin),
+        ErrorCode::TournamentSettled,
+    );
+
+    let prizes = calculate_prizes(prize_pool, 2, false, native, 0).unwrap();
+    for (player, prize) in [ben, ann].into_iter().zip(prizes.iter().copied()) {
+        // The tournament stays open until every winner has been paid
+        assert_error(
+            env.send(tournament.close_ix(), tournament.admin),
+            ErrorCode::UnclaimedPrizes,
+        );
+        let before = tournament.balance(&env, &player);
+        env.send(tournament.claim_ix(&last, player), player)
+            .unwrap();
+        assert_eq!(tournament.balance(&env, &player), before + prize);
+    }
+    let dust = prize_pool - prizes.iter().sum::<u64>();
+    assert_eq!(tournament.pot(&env), held_back + dust);
+    assert_error(
+        env.send(tournament.claim_ix(&last, ben), ben),
+        ErrorCode::PrizeAlreadyClaimed,
+    );
+    assert_error(
+        env.send(tournament.claim_ix(&last, cat), cat),
+        ErrorCode::NotAWinner,
+    );
+
+    // Closing hands the admin the vault's rent and whatever the split left over
+    let mut reclaimable = [tournament.address, tournament.vault]
+        .iter()
+        .map(|account| env.svm.lamports(account))
+        .sum::<u64>();
+    if let Some(vault_token_account) = tournament.token_account(&tournament.vault) {
+        reclaimable += env.svm.lamports(&vault_token_account);
+    }
+    let tokens_before = tournament
+        .token_account(&tournament.admin)
+        .map(|admin_token_account| env.svm.token_balance(&admin_token_account));
+    let before = env.svm.lamports(&tournament.admin);
+    env.send(tournament.close_ix(), tournament.admin).unwrap();
+    assert_eq!(env.svm.lamports(&tournament.admin), before + reclaimable);
+    if let Some(tokens_before) = tokens_before {
+        let admin_token_account = tournament.token_account(&tournament.admin).unwrap();
+        assert_eq!(
+            env.svm.token_balance(&admin_token_account),
+            tokens_before + dust
+        );
+    }
+    assert!(env.svm.account(&tournament.address).is_none());
+    assert_eq!(env.svm.lamports(&tournament.vault), 0);
+}
+
+#[test]
+fn native_tournament() {
+    tournament_pays_the_final(true);
+}
+
+#[test]
+fn spl_tournament() {
+    tournament_pays_the_final(false);
+}
+
+#[test]
+fn registration_closes() {
+    let mut env = Env::new();
+    let tournament = create_tournament(&mut env, NATIVE_MINT, 1);
+    env.svm.advance_ms(REGISTRATION);
+    let player = env.wallet();
+    assert_error(
+        env.send(tournament.join_ix(player), player),
+        ErrorCode::TournamentRegistrationClosed,
+    );
+}
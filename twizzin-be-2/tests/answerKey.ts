@@ -0,0 +1,256 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { AnswerType, findGameCodeRegistryPda, MerkleTree } from './utils';
+import { expect } from 'chai';
+import { PublicKey, LAMPORTS_PER_SOL, SystemProgram } from '@solana/web3.js';
+import { NATIVE_MINT } from '@solana/spl-token';
+
+export async function answerKey(
+  program: Program<TwizzinBe2>,
+  provider: anchor.AnchorProvider,
+  confirm: (signature: string) => Promise<string>
+) {
+  console.log('Starting answer key tests');
+
+  // Helper function for error checking
+  const expectError = (error: any, errorTypes: string[]) => {
+    const errorString = error.toString();
+    const hasExpectedError = errorTypes.some((type) =>
+      errorString.includes(type)
+    );
+    expect(
+      hasExpectedError,
+      `Expected one of [${errorTypes}] but got: ${errorString}`
+    ).to.be.true;
+  };
+
+  const admin = provider.wallet.publicKey;
+  const now = Date.now();
+  const gameCode = 'KEY' + (now % 10_000);
+  const endTime = now + 10_000;
+
+  const choice: AnswerType = { choice: {} };
+  const key = [
+    { displayOrder: 0, answer: 'a', questionId: 'q1', points: 1 },
+    { displayOrder: 1, answer: 'b', questionId: 'q2', points: 2 },
+    { displayOrder: 2, answer: 'c', questionId: 'q3', points: 3 },
+  ].map((entry) => ({ ...entry, answerType: choice }));
+  const tree = new MerkleTree(key);
+  const toBytes = (buffer: Buffer) => Array.from(new Uint8Array(buffer));
+
+  const [gamePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('game'), admin.toBuffer(), Buffer.from(gameCode)],
+    program.programId
+  );
+  const [vaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('vault'), admin.toBuffer(), Buffer.from(gameCode)],
+    program.programId
+  );
+  const [answerKeyPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('answer_key'), gamePda.toBuffer()],
+    program.programId
+  );
+
+  await confirm(
+    await program.methods
+      .initGame(
+        'Answer Key Game',
+        gameCode,
+        new anchor.BN(0),
+        0,
+        new anchor.BN(now - 120_000),
+        new anchor.BN(endTime),
+        3,
+        toBytes(tree.getRoot()),
+        new anchor.BN(0),
+        false,
+        false,
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
+      )
+      .accounts({
+        admin,
+        game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
+        tokenMint: NATIVE_MINT,
+        vault: vaultPda,
+        adminTokenAccount: null,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+  );
+
+  // Player gets the last question wrong
+  const player = anchor.web3.Keypair.generate();
+  await confirm(
+    await provider.connection.requestAirdrop(player.publicKey, LAMPORTS_PER_SOL)
+  );
+  const [playerPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('player'), gamePda.toBuffer(), player.publicKey.toBuffer()],
+    program.programId
+  );
+  await confirm(
+    await program.methods
+      .joinGame(null, null, null)
+      .accounts({
+        player: player.publicKey,
+        game: gamePda,
+        playerAccount: playerPda,
+        vault: vaultPda,
+        playerTokenAccount: null,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc()
+  );
+
+  const submitted = [
+    { displayOrder: 0, answer: 'a' },
+    { displayOrder: 1, answer: 'b' },
+    { displayOrder: 2, answer: 'a' },
+  ];
+  await confirm(
+    await program.methods
+      .submitAnswers(
+        submitted.map((answer, i) => ({
+          ...answer,
+          answerType: key[i].answerType,
+          questionId: key[i].questionId,
+          points: key[i].points,
+          answeredAt: new anchor.BN(0),
+          proof: tree.getProof(i).map(toBytes),
+        })),
+        new anchor.BN(Date.now() - 1000)
+      )
+      .accounts({
+        player: player.publicKey,
+        game: gamePda,
+        playerAccount: playerPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc()
+  );
+
+  const revealKey = (entries: typeof key) =>
+    program.methods
+      .revealAnswerKey(entries)
+      .accounts({
+        admin,
+        game: gamePda,
+        answerKey: answerKeyPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const [submissionPda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('submission'),
+      gamePda.toBuffer(),
+      player.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+
+  // Test 1: The key can't be revealed while the game is running
+  console.log('Testing reveal before the game ends...');
+  try {
+    await revealKey(key);
+    throw new Error('Should have failed with game not ended');
+  } catch (error) {
+    expectError(error, ['GameNotEnded']);
+    console.log('Early reveal test passed');
+  }
+
+  await new Promise((resolve) =>
+    setTimeout(resolve, Math.max(0, endTime - Date.now()) + 2000)
+  );
+
+  // Test 2: A key that doesn't rebuild the committed root is rejected
+  console.log('Testing reveal with a tampered key...');
+  try {
+    const tampered = key.map((entry) => ({ ...entry }));
+    tampered[2].answer = 'a';
+    await revealKey(tampered);
+    throw new Error('Should have failed with answer key mismatch');
+  } catch (error) {
+    expectError(error, ['AnswerKeyMismatch']);
+    console.log('Tampered key test passed');
+  }
+
+  // Test 3: The real key is stored on-chain
+  console.log('Testing answer key reveal...');
+  try {
+    await confirm(await revealKey(key));
+
+    const stored = await program.account.answerKey.fetch(answerKeyPda);
+    expect(stored.game.toString()).to.equal(gamePda.toString());
+    expect(stored.entries.map((entry) => entry.answer)).to.deep.equal([
+      'a',
+      'b',
+      'c',
+    ]);
+    console.log('Answer key reveal test passed');
+  } catch (error) {
+    console.error('Answer key reveal test failed:', error);
+    throw error;
+  }
+
+  // Test 4: The submission is stored with per-question results
+  console.log('Testing stored submission...');
+  try {
+    const submission = await program.account.submission.fetch(submissionPda);
+    expect(submission.answers.map((answer) => answer.answer)).to.deep.equal([
+      'a',
+      'b',
+      'a',
+    ]);
+    expect(
+      submission.answers.map((answer) => answer.isCorrect)
+    ).to.deep.equal([true, true, false]);
+    console.log('Stored submission test passed');
+  } catch (error) {
+    console.error('Stored submission test failed:', error);
+    throw error;
+  }
+
+  // Test 5: Anyone can re-score from the stored key and submission
+  console.log('Testing rescore...');
+  try {
+    const stranger = anchor.web3.Keypair.generate();
+    await confirm(
+      await program.methods
+        .rescorePlayer()
+        .accounts({
+          signer: stranger.publicKey,
+          game: gamePda,
+          answerKey: answerKeyPda,
+          playerAccount: playerPda,
+          submission: submissionPda,
+        })
+        .signers([stranger])
+        .rpc()
+    );
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.numCorrect).to.equal(2);
+    expect(playerAccount.score).to.equal(3);
+    console.log('Rescore test passed');
+  } catch (error) {
+    console.error('Rescore test failed:', error);
+    throw error;
+  }
+
+  console.log('All answer key tests completed successfully');
+}
@@ -0,0 +1,236 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { AnswerType, findGameCodeRegistryPda, MerkleTree } from './utils';
+import { expect } from 'chai';
+import { PublicKey, LAMPORTS_PER_SOL, SystemProgram } from '@solana/web3.js';
+import { NATIVE_MINT } from '@solana/spl-token';
+
+export async function chunkedSubmission(
+  program: Program<TwizzinBe2>,
+  provider: anchor.AnchorProvider,
+  confirm: (signature: string) => Promise<string>
+) {
+  console.log('Starting chunked submission tests');
+
+  // Helper function for error checking
+  const expectError = (error: any, errorTypes: string[]) => {
+    const errorString = error.toString();
+    const hasExpectedError = errorTypes.some((type) =>
+      errorString.includes(type)
+    );
+    expect(
+      hasExpectedError,
+      `Expected one of [${errorTypes}] but got: ${errorString}`
+    ).to.be.true;
+  };
+
+  const admin = provider.wallet.publicKey;
+  const now = Date.now();
+  const gameCode = 'CHUNK' + (now % 10_000);
+
+  // More questions than fit in a single submit_answers transaction
+  const NUM_QUESTIONS = 100;
+  const CHUNK_SIZE = 3;
+  const choice: AnswerType = { choice: {} };
+  const key = Array.from({ length: NUM_QUESTIONS }, (_, i) => ({
+    displayOrder: i,
+    answerType: choice,
+    answer: 'a',
+    questionId: `q${i}`,
+    points: 1,
+  }));
+  const tree = new MerkleTree(key, 2);
+  const toBytes = (buffer: Buffer) => Array.from(new Uint8Array(buffer));
+
+  const [gamePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('game'), admin.toBuffer(), Buffer.from(gameCode)],
+    program.programId
+  );
+  const [vaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('vault'), admin.toBuffer(), Buffer.from(gameCode)],
+    program.programId
+  );
+
+  await confirm(
+    await program.methods
+      .initGame(
+        'Chunked Game',
+        gameCode,
+        new anchor.BN(0),
+        0,
+        new anchor.BN(now - 120_000),
+        new anchor.BN(now + 3_600_000),
+        3,
+        toBytes(tree.getRoot()),
+        new anchor.BN(0),
+        false,
+        false,
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        2
+      )
+      .accounts({
+        admin,
+        game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
+        tokenMint: NATIVE_MINT,
+        vault: vaultPda,
+        adminTokenAccount: null,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+  );
+
+  const player = anchor.web3.Keypair.generate();
+  await confirm(
+    await provider.connection.requestAirdrop(player.publicKey, LAMPORTS_PER_SOL)
+  );
+  const [playerPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('player'), gamePda.toBuffer(), player.publicKey.toBuffer()],
+    program.programId
+  );
+  const [submissionPda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('submission'),
+      gamePda.toBuffer(),
+      player.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+  await confirm(
+    await program.methods
+      .joinGame(null, null, null)
+      .accounts({
+        player: player.publicKey,
+        game: gamePda,
+        playerAccount: playerPda,
+        vault: vaultPda,
+        playerTokenAccount: null,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc()
+  );
+
+  // Every answer is right except the last question's
+  const answerInput = (i: number) => ({
+    displayOrder: i,
+    answerType: choice,
+    answer: i === NUM_QUESTIONS - 1 ? 'b' : 'a',
+    questionId: key[i].questionId,
+    points: 1,
+    answeredAt: new anchor.BN(0),
+    proof: tree.getProof(i).map(toBytes),
+  });
+
+  const submitChunk = (indices: number[]) =>
+    program.methods
+      .submitAnswerChunk(indices.map(answerInput))
+      .accounts({
+        player: player.publicKey,
+        game: gamePda,
+        playerAccount: playerPda,
+        submission: submissionPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+
+  const finalize = (clientFinishTime: anchor.BN) =>
+    program.methods
+      .finalizeSubmission(clientFinishTime)
+      .accounts({
+        player: player.publicKey,
+        game: gamePda,
+        playerAccount: playerPda,
+        submission: submissionPda,
+        profile: null,
+      })
+      .signers([player])
+      .rpc();
+
+  // Test 1: Nothing to finalize before the first chunk
+  console.log('Testing finalize without answers...');
+  try {
+    await finalize(new anchor.BN(Date.now() - 1000));
+    throw new Error('Should have failed without a submission');
+  } catch (error) {
+    expectError(error, ['AccountNotInitialized', 'NoAnswersSubmitted']);
+    console.log('Finalize without answers test passed');
+  }
+
+  // Test 2: Chunks build up the score
+  console.log('Testing answer chunks...');
+  try {
+    for (let start = 0; start < NUM_QUESTIONS; start += CHUNK_SIZE) {
+      const end = Math.min(start + CHUNK_SIZE, NUM_QUESTIONS);
+      const indices = Array.from({ length: end - start }, (_, i) => start + i);
+      await confirm(await submitChunk(indices));
+    }
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.numCorrect).to.equal(NUM_QUESTIONS - 1);
+    expect(playerAccount.score).to.equal(NUM_QUESTIONS - 1);
+    expect(playerAccount.finishedTime.toNumber()).to.equal(0);
+    console.log('Answer chunks test passed');
+  } catch (error) {
+    console.error('Answer chunks test failed:', error);
+    throw error;
+  }
+
+  // Test 3: Answering a question again doesn't count it twice
+  console.log('Testing repeated answers...');
+  try {
+    await confirm(await submitChunk([0, 1]));
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.numCorrect).to.equal(NUM_QUESTIONS - 1);
+    console.log('Repeated answers test passed');
+  } catch (error) {
+    console.error('Repeated answers test failed:', error);
+    throw error;
+  }
+
+  // Test 4: Finalizing locks in the finish time and the whole submission
+  console.log('Testing finalize...');
+  try {
+    const clientFinishTime = new anchor.BN(Date.now() - 1000);
+    await confirm(await finalize(clientFinishTime));
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.finishedTime.toString()).to.equal(
+      clientFinishTime.toString()
+    );
+    const submission = await program.account.submission.fetch(submissionPda);
+    expect(submission.answers.length).to.equal(NUM_QUESTIONS + 2);
+    expect(
+      submission.answers.filter((answer) => answer.isCorrect).length
+    ).to.equal(NUM_QUESTIONS - 1);
+    console.log('Finalize test passed');
+  } catch (error) {
+    console.error('Finalize test failed:', error);
+    throw error;
+  }
+
+  // Test 5: No more chunks once the submission is final
+  console.log('Testing chunk after finalize...');
+  try {
+    await submitChunk([0]);
+    throw new Error('Should have failed with already submitted');
+  } catch (error) {
+    expectError(error, ['AlreadySubmitted']);
+    console.log('Chunk after finalize test passed');
+  }
+
+  console.log('All chunked submission tests completed successfully');
+}
@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect, assert } from 'chai';
 import {
   SystemProgram,
@@ -60,7 +61,7 @@ export async function claim(
     try {
       const account = await program.account.playerAccount.fetch(pda);
       return {
-        numCorrect: account.numCorrect,
+        score: account.score,
         finishedTime: account.finishedTime,
         player: account.player,
       };
@@ -84,7 +85,7 @@ export async function claim(
           return {
             pubkey: winnerPubkeys[index],
             pda,
-            numCorrect: account.numCorrect,
+            score: account.score,
             finishedTime: account.finishedTime,
           };
         } catch (error) {
@@ -100,8 +101,8 @@ export async function claim(
 
     playerAccounts.sort((a, b) => {
       if (!a || !b) return 0;
-      if (a.numCorrect !== b.numCorrect) {
-        return b.numCorrect - a.numCorrect;
+      if (a.score !== b.score) {
+        return b.score - a.score;
       }
       if (a.finishedTime.eq(b.finishedTime)) {
         return a.pubkey.toBuffer().compare(b.pubkey.toBuffer());
@@ -129,7 +130,7 @@ export async function claim(
       const tx = await program.methods
         .declareWinners(sortedWinners)
         .accounts({
-          admin: provider.wallet.publicKey,
+          authority: provider.wallet.publicKey,
           game: gamePda,
           vault: vaultPda,
           vaultTokenAccount: isNative ? null : vaultTokenAccount,
@@ -181,11 +182,20 @@ export async function claim(
       answerHash,
       donationAmount,
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode1, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda,
       vaultTokenAccount: null,
@@ -216,7 +226,7 @@ export async function claim(
     playerPDAs.push(playerPda);
 
     await program.methods
-      .joinGame()
+      .joinGame(null, null, null)
       .accounts({
         player: player.publicKey,
         game: gamePda,
@@ -235,8 +245,11 @@ export async function claim(
     for (let j = 0; j < i + 1; j++) {
       answers.push({
         displayOrder: j + 1,
+        answerType: { choice: {} },
         answer: 'test',
         questionId: 'test',
+        points: 1,
+        answeredAt: new anchor.BN(0),
         proof: [],
       });
     }
@@ -260,6 +273,7 @@ export async function claim(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda,
       vault: vaultPda,
@@ -379,11 +393,20 @@ export async function claim(
       answerHash,
       donationAmount,
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda2,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode2, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda2,
       vaultTokenAccount: null,
@@ -412,7 +435,7 @@ export async function claim(
     playerPDAs2.push(playerPda);
 
     await program.methods
-      .joinGame()
+      .joinGame(null, null, null)
       .accounts({
         player: player.publicKey,
         game: gamePda2,
@@ -436,8 +459,11 @@ export async function claim(
         [
           {
             displayOrder: 1,
+            answerType: { choice: {} },
             answer: 'test',
             questionId: 'test',
+            points: 1,
+            answeredAt: new anchor.BN(0),
             proof: [],
           },
         ],
@@ -456,6 +482,7 @@ export async function claim(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda2,
       vault: vaultPda2,
@@ -556,11 +583,20 @@ export async function claim(
       answerHash,
       donationAmount,
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda3,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode3, program.programId),
       tokenMint: mint,
       vault: vaultPda3,
       vaultTokenAccount: vaultTokenAccount.address,
@@ -619,7 +655,7 @@ export async function claim(
     playerTokenAccounts.push(playerTokenAccount);
 
     await program.methods
-      .joinGame()
+      .joinGame(null, null, null)
       .accounts({
         player: player.publicKey,
         game: gamePda3,
@@ -644,8 +680,11 @@ export async function claim(
         [
           {
             displayOrder: 1,
+            answerType: { choice: {} },
             answer: 'test',
             questionId: 'test',
+            points: 1,
+            answeredAt: new anchor.BN(0),
             proof: [],
           },
         ],
@@ -672,6 +711,7 @@ export async function claim(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda3,
       vault: vaultPda3,
@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect, assert } from 'chai';
 import {
   SystemProgram,
@@ -60,7 +61,7 @@ export async function closeGame(
     try {
       const account = await program.account.playerAccount.fetch(pda);
       return {
-        numCorrect: account.numCorrect,
+        score: account.score,
         finishedTime: account.finishedTime,
         player: account.player,
       };
@@ -84,7 +85,7 @@ export async function closeGame(
           return {
             pubkey: winnerPubkeys[index],
             pda,
-            numCorrect: account.numCorrect,
+            score: account.score,
             finishedTime: account.finishedTime,
           };
         } catch (error) {
@@ -100,8 +101,8 @@ export async function closeGame(
 
     playerAccounts.sort((a, b) => {
       if (!a || !b) return 0;
-      if (a.numCorrect !== b.numCorrect) {
-        return b.numCorrect - a.numCorrect;
+      if (a.score !== b.score) {
+        return b.score - a.score;
       }
       if (a.finishedTime.eq(b.finishedTime)) {
         return a.pubkey.toBuffer().compare(b.pubkey.toBuffer());
@@ -129,7 +130,7 @@ export async function closeGame(
       const tx = await program.methods
         .declareWinners(sortedWinners)
         .accounts({
-          admin: provider.wallet.publicKey,
+          authority: provider.wallet.publicKey,
           game: gamePda,
           vault: vaultPda,
           vaultTokenAccount: isNative ? null : vaultTokenAccount,
@@ -181,11 +182,20 @@ export async function closeGame(
       answerHash,
       donationAmount,
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode1, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda,
       vaultTokenAccount: null,
@@ -215,7 +225,7 @@ export async function closeGame(
     playerPDAs.push(playerPda);
 
     await program.methods
-      .joinGame()
+      .joinGame(null, null, null)
       .accounts({
         player: player.publicKey,
         game: gamePda,
@@ -234,8 +244,11 @@ export async function closeGame(
     for (let j = 0; j < i + 1; j++) {
       answers.push({
         displayOrder: j + 1,
+        answerType: { choice: {} },
         answer: 'test',
         questionId: 'test',
+        points: 1,
+        answeredAt: new anchor.BN(0),
         proof: [],
       });
     }
@@ -259,6 +272,7 @@ export async function closeGame(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda,
       vault: vaultPda,
@@ -377,11 +391,20 @@ export async function closeGame(
       answerHash,
       donationAmount,
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda2,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode2, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda2,
       vaultTokenAccount: null,
@@ -410,7 +433,7 @@ export async function closeGame(
     playerPDAs2.push(playerPda);
 
     await program.methods
-      .joinGame()
+      .joinGame(null, null, null)
       .accounts({
         player: player.publicKey,
         game: gamePda2,
@@ -434,8 +457,11 @@ export async function closeGame(
         [
           {
             displayOrder: 1,
+            answerType: { choice: {} },
             answer: 'test',
             questionId: 'test',
+            points: 1,
+            answeredAt: new anchor.BN(0),
             proof: [],
           },
         ],
@@ -454,6 +480,7 @@ export async function closeGame(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda2,
       vault: vaultPda2,
@@ -555,11 +582,20 @@ export async function closeGame(
       answerHash,
       donationAmount,
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda3,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode3, program.programId),
       tokenMint: mint,
       vault: vaultPda3,
       vaultTokenAccount: vaultTokenAccount.address,
@@ -618,7 +654,7 @@ export async function closeGame(
     playerTokenAccounts.push(playerTokenAccount);
 
     await program.methods
-      .joinGame()
+      .joinGame(null, null, null)
       .accounts({
         player: player.publicKey,
         game: gamePda3,
@@ -643,8 +679,11 @@ export async function closeGame(
         [
           {
             displayOrder: 1,
+            answerType: { choice: {} },
             answer: 'test',
             questionId: 'test',
+            points: 1,
+            answeredAt: new anchor.BN(0),
             proof: [],
           },
         ],
@@ -671,6 +710,7 @@ export async function closeGame(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda3,
       vault: vaultPda3,
@@ -757,11 +797,20 @@ export async function closeGame(
       answerHash,
       donationAmount,
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda4,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode4, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda4,
       vaultTokenAccount: null,
@@ -785,7 +834,7 @@ export async function closeGame(
   const playerPda4 = findPlayerPDA(gamePda4, player4.publicKey);
 
   await program.methods
-    .joinGame()
+    .joinGame(null, null, null)
     .accounts({
       player: player4.publicKey,
       game: gamePda4,
@@ -809,8 +858,11 @@ export async function closeGame(
       [
         {
           displayOrder: 1,
+          answerType: { choice: {} },
           answer: 'test',
           questionId: 'test',
+          points: 1,
+          answeredAt: new anchor.BN(0),
           proof: [],
         },
       ],
@@ -828,6 +880,7 @@ export async function closeGame(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda4,
       vault: vaultPda4,
@@ -858,6 +911,7 @@ export async function closeGame(
       .accounts({
         admin: provider.wallet.publicKey,
         game: gamePda4,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode4, program.programId),
         winners: winnersPda4,
         vault: vaultPda4,
         vaultTokenAccount: null,
@@ -895,6 +949,7 @@ export async function closeGame(
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda4,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode4, program.programId),
       winners: winnersPda4,
       vault: vaultPda4,
       vaultTokenAccount: null,
@@ -911,6 +966,7 @@ export async function closeGame(
       .accounts({
         admin: provider.wallet.publicKey,
         game: gamePda4,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode4, program.programId),
         winners: winnersPda4,
         vault: vaultPda4,
         vaultTokenAccount: null,
@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect, assert } from 'chai';
 import {
   SystemProgram,
@@ -54,7 +55,7 @@ export async function closePlayerAccount(
     await program.methods
       .declareWinners(winnerPubkeys)
       .accounts({
-        admin: provider.wallet.publicKey,
+        authority: provider.wallet.publicKey,
         game: gamePda,
         vault: vaultPda,
         vaultTokenAccount: isNative ? null : vaultTokenAccount,
@@ -112,11 +113,20 @@ export async function closePlayerAccount(
       Array(32).fill(1),
       new anchor.BN(1 * LAMPORTS_PER_SOL),
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda,
       vaultTokenAccount: null,
@@ -156,7 +166,7 @@ export async function closePlayerAccount(
   );
 
   await program.methods
-    .joinGame()
+    .joinGame(null, null, null)
     .accounts({
       player: player.publicKey,
       game: gamePda,
@@ -172,7 +182,7 @@ export async function closePlayerAccount(
     .rpc();
 
   await program.methods
-    .joinGame()
+    .joinGame(null, null, null)
     .accounts({
       player: winningPlayer.publicKey,
       game: gamePda,
@@ -217,8 +227,11 @@ export async function closePlayerAccount(
       [
         {
           displayOrder: 1,
+          answerType: { choice: {} },
           answer: 'test',
           questionId: 'test',
+          points: 1,
+          answeredAt: new anchor.BN(0),
           proof: [],
         },
       ],
@@ -238,8 +251,11 @@ export async function closePlayerAccount(
       [
         {
           displayOrder: 1,
+          answerType: { choice: {} },
           answer: 'test',
           questionId: 'test',
+          points: 1,
+          answeredAt: new anchor.BN(0),
           proof: [],
         },
       ],
@@ -258,6 +274,7 @@ export async function closePlayerAccount(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda,
       vault: vaultPda,
@@ -315,6 +332,20 @@ export async function closePlayerAccount(
     expect(error.message).to.include('Account does not exist');
   }
 
+  // The stored submission is closed along with the player account
+  const [submissionPda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('submission'),
+      gamePda.toBuffer(),
+      player.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+  const submissionInfo = await provider.connection.getAccountInfo(
+    submissionPda
+  );
+  expect(submissionInfo).to.be.null;
+
   console.log('\nTest 4: Winner Account Before Claiming');
   console.log('Declaring winners...');
 
@@ -0,0 +1,208 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { expect } from 'chai';
+import { SystemProgram, PublicKey, LAMPORTS_PER_SOL } from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
+} from '@solana/spl-token';
+
+export async function createSeason(
+  program: Program<TwizzinBe2>,
+  provider: anchor.AnchorProvider,
+  confirm: (signature: string) => Promise<string>,
+  authorityKeypair: anchor.web3.Keypair
+) {
+  console.log('Starting create season tests');
+
+  // Helper function for error checking
+  const expectError = (error: any, errorTypes: string[]) => {
+    const errorString = error.toString();
+    const hasExpectedError = errorTypes.some((type) =>
+      errorString.includes(type)
+    );
+    expect(
+      hasExpectedError,
+      `Expected one of [${errorTypes}] but got: ${errorString}`
+    ).to.be.true;
+  };
+
+  const deriveSeason = (seasonId: number) => {
+    const idBuffer = Buffer.alloc(4);
+    idBuffer.writeUInt32LE(seasonId);
+    const [seasonPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('season'), idBuffer],
+      program.programId
+    );
+    const [seasonVaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('season_vault'), seasonPda.toBuffer()],
+      program.programId
+    );
+    return { seasonPda, seasonVaultPda };
+  };
+
+  const executeCreateSeason = (
+    seasonId: number,
+    startTime: number,
+    endTime: number,
+    numWinners: number,
+    prizePool: number,
+    authority: anchor.web3.Keypair = authorityKeypair
+  ) => {
+    const { seasonPda, seasonVaultPda } = deriveSeason(seasonId);
+    return program.methods
+      .createSeason(
+        seasonId,
+        'Test Season',
+        new anchor.BN(startTime),
+        new anchor.BN(endTime),
+        numWinners,
+        false,
+        10,
+        new anchor.BN(prizePool)
+      )
+      .accounts({
+        authority: authority.publicKey,
+        season: seasonPda,
+        tokenMint: NATIVE_MINT,
+        seasonVault: seasonVaultPda,
+        seasonVaultTokenAccount: null,
+        authorityTokenAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc();
+  };
+
+  const now = Date.now();
+  const prizePool = 0.1 * LAMPORTS_PER_SOL;
+  const seasonId = Math.floor(Math.random() * 1_000_000);
+
+  // Test 1: Fail to create a season with a non-authority signer
+  console.log('Testing unauthorized season creation...');
+  try {
+    const nonAuthority = anchor.web3.Keypair.generate();
+    await confirm(
+      await provider.connection.requestAirdrop(
+        nonAuthority.publicKey,
+        0.5 * LAMPORTS_PER_SOL
+      )
+    );
+    await executeCreateSeason(
+      seasonId,
+      now,
+      now + 86_400_000,
+      3,
+      prizePool,
+      nonAuthority
+    );
+    throw new Error('Should have failed with invalid authority');
+  } catch (error) {
+    expectError(error, ['InvalidAuthority', 'Invalid authority']);
+    console.log('Unauthorized season creation test passed');
+  }
+
+  // Test 2: Fail to create a season that ends before it starts
+  console.log('Testing invalid season time range...');
+  try {
+    await executeCreateSeason(seasonId, now, now - 1000, 3, prizePool);
+    throw new Error('Should have failed with invalid time range');
+  } catch (error) {
+    expectError(error, [
+      'InvalidTimeRange',
+      'Start time is greater than end time',
+    ]);
+    console.log('Invalid time range test passed');
+  }
+
+  // Test 3: Successfully create a season funded with SOL
+  console.log('Testing valid season creation...');
+  try {
+    const { seasonPda, seasonVaultPda } = deriveSeason(seasonId);
+    await confirm(
+      await executeCreateSeason(
+        seasonId,
+        now,
+        now + 86_400_000,
+        3,
+        prizePool
+      )
+    );
+
+    const season = await program.account.season.fetch(seasonPda);
+    expect(season.seasonId).to.equal(seasonId);
+    expect(season.name).to.equal('Test Season');
+    expect(season.isNative).to.be.true;
+    expect(season.numWinners).to.equal(3);
+    expect(season.numGames).to.equal(0);
+    expect(season.settled).to.be.false;
+    expect(season.leaderboard.length).to.equal(0);
+    expect(season.prizePool.toNumber()).to.equal(prizePool);
+
+    const vaultBalance = await provider.connection.getBalance(seasonVaultPda);
+    expect(vaultBalance).to.equal(prizePool);
+
+    console.log('Valid season creation test passed');
+  } catch (error) {
+    console.error('Valid season creation test failed:', error);
+    throw error;
+  }
+
+  // Test 4: Fail to settle a season before it ends
+  console.log('Testing early season settlement...');
+  try {
+    const { seasonPda, seasonVaultPda } = deriveSeason(seasonId);
+    await program.methods
+      .settleSeason()
+      .accounts({
+        payer: provider.wallet.publicKey,
+        season: seasonPda,
+        seasonVault: seasonVaultPda,
+        seasonVaultTokenAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    throw new Error('Should have failed settling an active season');
+  } catch (error) {
+    expectError(error, ['SeasonNotEnded']);
+    console.log('Early season settlement test passed');
+  }
+
+  // Test 5: Fail to settle an ended season nobody scored in
+  console.log('Testing settlement of an empty season...');
+  try {
+    const emptySeasonId = seasonId + 1;
+    const { seasonPda, seasonVaultPda } = deriveSeason(emptySeasonId);
+    await confirm(
+      await executeCreateSeason(
+        emptySeasonId,
+        now - 2000,
+        now - 1000,
+        3,
+        prizePool
+      )
+    );
+    await program.methods
+      .settleSeason()
+      .accounts({
+        payer: provider.wallet.publicKey,
+        season: seasonPda,
+        seasonVault: seasonVaultPda,
+        seasonVaultTokenAccount: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    throw new Error('Should have failed settling an empty season');
+  } catch (error) {
+    expectError(error, ['EmptySeasonLeaderboard']);
+    console.log('Empty season settlement test passed');
+  }
+
+  console.log('All create season tests completed successfully');
+}
@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect, assert } from 'chai';
 import {
   SystemProgram,
@@ -102,11 +103,20 @@ export async function declareWinners(
       answerHash,
       donationAmount,
       allAreWinners,
-      evenSplit
+      evenSplit,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode1, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda,
       vaultTokenAccount: null,
@@ -154,7 +164,7 @@ export async function declareWinners(
       };
 
       const tx = await program.methods
-        .joinGame()
+        .joinGame(null, null, null)
         .accounts(accounts)
         .signers([player])
         .rpc();
@@ -177,8 +187,11 @@ export async function declareWinners(
   ) {
     const answers = Array(numCorrect).fill({
       displayOrder: 1,
+      answerType: { choice: {} },
       answer: 'test',
       questionId: 'test',
+      points: 1,
+      answeredAt: new anchor.BN(0),
       proof: [],
     });
 
@@ -219,6 +232,7 @@ export async function declareWinners(
     const tx = await program.methods
       .endGame()
       .accounts({
+        authority: provider.wallet.publicKey,
         admin: provider.wallet.publicKey,
         game: gamePda,
         vault: vaultPda,
@@ -251,7 +265,7 @@ export async function declareWinners(
     );
 
     const accounts = {
-      admin: provider.wallet.publicKey,
+      authority: provider.wallet.publicKey,
       game: gamePda,
       vault: vaultPda,
       vaultTokenAccount: isNative ? null : vaultTokenAccount,
@@ -340,11 +354,20 @@ export async function declareWinners(
       answerHash,
       donationAmount,
       false,
-      true // evenSplit
+      true, // evenSplit
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda2,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode2, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda2,
       vaultTokenAccount: null,
@@ -440,11 +463,20 @@ export async function declareWinners(
       answerHash,
       donationAmount,
       true, // allAreWinners
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda3,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode3, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda3,
       vaultTokenAccount: null,
@@ -587,11 +619,20 @@ export async function declareWinners(
       answerHash,
       new anchor.BN(1 * LAMPORTS_PER_SOL),
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda4,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode4, program.programId),
       tokenMint: mint,
       vault: vaultPda4,
       vaultTokenAccount: vaultTokenAccount.address,
@@ -633,6 +674,7 @@ export async function declareWinners(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda4,
       vault: vaultPda4,
@@ -770,11 +812,20 @@ export async function declareWinners(
       answerHash,
       new anchor.BN(1 * LAMPORTS_PER_SOL),
       false,
-      false
+      false,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda5,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode5, program.programId),
       tokenMint: mint5,
       vault: vaultPda5,
       vaultTokenAccount: vaultTokenAccount5.address,
@@ -816,6 +867,7 @@ export async function declareWinners(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda5,
       vault: vaultPda5,
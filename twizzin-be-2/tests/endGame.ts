@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect } from 'chai';
 import {
   SystemProgram,
@@ -118,11 +119,20 @@ export async function endGame(
       answerHash,
       donationAmount,
       allAreWinners,
-      evenSplit
+      evenSplit,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode1, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda,
       vaultTokenAccount: null,
@@ -170,7 +180,7 @@ export async function endGame(
       };
 
       const tx = await program.methods
-        .joinGame()
+        .joinGame(null, null, null)
         .accounts(accounts)
         .signers([player])
         .rpc();
@@ -195,8 +205,11 @@ export async function endGame(
   ) {
     const answers = Array(numCorrect).fill({
       displayOrder: 1,
+      answerType: { choice: {} },
       answer: 'test',
       questionId: 'test',
+      points: 1,
+      answeredAt: new anchor.BN(0),
       proof: [],
     });
 
@@ -239,6 +252,7 @@ export async function endGame(
     const tx = await program.methods
       .endGame()
       .accounts({
+        authority: provider.wallet.publicKey,
         admin: provider.wallet.publicKey,
         game: gamePda,
         vault: vaultPda,
@@ -344,11 +358,20 @@ export async function endGame(
       answerHash,
       donationAmount,
       allAreWinners,
-      evenSplit
+      evenSplit,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda2,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode2, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda2,
       vaultTokenAccount: null,
@@ -458,11 +481,20 @@ export async function endGame(
       answerHash,
       donationAmount,
       allAreWinners,
-      evenSplit
+      evenSplit,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda3,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode3, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda3,
       vaultTokenAccount: null,
@@ -594,11 +626,20 @@ export async function endGame(
       answerHash,
       tokenDonationAmount,
       allAreWinners,
-      evenSplit
+      evenSplit,
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda4,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode4, program.programId),
       tokenMint: tokenMint,
       vault: vaultPda4,
       vaultTokenAccount: vaultTokenAccount.address,
@@ -682,6 +723,7 @@ export async function endGame(
   await program.methods
     .endGame()
     .accounts({
+      authority: provider.wallet.publicKey,
       admin: provider.wallet.publicKey,
       game: gamePda4,
       vault: vaultPda4,
@@ -0,0 +1,238 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
+import { expect } from 'chai';
+import { PublicKey, LAMPORTS_PER_SOL, SystemProgram } from '@solana/web3.js';
+import { NATIVE_MINT } from '@solana/spl-token';
+
+// Permission bits - must match state/operator.rs
+const OPERATOR_START_END = 1;
+const OPERATOR_DECLARE_WINNERS = 2;
+const OPERATOR_UPDATE_GAME = 4;
+
+export async function gameOperator(
+  program: Program<TwizzinBe2>,
+  provider: anchor.AnchorProvider,
+  confirm: (signature: string) => Promise<string>
+) {
+  console.log('Starting game operator tests');
+
+  // Helper function for error checking
+  const expectError = (error: any, errorTypes: string[]) => {
+    const errorString = error.toString();
+    const hasExpectedError = errorTypes.some((type) =>
+      errorString.includes(type)
+    );
+    expect(
+      hasExpectedError,
+      `Expected one of [${errorTypes}] but got: ${errorString}`
+    ).to.be.true;
+  };
+
+  const admin = provider.wallet.publicKey;
+  const now = Date.now();
+  const gameCode = 'OPER' + (now % 10_000);
+
+  const [gamePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('game'), admin.toBuffer(), Buffer.from(gameCode)],
+    program.programId
+  );
+  const [vaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('vault'), admin.toBuffer(), Buffer.from(gameCode)],
+    program.programId
+  );
+
+  await confirm(
+    await program.methods
+      .initGame(
+        'Operated Game',
+        gameCode,
+        new anchor.BN(0),
+        0,
+        new anchor.BN(now + 60_000),
+        new anchor.BN(now + 3_600_000),
+        3,
+        Array(32).fill(0),
+        new anchor.BN(0),
+        false,
+        false,
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
+      )
+      .accounts({
+        admin,
+        game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
+        tokenMint: NATIVE_MINT,
+        vault: vaultPda,
+        adminTokenAccount: null,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+  );
+
+  const operator = anchor.web3.Keypair.generate();
+  await confirm(
+    await provider.connection.requestAirdrop(
+      operator.publicKey,
+      LAMPORTS_PER_SOL
+    )
+  );
+  const [gameOperatorPda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('game_operator'),
+      gamePda.toBuffer(),
+      operator.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+
+  const setOperator = (permissions: number) =>
+    program.methods
+      .setGameOperator(operator.publicKey, permissions)
+      .accounts({
+        admin,
+        game: gamePda,
+        gameOperator: gameOperatorPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  const executeUpdate = (newName: string, entryFee: anchor.BN | null) =>
+    program.methods
+      .updateGame(
+        newName,
+        entryFee,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        authority: operator.publicKey,
+        game: gamePda,
+        gameOperator: gameOperatorPda,
+        vault: vaultPda,
+        vaultTokenAccount: null,
+        tokenMint: NATIVE_MINT,
+        adminTokenAccount: null,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([operator])
+      .rpc();
+
+  // Test 1: Unknown permission bits are rejected
+  console.log('Testing invalid operator permissions...');
+  try {
+    await setOperator(8);
+    throw new Error('Should have failed with invalid permissions');
+  } catch (error) {
+    expectError(error, ['InvalidOperatorPermissions']);
+    console.log('Invalid permissions test passed');
+  }
+
+  // Test 2: Operator without update permission can't update the game
+  console.log('Testing update without permission...');
+  try {
+    await confirm(await setOperator(OPERATOR_START_END));
+    await executeUpdate('Renamed by operator', null);
+    throw new Error('Should have failed with operator not permitted');
+  } catch (error) {
+    expectError(error, ['OperatorNotPermitted']);
+    console.log('Update without permission test passed');
+  }
+
+  // Test 3: Operator can start the game
+  console.log('Testing operator start game...');
+  try {
+    await confirm(
+      await program.methods
+        .startGame(new anchor.BN(5 * 60 * 1000))
+        .accounts({
+          authority: operator.publicKey,
+          game: gamePda,
+          gameOperator: gameOperatorPda,
+        })
+        .signers([operator])
+        .rpc()
+    );
+
+    const game = await program.account.game.fetch(gamePda);
+    expect(game.admin.toString()).to.equal(admin.toString());
+    console.log('Operator start game test passed');
+  } catch (error) {
+    console.error('Operator start game test failed:', error);
+    throw error;
+  }
+
+  // Test 4: Operator with update permission can rename the game
+  console.log('Testing operator update...');
+  try {
+    await confirm(
+      await setOperator(OPERATOR_UPDATE_GAME | OPERATOR_DECLARE_WINNERS)
+    );
+    await confirm(await executeUpdate('Renamed by operator', null));
+
+    const game = await program.account.game.fetch(gamePda);
+    expect(game.name).to.equal('Renamed by operator');
+    console.log('Operator update test passed');
+  } catch (error) {
+    console.error('Operator update test failed:', error);
+    throw error;
+  }
+
+  // Test 5: Operators can't change the money settings
+  console.log('Testing operator entry fee update...');
+  try {
+    await executeUpdate('Renamed again', new anchor.BN(LAMPORTS_PER_SOL));
+    throw new Error('Should have failed with operator not permitted');
+  } catch (error) {
+    expectError(error, ['OperatorNotPermitted']);
+    console.log('Operator entry fee test passed');
+  }
+
+  // Test 6: Removed operators lose access
+  console.log('Testing removed operator...');
+  try {
+    await confirm(
+      await program.methods
+        .removeGameOperator()
+        .accounts({
+          admin,
+          game: gamePda,
+          gameOperator: gameOperatorPda,
+        })
+        .rpc()
+    );
+    await executeUpdate('Renamed after removal', null);
+    throw new Error('Should have failed after operator removal');
+  } catch (error) {
+    expectError(error, ['AccountNotInitialized', 'InvalidAuthority']);
+    console.log('Removed operator test passed');
+  }
+
+  console.log('All game operator tests completed successfully');
+}
@@ -33,7 +33,7 @@ export async function initializeProgramConfig(
   ) => {
     const signers = adminSigner ? [adminSigner] : [];
     return program.methods
-      .initConfig(treasury, fee)
+      .initConfig(treasury, fee, [], 0, { treasury: {} })
       .accounts({
         admin,
         config: configPda,
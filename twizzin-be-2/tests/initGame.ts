@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect } from 'chai';
 import { SystemProgram, PublicKey, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import {
@@ -51,6 +52,8 @@ export async function initializeGame(
     adminTokenAccount?: PublicKey | null; // Made optional to handle native SOL case
     allAreWinners?: boolean;
     evenSplit?: boolean;
+    metadataUri?: string;
+    questionSetHash?: number[];
   }) => {
     const adminPubkey = params.admin
       ? params.admin.publicKey
@@ -80,6 +83,10 @@ export async function initializeGame(
     const accounts = {
       admin: adminPubkey,
       game: gamePda,
+      gameCodeRegistry: findGameCodeRegistryPda(
+        params.gameCode,
+        program.programId
+      ),
       tokenMint: params.tokenMint,
       vault: vaultPda,
       adminTokenAccount: isNative ? null : params.adminTokenAccount || null,
@@ -100,7 +107,15 @@ export async function initializeGame(
         params.answerHash,
         params.donationAmount || new anchor.BN(0),
         params.allAreWinners || false,
-        params.evenSplit || false
+        params.evenSplit || false,
+        { public: {} },
+        { open: {} },
+        params.metadataUri || null,
+        params.questionSetHash || Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
       )
       .accounts(accounts)
       .signers(adminSigner)
@@ -213,6 +228,8 @@ export async function initializeGame(
       answerHash: validAnswerHash,
       tokenMint: NATIVE_MINT,
       adminTokenAccount: null,
+      metadataUri: 'https://twizzin.xyz/games/game1.json',
+      questionSetHash: Array(32).fill(3),
     });
     await confirm(tx);
 
@@ -232,6 +249,10 @@ export async function initializeGame(
     expect(gameState.tokenMint.equals(NATIVE_MINT)).to.be.true;
     expect(gameState.entryFee.eq(validEntryFee)).to.be.true;
     expect(gameState.isNative).to.be.true;
+    expect(gameState.metadataUri).to.equal(
+      'https://twizzin.xyz/games/game1.json'
+    );
+    expect(gameState.questionSetHash).to.deep.equal(Array(32).fill(3));
 
     console.log('Native SOL game creation test passed');
   } catch (error) {
@@ -239,6 +260,34 @@ export async function initializeGame(
     throw error;
   }
 
+  // Test: Another host can't reuse a taken code, whatever its case
+  console.log('Testing game code already taken...');
+  try {
+    const otherAdmin = anchor.web3.Keypair.generate();
+    await confirm(
+      await provider.connection.requestAirdrop(
+        otherAdmin.publicKey,
+        LAMPORTS_PER_SOL
+      )
+    );
+    await executeInitGame({
+      name: validName,
+      gameCode: validGameCode.toLowerCase(),
+      entryFee: validEntryFee,
+      commission: validCommission,
+      startTime: validStartTime,
+      endTime: validEndTime,
+      maxWinners: validMaxWinners,
+      answerHash: validAnswerHash,
+      tokenMint: NATIVE_MINT,
+      admin: otherAdmin,
+      adminTokenAccount: null,
+    });
+    throw new Error('Should have failed with game code taken');
+  } catch (error) {
+    expectError(error, ['GameCodeTaken']);
+  }
+
   // Test 5: Test SPL token game creation
   console.log('Testing SPL token game creation...');
   try {
@@ -311,11 +360,23 @@ export async function initializeGame(
         validAnswerHash,
         new anchor.BN(0),
         allAreWinners,
-        evenSplit
+        evenSplit,
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
       )
       .accounts({
         admin: provider.wallet.publicKey,
         game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(
+          newGameCode,
+          program.programId
+        ),
         tokenMint: mint,
         vault: vaultPda,
         vaultTokenAccount: associatedTokenAddress,
@@ -399,11 +460,23 @@ export async function initializeGame(
         validAnswerHash,
         validDonationAmount,
         allAreWinners,
-        evenSplit
+        evenSplit,
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
       )
       .accounts({
         admin: provider.wallet.publicKey,
         game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(
+          newGameCode,
+          program.programId
+        ),
         tokenMint: NATIVE_MINT,
         vault: vaultPda,
         vaultTokenAccount: null,
@@ -533,11 +606,23 @@ export async function initializeGame(
         validAnswerHash,
         validDonationAmount,
         allAreWinners,
-        evenSplit
+        evenSplit,
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
       )
       .accounts({
         admin: provider.wallet.publicKey,
         game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(
+          newGameCode,
+          program.programId
+        ),
         tokenMint: mint,
         vault: vaultPda,
         vaultTokenAccount: associatedTokenAddress,
@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect } from 'chai';
 import { SystemProgram, PublicKey, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import {
@@ -56,8 +57,14 @@ export async function joinGame(
     tokenMint?: PublicKey;
     playerTokenAccount?: PublicKey;
     vaultTokenAccount?: PublicKey;
+    referrer?: PublicKey;
   }) => {
-    const { gameCode, player, tokenMint = NATIVE_MINT } = params;
+    const {
+      gameCode,
+      player,
+      tokenMint = NATIVE_MINT,
+      referrer = null,
+    } = params;
 
     const [gamePda] = PublicKey.findProgramAddressSync(
       [
@@ -84,6 +91,36 @@ export async function joinGame(
 
     const isNative = tokenMint.equals(NATIVE_MINT);
 
+    // Referral accounts are only needed when joining with a referrer
+    const referralAccounts = referrer
+      ? {
+          referrerPlayerAccount: PublicKey.findProgramAddressSync(
+            [Buffer.from('player'), gamePda.toBuffer(), referrer.toBuffer()],
+            program.programId
+          )[0],
+          gameReferral: PublicKey.findProgramAddressSync(
+            [
+              Buffer.from('game_referral'),
+              gamePda.toBuffer(),
+              referrer.toBuffer(),
+            ],
+            program.programId
+          )[0],
+          referralBalance: PublicKey.findProgramAddressSync(
+            [Buffer.from('referral'), referrer.toBuffer(), tokenMint.toBuffer()],
+            program.programId
+          )[0],
+          referralTokenAccount: null,
+          tokenMint: null,
+        }
+      : {
+          referrerPlayerAccount: null,
+          gameReferral: null,
+          referralBalance: null,
+          referralTokenAccount: null,
+          tokenMint: null,
+        };
+
     const accounts = {
       player: player.publicKey,
       game: gamePda,
@@ -91,13 +128,14 @@ export async function joinGame(
       vault: vaultPda,
       vaultTokenAccount: isNative ? null : params.vaultTokenAccount,
       playerTokenAccount: isNative ? null : params.playerTokenAccount,
+      ...referralAccounts,
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
     };
 
     return program.methods
-      .joinGame()
+      .joinGame(referrer, null, null)
       .accounts(accounts)
       .signers([player])
       .rpc();
@@ -197,11 +235,20 @@ export async function joinGame(
         validAnswerHash,
         new anchor.BN(0),
         false, // allAreWinners
-        false // evenSplit
+        false, // evenSplit
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
       )
       .accounts({
         admin: provider.wallet.publicKey,
         game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
         tokenMint,
         vault: vaultPda,
         vaultTokenAccount: vaultTokenAccount?.address || null,
@@ -575,5 +622,117 @@ export async function joinGame(
     throw error;
   }
 
+  // Test 10: Successfully join with a referrer who has joined the game
+  console.log('Testing joining with a referrer...');
+  try {
+    const gameCode = 'REFER1';
+    const referrer = await setupPlayer();
+    const player = await setupPlayer();
+
+    await createGame({
+      gameCode,
+      entryFee: validEntryFee,
+    });
+
+    await confirm(await executeJoinGame({ gameCode, player: referrer }));
+    await confirm(
+      await executeJoinGame({
+        gameCode,
+        player,
+        referrer: referrer.publicKey,
+      })
+    );
+
+    const [gamePda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('game'),
+        provider.wallet.publicKey.toBuffer(),
+        Buffer.from(gameCode),
+      ],
+      program.programId
+    );
+    const [playerPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('player'), gamePda.toBuffer(), player.publicKey.toBuffer()],
+      program.programId
+    );
+    const [gameReferralPda] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('game_referral'),
+        gamePda.toBuffer(),
+        referrer.publicKey.toBuffer(),
+      ],
+      program.programId
+    );
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.referrer.equals(referrer.publicKey)).to.be.true;
+
+    const gameReferral = await program.account.gameReferral.fetch(
+      gameReferralPda
+    );
+    expect(gameReferral.referredPlayers).to.equal(1);
+
+    const game = await program.account.game.fetch(gamePda);
+    expect(game.referredPlayers).to.equal(1);
+
+    console.log('Referred join test passed');
+  } catch (error) {
+    console.error('Referred join test failed:', error);
+    throw error;
+  }
+
+  // Test 11: Fail to refer yourself
+  console.log('Testing self referral...');
+  try {
+    const gameCode = 'REFER2';
+    const player = await setupPlayer();
+
+    await createGame({
+      gameCode,
+      entryFee: validEntryFee,
+    });
+
+    await executeJoinGame({
+      gameCode,
+      player,
+      referrer: player.publicKey,
+    });
+    throw new Error('Should have failed with self referral');
+  } catch (error) {
+    expectError(error, [
+      'SelfReferral',
+      'AccountNotInitialized',
+      "Players can't refer themselves",
+    ]);
+    console.log('Self referral test passed');
+  }
+
+  // Test 12: Fail to refer via a wallet that hasn't joined the game
+  console.log('Testing referral by a non-player...');
+  try {
+    const gameCode = 'REFER3';
+    const referrer = await setupPlayer();
+    const player = await setupPlayer();
+
+    await createGame({
+      gameCode,
+      entryFee: validEntryFee,
+    });
+
+    await executeJoinGame({
+      gameCode,
+      player,
+      referrer: referrer.publicKey,
+    });
+    throw new Error('Should have failed with referrer not a player');
+  } catch (error) {
+    expectError(error, [
+      'ReferrerNotPlayer',
+      'AccountNotInitialized',
+      'Referrer must have joined the game',
+    ]);
+    console.log('Non-player referrer test passed');
+  }
+
   console.log('All join game tests completed successfully');
 }
@@ -0,0 +1,81 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import {
+  AnswerType,
+  createLeafHash,
+  createLeafHashV2,
+  MerkleTree,
+} from './utils';
+import { expect } from 'chai';
+
+// Same inputs and hashes as test_cross_language_vectors in utils/merkle.rs -
+// change both together
+const vectors = {
+  v1Leaf0: 'ed4087381daf3a41bfc20653259b17a8a01be7a34337831be515cb8365d3bddb',
+  v1Leaf3: '5fc6006f278403d6beca755af6fadf8d098b42f7c5a1221b5fceb70c7b9f8fae',
+  v1Root: '3dbad17fa49bb519347f206314a503295b45ed267282f76abee9bf2402367601',
+  v2Leaf0: 'bc29b8f9cd665cdb4d1f5941f2d22db9507e4d012a76828d044412aa6c2f7042',
+  v2Leaf3: 'b036dbb434b038ef6d2002d7a784758af53dd9daa82130d48d7999563117d71d',
+  v2Root: 'a9a4ad2809f047af2ec029096549f758c41d030cb55c4d95086044cf6c7c35f2',
+  v2Proof4: [
+    '0000000000000000000000000000000000000000000000000000000000000000',
+    '0000000000000000000000000000000000000000000000000000000000000000',
+    '3e1ceabf41165ef55276e95eda494a37b6835379a028d63ec233da53837907b3',
+  ],
+};
+
+const questions: Array<{
+  answerType: AnswerType;
+  answer: string;
+  points: number;
+}> = [
+  { answerType: { choice: {} }, answer: 'a', points: 1 },
+  { answerType: { multiSelect: {} }, answer: 'c, a', points: 2 },
+  { answerType: { freeText: {} }, answer: ' New  York', points: 3 },
+  {
+    answerType: {
+      numeric: { min: new anchor.BN(-5), max: new anchor.BN(105) },
+    },
+    answer: '',
+    points: 5,
+  },
+  { answerType: { choice: {} }, answer: 'b', points: 8 },
+];
+
+export async function merkleVectors(
+  program: Program<TwizzinBe2>,
+  provider: anchor.AnchorProvider,
+  confirm: (signature: string) => Promise<string>
+) {
+  console.log('Starting merkle vector tests');
+
+  const answers = questions.map((question, i) => ({
+    ...question,
+    displayOrder: i,
+    questionId: `q${i + 1}`,
+  }));
+  const hex = (buffer: Buffer) => buffer.toString('hex');
+  const leaf = (create: typeof createLeafHash, i: number) =>
+    hex(
+      create(
+        answers[i].displayOrder,
+        answers[i].answerType,
+        answers[i].answer,
+        answers[i].questionId,
+        answers[i].points
+      )
+    );
+
+  expect(leaf(createLeafHash, 0)).to.equal(vectors.v1Leaf0);
+  expect(leaf(createLeafHash, 3)).to.equal(vectors.v1Leaf3);
+  expect(hex(new MerkleTree(answers).getRoot())).to.equal(vectors.v1Root);
+
+  const treeV2 = new MerkleTree(answers, 2);
+  expect(leaf(createLeafHashV2, 0)).to.equal(vectors.v2Leaf0);
+  expect(leaf(createLeafHashV2, 3)).to.equal(vectors.v2Leaf3);
+  expect(hex(treeV2.getRoot())).to.equal(vectors.v2Root);
+  expect(treeV2.getProof(4).map(hex)).to.deep.equal(vectors.v2Proof4);
+
+  console.log('All merkle vector tests completed successfully');
+}
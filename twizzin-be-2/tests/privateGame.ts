@@ -0,0 +1,265 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
+import { expect } from 'chai';
+import {
+  PublicKey,
+  LAMPORTS_PER_SOL,
+  SystemProgram,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+} from '@solana/web3.js';
+import { NATIVE_MINT } from '@solana/spl-token';
+import { createHash } from 'crypto';
+
+export async function privateGame(
+  program: Program<TwizzinBe2>,
+  provider: anchor.AnchorProvider,
+  confirm: (signature: string) => Promise<string>
+) {
+  console.log('Starting private game tests');
+
+  // Helper function for error checking
+  const expectError = (error: any, errorTypes: string[]) => {
+    const errorString = error.toString();
+    const hasExpectedError = errorTypes.some((type) =>
+      errorString.includes(type)
+    );
+    expect(
+      hasExpectedError,
+      `Expected one of [${errorTypes}] but got: ${errorString}`
+    ).to.be.true;
+  };
+
+  const admin = provider.wallet.publicKey;
+  const now = Date.now();
+
+  const createPlayer = async () => {
+    const player = anchor.web3.Keypair.generate();
+    await confirm(
+      await provider.connection.requestAirdrop(
+        player.publicKey,
+        LAMPORTS_PER_SOL
+      )
+    );
+    return player;
+  };
+
+  const derivePdas = (gameCode: string) => {
+    const [gamePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('game'), admin.toBuffer(), Buffer.from(gameCode)],
+      program.programId
+    );
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), admin.toBuffer(), Buffer.from(gameCode)],
+      program.programId
+    );
+    return { gamePda, vaultPda };
+  };
+
+  const createGame = async (gameCode: string, accessMode: any) => {
+    const { gamePda, vaultPda } = derivePdas(gameCode);
+    await confirm(
+      await program.methods
+        .initGame(
+          'Private Game',
+          gameCode,
+          new anchor.BN(0.01 * LAMPORTS_PER_SOL),
+          0,
+          new anchor.BN(now + 60_000),
+          new anchor.BN(now + 3_600_000),
+          3,
+          Array(32).fill(0),
+          new anchor.BN(0),
+          false,
+          false,
+          accessMode,
+          { open: {} },
+          null,
+          Array(32).fill(0),
+          0,
+          0,
+          0,
+          1
+        )
+        .accounts({
+          admin,
+          game: gamePda,
+          gameCodeRegistry: findGameCodeRegistryPda(
+            gameCode,
+            program.programId
+          ),
+          tokenMint: NATIVE_MINT,
+          vault: vaultPda,
+          adminTokenAccount: null,
+          tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+          associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+    );
+    return { gamePda, vaultPda };
+  };
+
+  const joinGameIx = (
+    gamePda: PublicKey,
+    vaultPda: PublicKey,
+    player: anchor.web3.Keypair,
+    accessProof: any
+  ) => {
+    const [playerAccountPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('player'), gamePda.toBuffer(), player.publicKey.toBuffer()],
+      program.programId
+    );
+    return program.methods.joinGame(null, accessProof, null).accounts({
+      player: player.publicKey,
+      game: gamePda,
+      playerAccount: playerAccountPda,
+      vault: vaultPda,
+      vaultTokenAccount: null,
+      playerTokenAccount: null,
+      instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+      tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+      associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+  };
+
+  // Allowlist with a single wallet - the root is that wallet's leaf, behind the
+  // 0x00 leaf prefix
+  const allowedPlayer = await createPlayer();
+  const allowlistRoot = createHash('sha256')
+    .update(Buffer.from([0]))
+    .update(allowedPlayer.publicKey.toBuffer())
+    .digest();
+  const allowlistGame = await createGame('ALLOW' + (now % 10_000), {
+    allowlist: { root: Array.from(allowlistRoot) },
+  });
+
+  // Test 1: Allowlisted wallet can join
+  console.log('Testing allowlisted join...');
+  try {
+    await confirm(
+      await joinGameIx(
+        allowlistGame.gamePda,
+        allowlistGame.vaultPda,
+        allowedPlayer,
+        { allowlist: { proof: [], index: 0 } }
+      )
+        .signers([allowedPlayer])
+        .rpc()
+    );
+    console.log('Allowlisted join test passed');
+  } catch (error) {
+    console.error('Allowlisted join test failed:', error);
+    throw error;
+  }
+
+  // Test 2: Wallets off the allowlist can't join
+  console.log('Testing join from outside the allowlist...');
+  try {
+    const outsider = await createPlayer();
+    await joinGameIx(
+      allowlistGame.gamePda,
+      allowlistGame.vaultPda,
+      outsider,
+      { allowlist: { proof: [], index: 0 } }
+    )
+      .signers([outsider])
+      .rpc();
+    throw new Error('Should have failed with player not on allowlist');
+  } catch (error) {
+    expectError(error, ['NotOnAllowlist']);
+    console.log('Outside allowlist test passed');
+  }
+
+  // Invites signed by a designated signer
+  const inviteSigner = anchor.web3.Keypair.generate();
+  const inviteGame = await createGame('INVITE' + (now % 10_000), {
+    signedInvite: { inviteSigner: inviteSigner.publicKey },
+  });
+
+  const signInvite = (player: PublicKey, expiry: number) => {
+    const expiryBuffer = Buffer.alloc(8);
+    expiryBuffer.writeBigInt64LE(BigInt(expiry));
+    const message = Buffer.concat([
+      inviteGame.gamePda.toBuffer(),
+      player.toBuffer(),
+      expiryBuffer,
+    ]);
+    return Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: inviteSigner.secretKey,
+      message,
+    });
+  };
+
+  // Test 3: Player with a valid invite can join
+  console.log('Testing signed invite join...');
+  try {
+    const invited = await createPlayer();
+    const expiry = now + 600_000;
+    await confirm(
+      await joinGameIx(inviteGame.gamePda, inviteGame.vaultPda, invited, {
+        signedInvite: { expiry: new anchor.BN(expiry) },
+      })
+        .preInstructions([signInvite(invited.publicKey, expiry)])
+        .signers([invited])
+        .rpc()
+    );
+    console.log('Signed invite join test passed');
+  } catch (error) {
+    console.error('Signed invite join test failed:', error);
+    throw error;
+  }
+
+  // Test 4: An invite signed for someone else can't be reused
+  console.log('Testing invite for another player...');
+  try {
+    const invited = await createPlayer();
+    const other = await createPlayer();
+    const expiry = now + 600_000;
+    await joinGameIx(inviteGame.gamePda, inviteGame.vaultPda, other, {
+      signedInvite: { expiry: new anchor.BN(expiry) },
+    })
+      .preInstructions([signInvite(invited.publicKey, expiry)])
+      .signers([other])
+      .rpc();
+    throw new Error('Should have failed with invalid invite');
+  } catch (error) {
+    expectError(error, ['InvalidInvite']);
+    console.log('Invite for another player test passed');
+  }
+
+  // Test 5: Expired invites are rejected
+  console.log('Testing expired invite...');
+  try {
+    const invited = await createPlayer();
+    const expiry = now - 1000;
+    await joinGameIx(inviteGame.gamePda, inviteGame.vaultPda, invited, {
+      signedInvite: { expiry: new anchor.BN(expiry) },
+    })
+      .preInstructions([signInvite(invited.publicKey, expiry)])
+      .signers([invited])
+      .rpc();
+    throw new Error('Should have failed with expired invite');
+  } catch (error) {
+    expectError(error, ['InviteExpired']);
+    console.log('Expired invite test passed');
+  }
+
+  // Test 6: Private games can't be joined without a proof
+  console.log('Testing join without an invite...');
+  try {
+    const uninvited = await createPlayer();
+    await joinGameIx(inviteGame.gamePda, inviteGame.vaultPda, uninvited, null)
+      .signers([uninvited])
+      .rpc();
+    throw new Error('Should have failed without an access proof');
+  } catch (error) {
+    expectError(error, ['AccessProofNotProvided']);
+    console.log('Join without invite test passed');
+  }
+
+  console.log('All private game tests completed successfully');
+}
@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect } from 'chai';
 import { PublicKey, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import { NATIVE_MINT } from '@solana/spl-token';
@@ -61,11 +62,20 @@ export async function startGame(
       Array(32).fill(1),
       new anchor.BN(0),
       false, // allAreWinners
-      false // evenSplit
+      false, // evenSplit
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda,
       adminTokenAccount: null,
@@ -85,7 +95,7 @@ export async function startGame(
     await program.methods
       .startGame(new anchor.BN(totalTimeMs))
       .accounts({
-        admin: wrongAdmin.publicKey,
+        authority: wrongAdmin.publicKey,
         game: gamePda,
       })
       .signers([wrongAdmin])
@@ -101,7 +111,7 @@ export async function startGame(
     const tx = await program.methods
       .startGame(new anchor.BN(totalTimeMs))
       .accounts({
-        admin: provider.wallet.publicKey,
+        authority: provider.wallet.publicKey,
         game: gamePda,
       })
       .rpc();
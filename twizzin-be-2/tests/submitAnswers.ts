@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { AnswerType, findGameCodeRegistryPda, MerkleTree } from './utils';
 import { expect } from 'chai';
 import {
   SystemProgram,
@@ -13,103 +14,24 @@ import {
   NATIVE_MINT,
   ASSOCIATED_TOKEN_PROGRAM_ID,
 } from '@solana/spl-token';
-import { createHash } from 'crypto';
 
 interface AnswerInput {
   displayOrder: number;
+  answerType: AnswerType;
   answer: string;
   questionId: string;
+  points: number;
+  answeredAt?: anchor.BN;
   proof: number[][];
 }
 
-const createLeafHash = (
-  displayOrder: number,
-  answer: string,
-  questionId: string
-): Buffer => {
-  const hash = createHash('sha256');
-  hash.update(Buffer.from([displayOrder]));
-  hash.update(Buffer.from(answer));
-  hash.update(Buffer.from(questionId));
-  return hash.digest();
-};
-
-const hashPair = (first: Buffer, second: Buffer): Buffer => {
-  const hash = createHash('sha256');
-  if (Buffer.compare(first, second) <= 0) {
-    hash.update(first);
-    hash.update(second);
-  } else {
-    hash.update(second);
-    hash.update(first);
-  }
-  return hash.digest();
-};
-
-class MerkleTree {
-  private layers: Buffer[][];
-
-  constructor(
-    answers: Array<{ displayOrder: number; answer: string; questionId: string }>
-  ) {
-    const leaves = answers.map((answer) =>
-      createLeafHash(answer.displayOrder, answer.answer, answer.questionId)
-    );
-    this.layers = [leaves];
-
-    while (this.layers[this.layers.length - 1].length > 1) {
-      this.layers.push(
-        this.createNextLayer(this.layers[this.layers.length - 1])
-      );
-    }
-  }
-
-  private createNextLayer(nodes: Buffer[]): Buffer[] {
-    const layerNodes: Buffer[] = [];
-    for (let i = 0; i < nodes.length; i += 2) {
-      if (i + 1 === nodes.length) {
-        layerNodes.push(nodes[i]);
-      } else {
-        layerNodes.push(hashPair(nodes[i], nodes[i + 1]));
-      }
-    }
-    return layerNodes;
-  }
-
-  getRoot(): Buffer {
-    return this.layers[this.layers.length - 1][0];
-  }
-
-  getProof(index: number): Buffer[] {
-    let currentIndex = index;
-    const proof: Buffer[] = [];
-
-    for (
-      let layerIndex = 0;
-      layerIndex < this.layers.length - 1;
-      layerIndex++
-    ) {
-      const currentLayer = this.layers[layerIndex];
-      const isRightNode = currentIndex % 2 === 0;
-      const pairIndex = isRightNode ? currentIndex + 1 : currentIndex - 1;
-
-      if (pairIndex < currentLayer.length) {
-        proof.push(currentLayer[pairIndex]);
-      }
-
-      currentIndex = Math.floor(currentIndex / 2);
-    }
-
-    return proof;
-  }
-}
-
 const createTestAnswers = () => {
+  const choice: AnswerType = { choice: {} };
   const correctAnswers = [
-    { displayOrder: 0, answer: 'a', questionId: 'q1' },
-    { displayOrder: 1, answer: 'b', questionId: 'q2' },
-    { displayOrder: 2, answer: 'c', questionId: 'q3' },
-  ];
+    { displayOrder: 0, answer: 'a', questionId: 'q1', points: 1 },
+    { displayOrder: 1, answer: 'b', questionId: 'q2', points: 2 },
+    { displayOrder: 2, answer: 'c', questionId: 'q3', points: 3 },
+  ].map((entry) => ({ ...entry, answerType: choice }));
 
   const tree = new MerkleTree(correctAnswers);
   const root = tree.getRoot();
@@ -118,8 +40,10 @@ const createTestAnswers = () => {
     const proof = tree.getProof(index);
     return {
       displayOrder: answer.displayOrder,
+      answerType: answer.answerType,
       answer: answer.answer,
       questionId: answer.questionId,
+      points: answer.points,
       proof: proof.map((proofElement) => {
         const bytes = new Uint8Array(32).fill(0);
         bytes.set(new Uint8Array(proofElement));
@@ -143,7 +67,22 @@ export async function submitAnswers(
 
   const { root: validAnswerHash, testAnswers } = createTestAnswers();
 
-  const setupGameAndPlayer = async () => {
+  const setupGameAndPlayer = async (
+    options: {
+      wrongAnswerPenalty?: number;
+      answerHash?: number[];
+      speedBonus?: number;
+      speedBonusWindow?: number;
+      merkleVersion?: number;
+    } = {}
+  ) => {
+    const {
+      wrongAnswerPenalty = 0,
+      answerHash = validAnswerHash,
+      speedBonus = 0,
+      speedBonusWindow = 0,
+      merkleVersion = 1,
+    } = options;
     const gameCode = Math.random().toString(36).substring(2, 8).toUpperCase();
     const player = new Keypair();
 
@@ -185,14 +124,23 @@ export async function submitAnswers(
         gameStartTime,
         gameEndTime,
         5,
-        validAnswerHash,
+        answerHash,
         new anchor.BN(0),
         false, // allAreWinners
-        false // evenSplit
+        false, // evenSplit
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        wrongAnswerPenalty,
+        speedBonus,
+        speedBonusWindow,
+        merkleVersion
       )
       .accounts({
         admin: provider.wallet.publicKey,
         game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
         tokenMint: NATIVE_MINT,
         vault: vaultPda,
         adminTokenAccount: null,
@@ -208,7 +156,7 @@ export async function submitAnswers(
     );
 
     await program.methods
-      .joinGame()
+      .joinGame(null, null, null)
       .accounts({
         player: player.publicKey,
         game: gamePda,
@@ -238,8 +186,11 @@ export async function submitAnswers(
       .submitAnswers(
         answers.map((a) => ({
           displayOrder: a.displayOrder,
+          answerType: a.answerType,
           answer: a.answer,
           questionId: a.questionId,
+          points: a.points,
+          answeredAt: a.answeredAt || new anchor.BN(0),
           proof: a.proof,
         })),
         clientFinishTime
@@ -301,6 +252,7 @@ export async function submitAnswers(
     const playerAccount = await program.account.playerAccount.fetch(playerPda);
     expect(playerAccount.finishedTime.eq(clientFinishTime)).to.be.true;
     expect(playerAccount.numCorrect).to.equal(testAnswers.length);
+    expect(playerAccount.score).to.equal(6);
 
     console.log('Successful answer submission test passed');
   } catch (error) {
@@ -402,5 +354,250 @@ export async function submitAnswers(
     console.log('Double submission test passed');
   }
 
+  // Test 6: Negative marking - wrong answers cost points, skips don't
+  console.log('Testing negative marking...');
+  try {
+    const { gamePda, player, playerPda } = await setupGameAndPlayer({
+      wrongAnswerPenalty: 2,
+    });
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    const now = Math.floor(Date.now() / 1000);
+    const clientFinishTime = new anchor.BN((now - 30) * 1000);
+
+    // Right (1 point), wrong (-2) and skipped (0)
+    const answers = [
+      testAnswers[0],
+      { ...testAnswers[1], answer: 'c' },
+      { ...testAnswers[2], answer: '' },
+    ];
+    const tx = await executeSubmitAnswers({
+      player,
+      gamePda,
+      playerPda,
+      answers,
+      clientFinishTime,
+    });
+    await confirm(tx);
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.numCorrect).to.equal(1);
+    expect(playerAccount.score).to.equal(-1);
+
+    console.log('Negative marking test passed');
+  } catch (error) {
+    console.error('Negative marking test failed:', error);
+    throw error;
+  }
+
+  // Test 7: Multi-select, free text and numeric questions
+  console.log('Testing answer types...');
+  try {
+    const questions: { answerType: AnswerType; answer: string }[] = [
+      { answerType: { multiSelect: {} }, answer: 'a,c' },
+      { answerType: { freeText: {} }, answer: 'new york' },
+      {
+        answerType: {
+          numeric: { min: new anchor.BN(95), max: new anchor.BN(105) },
+        },
+        answer: '',
+      },
+    ];
+    const key = questions.map((entry, i) => ({
+      ...entry,
+      displayOrder: i,
+      questionId: `typed-${i}`,
+      points: 1,
+    }));
+    const tree = new MerkleTree(key);
+    const root = Array.from(new Uint8Array(tree.getRoot()));
+    const { gamePda, player, playerPda } = await setupGameAndPlayer({
+      answerHash: root,
+    });
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    const now = Math.floor(Date.now() / 1000);
+    // Equivalent answers in whatever form the player typed them
+    const typed = ['c, a', '  New York ', '101'];
+    const tx = await executeSubmitAnswers({
+      player,
+      gamePda,
+      playerPda,
+      answers: key.map((entry, i) => ({
+        ...entry,
+        answer: typed[i],
+        proof: tree.getProof(i).map((node) => Array.from(node)),
+      })),
+      clientFinishTime: new anchor.BN((now - 30) * 1000),
+    });
+    await confirm(tx);
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.numCorrect).to.equal(key.length);
+
+    console.log('Answer types test passed');
+  } catch (error) {
+    console.error('Answer types test failed:', error);
+    throw error;
+  }
+
+  // Test 8: Speed bonus - fast correct answers earn extra points
+  console.log('Testing speed bonus...');
+  try {
+    // Up to 10 extra points, gone after 2 seconds on a question
+    const { gamePda, player, playerPda } = await setupGameAndPlayer({
+      speedBonus: 10,
+      speedBonusWindow: 2000,
+    });
+    const { joinTime } = await program.account.playerAccount.fetch(playerPda);
+
+    await new Promise((resolve) => setTimeout(resolve, 2500));
+
+    // Instant answers to the first two, a second on the last
+    const answerTimes = [0, 0, 1000].map((ms) =>
+      joinTime.add(new anchor.BN(ms))
+    );
+    const tx = await executeSubmitAnswers({
+      player,
+      gamePda,
+      playerPda,
+      answers: testAnswers.map((answer, i) => ({
+        ...answer,
+        answeredAt: answerTimes[i],
+      })),
+      clientFinishTime: answerTimes[2],
+    });
+    await confirm(tx);
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    // (1 + 10) + (2 + 10) + (3 + 5)
+    expect(playerAccount.score).to.equal(31);
+
+    console.log('Speed bonus test passed');
+  } catch (error) {
+    console.error('Speed bonus test failed:', error);
+    throw error;
+  }
+
+  // Test 9: Merkle v2 - proofs are bound to each answer's position
+  console.log('Testing merkle v2 answers...');
+  try {
+    const treeV2 = new MerkleTree(testAnswers, 2);
+    const { gamePda, player, playerPda } = await setupGameAndPlayer({
+      answerHash: Array.from(new Uint8Array(treeV2.getRoot())),
+      merkleVersion: 2,
+    });
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    const now = Math.floor(Date.now() / 1000);
+    // Only the first answer comes with a v2 proof - v1 proofs don't verify
+    const tx = await executeSubmitAnswers({
+      player,
+      gamePda,
+      playerPda,
+      answers: testAnswers.map((answer, i) =>
+        i === 0
+          ? {
+              ...answer,
+              proof: treeV2.getProof(0).map((node) => Array.from(node)),
+            }
+          : answer
+      ),
+      clientFinishTime: new anchor.BN((now - 30) * 1000),
+    });
+    await confirm(tx);
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.numCorrect).to.equal(1);
+
+    console.log('Merkle v2 test passed');
+  } catch (error) {
+    console.error('Merkle v2 test failed:', error);
+    throw error;
+  }
+
+  // Test 10: Batch submission - one multiproof covers every claimed answer
+  console.log('Testing batch submission...');
+  try {
+    const treeV2 = new MerkleTree(testAnswers, 2);
+    const { gamePda, player, playerPda } = await setupGameAndPlayer({
+      answerHash: Array.from(new Uint8Array(treeV2.getRoot())),
+      merkleVersion: 2,
+    });
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    // The last answer is wrong, so it's left out of the proof
+    const answers = testAnswers.map((answer, i) => ({
+      ...answer,
+      answer: i === 2 ? 'a' : answer.answer,
+      answeredAt: new anchor.BN(0),
+      proof: [],
+    }));
+    const multiproof = treeV2.getMultiproof([0, 1]);
+    const now = Math.floor(Date.now() / 1000);
+    const tx = await program.methods
+      .submitAnswersBatch(
+        answers,
+        {
+          claimed: multiproof.claimed,
+          proof: multiproof.proof.map((node) => Array.from(node)),
+          depth: multiproof.depth,
+        },
+        new anchor.BN((now - 30) * 1000)
+      )
+      .accounts({
+        player: player.publicKey,
+        game: gamePda,
+        playerAccount: playerPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([player])
+      .rpc();
+    await confirm(tx);
+
+    const playerAccount = await program.account.playerAccount.fetch(playerPda);
+    expect(playerAccount.numCorrect).to.equal(2);
+    expect(playerAccount.score).to.equal(3);
+
+    // Claiming the wrong answer breaks the multiproof
+    const other = await setupGameAndPlayer({
+      answerHash: Array.from(new Uint8Array(treeV2.getRoot())),
+      merkleVersion: 2,
+    });
+    const overclaimed = treeV2.getMultiproof([0, 1, 2]);
+    try {
+      await program.methods
+        .submitAnswersBatch(
+          answers,
+          {
+            claimed: overclaimed.claimed,
+            proof: overclaimed.proof.map((node) => Array.from(node)),
+            depth: overclaimed.depth,
+          },
+          new anchor.BN((now - 30) * 1000)
+        )
+        .accounts({
+          player: other.player.publicKey,
+          game: other.gamePda,
+          playerAccount: other.playerPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([other.player])
+        .rpc();
+      throw new Error('Should have failed with invalid multiproof');
+    } catch (error) {
+      expectError(error, ['InvalidMultiproof']);
+    }
+
+    console.log('Batch submission test passed');
+  } catch (error) {
+    console.error('Batch submission test failed:', error);
+    throw error;
+  }
+
   console.log('All submit answers tests completed successfully');
 }
@@ -0,0 +1,228 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
+import { expect } from 'chai';
+import { SystemProgram, PublicKey, LAMPORTS_PER_SOL } from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
+} from '@solana/spl-token';
+
+export async function tournament(
+  program: Program<TwizzinBe2>,
+  provider: anchor.AnchorProvider,
+  confirm: (signature: string) => Promise<string>
+) {
+  console.log('Starting tournament tests');
+
+  // Helper function for error checking
+  const expectError = (error: any, errorTypes: string[]) => {
+    const errorString = error.toString();
+    const hasExpectedError = errorTypes.some((type) =>
+      errorString.includes(type)
+    );
+    expect(
+      hasExpectedError,
+      `Expected one of [${errorTypes}] but got: ${errorString}`
+    ).to.be.true;
+  };
+
+  const admin = provider.wallet.publicKey;
+  const tournamentCode = 'TOURNEY' + Math.floor(Math.random() * 10_000);
+  const entryFee = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+
+  const [tournamentPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('tournament'), admin.toBuffer(), Buffer.from(tournamentCode)],
+    program.programId
+  );
+  const [tournamentVaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('tournament_vault'), tournamentPda.toBuffer()],
+    program.programId
+  );
+
+  const roundCode = (prefix: string) =>
+    prefix + Math.floor(Math.random() * 10_000);
+
+  const initRound = (gameCode: string, roundEntryFee: anchor.BN) => {
+    const [gamePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('game'), admin.toBuffer(), Buffer.from(gameCode)],
+      program.programId
+    );
+    const [vaultPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), admin.toBuffer(), Buffer.from(gameCode)],
+      program.programId
+    );
+    const now = Date.now();
+    return program.methods
+      .initGame(
+        'Tournament Round',
+        gameCode,
+        roundEntryFee,
+        0,
+        new anchor.BN(now + 60_000),
+        new anchor.BN(now + 120_000),
+        3,
+        Array(32).fill(0),
+        new anchor.BN(0),
+        false,
+        false,
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
+      )
+      .accounts({
+        admin,
+        game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
+        tokenMint: NATIVE_MINT,
+        vault: vaultPda,
+        vaultTokenAccount: null,
+        adminTokenAccount: null,
+        season: null,
+        tournament: tournamentPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  };
+
+  // Test 1: Create a two round tournament where the top 2 advance
+  console.log('Testing tournament creation...');
+  try {
+    await confirm(
+      await program.methods
+        .initTournament(
+          tournamentCode,
+          'Test Tournament',
+          entryFee,
+          500,
+          2,
+          2,
+          false,
+          new anchor.BN(Date.now() + 3_600_000)
+        )
+        .accounts({
+          admin,
+          tournament: tournamentPda,
+          tokenMint: NATIVE_MINT,
+          tournamentVault: tournamentVaultPda,
+          tournamentVaultTokenAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc()
+    );
+
+    const tournamentAccount = await program.account.tournament.fetch(
+      tournamentPda
+    );
+    expect(tournamentAccount.numRounds).to.equal(2);
+    expect(tournamentAccount.advanceCount).to.equal(2);
+    expect(tournamentAccount.isNative).to.be.true;
+    expect(tournamentAccount.rounds.length).to.equal(0);
+    console.log('Tournament creation test passed');
+  } catch (error) {
+    console.error('Tournament creation test failed:', error);
+    throw error;
+  }
+
+  // Test 2: Players pay the entry fee once into the tournament vault
+  console.log('Testing joining the tournament...');
+  const player = anchor.web3.Keypair.generate();
+  await confirm(
+    await provider.connection.requestAirdrop(player.publicKey, LAMPORTS_PER_SOL)
+  );
+  const [tournamentEntryPda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from('tournament_entry'),
+      tournamentPda.toBuffer(),
+      player.publicKey.toBuffer(),
+    ],
+    program.programId
+  );
+  try {
+    await confirm(
+      await program.methods
+        .joinTournament()
+        .accounts({
+          player: player.publicKey,
+          tournament: tournamentPda,
+          tournamentEntry: tournamentEntryPda,
+          tournamentVault: tournamentVaultPda,
+          tournamentVaultTokenAccount: null,
+          playerTokenAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([player])
+        .rpc()
+    );
+
+    const vaultBalance = await provider.connection.getBalance(
+      tournamentVaultPda
+    );
+    expect(vaultBalance).to.equal(entryFee.toNumber());
+
+    const tournamentAccount = await program.account.tournament.fetch(
+      tournamentPda
+    );
+    expect(tournamentAccount.totalPlayers).to.equal(1);
+    console.log('Join tournament test passed');
+  } catch (error) {
+    console.error('Join tournament test failed:', error);
+    throw error;
+  }
+
+  // Test 3: Rounds can't charge their own entry fee
+  console.log('Testing round with an entry fee...');
+  try {
+    await initRound(roundCode('R1FEE'), entryFee);
+    throw new Error('Should have failed with a round entry fee');
+  } catch (error) {
+    expectError(error, ['TournamentRoundEntryFee']);
+    console.log('Round entry fee test passed');
+  }
+
+  // Test 4: Rounds are added in order until the tournament is full
+  console.log('Testing adding tournament rounds...');
+  try {
+    await confirm(await initRound(roundCode('R1'), new anchor.BN(0)));
+    await confirm(await initRound(roundCode('R2'), new anchor.BN(0)));
+
+    const tournamentAccount = await program.account.tournament.fetch(
+      tournamentPda
+    );
+    expect(tournamentAccount.rounds.length).to.equal(2);
+
+    const finalRound = await program.account.game.fetch(
+      tournamentAccount.rounds[1]
+    );
+    expect(finalRound.tournament.equals(tournamentPda)).to.be.true;
+    expect(finalRound.tournamentRound).to.equal(1);
+    console.log('Add tournament rounds test passed');
+  } catch (error) {
+    console.error('Add tournament rounds test failed:', error);
+    throw error;
+  }
+
+  // Test 5: No more rounds than the tournament was created with
+  console.log('Testing adding too many rounds...');
+  try {
+    await initRound(roundCode('R3'), new anchor.BN(0));
+    throw new Error('Should have failed with a full tournament');
+  } catch (error) {
+    expectError(error, ['TournamentFull']);
+    console.log('Full tournament test passed');
+  }
+
+  console.log('All tournament tests completed successfully');
+}
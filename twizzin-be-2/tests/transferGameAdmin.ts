@@ -0,0 +1,173 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
+import { expect } from 'chai';
+import { PublicKey, LAMPORTS_PER_SOL, SystemProgram } from '@solana/web3.js';
+import { NATIVE_MINT } from '@solana/spl-token';
+
+export async function transferGameAdmin(
+  program: Program<TwizzinBe2>,
+  provider: anchor.AnchorProvider,
+  confirm: (signature: string) => Promise<string>
+) {
+  console.log('Starting game admin transfer tests');
+
+  // Helper function for error checking
+  const expectError = (error: any, errorTypes: string[]) => {
+    const errorString = error.toString();
+    const hasExpectedError = errorTypes.some((type) =>
+      errorString.includes(type)
+    );
+    expect(
+      hasExpectedError,
+      `Expected one of [${errorTypes}] but got: ${errorString}`
+    ).to.be.true;
+  };
+
+  const admin = provider.wallet.publicKey;
+  const now = Date.now();
+  const gameCode = 'XFER' + (now % 10_000);
+
+  // PDAs are always derived from the creating wallet
+  const [gamePda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('game'), admin.toBuffer(), Buffer.from(gameCode)],
+    program.programId
+  );
+  const [vaultPda] = PublicKey.findProgramAddressSync(
+    [Buffer.from('vault'), admin.toBuffer(), Buffer.from(gameCode)],
+    program.programId
+  );
+
+  await confirm(
+    await program.methods
+      .initGame(
+        'Transferred Game',
+        gameCode,
+        new anchor.BN(0),
+        0,
+        new anchor.BN(now + 60_000),
+        new anchor.BN(now + 3_600_000),
+        3,
+        Array(32).fill(0),
+        new anchor.BN(0),
+        false,
+        false,
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
+      )
+      .accounts({
+        admin,
+        game: gamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
+        tokenMint: NATIVE_MINT,
+        vault: vaultPda,
+        adminTokenAccount: null,
+        tokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
+        associatedTokenProgram: anchor.utils.token.ASSOCIATED_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+  );
+
+  const newAdmin = anchor.web3.Keypair.generate();
+  await confirm(
+    await provider.connection.requestAirdrop(
+      newAdmin.publicKey,
+      LAMPORTS_PER_SOL
+    )
+  );
+
+  const acceptAdmin = (signer: anchor.web3.Keypair) =>
+    program.methods
+      .acceptGameAdmin()
+      .accounts({ newAdmin: signer.publicKey, game: gamePda })
+      .signers([signer])
+      .rpc();
+
+  // Test 1: Nobody can accept before a transfer starts
+  console.log('Testing accept without a pending transfer...');
+  try {
+    await acceptAdmin(newAdmin);
+    throw new Error('Should have failed with no pending admin');
+  } catch (error) {
+    expectError(error, ['NotPendingAdmin']);
+    console.log('Accept without transfer test passed');
+  }
+
+  // Test 2: Only the pending admin can accept
+  console.log('Testing accept from the wrong wallet...');
+  try {
+    await confirm(
+      await program.methods
+        .transferGameAdmin(newAdmin.publicKey)
+        .accounts({ admin, game: gamePda })
+        .rpc()
+    );
+
+    const game = await program.account.game.fetch(gamePda);
+    expect(game.admin.toString()).to.equal(admin.toString());
+    expect(game.pendingAdmin.toString()).to.equal(
+      newAdmin.publicKey.toString()
+    );
+
+    const stranger = anchor.web3.Keypair.generate();
+    await acceptAdmin(stranger);
+    throw new Error('Should have failed with wrong pending admin');
+  } catch (error) {
+    expectError(error, ['NotPendingAdmin']);
+    console.log('Wrong wallet accept test passed');
+  }
+
+  // Test 3: Pending admin accepts and the game keeps its address
+  console.log('Testing accept by the pending admin...');
+  try {
+    await confirm(await acceptAdmin(newAdmin));
+
+    const game = await program.account.game.fetch(gamePda);
+    expect(game.admin.toString()).to.equal(newAdmin.publicKey.toString());
+    expect(game.creator.toString()).to.equal(admin.toString());
+    expect(game.pendingAdmin).to.be.null;
+    console.log('Accept test passed');
+  } catch (error) {
+    console.error('Accept test failed:', error);
+    throw error;
+  }
+
+  // Test 4: The previous admin loses control
+  console.log('Testing start game as the previous admin...');
+  try {
+    await program.methods
+      .startGame(new anchor.BN(5 * 60 * 1000))
+      .accounts({ authority: admin, game: gamePda })
+      .rpc();
+    throw new Error('Should have failed with invalid authority');
+  } catch (error) {
+    expectError(error, ['InvalidAuthority']);
+    console.log('Previous admin test passed');
+  }
+
+  // Test 5: The new admin runs the game
+  console.log('Testing start game as the new admin...');
+  try {
+    await confirm(
+      await program.methods
+        .startGame(new anchor.BN(5 * 60 * 1000))
+        .accounts({ authority: newAdmin.publicKey, game: gamePda })
+        .signers([newAdmin])
+        .rpc()
+    );
+    console.log('New admin start game test passed');
+  } catch (error) {
+    console.error('New admin start game test failed:', error);
+    throw error;
+  }
+
+  console.log('All game admin transfer tests completed successfully');
+}
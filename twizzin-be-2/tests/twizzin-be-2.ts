@@ -14,6 +14,16 @@ import { declareWinners } from './declareWinners';
 import { claim } from './claim';
 import { closeGame } from './closeGame';
 import { closePlayerAccount } from './closePlayerAccount';
+import { initProfile } from './initProfile';
+import { createSeason } from './createSeason';
+import { tournament } from './tournament';
+import { privateGame } from './privateGame';
+import { gatedGame } from './gatedGame';
+import { gameOperator } from './gameOperator';
+import { transferGameAdmin } from './transferGameAdmin';
+import { answerKey } from './answerKey';
+import { merkleVectors } from './merkleVectors';
+import { chunkedSubmission } from './chunkedSubmission';
 import { LAMPORTS_PER_SOL, PublicKey, Keypair } from '@solana/web3.js';
 
 let configPubkey: PublicKey;
@@ -84,6 +94,10 @@ describe('twizzin-be-2', () => {
       authorityKeypair
     );
   });
+  it('Creates a season', async () => {
+    await createSeason(program, provider, confirm, authorityKeypair);
+  });
+
   it('Initializes a game', async () => {
     await initializeGame(program, provider, confirm);
   });
@@ -92,6 +106,10 @@ describe('twizzin-be-2', () => {
     await updateGame(program, provider, confirm);
   });
 
+  it('Initializes a player profile', async () => {
+    await initProfile(program, provider, confirm);
+  });
+
   it('Joins a game', async () => {
     await joinGame(program, provider, confirm);
   });
@@ -116,6 +134,38 @@ describe('twizzin-be-2', () => {
     await claim(program, provider, confirm);
   });
 
+  it('Restricts private games', async () => {
+    await privateGame(program, provider, confirm);
+  });
+
+  it('Gates entry on token holdings', async () => {
+    await gatedGame(program, provider, confirm);
+  });
+
+  it('Delegates game operations to operators', async () => {
+    await gameOperator(program, provider, confirm);
+  });
+
+  it('Transfers game admin', async () => {
+    await transferGameAdmin(program, provider, confirm);
+  });
+
+  it('Reveals the answer key and re-scores players', async () => {
+    await answerKey(program, provider, confirm);
+  });
+
+  it('Matches the program merkle test vectors', async () => {
+    await merkleVectors(program, provider, confirm);
+  });
+
+  it('Submits answers in chunks', async () => {
+    await chunkedSubmission(program, provider, confirm);
+  });
+
+  it('Runs a tournament', async () => {
+    await tournament(program, provider, confirm);
+  });
+
   it('Closes a game', async () => {
     await closeGame(program, provider, confirm);
   });
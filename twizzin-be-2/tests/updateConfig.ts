@@ -43,7 +43,8 @@ export async function updateProgramConfig(
     treasury: PublicKey | null,
     fee: number | null,
     authority: PublicKey,
-    additionalSigners: anchor.web3.Keypair[] = []
+    additionalSigners: anchor.web3.Keypair[] = [],
+    feeRecipients: { recipient: PublicKey; weightBps: number }[] | null = null
   ) => {
     console.log('\nDEBUG: Update Transaction Parameters');
     console.log('Treasury:', treasury?.toBase58() ?? 'null');
@@ -56,7 +57,7 @@ export async function updateProgramConfig(
     );
 
     return program.methods
-      .updateConfig(treasury, fee)
+      .updateConfig(treasury, fee, feeRecipients, null, null)
       .accounts({
         authority: authorityKeypair.publicKey,
         config: configPda,
@@ -160,6 +161,65 @@ export async function updateProgramConfig(
     console.log('Zero treasury address test passed');
   }
 
+  // Test fee recipient split
+  console.log('Testing fee recipient split...');
+  try {
+    const feeRecipients = [
+      { recipient: anchor.web3.Keypair.generate().publicKey, weightBps: 5000 },
+      { recipient: anchor.web3.Keypair.generate().publicKey, weightBps: 3000 },
+      { recipient: anchor.web3.Keypair.generate().publicKey, weightBps: 2000 },
+    ];
+    const splitTx = await executeUpdateConfig(
+      null,
+      null,
+      authorityKeypair.publicKey,
+      [],
+      feeRecipients
+    );
+    await confirm(splitTx);
+
+    const [configPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('config')],
+      program.programId
+    );
+    const configState = await program.account.programConfig.fetch(configPda);
+    expect(configState.feeRecipients.length).to.equal(3);
+    expect(configState.feeRecipients[0].recipient.toBase58()).to.equal(
+      feeRecipients[0].recipient.toBase58()
+    );
+    expect(configState.feeRecipients[2].weightBps).to.equal(2000);
+
+    // Reset so later tests pay the whole fee to the treasury
+    const resetTx = await executeUpdateConfig(
+      null,
+      null,
+      authorityKeypair.publicKey,
+      [],
+      []
+    );
+    await confirm(resetTx);
+    console.log('Fee recipient split test passed');
+  } catch (error) {
+    console.error('Fee recipient split test failed:', error);
+    throw error;
+  }
+
+  // Test invalid fee recipient weights
+  console.log('Testing invalid fee recipient weights...');
+  try {
+    await executeUpdateConfig(null, null, authorityKeypair.publicKey, [], [
+      { recipient: anchor.web3.Keypair.generate().publicKey, weightBps: 5000 },
+      { recipient: anchor.web3.Keypair.generate().publicKey, weightBps: 4000 },
+    ]);
+    throw new Error('Should have failed with invalid fee recipient weights');
+  } catch (error) {
+    expectError(error, [
+      'InvalidFeeRecipientWeights',
+      'Fee recipient weights must sum to 10000 basis points',
+    ]);
+    console.log('Invalid fee recipient weights test passed');
+  }
+
   // Test unauthorized update
   console.log('Testing unauthorized update...');
   const unauthorizedKeypair = anchor.web3.Keypair.generate();
@@ -177,7 +237,7 @@ export async function updateProgramConfig(
 
   try {
     await program.methods
-      .updateConfig(null, 500)
+      .updateConfig(null, 500, null, null, null)
       .accounts({
         authority: unauthorizedKeypair.publicKey,
         config: configPubkey,
@@ -1,6 +1,7 @@
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
 import { TwizzinBe2 } from '../target/types/twizzin_be_2';
+import { findGameCodeRegistryPda } from './utils';
 import { expect } from 'chai';
 import { PublicKey, LAMPORTS_PER_SOL, SystemProgram } from '@solana/web3.js';
 import {
@@ -49,6 +50,9 @@ export async function updateGame(
       vaultTokenAccount?: PublicKey | null;
       allAreWinners?: boolean;
       evenSplit?: boolean;
+      metadataUri?: string;
+      questionSetHash?: number[];
+      wrongAnswerPenalty?: number;
     }
   ) => {
     const gameState = await program.account.game.fetch(game);
@@ -85,7 +89,7 @@ export async function updateGame(
 
     // Build accounts object
     const accounts = {
-      admin: adminPubkey,
+      authority: adminPubkey,
       game,
       vault: vaultPda,
       vaultTokenAccount: isNative ? null : vaultTokenAccount,
@@ -117,7 +121,17 @@ export async function updateGame(
         params.answerHash === undefined ? null : params.answerHash,
         params.donationAmount === undefined ? null : params.donationAmount,
         params.allAreWinners === undefined ? null : params.allAreWinners,
-        params.evenSplit === undefined ? null : params.evenSplit
+        params.evenSplit === undefined ? null : params.evenSplit,
+        null,
+        null,
+        params.metadataUri === undefined ? null : params.metadataUri,
+        params.questionSetHash === undefined ? null : params.questionSetHash,
+        params.wrongAnswerPenalty === undefined
+          ? null
+          : params.wrongAnswerPenalty,
+          null,
+          null,
+          null
       )
       .accounts(accounts)
       .signers(adminSigner)
@@ -178,11 +192,20 @@ export async function updateGame(
       Array(32).fill(1),
       new anchor.BN(0),
       false, // allAreWinners
-      false // evenSplit
+      false, // evenSplit
+      { public: {} },
+      { open: {} },
+      null,
+      Array(32).fill(0),
+      0,
+      0,
+      0,
+      1
     )
     .accounts({
       admin: provider.wallet.publicKey,
       game: gamePda,
+      gameCodeRegistry: findGameCodeRegistryPda(gameCode, program.programId),
       tokenMint: NATIVE_MINT,
       vault: vaultPda,
       vaultTokenAccount: null,
@@ -443,11 +466,23 @@ export async function updateGame(
         Array(32).fill(1),
         new anchor.BN(0),
         false, // allAreWinners
-        false // evenSplit
+        false, // evenSplit
+        { public: {} },
+        { open: {} },
+        null,
+        Array(32).fill(0),
+        0,
+        0,
+        0,
+        1
       )
       .accounts({
         admin: provider.wallet.publicKey,
         game: splGamePda,
+        gameCodeRegistry: findGameCodeRegistryPda(
+          splGameCode,
+          program.programId
+        ),
         tokenMint: mint,
         vault: splVaultPda,
         vaultTokenAccount: vaultAta.address,
@@ -618,5 +653,68 @@ export async function updateGame(
     throw error;
   }
 
+  // Test 11: Metadata can change before the game starts
+  console.log('Testing metadata update before start...');
+  const metadataUri = 'https://twizzin.xyz/games/updategame1.json';
+  const questionSetHash = Array(32).fill(7);
+  try {
+    const tx = await executeUpdateGame(gamePda, {
+      startTime: new anchor.BN(Date.now() + 3_600_000),
+      endTime: new anchor.BN(Date.now() + 7_200_000),
+      metadataUri,
+      questionSetHash,
+      tokenMint: NATIVE_MINT,
+      adminTokenAccount: null,
+      vaultTokenAccount: null,
+    });
+    await confirm(tx);
+
+    const gameState = await program.account.game.fetch(gamePda);
+    expect(gameState.metadataUri).to.equal(metadataUri);
+    expect(gameState.questionSetHash).to.deep.equal(questionSetHash);
+    console.log('Metadata update test passed');
+  } catch (error) {
+    console.error('Metadata update failed:', error);
+    throw error;
+  }
+
+  // Test 12: Metadata is frozen once the game has started
+  console.log('Testing metadata update after start...');
+  try {
+    await confirm(
+      await executeUpdateGame(gamePda, {
+        startTime: new anchor.BN(Date.now() - 60_000),
+        tokenMint: NATIVE_MINT,
+        adminTokenAccount: null,
+        vaultTokenAccount: null,
+      })
+    );
+    await executeUpdateGame(gamePda, {
+      questionSetHash: Array(32).fill(8),
+      tokenMint: NATIVE_MINT,
+      adminTokenAccount: null,
+      vaultTokenAccount: null,
+    });
+    throw new Error('Should have failed with metadata frozen');
+  } catch (error) {
+    expectError(error, ['GameMetadataFrozen']);
+    console.log('Metadata frozen test passed');
+  }
+
+  // Test 13: Negative marking can't be switched on mid-game
+  console.log('Testing wrong answer penalty update after start...');
+  try {
+    await executeUpdateGame(gamePda, {
+      wrongAnswerPenalty: 1,
+      tokenMint: NATIVE_MINT,
+      adminTokenAccount: null,
+      vaultTokenAccount: null,
+    });
+    throw new Error('Should have failed with scoring frozen');
+  } catch (error) {
+    expectError(error, ['ScoringFrozen']);
+    console.log('Scoring frozen test passed');
+  }
+
   console.log('All game update tests completed successfully');
 }
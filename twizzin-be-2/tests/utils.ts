@@ -0,0 +1,221 @@
+import { PublicKey } from '@solana/web3.js';
+import { createHash, Hash } from 'crypto';
+import * as anchor from '@coral-xyz/anchor';
+
+// Game codes are registered upper-cased and trimmed, like the program does
+export const findGameCodeRegistryPda = (
+  gameCode: string,
+  programId: PublicKey
+) =>
+  PublicKey.findProgramAddressSync(
+    [Buffer.from('game_code'), Buffer.from(gameCode.trim().toUpperCase())],
+    programId
+  )[0];
+
+export type AnswerType =
+  | { choice: {} }
+  | { multiSelect: {} }
+  | { freeText: {} }
+  | { numeric: { min: anchor.BN; max: anchor.BN } };
+
+// Mirrors utils::answer_format::canonicalize_answer
+const canonicalizeAnswer = (answerType: AnswerType, answer: string) => {
+  if ('multiSelect' in answerType) {
+    const options = answer
+      .split(',')
+      .map((option) => option.trim())
+      .filter((option) => option.length > 0);
+    return [...new Set(options)].sort().join(',');
+  }
+  if ('freeText' in answerType) {
+    return answer.trim().split(/\s+/).join(' ').toLowerCase();
+  }
+  return answer;
+};
+
+// Leaf format version 1 - version, type tag and display order, then the
+// length-prefixed canonical answer or a numeric question's range
+const updateLeaf = (
+  hash: Hash,
+  displayOrder: number,
+  answerType: AnswerType,
+  answer: string,
+  questionId: string,
+  points: number
+) => {
+  const pointsBytes = Buffer.alloc(2);
+  pointsBytes.writeUInt16LE(points);
+  if ('numeric' in answerType) {
+    hash.update(Buffer.from([1, 3, displayOrder]));
+    const { min, max } = answerType.numeric;
+    hash.update(min.toTwos(64).toArrayLike(Buffer, 'le', 8));
+    hash.update(max.toTwos(64).toArrayLike(Buffer, 'le', 8));
+  } else {
+    const tag =
+      'choice' in answerType ? 0 : 'multiSelect' in answerType ? 1 : 2;
+    const answerBytes = Buffer.from(canonicalizeAnswer(answerType, answer));
+    const length = Buffer.alloc(4);
+    length.writeUInt32LE(answerBytes.length);
+    hash.update(Buffer.from([1, tag, displayOrder]));
+    hash.update(length);
+    hash.update(answerBytes);
+  }
+  hash.update(Buffer.from(questionId));
+  hash.update(pointsBytes);
+};
+
+export const createLeafHash = (
+  displayOrder: number,
+  answerType: AnswerType,
+  answer: string,
+  questionId: string,
+  points: number
+): Buffer => {
+  const hash = createHash('sha256');
+  updateLeaf(hash, displayOrder, answerType, answer, questionId, points);
+  return hash.digest();
+};
+
+// Merkle v2 leaves are the same fields behind a 0x00 prefix
+export const createLeafHashV2 = (
+  displayOrder: number,
+  answerType: AnswerType,
+  answer: string,
+  questionId: string,
+  points: number
+): Buffer => {
+  const hash = createHash('sha256');
+  hash.update(Buffer.from([0]));
+  updateLeaf(hash, displayOrder, answerType, answer, questionId, points);
+  return hash.digest();
+};
+
+const hashPair = (first: Buffer, second: Buffer): Buffer => {
+  const hash = createHash('sha256');
+  if (Buffer.compare(first, second) <= 0) {
+    hash.update(first);
+    hash.update(second);
+  } else {
+    hash.update(second);
+    hash.update(first);
+  }
+  return hash.digest();
+};
+
+// Merkle v2 nodes are hashed in position order behind a 0x01 prefix
+const hashNode = (left: Buffer, right: Buffer): Buffer =>
+  createHash('sha256')
+    .update(Buffer.from([1]))
+    .update(left)
+    .update(right)
+    .digest();
+
+// Merkle v2 pads odd layers with an all-zero sibling
+export const EMPTY_NODE = Buffer.alloc(32);
+
+// Builds the answer tree for a game's merkle version (see utils::merkle).
+// v1 sorts each pair and carries an odd node up, v2 keeps leaves in position.
+export class MerkleTree {
+  private layers: Buffer[][];
+
+  constructor(
+    answers: Array<{
+      displayOrder: number;
+      answerType: AnswerType;
+      answer: string;
+      questionId: string;
+      points: number;
+    }>,
+    private merkleVersion = 1
+  ) {
+    const createLeaf = merkleVersion === 2 ? createLeafHashV2 : createLeafHash;
+    const leaves = answers.map((answer) =>
+      createLeaf(
+        answer.displayOrder,
+        answer.answerType,
+        answer.answer,
+        answer.questionId,
+        answer.points
+      )
+    );
+    this.layers = [leaves];
+
+    while (this.layers[this.layers.length - 1].length > 1) {
+      this.layers.push(
+        this.createNextLayer(this.layers[this.layers.length - 1])
+      );
+    }
+  }
+
+  private createNextLayer(nodes: Buffer[]): Buffer[] {
+    const layerNodes: Buffer[] = [];
+    for (let i = 0; i < nodes.length; i += 2) {
+      if (this.merkleVersion === 2) {
+        layerNodes.push(hashNode(nodes[i], nodes[i + 1] || EMPTY_NODE));
+      } else if (i + 1 === nodes.length) {
+        layerNodes.push(nodes[i]);
+      } else {
+        layerNodes.push(hashPair(nodes[i], nodes[i + 1]));
+      }
+    }
+    return layerNodes;
+  }
+
+  getRoot(): Buffer {
+    return this.layers[this.layers.length - 1][0];
+  }
+
+  getProof(index: number): Buffer[] {
+    let currentIndex = index;
+    const proof: Buffer[] = [];
+
+    for (
+      let layerIndex = 0;
+      layerIndex < this.layers.length - 1;
+      layerIndex++
+    ) {
+      const currentLayer = this.layers[layerIndex];
+      const isRightNode = currentIndex % 2 === 0;
+      const pairIndex = isRightNode ? currentIndex + 1 : currentIndex - 1;
+
+      if (pairIndex < currentLayer.length) {
+        proof.push(currentLayer[pairIndex]);
+      } else if (this.merkleVersion === 2) {
+        proof.push(EMPTY_NODE);
+      }
+
+      currentIndex = Math.floor(currentIndex / 2);
+    }
+
+    return proof;
+  }
+
+  // One proof covering every leaf in `indices` - v2 trees only. Siblings are
+  // listed bottom layer first, left to right, skipping any the leaves rebuild.
+  getMultiproof(indices: number[]): {
+    claimed: number[];
+    proof: Buffer[];
+    depth: number;
+  } {
+    const claimed = [...new Set(indices)].sort((a, b) => a - b);
+    const proof: Buffer[] = [];
+    let known = claimed;
+
+    for (
+      let layerIndex = 0;
+      layerIndex < this.layers.length - 1;
+      layerIndex++
+    ) {
+      const currentLayer = this.layers[layerIndex];
+      for (const index of known) {
+        const sibling = index ^ 1;
+        if (!known.includes(sibling)) {
+          proof.push(currentLayer[sibling] || EMPTY_NODE);
+        }
+      }
+      known = [...new Set(known.map((index) => index >> 1))];
+    }
+
+    return { claimed, proof, depth: this.layers.length - 1 };
+  }
+}